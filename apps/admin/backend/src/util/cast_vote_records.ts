@@ -5,6 +5,7 @@ import {
   ElectionDefinition,
   MarkThresholds,
   Tabulation,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 import { CachedElectionLookups, hasCrossoverVote } from '@votingworks/utils';
 import { throwIllegalValue } from '@votingworks/basics';
@@ -18,6 +19,10 @@ import {
  * Returns the number of allowed votes for the contest
  */
 export function getNumberVotesAllowed(contest: Contest): number {
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return contest.seats;
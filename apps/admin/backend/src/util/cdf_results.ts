@@ -5,6 +5,7 @@ import {
   ResultsReporting,
   Tabulation,
   YesNoContest,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 import { getBallotCount } from '@votingworks/utils';
 import { assert, assertDefined } from '@votingworks/basics';
@@ -183,6 +184,10 @@ function buildContests(
        */
       continue;
     }
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      rankedChoiceNotYetImplemented();
+    }
     const contestResults = electionResults.contestResults[contest.id];
     assert(contestResults);
     if (contest.type === 'yesno') {
@@ -6,6 +6,7 @@ import {
   BallotStyleGroupId,
   Election,
   getContests,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
   Tabulation,
 } from '@votingworks/types';
@@ -206,6 +207,10 @@ test('entering initial ballot count and contest tallies', async () => {
     if (contest.type === 'straight-party') {
       straightPartyNotYetImplemented();
     }
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      rankedChoiceNotYetImplemented();
+    }
     if (contest.type === 'candidate') {
       assert(contestResults.contestType === 'candidate');
       for (const candidate of contest.candidates) {
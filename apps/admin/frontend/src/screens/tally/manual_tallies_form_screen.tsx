@@ -21,6 +21,7 @@ import {
   BallotStyleGroupId,
   Precinct,
   Election,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import {
@@ -307,6 +308,10 @@ function emptyFormContestResults(
   if (contest.type === 'straight-party') {
     return straightPartyNotYetImplemented();
   }
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'yesno':
       return {
@@ -19,6 +19,7 @@ import {
   Side,
   Vote,
   VotesDict,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 import { contestOptionName, hasCrossoverVote } from '@votingworks/utils';
 
@@ -111,6 +112,10 @@ export function adjudicatedVotes(
   return Object.fromEntries(
     contests.map(({ contest, adjudicationData }): [string, Vote] => {
       const adjudicatedContest = adjudicatedContests.get(contest.id);
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        return rankedChoiceNotYetImplemented();
+      }
       switch (contest.type) {
         case 'candidate':
           return [
@@ -5,6 +5,7 @@ import { dirSync } from 'tmp';
 import {
   BmdBallotPaperSize,
   HmpbBallotPaperSize,
+  InterpretableHmpbBallotPaperSize,
   Tabulation,
   ballotPaperDimensions,
 } from '@votingworks/types';
@@ -41,7 +42,7 @@ export interface BatchControl {
 
 export interface ScanOptions {
   directory?: string;
-  pageSize?: HmpbBallotPaperSize;
+  pageSize?: InterpretableHmpbBallotPaperSize;
   imprintIdPrefix?: string; // Prefix for the audit ID to imprint on the ballot, an undefined value means no imprinting
 }
 
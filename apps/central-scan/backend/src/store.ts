@@ -5,6 +5,7 @@
 import { Client as DbClient } from '@votingworks/db';
 import {
   HmpbBallotPaperSize,
+  InterpretableHmpbBallotPaperSize,
   BatchInfo,
   Iso8601Timestamp,
   mapSheet,
@@ -404,7 +405,7 @@ export class Store {
     );
   }
 
-  getBallotPaperSizeForElection(): HmpbBallotPaperSize {
+  getBallotPaperSizeForElection(): InterpretableHmpbBallotPaperSize {
     const electionRecord = this.getElectionRecord();
     return (
       electionRecord?.electionDefinition.election.ballotLayout.paperSize ??
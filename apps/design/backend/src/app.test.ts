@@ -65,6 +65,7 @@ import {
   safeParseElectionDefinitionV4p0,
   LATEST_SOFTWARE_VERSION,
   convertLatestElectionToV4p0,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
   EncodedBallotEntry,
   UiStringAudioClip,
@@ -574,6 +575,10 @@ test('create/list/delete elections', async () => {
       if (contest.type === 'straight-party') {
         return straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        return rankedChoiceNotYetImplemented();
+      }
       return {
         ...contest,
         id: expectNotEqualTo(contest.id),
@@ -3252,6 +3257,10 @@ test('cloneElection', async () => {
       if (contest.type === 'straight-party') {
         return straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        return rankedChoiceNotYetImplemented();
+      }
       switch (contest.type) {
         case 'candidate':
           return {
@@ -3,6 +3,7 @@ import {
   formatBallotHash,
   safeParseInt,
   Tabulation,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import { parse } from 'csv-parse/sync';
@@ -232,6 +233,10 @@ export function convertMsResults(
         if (contest.type === 'straight-party') {
           return straightPartyNotYetImplemented();
         }
+        /* istanbul ignore next */
+        if (contest.type === 'ranked-choice') {
+          return rankedChoiceNotYetImplemented();
+        }
         switch (contest.type) {
           case 'candidate': {
             if (row.selectionId === Tabulation.GENERIC_WRITE_IN_ID) {
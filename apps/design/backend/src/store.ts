@@ -60,6 +60,7 @@ import {
   safeParseElectionDefinitionForAnySoftwareVersion,
   SoftwareVersion,
   ElectionType,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
   StraightPartyContest,
   Tabulation,
@@ -406,6 +407,10 @@ async function insertContest(
   if (contest.type === 'straight-party') {
     return straightPartyNotYetImplemented();
   }
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate': {
       await client.query(
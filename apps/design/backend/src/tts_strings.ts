@@ -5,6 +5,7 @@ import {
   LanguageCode,
   TtsEdit,
   TtsEditKey,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 import {
   SpeechSynthesizer,
@@ -122,6 +123,10 @@ export function apiMethods(ctx: TtsApiContext) {
           text: contest.title,
         });
 
+        /* istanbul ignore next */
+        if (contest.type === 'ranked-choice') {
+          rankedChoiceNotYetImplemented();
+        }
         switch (contest.type) {
           case 'candidate':
             if (contest.termDescription) {
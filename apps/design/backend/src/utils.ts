@@ -12,6 +12,7 @@ import {
   pollingPlacesGenerateFromPrecincts,
   Precinct,
   PrecinctId,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import { customAlphabet } from 'nanoid';
@@ -115,6 +116,10 @@ export function regenerateElectionIds(
       if (contest.type === 'straight-party') {
         return straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        return rankedChoiceNotYetImplemented();
+      }
       switch (contest.type) {
         case 'candidate':
           return {
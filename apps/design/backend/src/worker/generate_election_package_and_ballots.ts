@@ -15,6 +15,7 @@ import {
   EncodedBallotEntry,
   BaseBallotProps,
   SoftwareVersion,
+  isHmpbBallotPaperSize,
 } from '@votingworks/types';
 import {
   hmpbStringsCatalog,
@@ -28,6 +29,7 @@ import {
   getAllStringsForElectionPackage,
 } from '@votingworks/backend';
 import {
+  assert,
   extractErrorMessage,
   find,
   iter,
@@ -455,8 +457,13 @@ export async function generateElectionPackageAndBallots(
   }
 
   const calibrationSheetFilename = 'VxScan-calibration-sheet.pdf';
+  const { paperSize } = election.ballotLayout;
+  assert(
+    isHmpbBallotPaperSize(paperSize),
+    `Cannot print ballots on ${paperSize} paper`
+  );
   const calibrationSheetPdf = await rendererPool.runTask((renderer) =>
-    renderCalibrationSheetPdf(renderer, election.ballotLayout.paperSize)
+    renderCalibrationSheetPdf(renderer, paperSize)
   );
 
   officialBallotsZip.file(calibrationSheetFilename, calibrationSheetPdf);
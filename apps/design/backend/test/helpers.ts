@@ -24,6 +24,7 @@ import {
   formatBallotHash,
   LanguageCode,
   safeParseInt,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import { Server } from 'node:http';
@@ -401,6 +402,10 @@ export function generateAllPrecinctsTallyReportRows(
       if (contest.type === 'straight-party') {
         return straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        return rankedChoiceNotYetImplemented();
+      }
       const rowBase = {
         precinct: precinct.name,
         precinctId: precinct.id,
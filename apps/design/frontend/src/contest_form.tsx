@@ -17,6 +17,7 @@ import {
   YesNoContestSchema,
   ElectionStringKey,
   StraightPartyContest,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 import {
   Callout,
@@ -1030,6 +1031,10 @@ function draftCandidateFromCandidate(candidate: Candidate): DraftCandidate {
 }
 
 function draftContestFromContest(contest: Contest): DraftContest {
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return {
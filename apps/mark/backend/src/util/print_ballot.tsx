@@ -15,6 +15,8 @@ import {
   Election,
   getBallotStyle,
   getContests,
+  HmpbBallotPaperSize,
+  isHmpbBallotPaperSize,
 } from '@votingworks/types';
 import {
   BmdPaperBallot,
@@ -206,6 +208,15 @@ function getBaseBallotPdf(
   return { election, baseBallotPdf };
 }
 
+function bubbleBallotPaperSize(election: Election): HmpbBallotPaperSize {
+  const { paperSize } = election.ballotLayout;
+  assert(
+    isHmpbBallotPaperSize(paperSize),
+    `Cannot print ballots on ${paperSize} paper`
+  );
+  return paperSize;
+}
+
 async function printBubbleBallot(p: PrintBallotProps): Promise<void> {
   const { election, baseBallotPdf } = getBaseBallotPdf(
     p.store,
@@ -224,7 +235,7 @@ async function printBubbleBallot(p: PrintBallotProps): Promise<void> {
   return p.printer.print({
     data: markedBallotPdf,
     sides: PrintSides.TwoSidedLongEdge,
-    size: election.ballotLayout.paperSize,
+    size: bubbleBallotPaperSize(election),
   });
 }
 
@@ -240,7 +251,7 @@ export async function printBlankBallot(
   return p.printer.print({
     data: baseBallotPdf,
     sides: PrintSides.TwoSidedLongEdge,
-    size: election.ballotLayout.paperSize,
+    size: bubbleBallotPaperSize(election),
   });
 }
 
@@ -258,6 +269,6 @@ async function printMarkOverlay(p: PrintBallotProps): Promise<void> {
   return p.printer.print({
     data: markOverlayPdf,
     sides: PrintSides.TwoSidedLongEdge,
-    size: election.ballotLayout.paperSize,
+    size: bubbleBallotPaperSize(election),
   });
 }
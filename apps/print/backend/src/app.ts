@@ -17,6 +17,7 @@ import {
   pollingPlaceFromElection,
   PrecinctId,
   Election,
+  isHmpbBallotPaperSize,
   Tabulation,
 } from '@votingworks/types';
 import {
@@ -74,9 +75,14 @@ export function buildApi(ctx: AppContext) {
     electionDefinition: ElectionDefinition,
     options: PrintProps
   ) {
+    const { paperSize } = electionDefinition.election.ballotLayout;
+    assert(
+      isHmpbBallotPaperSize(paperSize),
+      `Cannot print ballots on ${paperSize} paper`
+    );
     return printer.print({
       ...options,
-      size: electionDefinition.election.ballotLayout.paperSize,
+      size: paperSize,
       sides: PrintSides.TwoSidedLongEdge,
     });
   }
@@ -5,6 +5,7 @@
 import { Client as DbClient } from '@votingworks/db';
 import {
   HmpbBallotPaperSize,
+  InterpretableHmpbBallotPaperSize,
   BatchInfo,
   Iso8601Timestamp,
   mapSheet,
@@ -444,7 +445,7 @@ export class Store {
     clearDoesUsbDriveRequireCastVoteRecordSyncCachedResult();
   }
 
-  getBallotPaperSizeForElection(): HmpbBallotPaperSize {
+  getBallotPaperSizeForElection(): InterpretableHmpbBallotPaperSize {
     const electionRecord = this.getElectionRecord();
     return (
       electionRecord?.electionDefinition.election.ballotLayout.paperSize ??
@@ -27,6 +27,7 @@ import {
   VotesDict,
   YesNoContest,
   YesNoVote,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 import {
   UNMARKED_WRITE_IN_SELECTION_POSITION_OTHER_STATUS,
@@ -439,6 +440,10 @@ export function buildCVRContestsFromVotes({
     const contestUnmarkedWriteIns = unmarkedWriteIns?.filter(
       ({ contestId }) => contestId === contest.id
     );
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      rankedChoiceNotYetImplemented();
+    }
     switch (contest.type) {
       case 'yesno':
         cvrContests.push(
@@ -9,6 +9,7 @@ import {
   ScannerMachineType,
   YesNoContest,
   StraightPartyContest,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 
 /**
@@ -98,6 +99,10 @@ function buildStraightPartyContest(
 function buildContest(
   contest: Contest
 ): CVR.CandidateContest | CVR.BallotMeasureContest | CVR.PartyContest {
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return buildCandidateContest(contest);
@@ -5,6 +5,7 @@ import {
   SYSTEM_LIMITS,
   SystemLimits,
   SystemLimitViolation,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 import {
   getContestIdsForBallotStyle,
@@ -58,6 +59,10 @@ export function validateElectionDefinitionAgainstSystemLimits(
 
   let totalCandidates = 0;
   for (const contest of election.contests) {
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      rankedChoiceNotYetImplemented();
+    }
     switch (contest.type) {
       case 'candidate': {
         if (contest.candidates.length > systemLimits.contest.candidates) {
@@ -178,6 +183,10 @@ export function validateElectionDefinitionAgainstSystemLimits(
       let seatsSummedAcrossContests = 0;
       let candidatesSummedAcrossContests = 0;
       for (const contest of ballotStyleContests) {
+        /* istanbul ignore next */
+        if (contest.type === 'ranked-choice') {
+          rankedChoiceNotYetImplemented();
+        }
         switch (contest.type) {
           case 'candidate': {
             seatsSummedAcrossContests += contest.seats;
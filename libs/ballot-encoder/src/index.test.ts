@@ -9,9 +9,11 @@ import {
   BallotTypeMaximumValue,
   Candidate,
   CandidateContest,
+  Election,
   getContests,
   HmpbBallotPageMetadata,
   LATEST_SOFTWARE_VERSION,
+  RankedChoiceContest,
   vote,
   VotesDict,
 } from '@votingworks/types';
@@ -491,6 +493,122 @@ test('encodes & decodes summary ballot with a straight party contest vote', () =
   expect(decoded.votes).toEqual(votes);
 });
 
+function buildRankedChoiceBmdPage(votes: VotesDict): {
+  electionDefinition: ReturnType<typeof readElectionDefinition>;
+  page: SummaryBallotPage;
+} {
+  const baseElectionDefinition = readElectionDefinition();
+  const { election: baseElection, ballotHash } = baseElectionDefinition;
+  const ballotStyle = baseElection.ballotStyles[0]!;
+  const precinct = baseElection.precincts[0]!;
+  const contest: RankedChoiceContest = {
+    id: 'mayor-rcv',
+    type: 'ranked-choice',
+    districtId: ballotStyle.districts[0]!,
+    title: 'Mayor',
+    maxRankings: 3,
+    candidates: [
+      { id: 'alice', name: 'Alice' },
+      { id: 'bob', name: 'Bob' },
+      { id: 'carol', name: 'Carol' },
+      { id: 'dave', name: 'Dave' },
+    ],
+    allowWriteIns: true,
+  };
+  const election: Election = {
+    ...baseElection,
+    contests: [...baseElection.contests, contest],
+  };
+
+  return {
+    electionDefinition: { ...baseElectionDefinition, election },
+    page: {
+      ballotHash,
+      ballotStyleId: ballotStyle.id,
+      precinctId: precinct.id,
+      isTestMode: false,
+      ballotType: BallotType.Precinct,
+      pageNumber: 1,
+      totalPages: 1,
+      ballotAuditId: 'ranked-choice-audit-id',
+      contests: [contest],
+      votes,
+    },
+  };
+}
+
+test('encodes & decodes summary ballot with a ranked-choice contest vote', () => {
+  const writeIn: Candidate = {
+    id: 'write-in-ZED',
+    name: 'ZED',
+    isWriteIn: true,
+  };
+  const rankings: Candidate[] = [
+    { id: 'carol', name: 'Carol' },
+    writeIn,
+    { id: 'alice', name: 'Alice' },
+  ];
+  const { electionDefinition, page } = buildRankedChoiceBmdPage({
+    'mayor-rcv': rankings,
+  });
+
+  const decoded = decodeSummaryBallotPage(
+    electionDefinition,
+    encodeSummaryBallotPage(electionDefinition.election, page)
+  );
+
+  // Rankings keep the voter's order, first choice first.
+  expect(decoded.votes['mayor-rcv']).toEqual(rankings);
+});
+
+test('throws on trying to encode an invalid ranked-choice vote', () => {
+  const alice: Candidate = { id: 'alice', name: 'Alice' };
+  const bob: Candidate = { id: 'bob', name: 'Bob' };
+  const carol: Candidate = { id: 'carol', name: 'Carol' };
+  const dave: Candidate = { id: 'dave', name: 'Dave' };
+
+  for (const [rankings, message] of [
+    [[alice, bob, carol, dave], 'allows at most 3 rankings, got 4'],
+    [[alice, bob, alice], "ranks candidate 'alice' more than once"],
+    [[{ id: 'eve', name: 'Eve' }], "has no candidate 'eve'"],
+  ] as const) {
+    const { electionDefinition, page } = buildRankedChoiceBmdPage({
+      'mayor-rcv': rankings,
+    });
+    expect(() =>
+      encodeSummaryBallotPage(electionDefinition.election, page)
+    ).toThrowError(message);
+  }
+});
+
+test('cannot decode a ranked-choice vote that ranks a candidate twice', () => {
+  const { electionDefinition, page } = buildRankedChoiceBmdPage({
+    'mayor-rcv': [
+      { id: 'alice', name: 'Alice' },
+      { id: 'bob', name: 'Bob' },
+    ],
+  });
+  const encoded = encodeSummaryBallotPage(electionDefinition.election, page);
+
+  // Decoding with the second candidate replaced by the first reads the same
+  // candidate at both ranks.
+  const { election } = electionDefinition;
+  const contests = election.contests.map((contest) =>
+    contest.type === 'ranked-choice'
+      ? {
+          ...contest,
+          candidates: [contest.candidates[0]!, ...contest.candidates],
+        }
+      : contest
+  );
+  expect(() =>
+    decodeSummaryBallotPage(
+      { ...electionDefinition, election: { ...election, contests } },
+      encoded
+    )
+  ).toThrowError("ranks candidate 'alice' more than once");
+});
+
 test('throws on trying to encode a bad yes/no vote', () => {
   const electionDefinition = readElectionDefinition();
   const { election, ballotHash } = electionDefinition;
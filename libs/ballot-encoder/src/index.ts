@@ -15,6 +15,7 @@ import {
   HmpbBallotPageMetadata,
   isVotePresent,
   PrecinctId,
+  RankedChoiceContest,
   SoftwareVersion,
   unsafeParse,
   VotesDict,
@@ -202,6 +203,106 @@ function writeYesNoVote(
   }
 }
 
+/**
+ * Finds a candidate ranked more than once, which a ranked-choice vote can't
+ * encode or decode to.
+ */
+function findDuplicateRanking(rankings: CandidateVote): Candidate | undefined {
+  return rankings.find((candidate, index) =>
+    rankings.slice(0, index).some((earlier) => earlier.id === candidate.id)
+  );
+}
+
+/**
+ * Writes the number of rankings followed by one candidate index per ranking,
+ * first choice first. The index one past the last candidate marks a write-in,
+ * which is followed by the write-in name.
+ */
+function writeRankedChoiceVote(
+  bits: BitWriter,
+  rankings: CandidateVote,
+  contest: RankedChoiceContest
+): void {
+  if (rankings.length > contest.maxRankings) {
+    throw new Error(
+      `contest '${contest.id}' allows at most ${contest.maxRankings} rankings, got ${rankings.length}`
+    );
+  }
+  const duplicate = findDuplicateRanking(rankings);
+  if (duplicate) {
+    throw new Error(
+      `contest '${contest.id}' ranks candidate '${duplicate.id}' more than once`
+    );
+  }
+
+  const writeInIndex = contest.candidates.length;
+  bits.writeUint(rankings.length, { max: contest.maxRankings });
+
+  for (const choice of rankings) {
+    if (choice.isWriteIn) {
+      if (!contest.allowWriteIns) {
+        throw new Error(`contest '${contest.id}' does not allow write-ins`);
+      }
+      bits.writeUint(writeInIndex, { max: writeInIndex });
+      bits.writeString(choice.name, {
+        encoding: WriteInEncoding,
+        maxLength: MAXIMUM_WRITE_IN_LENGTH,
+      });
+    } else {
+      const index = contest.candidates.findIndex(
+        (candidate) => candidate.id === choice.id
+      );
+      if (index < 0) {
+        throw new Error(
+          `contest '${contest.id}' has no candidate '${choice.id}'`
+        );
+      }
+      bits.writeUint(index, { max: writeInIndex });
+    }
+  }
+}
+
+function readRankedChoiceVote(
+  bits: BitReader,
+  contest: RankedChoiceContest
+): Candidate[] {
+  const writeInIndex = contest.candidates.length;
+  const rankingCount = bits.readUint({ max: contest.maxRankings });
+  if (rankingCount > contest.maxRankings) {
+    throw new Error(
+      `contest '${contest.id}' allows at most ${contest.maxRankings} rankings, got ${rankingCount}`
+    );
+  }
+
+  const rankings: Candidate[] = [];
+  for (let i = 0; i < rankingCount; i += 1) {
+    const index = bits.readUint({ max: writeInIndex });
+    if (index === writeInIndex && contest.allowWriteIns) {
+      const name = bits.readString({
+        encoding: WriteInEncoding,
+        maxLength: MAXIMUM_WRITE_IN_LENGTH,
+      });
+      rankings.push({ id: `write-in-${name}`, name, isWriteIn: true });
+    } else {
+      const candidate = contest.candidates[index];
+      if (!candidate) {
+        throw new Error(
+          `contest '${contest.id}' has no candidate at index ${index}`
+        );
+      }
+      rankings.push(candidate);
+    }
+  }
+
+  const duplicate = findDuplicateRanking(rankings);
+  if (duplicate) {
+    throw new Error(
+      `contest '${contest.id}' ranks candidate '${duplicate.id}' more than once`
+    );
+  }
+  return rankings;
+}
+
 function encodeBallotVotesInto(
   contests: readonly Contest[],
   votes: VotesDict,
@@ -267,6 +368,10 @@ function encodeBallotVotesInto(
           }
           break;
         }
+        case 'ranked-choice': {
+          writeRankedChoiceVote(bits, contestVote as CandidateVote, contest);
+          break;
+        }
         default:
           /* istanbul ignore next */
           throwIllegalValue(contest);
@@ -378,6 +483,10 @@ function decodeBallotVotes(
         votes[contest.id] = contestVote;
         break;
       }
+      case 'ranked-choice': {
+        votes[contest.id] = readRankedChoiceVote(bits, contest);
+        break;
+      }
       default:
         /* istanbul ignore next */
         throwIllegalValue(contest);
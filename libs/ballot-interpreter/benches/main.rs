@@ -2,10 +2,8 @@
 
 use std::{fmt::Display, path::PathBuf};
 
-use ballot_interpreter::interpret::{
-    ScanInterpreter, VerticalStreakDetection, WriteInScoring, DEFAULT_MAX_CUMULATIVE_STREAK_WIDTH,
-    DEFAULT_RETRY_STREAK_WIDTH_THRESHOLD,
-};
+use ballot_interpreter::interpret::{ScanInterpreter, ScanInterpreterOptions, Toggle};
+use ballot_interpreter::votes::election_mark_thresholds;
 use divan::{black_box, Bencher};
 use image::GrayImage;
 use sha2::{Digest, Sha256};
@@ -78,14 +76,15 @@ impl InterpretFixture {
         let side_a_image = image::open(&side_a_path)?.to_luma8();
         let side_b_image = image::open(&side_b_path)?.to_luma8();
 
+        let mark_thresholds = election_mark_thresholds(&election);
         let interpreter = ScanInterpreter::new(
             election,
             expected_ballot_hash,
-            WriteInScoring::Enabled,
-            VerticalStreakDetection::default(),
-            None,
-            DEFAULT_MAX_CUMULATIVE_STREAK_WIDTH,
-            DEFAULT_RETRY_STREAK_WIDTH_THRESHOLD,
+            ScanInterpreterOptions {
+                write_in_scoring: Toggle::Enabled,
+                mark_thresholds,
+                ..ScanInterpreterOptions::default()
+            },
         );
         Ok((side_a_image, side_b_image, interpreter))
     }
@@ -0,0 +1,390 @@
+#![allow(clippy::cast_precision_loss)]
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_sign_loss)]
+
+mod common;
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use ballot_interpreter::{
+    ballot_card::BubbleTemplate,
+    calibration::{calibrate, fill_score_histogram, Calibration, HISTOGRAM_BINS},
+    interpret::{ChannelMix, ScanInterpreter, ScanInterpreterOptions, ScanResolution},
+    scoring::UnitIntervalScore,
+    votes::election_mark_thresholds,
+};
+use clap::Parser;
+use color_eyre::owo_colors::OwoColorize;
+use common::SheetImages;
+use image::{imageops::crop_imm, GrayImage, ImageFormat, Rgb, RgbImage};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use types_rs::{
+    bubble_ballot::PartialBallotHash,
+    election::{Election, MarkThresholds},
+    geometry::Rect,
+};
+
+/// Interprets a corpus of scanned ballot cards and recommends mark thresholds
+/// from the distribution of their bubble fill scores.
+#[derive(Debug, clap::Parser)]
+struct Options {
+    /// Path to an election definition file.
+    election_path: PathBuf,
+
+    /// Directory of scanned cards, as pairs of <id>-front and <id>-back images.
+    scans_path: PathBuf,
+
+    /// Directory to write CSVs and images to.
+    #[clap(long, short = 'o', default_value = "calibration")]
+    output_path: PathBuf,
+
+    /// How many of the bubbles nearest the recommended definite threshold to
+    /// write out for review.
+    #[clap(long, default_value_t = 25)]
+    boundary_count: usize,
+
+    /// Pixel density of the scanned images (100, 150, 200 or 300), or
+    /// "detect" to infer it from the image dimensions.
+    #[clap(long, default_value_t = Default::default())]
+    scan_resolution: ScanResolution,
+
+    /// How to turn color images into grayscale: "luma", a single channel
+    /// ("red", "green" or "blue"), or comma-separated red, green and blue
+    /// weights.
+    #[clap(long, default_value_t = Default::default())]
+    channel_mix: ChannelMix,
+
+    /// The bubble to look for: "scanned" for the bubble on our ballots, or
+    /// "{oval,rectangle}-{width}x{height}" in inches.
+    #[clap(long, default_value = "scanned")]
+    bubble_template: BubbleTemplate,
+}
+
+/// The score of one bubble in the corpus.
+struct ScoredBubble {
+    sheet_id: String,
+    side: &'static str,
+    contest_id: String,
+    option_id: String,
+    fill_score: UnitIntervalScore,
+    match_score: UnitIntervalScore,
+    matched_bounds: Rect,
+}
+
+/// The bubbles of one card, along with the normalized images their bounds
+/// refer to.
+struct ScoredCard {
+    bubbles: Vec<ScoredBubble>,
+    normalized_images: [Vec<u8>; 2],
+}
+
+fn build_interpreter(
+    options: &Options,
+    election: Election,
+    expected_ballot_hash: PartialBallotHash,
+    mark_thresholds: MarkThresholds,
+) -> ScanInterpreter {
+    ScanInterpreter::new(
+        election,
+        expected_ballot_hash,
+        ScanInterpreterOptions {
+            mark_thresholds,
+            scan_resolution: options.scan_resolution,
+            channel_mix: options.channel_mix,
+            bubble_template: options.bubble_template.clone(),
+            ..ScanInterpreterOptions::default()
+        },
+    )
+}
+
+fn score_sheet(
+    interpreter: &ScanInterpreter,
+    sheet: &SheetImages,
+) -> color_eyre::Result<ScoredCard> {
+    let card = interpreter.interpret(
+        image::open(&sheet.front)?,
+        image::open(&sheet.back)?,
+        None,
+        None,
+    )?;
+    let mut bubbles = vec![];
+    for (side, page) in [("front", &card.front), ("back", &card.back)] {
+        for (grid_position, mark) in &page.marks {
+            let Some(mark) = mark else {
+                continue;
+            };
+            bubbles.push(ScoredBubble {
+                sheet_id: sheet.id.clone(),
+                side,
+                contest_id: grid_position.contest_id().to_string(),
+                option_id: grid_position.option_id().to_string(),
+                fill_score: mark.fill_score,
+                match_score: mark.match_score,
+                matched_bounds: mark.matched_bounds,
+            });
+        }
+    }
+    let normalized_image = |page: ballot_interpreter::interpret::InterpretedBallotPage| {
+        page.encoded_normalized_image.unwrap_or_default()
+    };
+    Ok(ScoredCard {
+        bubbles,
+        normalized_images: [normalized_image(card.front), normalized_image(card.back)],
+    })
+}
+
+fn write_scores_csv(path: &Path, bubbles: &[&ScoredBubble]) -> color_eyre::Result<()> {
+    let mut csv = BufWriter::new(File::create(path)?);
+    writeln!(csv, "sheet,side,contest,option,fill score,match score")?;
+    for bubble in bubbles {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{}",
+            bubble.sheet_id,
+            bubble.side,
+            bubble.contest_id,
+            bubble.option_id,
+            bubble.fill_score.0,
+            bubble.match_score.0
+        )?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+fn write_histogram_csv(path: &Path, histogram: &[u32]) -> color_eyre::Result<()> {
+    let mut csv = BufWriter::new(File::create(path)?);
+    writeln!(csv, "bin start,bin end,count")?;
+    for (index, count) in histogram.iter().enumerate() {
+        writeln!(
+            csv,
+            "{},{},{count}",
+            index as f32 / HISTOGRAM_BINS as f32,
+            (index + 1) as f32 / HISTOGRAM_BINS as f32
+        )?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+/// Draws the histogram with a logarithmic count axis, since blank bubbles
+/// usually far outnumber marked ones, and the thresholds as vertical lines.
+fn draw_histogram(histogram: &[u32], thresholds: &[(f32, Rgb<u8>)]) -> RgbImage {
+    const HEIGHT: u32 = 400;
+    let width = histogram.len() as u32;
+    let mut image = RgbImage::from_pixel(width, HEIGHT, Rgb([255, 255, 255]));
+    let max = histogram
+        .iter()
+        .copied()
+        .max()
+        .map_or(1.0, |max| f64::from(max + 1).log10());
+    for (x, &count) in histogram.iter().enumerate() {
+        let bar_height = (f64::from(count + 1).log10() / max * f64::from(HEIGHT)) as u32;
+        for y in HEIGHT - bar_height.min(HEIGHT)..HEIGHT {
+            image.put_pixel(x as u32, y, Rgb([0, 0, 0]));
+        }
+    }
+    for &(threshold, color) in thresholds {
+        let x = ((threshold * width as f32) as u32).min(width - 1);
+        for y in 0..HEIGHT {
+            image.put_pixel(x, y, color);
+        }
+    }
+    image
+}
+
+/// Writes the bubbles nearest `threshold` to a CSV and a crop of each one to
+/// a PNG, for a person to check which side of the threshold they belong on.
+fn write_boundary_bubbles(
+    output_path: &Path,
+    cards: &[ScoredCard],
+    threshold: f32,
+    count: usize,
+) -> color_eyre::Result<()> {
+    let mut bubbles = cards
+        .iter()
+        .flat_map(|card| card.bubbles.iter().map(move |bubble| (card, bubble)))
+        .collect::<Vec<_>>();
+    bubbles.sort_by(|(_, a), (_, b)| {
+        (a.fill_score.0 - threshold)
+            .abs()
+            .total_cmp(&(b.fill_score.0 - threshold).abs())
+    });
+
+    let boundary_path = output_path.join("boundary");
+    std::fs::create_dir_all(&boundary_path)?;
+    let mut csv = BufWriter::new(File::create(output_path.join("boundary.csv"))?);
+    writeln!(csv, "rank,sheet,side,contest,option,fill score,image")?;
+    for (rank, (card, bubble)) in bubbles.into_iter().take(count).enumerate() {
+        let image_name = format!("{:03}-{}-{}.png", rank + 1, bubble.sheet_id, bubble.side);
+        let normalized_image = &card.normalized_images[usize::from(bubble.side == "back")];
+        if let Ok(image) = image::load_from_memory_with_format(normalized_image, ImageFormat::Png) {
+            crop_bubble(&image.into_luma8(), &bubble.matched_bounds)
+                .save(boundary_path.join(&image_name))?;
+        }
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},boundary/{image_name}",
+            rank + 1,
+            bubble.sheet_id,
+            bubble.side,
+            bubble.contest_id,
+            bubble.option_id,
+            bubble.fill_score.0
+        )?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+/// Crops `bounds` out of `image` with a margin of half the bubble size, so
+/// that any ink just outside the bubble is visible too.
+fn crop_bubble(image: &GrayImage, bounds: &Rect) -> GrayImage {
+    let margin_x = bounds.width() / 2;
+    let margin_y = bounds.height() / 2;
+    let left = (bounds.left().max(0) as u32).saturating_sub(margin_x);
+    let top = (bounds.top().max(0) as u32).saturating_sub(margin_y);
+    crop_imm(
+        image,
+        left,
+        top,
+        bounds.width() + 2 * margin_x,
+        bounds.height() + 2 * margin_y,
+    )
+    .to_image()
+}
+
+/// Prints the fitted distributions, the recommended thresholds and their
+/// estimated error rates, a warning if the recommended definite threshold
+/// would count bubbles from the blank class as votes, and the thresholds as
+/// election definition JSON.
+fn print_calibration(
+    calibration: &Calibration,
+    current_thresholds: &MarkThresholds,
+) -> color_eyre::Result<()> {
+    for (label, distribution) in [("Blank", calibration.blank), ("Marked", calibration.marked)] {
+        println!(
+            "{label} bubbles: {} with mean {} (standard deviation {}), from {} to {}",
+            distribution.count,
+            UnitIntervalScore(distribution.mean),
+            UnitIntervalScore(distribution.std_dev),
+            UnitIntervalScore(distribution.min),
+            UnitIntervalScore(distribution.max),
+        );
+    }
+    println!(
+        "{}: definite {}, marginal {}",
+        "Recommended thresholds".green(),
+        UnitIntervalScore(calibration.definite),
+        UnitIntervalScore(calibration.marginal)
+    );
+    println!(
+        "Estimated false positive rate {:.2e}, false negative rate {:.2e}",
+        calibration.estimated_false_positive_rate, calibration.estimated_false_negative_rate
+    );
+    if calibration.blank_at_or_above_definite > 0 {
+        println!(
+            "{}: {} blank bubbles, up to {}, would count as votes; check the boundary bubbles",
+            "Warning".yellow(),
+            calibration.blank_at_or_above_definite,
+            UnitIntervalScore(calibration.blank.max)
+        );
+    }
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&MarkThresholds {
+            definite: calibration.definite,
+            marginal: calibration.marginal,
+            write_in_text_area: current_thresholds.write_in_text_area,
+        })?
+    );
+    Ok(())
+}
+
+fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+    let options = Options::parse();
+    let (election, expected_ballot_hash) = common::load_election(&options.election_path)?;
+    let current_thresholds = election_mark_thresholds(&election);
+    let interpreter = build_interpreter(
+        &options,
+        election,
+        expected_ballot_hash,
+        current_thresholds.clone(),
+    );
+
+    let sheets = common::find_sheet_images(&options.scans_path)?;
+    let results = sheets
+        .par_iter()
+        .map(|sheet| (sheet, score_sheet(&interpreter, sheet)))
+        .collect::<Vec<_>>();
+    let mut cards = vec![];
+    for (sheet, result) in results {
+        match result {
+            Ok(card) => cards.push(card),
+            Err(error) => eprintln!("{}: {}: {error}", "Skipped".yellow(), sheet.id),
+        }
+    }
+
+    let bubbles = cards
+        .iter()
+        .flat_map(|card| &card.bubbles)
+        .collect::<Vec<_>>();
+    let scores = bubbles
+        .iter()
+        .map(|bubble| bubble.fill_score)
+        .collect::<Vec<_>>();
+    let histogram = fill_score_histogram(&scores);
+
+    std::fs::create_dir_all(&options.output_path)?;
+    write_scores_csv(&options.output_path.join("scores.csv"), &bubbles)?;
+    write_histogram_csv(&options.output_path.join("histogram.csv"), &histogram)?;
+
+    println!(
+        "Scored {} bubbles on {} of {} cards",
+        scores.len(),
+        cards.len(),
+        sheets.len()
+    );
+    println!(
+        "Current thresholds: definite {}, marginal {}",
+        UnitIntervalScore(current_thresholds.definite),
+        UnitIntervalScore(current_thresholds.marginal)
+    );
+
+    let Some(calibration) = calibrate(&scores) else {
+        draw_histogram(
+            &histogram,
+            &[(current_thresholds.definite, Rgb([127, 127, 127]))],
+        )
+        .save(options.output_path.join("histogram.png"))?;
+        color_eyre::eyre::bail!("fill scores do not separate into blank and marked bubbles");
+    };
+
+    draw_histogram(
+        &histogram,
+        &[
+            (current_thresholds.definite, Rgb([127, 127, 127])),
+            (calibration.marginal, Rgb([255, 127, 0])),
+            (calibration.definite, Rgb([255, 0, 0])),
+        ],
+    )
+    .save(options.output_path.join("histogram.png"))?;
+    write_boundary_bubbles(
+        &options.output_path,
+        &cards,
+        calibration.definite,
+        options.boundary_count,
+    )?;
+
+    print_calibration(&calibration, &current_thresholds)?;
+    println!(
+        "Wrote scores, histogram and boundary bubbles to {}",
+        options.output_path.display()
+    );
+    Ok(())
+}
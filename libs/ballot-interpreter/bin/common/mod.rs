@@ -0,0 +1,56 @@
+//! Loading shared by the command-line tools in `bin/`.
+
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use types_rs::{bubble_ballot::PartialBallotHash, election::Election};
+
+/// Reads the election file as bytes, computes the partial ballot hash from
+/// those bytes, and deserializes the election. The hash is computed over the
+/// raw file bytes (matching the TS `sha256(electionData)` convention) so it
+/// agrees with the hash encoded into ballot QR codes for this election.
+pub fn load_election(path: &Path) -> color_eyre::Result<(Election, PartialBallotHash)> {
+    let bytes = std::fs::read(path)?;
+    let election: Election = serde_json::from_slice(&bytes)?;
+    let digest = Sha256::digest(&bytes);
+    let mut hash = PartialBallotHash::default();
+    let len = hash.len();
+    hash.copy_from_slice(&digest[..len]);
+    Ok((election, hash))
+}
+
+/// The images of both sides of a scanned sheet.
+pub struct SheetImages {
+    pub id: String,
+    pub front: PathBuf,
+    pub back: PathBuf,
+}
+
+/// Finds every `<id>-front.<ext>` image in `dir` with a matching
+/// `<id>-back.<ext>`, in order of their paths.
+pub fn find_sheet_images(dir: &Path) -> color_eyre::Result<Vec<SheetImages>> {
+    let mut sheets = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let front = entry?.path();
+        let (Some(stem), Some(extension)) = (
+            front.file_stem().and_then(|stem| stem.to_str()),
+            front.extension().and_then(|extension| extension.to_str()),
+        ) else {
+            continue;
+        };
+        let Some(id) = stem.strip_suffix("-front") else {
+            continue;
+        };
+        let back = front.with_file_name(format!("{id}-back.{extension}"));
+        if !back.exists() {
+            color_eyre::eyre::bail!("missing back image for {}", front.display());
+        }
+        sheets.push(SheetImages {
+            id: id.to_owned(),
+            front,
+            back,
+        });
+    }
+    sheets.sort_by(|a, b| a.front.cmp(&b.front));
+    Ok(sheets)
+}
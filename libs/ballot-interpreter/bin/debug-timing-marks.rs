@@ -12,15 +12,16 @@ use std::{
 };
 
 use ballot_interpreter::{
-    ballot_card::{BallotPage, PaperInfo},
+    ballot_card::BallotPage,
     debug,
-    interpret::Error,
+    interpret::{Error, ScanResolution},
     scoring::UnitIntervalScore,
     timing_marks::{self, DefaultForGeometry, TimingMarks},
 };
 use clap::Parser;
 use color_eyre::{eyre::bail, owo_colors::OwoColorize};
 use itertools::Itertools;
+use types_rs::ballot_card::PaperSize;
 
 #[derive(Debug, clap::Parser)]
 struct Options {
@@ -40,6 +41,11 @@ struct Options {
     #[clap(long)]
     minimum_detected_scale: Option<UnitIntervalScore>,
 
+    /// Pixel density of the scanned images (100, 150, 200 or 300), or
+    /// "detect" to infer it from the image dimensions.
+    #[clap(long, default_value_t = Default::default())]
+    scan_resolution: ScanResolution,
+
     /// Path for a CSV with various timing mark stats.
     #[clap(long)]
     stats_path: Option<PathBuf>,
@@ -90,7 +96,7 @@ fn process_path<W: Write>(
     let ballot_page = BallotPage::from_image(
         "image",
         image,
-        &PaperInfo::scanned(),
+        &options.scan_resolution.paper_infos(&PaperSize::STANDARD),
         if options.debug {
             Some(path.to_path_buf())
         } else {
@@ -1,21 +1,32 @@
+mod common;
+
 use std::{
-    collections::{HashMap, HashSet},
-    path::PathBuf,
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
     process,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use ballot_interpreter::{
+    ballot_card::BubbleTemplate,
+    cdf::{self, CvrImage, CvrMetadata},
     debug::ImageDebugWriter,
+    debug_report::ballot_card_report,
     interpret::{
-        ScanInterpreter, VerticalStreakDetection, WriteInScoring,
-        DEFAULT_MAX_CUMULATIVE_STREAK_WIDTH, DEFAULT_RETRY_STREAK_WIDTH_THRESHOLD,
+        ChannelMix, InterpretedBallotCard, InterpretedBallotPage, MetadataSource, ScanInterpreter,
+        ScanInterpreterOptions, ScanResolution, Toggle, DEFAULT_MAX_CUMULATIVE_STREAK_WIDTH,
+        DEFAULT_RETRY_STREAK_WIDTH_THRESHOLD,
     },
     qr_code,
     scoring::UnitIntervalScore,
+    summary_ballot::{self, decode_summary_ballot},
+    votes::{election_mark_thresholds, BallotCardVotes},
 };
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, Parser};
 use crossterm::style::Stylize;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use serde::Deserialize;
 use sha2::{Digest, Sha256};
 use types_rs::{
     ballot_card::BallotType,
@@ -25,8 +36,10 @@ use types_rs::{
         votes::{CandidateVote, ContestVote},
     },
     bubble_ballot::PartialBallotHash,
-    coding,
-    election::{BallotStyleId, Candidate, Contest, ContestId, Election, PrecinctId},
+    election::{
+        BallotStyleId, Candidate, Contest, ContestId, Election, MarkThresholds, PrecinctId,
+        RankedChoiceContest,
+    },
 };
 
 #[derive(Debug, clap::Parser)]
@@ -36,7 +49,8 @@ struct Options {
     election_path: PathBuf,
 
     /// Path to an image of the top side of the scanned ballot.
-    top_path: PathBuf,
+    #[clap(required_unless_present = "batch")]
+    top_path: Option<PathBuf>,
 
     /// Path to an image of the bottom side of the scanned ballot. If omitted,
     /// only summary ballot interpretation is attempted.
@@ -46,13 +60,43 @@ struct Options {
     #[clap(long, default_value = "false")]
     debug: bool,
 
+    /// Write a self-contained HTML report of the interpretation of each card
+    /// next to its top image (i.e. ballot-side-a_debug_report.html).
+    #[clap(long, default_value = "false")]
+    debug_report: bool,
+
     /// Determines whether to score write ins.
     #[clap(long, default_value = "false")]
     score_write_ins: bool,
 
+    /// Include perspective-corrected crops of each write-in area and contest
+    /// in the JSON output.
+    #[clap(long, default_value = "false")]
+    crop_areas: bool,
+
+    /// Flag contests with ink outside their bubbles and write-in areas, e.g.
+    /// circled names or checkmarks, as possible voter intent.
+    #[clap(long, default_value = "false")]
+    detect_stray_marks: bool,
+
+    /// Reject ballots with fold lines or horizontal or angled streaks that
+    /// cross bubbles.
+    #[clap(long, default_value = "false")]
+    detect_line_artifacts: bool,
+
+    /// When neither QR code can be read, identify the ballot style and sheet
+    /// from where the bubbles are printed and hold the card for adjudication.
+    #[clap(long, default_value = "false")]
+    infer_ballot_style: bool,
+
+    /// Where to read ballot metadata from: "qr-code", or "timing-marks" for
+    /// legacy ballots that encode it in the bottom row of timing marks.
+    #[clap(long, default_value_t = Default::default())]
+    metadata_source: MetadataSource,
+
     /// Vertical streak detection setting.
-    #[clap(long, short = 'v', default_value_t = Default::default())]
-    vertical_streak_detection: VerticalStreakDetection,
+    #[clap(long, short = 'v', default_value_t = Toggle::Enabled)]
+    vertical_streak_detection: Toggle,
 
     /// Detect and reject timing mark grid scales less than this value.
     #[clap(long)]
@@ -68,29 +112,175 @@ struct Options {
     #[clap(long, default_value_t = DEFAULT_RETRY_STREAK_WIDTH_THRESHOLD)]
     retry_streak_width_threshold: u32,
 
+    /// Pixel density of the scanned images (100, 150, 200 or 300), or
+    /// "detect" to infer it from the image dimensions.
+    #[clap(long, default_value_t = Default::default())]
+    scan_resolution: ScanResolution,
+
+    /// How to turn color images into grayscale: "luma", a single channel
+    /// ("red", "green" or "blue"), or comma-separated red, green and blue
+    /// weights. "red" drops out red and pink ink.
+    #[clap(long, default_value_t = Default::default())]
+    channel_mix: ChannelMix,
+
+    /// The bubble to look for: "scanned" for the bubble on our ballots,
+    /// "{oval,rectangle}-{width}x{height}" in inches (e.g. "oval-0.25x0.17"),
+    /// or the path to an image of a blank bubble scanned at 200 DPI.
+    #[clap(long, value_parser = parse_bubble_template, default_value = "scanned")]
+    bubble_template: BubbleTemplate,
+
+    /// Minimum fill score for a bubble to count as a vote. Defaults to the
+    /// election's mark thresholds, if any.
+    #[clap(long)]
+    definite_threshold: Option<UnitIntervalScore>,
+
+    /// Minimum fill score for a bubble to be flagged as a marginal mark.
+    /// Defaults to the election's mark thresholds, if any.
+    #[clap(long)]
+    marginal_threshold: Option<UnitIntervalScore>,
+
+    /// Interpret the top image as a single-sided ballot card, treating the
+    /// missing back as blank.
+    #[clap(long, conflicts_with_all = ["bottom_path", "batch", "cdf"])]
+    simplex: bool,
+
+    /// Accept only test summary ballots, as a scanner in test mode does,
+    /// rather than only official ones.
+    #[clap(long, default_value = "false")]
+    test_mode: bool,
+
+    /// Accept summary ballots only from this precinct. May be repeated.
+    /// Defaults to every precinct in the election.
+    #[clap(long = "precinct-id")]
+    precinct_ids: Vec<String>,
+
     /// Output as JSON instead of pretty-printed format.
     #[clap(long, short = 'j', default_value_t = false)]
     json: bool,
+
+    /// Output a NIST SP 1500-103 cast vote record as JSON.
+    #[clap(long, conflicts_with = "batch")]
+    cdf: bool,
+
+    /// Interpret every sheet in a directory or JSONL manifest in parallel,
+    /// writing one JSON result per line followed by a summary on stderr. A
+    /// directory must contain `<id>-front.<ext>` and `<id>-back.<ext>` image
+    /// pairs; each manifest line is `{"top": "...", "bottom": "..."}` with an
+    /// optional `"id"`, with relative paths resolved against the manifest.
+    #[clap(long, conflicts_with_all = ["top_path", "bottom_path"])]
+    batch: Option<PathBuf>,
+
+    /// Write batch results to this file instead of stdout.
+    #[clap(long, short = 'o', requires = "batch")]
+    output: Option<PathBuf>,
 }
 
 impl Options {
-    /// Reads the election file as bytes, computes the partial ballot hash
-    /// from those bytes, and deserializes the election. The hash is computed
-    /// over the raw file bytes (matching the TS `sha256(electionData)`
-    /// convention) so it agrees with the hash encoded into ballot QR codes
-    /// for this election.
-    fn load_election(&self) -> color_eyre::Result<(Election, PartialBallotHash)> {
-        let bytes = std::fs::read(&self.election_path)?;
-        let election: Election = serde_json::from_slice(&bytes)?;
-        let digest = Sha256::digest(&bytes);
-        let mut hash = PartialBallotHash::default();
-        let len = hash.len();
-        hash.copy_from_slice(&digest[..len]);
-        Ok((election, hash))
+    /// Resolves the mark thresholds to use, preferring those given on the
+    /// command line over those in the election definition.
+    fn mark_thresholds(&self, election: &Election) -> MarkThresholds {
+        let mut mark_thresholds = election_mark_thresholds(election);
+        if let Some(UnitIntervalScore(definite)) = self.definite_threshold {
+            mark_thresholds.definite = definite;
+        }
+        if let Some(UnitIntervalScore(marginal)) = self.marginal_threshold {
+            mark_thresholds.marginal = marginal;
+        }
+        mark_thresholds
+    }
+
+    /// Builds the options for validating summary ballots against this
+    /// election and the `--test-mode` and `--precinct-id` flags.
+    fn summary_ballot_options(
+        &self,
+        election: Election,
+        expected_ballot_hash: PartialBallotHash,
+    ) -> summary_ballot::Options {
+        let valid_precinct_ids = if self.precinct_ids.is_empty() {
+            election
+                .precincts
+                .iter()
+                .map(|precinct| precinct.id.clone())
+                .collect()
+        } else {
+            self.precinct_ids
+                .iter()
+                .cloned()
+                .map(PrecinctId::from)
+                .collect()
+        };
+        summary_ballot::Options {
+            election,
+            expected_ballot_hash,
+            is_test_mode: self.test_mode,
+            valid_precinct_ids,
+        }
     }
 
     fn load_top_image(&self) -> color_eyre::Result<image::DynamicImage> {
-        Ok(image::open(&self.top_path)?)
+        let top_path = self
+            .top_path
+            .as_ref()
+            .expect("top path is required outside of batch mode");
+        Ok(image::open(top_path)?)
+    }
+
+    /// Builds CVR metadata for `--cdf` output. The election ID is the SHA-256
+    /// hash of the election file and the CVR ID is the top image's file name.
+    fn cvr_metadata(&self) -> color_eyre::Result<CvrMetadata> {
+        let sha256_hex = |path: &Path| -> color_eyre::Result<String> {
+            Ok(hex::encode(Sha256::digest(std::fs::read(path)?)))
+        };
+        let cvr_image = |path: &Path| -> color_eyre::Result<CvrImage> {
+            Ok(CvrImage {
+                path: path.display().to_string(),
+                sha256: Some(sha256_hex(path)?),
+            })
+        };
+        let top_path = self
+            .top_path
+            .as_ref()
+            .expect("top path is required outside of batch mode");
+
+        Ok(CvrMetadata {
+            election_id: sha256_hex(&self.election_path)?,
+            creating_device_id: "interpret".to_owned(),
+            batch_id: String::new(),
+            batch_sequence_id: None,
+            cvr_id: top_path
+                .file_stem()
+                .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned()),
+            front_image: Some(cvr_image(top_path)?),
+            back_image: self.bottom_path.as_deref().map(cvr_image).transpose()?,
+        })
+    }
+
+    fn build_interpreter(
+        &self,
+        election: Election,
+        expected_ballot_hash: PartialBallotHash,
+    ) -> ScanInterpreter {
+        let mark_thresholds = self.mark_thresholds(&election);
+        ScanInterpreter::new(
+            election,
+            expected_ballot_hash,
+            ScanInterpreterOptions {
+                write_in_scoring: self.score_write_ins.into(),
+                vertical_streak_detection: self.vertical_streak_detection,
+                minimum_detected_scale: self.minimum_detected_scale,
+                max_cumulative_streak_width: self.max_cumulative_streak_width,
+                retry_streak_width_threshold: self.retry_streak_width_threshold,
+                mark_thresholds,
+                scan_resolution: self.scan_resolution,
+                area_cropping: self.crop_areas.into(),
+                stray_mark_detection: self.detect_stray_marks.into(),
+                line_artifact_detection: self.detect_line_artifacts.into(),
+                channel_mix: self.channel_mix,
+                bubble_template: self.bubble_template.clone(),
+                ballot_style_inference: self.infer_ballot_style.into(),
+                metadata_source: self.metadata_source.clone(),
+            },
+        )
     }
 
     fn load_bottom_image(&self) -> color_eyre::Result<Option<image::DynamicImage>> {
@@ -101,12 +291,62 @@ impl Options {
     }
 }
 
+fn parse_bubble_template(value: &str) -> Result<BubbleTemplate, String> {
+    value.parse().or_else(|parse_error| {
+        image::open(value)
+            .map(|image| BubbleTemplate::from_image(&image.into_luma8()))
+            .map_err(|open_error| format!("{parse_error} ({open_error})"))
+    })
+}
+
+/// Writes the HTML report for a card next to the image of its top side.
+fn write_debug_report(
+    top_path: &Path,
+    (top_image, bottom_image): (&image::GrayImage, &image::GrayImage),
+    mark_thresholds: &MarkThresholds,
+    result: &ballot_interpreter::interpret::Result<InterpretedBallotCard>,
+) -> color_eyre::Result<()> {
+    let report_path = top_path.with_file_name(format!(
+        "{}_debug_report.html",
+        top_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_str()
+            .unwrap_or_default()
+    ));
+    std::fs::write(
+        &report_path,
+        ballot_card_report((top_image, bottom_image).into(), mark_thresholds, result),
+    )?;
+    eprintln!("Wrote debug report to {}", report_path.display());
+    Ok(())
+}
+
 fn main() -> color_eyre::Result<()> {
     let options = Options::parse();
-    let (election, expected_ballot_hash) = options.load_election()?;
+    if let (Some(definite), Some(marginal)) =
+        (options.definite_threshold, options.marginal_threshold)
+    {
+        if marginal.0 > definite.0 {
+            Options::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "--marginal-threshold ({marginal}) must not be greater than --definite-threshold ({definite})"
+                    ),
+                )
+                .exit();
+        }
+    }
+    let (election, expected_ballot_hash) = common::load_election(&options.election_path)?;
+
+    if let Some(batch_path) = &options.batch {
+        let exit_code = interpret_batch(&options, batch_path, election, expected_ballot_hash)?;
+        process::exit(exit_code)
+    }
 
     let start = Instant::now();
-    let top_image = options.load_top_image()?.into_luma8();
+    let top_image = options.channel_mix.to_gray(options.load_top_image()?);
 
     // Detect QR code once
     let detected = qr_code::detect_with_strategy(
@@ -118,16 +358,39 @@ fn main() -> color_eyre::Result<()> {
 
     let exit_code = match detected {
         Some(ref d) if d.kind() == qr_code::QrCodeKind::SummaryBallot => {
-            let cvr = coding::decode_with::<CastVoteRecord>(d.bytes(), &election)
-                .map_err(|e| color_eyre::eyre::eyre!("failed to decode summary ballot: {e}"))?;
-            print_result(&options, &cvr, |cvr| {
-                pretty_print_cvr(cvr, &election);
-            })?;
-            0
+            let summary_ballot_options =
+                options.summary_ballot_options(election, expected_ballot_hash);
+            let election = &summary_ballot_options.election;
+            match decode_summary_ballot(d.bytes(), &summary_ballot_options) {
+                Ok(cvr) => {
+                    if options.cdf {
+                        let cdf_cvr =
+                            cdf::summary_ballot_cvr(election, &cvr, &options.cvr_metadata()?);
+                        println!("{}", serde_json::to_string_pretty(&cdf_cvr)?);
+                    } else {
+                        print_result(&options, &cvr, |cvr| {
+                            pretty_print_cvr(cvr, election);
+                        })?;
+                    }
+                    0
+                }
+
+                Err(err) => {
+                    eprintln!("Error: {err}");
+                    1
+                }
+            }
         }
 
         _ => {
-            if options.bottom_path.is_none() {
+            if options.simplex {
+                interpret_simplex_bubble_ballot(
+                    &options,
+                    election,
+                    expected_ballot_hash,
+                    top_image,
+                )?
+            } else if options.bottom_path.is_none() {
                 eprintln!("Error: no summary ballot QR code detected");
                 1
             } else {
@@ -136,7 +399,7 @@ fn main() -> color_eyre::Result<()> {
         }
     };
 
-    if !options.json {
+    if !options.json && !options.cdf {
         let duration = start.elapsed();
         println!("⚡ {duration:.2?}");
     }
@@ -351,77 +614,102 @@ fn pretty_print_contest_vote(contest: &Contest, vote: &ContestVote) {
                 );
             }
         }
+        Contest::RankedChoice(ranked_choice_contest) => {
+            pretty_print_rankings(ranked_choice_contest, vote);
+        }
         Contest::StraightParty(_) => {
             unimplemented!("STRAIGHT_PARTY_TODO: straight-party contests are not yet implemented")
         }
     }
 }
 
+fn pretty_print_rankings(ranked_choice_contest: &RankedChoiceContest, vote: &ContestVote) {
+    println!("{}", ranked_choice_contest.title.as_str().bold());
+
+    let rankings = if let ContestVote::RankedChoice(candidate_votes) = vote {
+        candidate_votes.as_slice()
+    } else {
+        &[]
+    };
+
+    if rankings.is_empty() {
+        println!("   {}", "No rankings".dark_grey().italic());
+    }
+
+    for (rank, candidate_vote) in rankings.iter().enumerate() {
+        let title = match candidate_vote {
+            CandidateVote::NamedCandidate { candidate_id } => ranked_choice_contest
+                .candidates
+                .iter()
+                .find_map(|candidate| match candidate {
+                    Candidate::Named(named) if named.id == *candidate_id => {
+                        Some(named.name.clone())
+                    }
+                    _ => None,
+                })
+                .unwrap_or_else(|| candidate_id.to_string()),
+            CandidateVote::WriteInCandidate { name, .. } => {
+                format!("{} {}", "Write-in: ".italic(), name.to_string().magenta())
+            }
+        };
+
+        println!("   {} {}", format!("{}.", rank + 1).green(), title);
+    }
+}
+
 fn interpret_bubble_ballot(
     options: &Options,
     election: Election,
     expected_ballot_hash: PartialBallotHash,
     top_image: image::GrayImage,
 ) -> color_eyre::Result<i32> {
-    let interpreter = ScanInterpreter::new(
-        election,
-        expected_ballot_hash,
-        if options.score_write_ins {
-            WriteInScoring::Enabled
-        } else {
-            WriteInScoring::Disabled
-        },
-        options.vertical_streak_detection,
-        options.minimum_detected_scale,
-        options.max_cumulative_streak_width,
-        options.retry_streak_width_threshold,
-    );
+    let cdf_context = options
+        .cdf
+        .then(|| (election.clone(), options.mark_thresholds(&election)));
+    let mark_thresholds = options.mark_thresholds(&election);
+    let interpreter = options.build_interpreter(election, expected_ballot_hash);
 
     let bottom_image = options
         .load_bottom_image()?
-        .expect("bubble ballot requires side B image")
-        .into_luma8();
+        .expect("bubble ballot requires side B image");
+    let report_images = options.debug_report.then(|| {
+        (
+            top_image.clone(),
+            options.channel_mix.to_gray(bottom_image.clone()),
+        )
+    });
     let result = interpreter.interpret(
         top_image,
         bottom_image,
-        options.debug.then_some(options.top_path.clone()),
+        options.debug.then(|| options.top_path.clone()).flatten(),
         options.debug.then(|| options.bottom_path.clone()).flatten(),
     );
 
+    if let (Some((top_image, bottom_image)), Some(top_path)) = (report_images, &options.top_path) {
+        write_debug_report(
+            top_path,
+            (&top_image, &bottom_image),
+            &mark_thresholds,
+            &result,
+        )?;
+    }
+
     match result {
         Ok(interpretation) => {
-            if options.json {
-                let marks: Vec<_> = [interpretation.front, interpretation.back]
-                    .iter()
-                    .flat_map(|page| {
-                        page.marks.iter().map(|(position, scored_mark)| {
-                            serde_json::json!({
-                                "column": position.location().column,
-                                "row": position.location().row,
-                                "contest_id": position.contest_id(),
-                                "option_id": position.option_id(),
-                                "fill_score": scored_mark.as_ref().map(|m| m.fill_score)
-                            })
-                        })
-                    })
-                    .collect();
-                println!("{}", serde_json::to_string_pretty(&marks)?);
+            if let Some((election, mark_thresholds)) = cdf_context {
+                let cdf_cvr = cdf::bubble_ballot_cvr(
+                    &election,
+                    &interpretation,
+                    &mark_thresholds,
+                    &options.cvr_metadata()?,
+                );
+                println!("{}", serde_json::to_string_pretty(&cdf_cvr)?);
             } else {
-                for page in [interpretation.front, interpretation.back] {
-                    for (position, scored_mark) in page.marks {
-                        println!(
-                            "({:05.2}, {:05.2}) ・ {} ・ {} → {}",
-                            position.location().column,
-                            position.location().row,
-                            position.contest_id(),
-                            position.option_id(),
-                            match scored_mark {
-                                Some(m) => m.fill_score.to_string(),
-                                None => "n/a".to_owned(),
-                            }
-                        );
-                    }
-                }
+                print_interpretation(
+                    options,
+                    &[&interpretation.front, &interpretation.back],
+                    &interpretation.votes,
+                )?;
             }
             Ok(0)
         }
@@ -432,3 +720,387 @@ fn interpret_bubble_ballot(
         }
     }
 }
+
+fn interpret_simplex_bubble_ballot(
+    options: &Options,
+    election: Election,
+    expected_ballot_hash: PartialBallotHash,
+    top_image: image::GrayImage,
+) -> color_eyre::Result<i32> {
+    let interpreter = options.build_interpreter(election, expected_ballot_hash);
+    let result = interpreter.interpret_simplex(
+        top_image,
+        options.debug.then(|| options.top_path.clone()).flatten(),
+    );
+
+    match result {
+        Ok(interpretation) => {
+            print_interpretation(options, &[&interpretation.page], &interpretation.votes)?;
+            Ok(0)
+        }
+
+        Err(err) => {
+            eprintln!("Error: {err}");
+            Ok(1)
+        }
+    }
+}
+
+/// Prints the fill score of every bubble on `pages` plus the derived votes,
+/// either as JSON or pretty-printed.
+fn print_interpretation(
+    options: &Options,
+    pages: &[&InterpretedBallotPage],
+    votes: &BallotCardVotes,
+) -> color_eyre::Result<()> {
+    if options.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&interpretation_json(pages, votes))?
+        );
+    } else {
+        for page in pages {
+            for (position, scored_mark) in &page.marks {
+                println!(
+                    "({:05.2}, {:05.2}) ・ {} ・ {} → {}",
+                    position.location().column,
+                    position.location().row,
+                    position.contest_id(),
+                    position.option_id(),
+                    match scored_mark {
+                        Some(m) => m.fill_score.to_string(),
+                        None => "n/a".to_owned(),
+                    }
+                );
+            }
+        }
+        println!();
+        for page in pages {
+            let quality = &page.quality;
+            println!(
+                "{} skew {:.2}°, stretch {:.3}×{:.3}, contrast {}, focus {}, threshold {}",
+                "📐 Quality:".yellow(),
+                quality.skew_degrees,
+                quality.horizontal_stretch,
+                quality.vertical_stretch,
+                quality.contrast,
+                quality.focus,
+                quality.threshold
+            );
+        }
+        println!();
+        pretty_print_ballot_card_votes(votes);
+    }
+    Ok(())
+}
+
+/// Builds the JSON output for a successfully interpreted ballot card: the
+/// fill score of every bubble on `pages`, the derived votes, the image
+/// quality of each page, and each page's area crops and stray marks if they
+/// were requested.
+fn interpretation_json(
+    pages: &[&InterpretedBallotPage],
+    votes: &BallotCardVotes,
+) -> serde_json::Value {
+    let marks: Vec<_> = pages
+        .iter()
+        .flat_map(|page| {
+            page.marks.iter().map(|(position, scored_mark)| {
+                serde_json::json!({
+                    "column": position.location().column,
+                    "row": position.location().row,
+                    "contest_id": position.contest_id(),
+                    "option_id": position.option_id(),
+                    "fill_score": scored_mark.as_ref().map(|m| m.fill_score)
+                })
+            })
+        })
+        .collect();
+    let quality: Vec<_> = pages.iter().map(|page| page.quality).collect();
+    let mut json = serde_json::json!({
+        "marks": marks,
+        "votes": votes,
+        "quality": quality,
+    });
+    if pages.iter().any(|page| page.crops.is_some()) {
+        let crops: Vec<_> = pages.iter().map(|page| &page.crops).collect();
+        json["crops"] = serde_json::json!(crops);
+    }
+    if pages.iter().any(|page| page.stray_marks.is_some()) {
+        let stray_marks: Vec<_> = pages.iter().map(|page| &page.stray_marks).collect();
+        json["stray_marks"] = serde_json::json!(stray_marks);
+    }
+    json
+}
+
+fn pretty_print_ballot_card_votes(votes: &BallotCardVotes) {
+    println!("{}", "🗳️ Votes".yellow());
+    for contest_votes in &votes.contests {
+        println!(
+            "{}{}",
+            " ▸ ".green(),
+            contest_votes.contest_id.to_string().bold()
+        );
+        for option_id in &contest_votes.option_ids {
+            if contest_votes.straight_party_option_ids.contains(option_id) {
+                println!(
+                    "   {} {}",
+                    stringify_vote!(option_id.to_string(), true),
+                    "(straight party)".dark_grey()
+                );
+            } else {
+                println!("   {}", stringify_vote!(option_id.to_string(), true));
+            }
+        }
+        for reason in &contest_votes.adjudication_reasons {
+            println!("   {} {reason:?}", "⚠".yellow());
+        }
+    }
+    if votes.is_blank_ballot {
+        println!("{}", "⚠ Blank ballot".yellow());
+    }
+    if votes.is_ballot_style_inferred {
+        println!(
+            "{}",
+            "⚠ Ballot style inferred from bubble positions".yellow()
+        );
+    }
+}
+
+/// A sheet to interpret in batch mode.
+#[derive(Debug, Deserialize)]
+struct BatchSheet {
+    id: Option<String>,
+    top: PathBuf,
+    bottom: PathBuf,
+}
+
+impl BatchSheet {
+    fn id(&self) -> String {
+        self.id.clone().unwrap_or_else(|| {
+            self.top
+                .file_stem()
+                .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned())
+        })
+    }
+}
+
+/// Finds the sheets to interpret in batch mode, either from a directory of
+/// `<id>-front.<ext>`/`<id>-back.<ext>` image pairs or from a JSONL manifest.
+fn load_batch_sheets(batch_path: &Path) -> color_eyre::Result<Vec<BatchSheet>> {
+    if batch_path.is_dir() {
+        return Ok(common::find_sheet_images(batch_path)?
+            .into_iter()
+            .map(|sheet| BatchSheet {
+                id: Some(sheet.id),
+                top: sheet.front,
+                bottom: sheet.back,
+            })
+            .collect());
+    }
+
+    let base = batch_path.parent().unwrap_or_else(|| Path::new(""));
+    let mut sheets = vec![];
+    for (index, line) in BufReader::new(std::fs::File::open(batch_path)?)
+        .lines()
+        .enumerate()
+    {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut sheet: BatchSheet = serde_json::from_str(&line).map_err(|err| {
+            color_eyre::eyre::eyre!("invalid manifest entry on line {}: {err}", index + 1)
+        })?;
+        sheet.top = base.join(sheet.top);
+        sheet.bottom = base.join(sheet.bottom);
+        sheets.push(sheet);
+    }
+    Ok(sheets)
+}
+
+/// The outcome of interpreting one sheet in batch mode.
+struct BatchResult {
+    line: String,
+    error_type: Option<String>,
+    duration: Duration,
+}
+
+fn interpret_batch_sheet(
+    options: &Options,
+    interpreter: &ScanInterpreter,
+    mark_thresholds: &MarkThresholds,
+    sheet: &BatchSheet,
+) -> color_eyre::Result<BatchResult> {
+    let start = Instant::now();
+    let top_image = image::open(&sheet.top)?;
+    let bottom_image = image::open(&sheet.bottom)?;
+    let report_images = options.debug_report.then(|| {
+        (
+            options.channel_mix.to_gray(top_image.clone()),
+            options.channel_mix.to_gray(bottom_image.clone()),
+        )
+    });
+    let result = interpreter.interpret(
+        top_image,
+        bottom_image,
+        options.debug.then(|| sheet.top.clone()),
+        options.debug.then(|| sheet.bottom.clone()),
+    );
+    let duration = start.elapsed();
+
+    if let Some((top_image, bottom_image)) = report_images {
+        // The report is only a debugging aid, so the rest of the batch
+        // shouldn't be lost if it can't be written.
+        if let Err(err) = write_debug_report(
+            &sheet.top,
+            (&top_image, &bottom_image),
+            mark_thresholds,
+            &result,
+        ) {
+            eprintln!(
+                "Unable to write debug report for {}: {err}",
+                sheet.top.display()
+            );
+        }
+    }
+
+    let (result, error_type) = match result {
+        Ok(interpretation) => (
+            serde_json::json!({ "type": "ok", "value": interpretation_json(
+                &[&interpretation.front, &interpretation.back],
+                &interpretation.votes,
+            ) }),
+            None,
+        ),
+        Err(err) => {
+            let error = serde_json::to_value(&err)?;
+            let error_type = error["type"].as_str().map(ToOwned::to_owned);
+            (
+                serde_json::json!({
+                    "type": "err",
+                    "value": error,
+                    "message": err.to_string(),
+                }),
+                error_type,
+            )
+        }
+    };
+
+    let line = serde_json::to_string(&serde_json::json!({
+        "id": sheet.id(),
+        "top": sheet.top,
+        "bottom": sheet.bottom,
+        "durationMs": duration.as_secs_f64() * 1000.0,
+        "result": result,
+    }))?;
+
+    Ok(BatchResult {
+        line,
+        error_type,
+        duration,
+    })
+}
+
+/// Records a sheet that couldn't be interpreted at all, e.g. because one of
+/// its images couldn't be read, as an error of type `sheetError` so that the
+/// rest of the batch can go on.
+fn failed_batch_sheet(
+    sheet: &BatchSheet,
+    err: &color_eyre::Report,
+    duration: Duration,
+) -> BatchResult {
+    let error_type = "sheetError";
+    let line = serde_json::json!({
+        "id": sheet.id(),
+        "top": sheet.top.display().to_string(),
+        "bottom": sheet.bottom.display().to_string(),
+        "durationMs": duration.as_secs_f64() * 1000.0,
+        "result": {
+            "type": "err",
+            "value": { "type": error_type },
+            "message": err.to_string(),
+        },
+    })
+    .to_string();
+
+    BatchResult {
+        line,
+        error_type: Some(error_type.to_owned()),
+        duration,
+    }
+}
+
+/// Interprets every sheet found at `batch_path` on a single shared
+/// interpreter, writing JSONL results in sheet order and a summary of failures
+/// by error type to stderr. A sheet that can't be interpreted at all is
+/// recorded as failed rather than stopping the batch. Returns a non-zero exit
+/// code if any sheet failed.
+fn interpret_batch(
+    options: &Options,
+    batch_path: &Path,
+    election: Election,
+    expected_ballot_hash: PartialBallotHash,
+) -> color_eyre::Result<i32> {
+    let sheets = load_batch_sheets(batch_path)?;
+    let mark_thresholds = options.mark_thresholds(&election);
+    let interpreter = options.build_interpreter(election, expected_ballot_hash);
+
+    let start = Instant::now();
+    let results = sheets
+        .par_iter()
+        .map(|sheet| {
+            let start = Instant::now();
+            interpret_batch_sheet(options, &interpreter, &mark_thresholds, sheet).unwrap_or_else(
+                |err| {
+                    eprintln!("Error: failed to interpret {}: {err}", sheet.id());
+                    failed_batch_sheet(sheet, &err, start.elapsed())
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    let elapsed = start.elapsed();
+
+    let mut output: Box<dyn Write> = match &options.output {
+        Some(path) => Box::new(BufWriter::new(std::fs::File::create(path)?)),
+        None => Box::new(BufWriter::new(std::io::stdout().lock())),
+    };
+    for result in &results {
+        writeln!(output, "{}", result.line)?;
+    }
+    output.flush()?;
+
+    let mut error_counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for result in &results {
+        if let Some(error_type) = &result.error_type {
+            *error_counts.entry(error_type).or_default() += 1;
+        }
+    }
+    let error_count: usize = error_counts.values().sum();
+
+    let mut durations: Vec<Duration> = results.iter().map(|result| result.duration).collect();
+    durations.sort();
+
+    eprintln!(
+        "{} {} sheets, {} ok, {} failed",
+        "📋 Batch Summary".yellow(),
+        results.len(),
+        results.len() - error_count,
+        error_count
+    );
+    for (error_type, count) in &error_counts {
+        eprintln!("   {} {count}", format!("{error_type}:").bold());
+    }
+    if let (Some(fastest), Some(slowest)) = (durations.first(), durations.last()) {
+        let total: Duration = durations.iter().sum();
+        eprintln!(
+            "   {} min {fastest:.2?}, median {:.2?}, mean {:.2?}, max {slowest:.2?}",
+            "Per sheet:".bold(),
+            durations[durations.len() / 2],
+            u32::try_from(durations.len()).map_or(Duration::ZERO, |count| total / count),
+        );
+    }
+    eprintln!("⚡ {elapsed:.2?}");
+
+    Ok(i32::from(error_count > 0))
+}
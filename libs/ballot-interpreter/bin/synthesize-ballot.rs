@@ -0,0 +1,184 @@
+use std::{path::PathBuf, str::FromStr};
+
+use ballot_interpreter::{
+    ballot_card::BubbleTemplate,
+    synthetic::{self, render_ballot_card, Streak},
+};
+use clap::Parser;
+use color_eyre::eyre::{bail, OptionExt};
+use sha2::{Digest, Sha256};
+use types_rs::{
+    ballot_card::BallotType,
+    bubble_ballot::PartialBallotHash,
+    election::{BallotStyleId, ContestId, Election, OptionId, PrecinctId},
+    geometry::PixelUnit,
+};
+
+/// Renders a ballot card from an election definition, e.g. to test the
+/// interpreter against ballots marked in a known way.
+#[derive(Debug, clap::Parser)]
+struct Options {
+    /// Path to an election definition file.
+    election_path: PathBuf,
+
+    /// The ballot style to render.
+    ballot_style_id: String,
+
+    /// Where to write the image of the front of the card.
+    front_path: PathBuf,
+
+    /// Where to write the image of the back of the card.
+    back_path: PathBuf,
+
+    /// The precinct to encode in the QR codes. Defaults to the first
+    /// precinct of the ballot style.
+    #[clap(long)]
+    precinct_id: Option<String>,
+
+    /// Which sheet of the ballot style to render.
+    #[clap(long, default_value_t = 1)]
+    sheet_number: u32,
+
+    /// Render a test ballot.
+    #[clap(long, default_value = "false")]
+    test_mode: bool,
+
+    /// A bubble to fill in, as "{contest id}:{option id}". Write-in options
+    /// are "write-in-{index}". May be given more than once.
+    #[clap(long = "mark")]
+    marks: Vec<Mark>,
+
+    /// Pixel density of the rendered images.
+    #[clap(long, default_value_t = 200)]
+    pixels_per_inch: PixelUnit,
+
+    /// The bubble to draw: "scanned" for the bubble on our ballots, or
+    /// "{oval,rectangle}-{width}x{height}" in inches.
+    #[clap(long, default_value = "scanned")]
+    bubble_template: BubbleTemplate,
+
+    /// Clockwise rotation of the page in degrees.
+    #[clap(long, default_value_t = 0.0, allow_hyphen_values = true)]
+    skew: f32,
+
+    /// Factor by which to stretch the page vertically.
+    #[clap(long, default_value_t = 1.0)]
+    stretch: f32,
+
+    /// Maximum amount by which to randomly lighten or darken each pixel.
+    #[clap(long, default_value_t = 0)]
+    noise: u8,
+
+    /// A vertical streak to draw, as "{x}:{width}" in pixels. May be given
+    /// more than once.
+    #[clap(long = "streak", value_parser = parse_streak)]
+    streaks: Vec<Streak>,
+
+    /// Seed for the noise.
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Mark {
+    contest_id: ContestId,
+    option_id: OptionId,
+}
+
+impl FromStr for Mark {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (contest_id, option_id) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Expected {{contest id}}:{{option id}}, got: {s}"))?;
+        Ok(Self {
+            contest_id: ContestId::from(contest_id.to_owned()),
+            option_id: OptionId::from(option_id.to_owned()),
+        })
+    }
+}
+
+fn parse_streak(value: &str) -> Result<Streak, String> {
+    let (x, width) = value
+        .split_once(':')
+        .ok_or_else(|| format!("Expected {{x}}:{{width}}, got: {value}"))?;
+    Ok(Streak {
+        x: x.parse()
+            .map_err(|error| format!("Invalid streak x: {error}"))?,
+        width: width
+            .parse()
+            .map_err(|error| format!("Invalid streak width: {error}"))?,
+    })
+}
+
+fn load_election(path: &PathBuf) -> color_eyre::Result<(Election, PartialBallotHash)> {
+    let bytes = std::fs::read(path)?;
+    let election: Election = serde_json::from_slice(&bytes)?;
+    let digest = Sha256::digest(&bytes);
+    let mut hash = PartialBallotHash::default();
+    let len = hash.len();
+    hash.copy_from_slice(&digest[..len]);
+    Ok((election, hash))
+}
+
+fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+    let options = Options::parse();
+    let (election, ballot_hash) = load_election(&options.election_path)?;
+
+    let ballot_style_id = BallotStyleId::from(options.ballot_style_id.clone());
+    let precinct_id = match options.precinct_id.clone() {
+        Some(precinct_id) => PrecinctId::from(precinct_id),
+        None => election
+            .ballot_styles
+            .iter()
+            .find(|ballot_style| ballot_style.id == ballot_style_id)
+            .and_then(|ballot_style| ballot_style.precincts.first().cloned())
+            .ok_or_eyre("ballot style has no precincts")?,
+    };
+
+    for mark in &options.marks {
+        if !election
+            .contests
+            .iter()
+            .any(|contest| contest.id() == &mark.contest_id)
+        {
+            bail!("no contest with ID {}", mark.contest_id);
+        }
+    }
+
+    let card = synthetic::BallotCard {
+        election: &election,
+        ballot_style_id,
+        precinct_id,
+        sheet_number: options.sheet_number,
+        ballot_hash,
+        is_test_mode: options.test_mode,
+        ballot_type: BallotType::Precinct,
+    };
+    let render_options = synthetic::Options {
+        pixels_per_inch: options.pixels_per_inch,
+        bubble_template: options.bubble_template.clone(),
+        skew_degrees: options.skew,
+        vertical_stretch: options.stretch,
+        noise: options.noise,
+        streaks: options.streaks.clone(),
+        seed: options.seed,
+    };
+    let (front, back) = render_ballot_card(
+        &card,
+        |grid_position| {
+            options.marks.iter().any(|mark| {
+                grid_position.contest_id() == mark.contest_id
+                    && grid_position.option_id() == mark.option_id
+            })
+        },
+        &render_options,
+    )?
+    .into();
+
+    front.save(&options.front_path)?;
+    back.save(&options.back_path)?;
+    Ok(())
+}
@@ -3,9 +3,11 @@
 import type {
   BridgeDecodeBmdResult,
   BridgeInterpretResult,
+  BridgeInterpretSummaryBallotResult,
+  ChannelMix,
   TimingMarks,
 } from './src/bubble-ballot-ts/types';
-import type { Election } from '@votingworks/types';
+import type { Election, MarkThresholds } from '@votingworks/types';
 
 export interface BridgeInterpretOptions {
   /**
@@ -19,7 +21,74 @@ export interface BridgeInterpretOptions {
   debugBasePathSideB?: string;
   minimumDetectedScale?: number;
   scoreWriteIns?: boolean;
+  /**
+   * Whether to include perspective-corrected crops of each write-in area and
+   * contest with each interpreted page.
+   */
+  cropAreas?: boolean;
+  /**
+   * Whether to flag contests with ink outside their bubbles and write-in
+   * areas as possible voter intent.
+   */
+  detectStrayMarks?: boolean;
+  /**
+   * Whether to reject ballots with fold lines or non-vertical streaks
+   * crossing bubbles.
+   */
+  detectLineArtifacts?: boolean;
+  /**
+   * Whether to identify the ballot style from where bubbles are printed when
+   * neither QR code can be read, holding the card for adjudication.
+   */
+  inferBallotStyle?: boolean;
   disableVerticalStreakDetection?: boolean;
   maxCumulativeStreakWidth: number;
   retryStreakWidthThreshold: number;
+  /**
+   * Thresholds used to derive votes from bubble scores. Defaults to the
+   * election's thresholds, then to `DEFAULT_MARK_THRESHOLDS`.
+   */
+  markThresholds?: MarkThresholds;
+  /**
+   * Pixel density of the ballot images: 100, 150, 200 or 300. Defaults to
+   * 200. Streak widths are given for a 200 DPI scan and scaled to match.
+   */
+  scanPixelsPerInch?: number;
+  /**
+   * How to combine the channels of color images before thresholding. Defaults
+   * to 'luma'.
+   */
+  channelMix?: ChannelMix;
+  /**
+   * The bubble to look for: 'scanned' (the default) for the bubble on our
+   * ballots, or '{oval,rounded,rectangle}-{width}x{height}' in inches, e.g.
+   * 'oval-0.25x0.17', for ballots with bubbles of a different size or shape.
+   */
+  bubbleTemplate?: string;
+  /**
+   * Where to read ballot metadata from: 'qr-code' (the default), or
+   * 'timing-marks' for AccuVote ballots, which encode it in the bottom row of
+   * timing marks instead of a QR code.
+   */
+  metadataSource?: 'qr-code' | 'timing-marks';
+}
+
+export interface BridgeInterpretSummaryBallotOptions {
+  /**
+   * Expected ballot hash as a hex string, sliced to the partial-hash length
+   * as for `BridgeInterpretOptions`.
+   */
+  expectedBallotHash: string;
+  /**
+   * Whether the scanner is in test mode. Test ballots are only accepted in
+   * test mode, and official ballots only outside it.
+   */
+  isTestMode: boolean;
+  /** The precincts the scanner is configured for. */
+  validPrecinctIds: string[];
+  /**
+   * How to combine the channels of color images before looking for the QR
+   * code. See `BridgeInterpretOptions.channelMix`.
+   */
+  channelMix?: ChannelMix;
 }
@@ -38,6 +38,7 @@ function getExpectedVoteCount(contest: Contest): number {
       return contest.seats;
     case 'yesno': // yes or no
     case 'straight-party':
+    case 'ranked-choice': // at least a first choice
       return 1;
     default:
       /* istanbul ignore next */
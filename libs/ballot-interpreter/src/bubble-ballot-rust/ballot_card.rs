@@ -1,26 +1,44 @@
-use std::{cmp::Ordering, io, mem::swap, ops::Range, path::PathBuf, sync::LazyLock};
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    io,
+    mem::swap,
+    ops::Range,
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, LazyLock, Mutex, PoisonError},
+};
 
 use crate::{
     image_utils::{crop_to_image, otsu_level, threshold},
     qr_code::SearchStrategy,
 };
-use image::{imageops::rotate180_in_place, GrayImage};
+use image::{
+    imageops::{resize, rotate180_in_place, FilterType},
+    GrayImage,
+};
 use itertools::Itertools;
 use serde::Serialize;
+use serde_with::DeserializeFromStr;
 
 use crate::{
+    crops::{crop_areas, AreaCrops},
     debug::ImageDebugWriter,
     image_utils::{
         bleed, detect_vertical_streaks, find_scanned_document_inset, Inset, VerticalStreak, BLACK,
+        WHITE,
     },
     interpret::{BallotPageAndGeometry, Error, Result},
     layout::{build_interpreted_page_layout, InterpretedContestLayout},
+    line_artifacts::{contests_crossed_by_line_artifacts, detect_line_artifacts},
     qr_code,
+    quality::{quality_report, QualityReport},
     scoring::{
         score_bubble_marks_from_grid_layout, score_write_in_areas, ScoredBubbleMarks,
         ScoredPositionAreas, UnitIntervalScore,
     },
-    timing_marks,
+    stray_marks::{detect_stray_marks, ContestStrayMarks},
+    timing_marks::{self, metadata::TimingMarkMetadata, BallotPageMetadata},
 };
 
 use types_rs::{
@@ -231,6 +249,8 @@ pub struct BallotPage {
     label: String,
     ballot_image: BallotImage,
     geometry: Geometry,
+    /// Every search made for this page's QR code so far, in order.
+    qr_code_searches: Mutex<Vec<qr_code::Result>>,
 }
 
 impl BallotPage {
@@ -266,6 +286,7 @@ impl BallotPage {
             label: label.to_owned(),
             ballot_image,
             geometry: paper_info.compute_geometry(),
+            qr_code_searches: Mutex::default(),
         })
     }
 
@@ -307,6 +328,245 @@ impl BallotPage {
         timing_marks::find_timing_mark_grid(&self.ballot_image, &self.geometry, options)
     }
 
+    /// Checks the scale of this page as computed from its timing marks is at
+    /// least a given minimum value.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the computed scale is less than the provided minimum scale.
+    #[allow(clippy::result_large_err)]
+    pub fn check_minimum_scale(
+        &self,
+        timing_marks: &timing_marks::TimingMarks,
+        minimum_scale: UnitIntervalScore,
+    ) -> Result<()> {
+        if let Some(scale) = timing_marks.compute_scale() {
+            if scale < minimum_scale {
+                return Err(Error::InvalidScale {
+                    label: self.label.clone(),
+                    scale,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Detects and decodes the barcode on this page and returns the decoded
+    /// data along with the page orientation implied by the barcode position.
+    /// Searches the corners of the page, or, given the `timing_marks` found on
+    /// this page, only where the grid says the barcode should be.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the barcode cannot be located or decoded, or if the decoded
+    /// ballot hash doesn't match `expected_ballot_hash`.
+    #[allow(clippy::result_large_err)]
+    pub fn decode_ballot_barcode(
+        &self,
+        election: &Election,
+        expected_ballot_hash: &PartialBallotHash,
+        timing_marks: Option<&timing_marks::TimingMarks>,
+    ) -> Result<(bubble_ballot::Metadata, Orientation)> {
+        let image = self.ballot_image().image();
+        let qr_code = match timing_marks {
+            None => {
+                qr_code::detect_with_strategy(image, SearchStrategy::BubbleCorners, self.debug())
+            }
+            Some(timing_marks) => {
+                qr_code::detect_near_timing_marks(image, timing_marks, self.debug())
+            }
+        };
+        self.qr_code_searches
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(qr_code.clone());
+        let qr_code = qr_code.map_err(|e| Error::InvalidQrCodeMetadata {
+            label: self.label().to_owned(),
+            message: e.to_string(),
+            qr_code_searches: self.qr_code_searches(),
+        })?;
+        let metadata = coding::decode_with(qr_code.bytes(), &(election, *expected_ballot_hash))
+            .map_err(|e| match e {
+                bubble_ballot::Error::InvalidBallotHash { expected, actual } => {
+                    Error::InvalidBallotHash { expected, actual }
+                }
+                _ => Error::InvalidQrCodeMetadata {
+                    label: self.label().to_owned(),
+                    message: format!(
+                        "Unable to decode QR code bytes: {e} (bytes={bytes:?})",
+                        bytes = qr_code.bytes()
+                    ),
+                    qr_code_searches: self.qr_code_searches(),
+                },
+            })?;
+        Ok((metadata, qr_code.orientation()))
+    }
+
+    /// Reads and decodes the metadata `AccuVote` ballots encode in the bottom
+    /// row of `timing_marks` found on this page, and returns it along with
+    /// the page orientation implied by which row it was read from.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the bottom row can't be read or doesn't decode.
+    #[allow(clippy::result_large_err)]
+    pub fn decode_timing_mark_metadata(
+        &self,
+        timing_marks: &timing_marks::TimingMarks,
+    ) -> Result<(TimingMarkMetadata, Orientation)> {
+        TimingMarkMetadata::read(self.ballot_image(), timing_marks).map_err(|message| {
+            Error::InvalidTimingMarkMetadata {
+                label: self.label().to_owned(),
+                message,
+            }
+        })
+    }
+
+    /// Scores the bubble marks on this page, which is `side` of sheet
+    /// `sheet_number`.
+    ///
+    /// # Errors
+    ///
+    /// If the bubbles cannot be properly scored due to streaks intersecting
+    /// with them, an error will be returned.
+    #[allow(clippy::result_large_err)]
+    pub fn score_bubble_marks(
+        &self,
+        timing_marks: &timing_marks::TimingMarks,
+        bubble_template: &GrayImage,
+        grid_layout: &GridLayout,
+        detected_vertical_streaks: &[VerticalStreak],
+        sheet_number: u32,
+        side: BallotSide,
+    ) -> Result<ScoredBubbleMarks> {
+        score_bubble_marks_from_grid_layout(
+            self.ballot_image(),
+            self.label(),
+            bubble_template,
+            timing_marks,
+            grid_layout,
+            detected_vertical_streaks,
+            sheet_number,
+            side,
+        )
+    }
+
+    /// Scores the write-in areas on this page in order to detect unmarked
+    /// write-ins.
+    #[must_use]
+    pub fn score_write_in_areas(
+        &self,
+        timing_marks: &timing_marks::TimingMarks,
+        grid_layout: &GridLayout,
+        sheet_number: u32,
+        side: BallotSide,
+    ) -> ScoredPositionAreas {
+        score_write_in_areas(
+            self.ballot_image(),
+            timing_marks,
+            grid_layout,
+            sheet_number,
+            side,
+        )
+    }
+
+    /// Crops the write-in areas and contests on this page.
+    #[must_use]
+    pub fn crop_areas(
+        &self,
+        timing_marks: &timing_marks::TimingMarks,
+        grid_layout: &GridLayout,
+        sheet_number: u32,
+        side: BallotSide,
+    ) -> AreaCrops {
+        crop_areas(
+            self.ballot_image(),
+            timing_marks,
+            grid_layout,
+            sheet_number,
+            side,
+        )
+    }
+
+    /// Measures the image quality of this page from its timing marks.
+    #[must_use]
+    pub fn quality_report(&self, timing_marks: &timing_marks::TimingMarks) -> QualityReport {
+        quality_report(self.ballot_image(), self.geometry(), timing_marks)
+    }
+
+    /// Finds ink within each contest on this page that is not on a bubble or
+    /// in a write-in area, as a possible indication of voter intent.
+    #[must_use]
+    pub fn detect_stray_marks(
+        &self,
+        timing_marks: &timing_marks::TimingMarks,
+        contest_layouts: &[InterpretedContestLayout],
+        marks: &ScoredBubbleMarks,
+    ) -> Vec<ContestStrayMarks> {
+        detect_stray_marks(
+            self.ballot_image(),
+            self.geometry(),
+            timing_marks,
+            contest_layouts,
+            marks,
+        )
+    }
+
+    /// Looks for fold lines and streaks other than vertical ones on this page.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any of them cross a bubble, listing the affected contests.
+    #[allow(clippy::result_large_err)]
+    pub fn reject_line_artifacts_through_bubbles(
+        &self,
+        timing_marks: &timing_marks::TimingMarks,
+        marks: &ScoredBubbleMarks,
+    ) -> Result<()> {
+        let bubble_bounds = marks
+            .iter()
+            .filter_map(|(_, scored_bubble_mark)| {
+                scored_bubble_mark
+                    .as_ref()
+                    .map(|scored_bubble_mark| scored_bubble_mark.expected_bounds)
+            })
+            .collect_vec();
+        let artifacts = detect_line_artifacts(
+            self.ballot_image(),
+            self.geometry(),
+            timing_marks,
+            &bubble_bounds,
+        );
+        let contest_ids = contests_crossed_by_line_artifacts(&artifacts, marks);
+        if contest_ids.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::LineArtifactsDetected {
+                label: self.label.clone(),
+                contest_ids,
+            })
+        }
+    }
+
+    /// Determines the bounds of all contest options on this page based on
+    /// its timing marks.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the contest layout cannot be determined.
+    #[allow(clippy::result_large_err)]
+    pub fn build_page_layout(
+        &self,
+        timing_marks: &timing_marks::TimingMarks,
+        grid_layout: &GridLayout,
+        sheet_number: u32,
+        side: BallotSide,
+    ) -> Result<Vec<InterpretedContestLayout>> {
+        build_interpreted_page_layout(timing_marks, grid_layout, sheet_number, side, self.debug())
+            .ok_or(Error::CouldNotComputeLayout { side })
+    }
+
     /// Gets the ballot geometry information for this page.
     pub fn geometry(&self) -> &Geometry {
         &self.geometry
@@ -346,9 +606,31 @@ impl BallotPage {
         self.ballot_image.height()
     }
 
-    /// Rotates the underlying ballot image.
+    /// Gets every search made for this page's QR code so far by
+    /// [`Self::decode_ballot_barcode`], in order.
+    #[must_use]
+    pub fn qr_code_searches(&self) -> Vec<qr_code::Result> {
+        self.qr_code_searches
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Rotates the underlying ballot image, and where its QR code was
+    /// searched for along with it.
     pub fn rotate180(&mut self) {
+        let image_size = self.dimensions().into();
         self.ballot_image.rotate180();
+        for search in self
+            .qr_code_searches
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            match search {
+                Ok(detected) => detected.rotate180(image_size),
+                Err(error) => error.rotate180(image_size),
+            }
+        }
     }
 }
 
@@ -481,16 +763,7 @@ impl BallotCard {
         self.as_pair()
             .zip(timing_marks)
             .map(|(ballot_page, timing_marks)| {
-                if let Some(scale) = timing_marks.compute_scale() {
-                    if scale < minimum_scale {
-                        return Err(Error::InvalidScale {
-                            label: ballot_page.label().to_owned(),
-                            scale,
-                        });
-                    }
-                }
-
-                Ok(())
+                ballot_page.check_minimum_scale(timing_marks, minimum_scale)
             })
             .into_result()?;
         Ok(())
@@ -512,34 +785,36 @@ impl BallotCard {
         &self,
         election: &Election,
         expected_ballot_hash: &PartialBallotHash,
+        timing_marks: Option<&Pair<timing_marks::TimingMarks>>,
     ) -> Result<Pair<(bubble_ballot::Metadata, Orientation)>> {
-        self.as_pair()
-            .par_map(|ballot_page| {
-                let qr_code = qr_code::detect_with_strategy(
-                    ballot_page.ballot_image().image(),
-                    SearchStrategy::BubbleCorners,
-                    ballot_page.debug(),
-                )
-                .map_err(|e| Error::InvalidQrCodeMetadata {
-                    label: ballot_page.label().to_owned(),
-                    message: e.to_string(),
-                })?;
-                let metadata =
-                    coding::decode_with(qr_code.bytes(), &(election, *expected_ballot_hash))
-                        .map_err(|e| match e {
-                            bubble_ballot::Error::InvalidBallotHash { expected, actual } => {
-                                Error::InvalidBallotHash { expected, actual }
-                            }
-                            _ => Error::InvalidQrCodeMetadata {
-                                label: ballot_page.label().to_owned(),
-                                message: format!(
-                                    "Unable to decode QR code bytes: {e} (bytes={bytes:?})",
-                                    bytes = qr_code.bytes()
-                                ),
-                            },
-                        })?;
-                Ok((metadata, qr_code.orientation()))
-            })
+        let timing_marks = match timing_marks {
+            Some(timing_marks) => Pair::from(timing_marks).map(Some),
+            None => Pair::new(None, None),
+        };
+        let decoded = self
+            .as_pair()
+            .zip(timing_marks)
+            .par_map(|(ballot_page, timing_marks)| {
+                ballot_page.decode_ballot_barcode(election, expected_ballot_hash, timing_marks)
+            });
+        Self::combine_decoded_ballot_barcodes(decoded, expected_ballot_hash)
+    }
+
+    /// Settles on the metadata for both pages given what decoding each page's
+    /// barcode with [`BallotPage::decode_ballot_barcode`] gave, as described
+    /// for [`Self::decode_ballot_barcodes`]. A page whose barcode couldn't be
+    /// read takes its metadata from the other page.
+    ///
+    /// # Errors
+    ///
+    /// Fails if neither barcode could be read, if either page failed for
+    /// another reason, or if the two sides' metadata disagree.
+    #[allow(clippy::result_large_err)]
+    pub fn combine_decoded_ballot_barcodes(
+        decoded: Pair<Result<(bubble_ballot::Metadata, Orientation)>>,
+        expected_ballot_hash: &PartialBallotHash,
+    ) -> Result<Pair<(bubble_ballot::Metadata, Orientation)>> {
+        decoded
             .join(|decode_front_result, decode_back_result| {
                 // If one side has a detected QR code and the other doesn't, we can
                 // infer the missing metadata from the detected metadata.
@@ -601,6 +876,48 @@ impl BallotCard {
             )
     }
 
+    /// Reads and decodes the metadata `AccuVote` ballots encode in the bottom
+    /// row of timing marks on each page, and maps it to the election. Returns
+    /// the metadata for each page along with its orientation.
+    ///
+    /// # Errors
+    ///
+    /// Fails if either bottom row can't be read or decoded, if the pages
+    /// aren't the front and back of one card, or if the card's precinct or
+    /// ballot style isn't in the election.
+    #[allow(clippy::result_large_err)]
+    pub fn decode_timing_mark_metadata(
+        &self,
+        election: &Election,
+        timing_marks: &Pair<timing_marks::TimingMarks>,
+    ) -> Result<Pair<(BallotPageMetadata, Orientation)>> {
+        let decoded = self
+            .as_pair()
+            .zip(timing_marks)
+            .par_map(|(ballot_page, timing_marks)| {
+                ballot_page.decode_timing_mark_metadata(timing_marks)
+            })
+            .into_result()?;
+        let orientations = Pair::from(&decoded).map(|(_, orientation)| *orientation);
+        let decoded = decoded.map(|(decoded, _)| decoded);
+        let metadata = timing_marks::metadata::map_to_election(election, Pair::from(&decoded))
+            .map_err(|message| Error::InvalidTimingMarkMetadata {
+                label: self.as_pair().first().label().to_owned(),
+                message,
+            })?;
+        Ok(metadata.zip(decoded).zip(orientations).map(
+            |((metadata, timing_mark_metadata), orientation)| {
+                (
+                    BallotPageMetadata::TimingMarks {
+                        metadata,
+                        timing_mark_metadata,
+                    },
+                    orientation,
+                )
+            },
+        ))
+    }
+
     /// Scores bubble marks on both ballot pages.
     ///
     /// # Errors
@@ -622,11 +939,9 @@ impl BallotCard {
             .zip((BallotSide::Front, BallotSide::Back))
             .par_map(
                 |(((ballot_page, timing_marks), detected_vertical_streaks), side)| {
-                    score_bubble_marks_from_grid_layout(
-                        ballot_page.ballot_image(),
-                        ballot_page.label(),
-                        bubble_template,
+                    ballot_page.score_bubble_marks(
                         timing_marks,
+                        bubble_template,
                         grid_layout,
                         detected_vertical_streaks,
                         sheet_number,
@@ -648,13 +963,7 @@ impl BallotCard {
             .zip(timing_marks)
             .zip((BallotSide::Front, BallotSide::Back))
             .par_map(|((ballot_page, timing_marks), side)| {
-                score_write_in_areas(
-                    ballot_page.ballot_image(),
-                    timing_marks,
-                    grid_layout,
-                    sheet_number,
-                    side,
-                )
+                ballot_page.score_write_in_areas(timing_marks, grid_layout, sheet_number, side)
             })
     }
 
@@ -675,14 +984,7 @@ impl BallotCard {
             .zip(timing_marks)
             .zip((BallotSide::Front, BallotSide::Back))
             .map(|((ballot_page, timing_marks), side)| {
-                build_interpreted_page_layout(
-                    timing_marks,
-                    grid_layout,
-                    sheet_number,
-                    side,
-                    ballot_page.debug(),
-                )
-                .ok_or(Error::CouldNotComputeLayout { side })
+                ballot_page.build_page_layout(timing_marks, grid_layout, sheet_number, side)
             })
             .into_result()
     }
@@ -774,8 +1076,14 @@ impl Geometry {
     }
 }
 
-/// Expected PPI for scanned ballot cards.
-const SCAN_PIXELS_PER_INCH: PixelUnit = 200;
+/// Expected PPI for scanned ballot cards. The bubble template and other
+/// pixel-based defaults are defined at this density.
+pub const SCAN_PIXELS_PER_INCH: PixelUnit = 200;
+
+/// Scan densities the interpreter can work with. 200 is the native density of
+/// our scanners, 100 is what they produce at half native resolution, and the
+/// others are common for archival scans and alternate scanners.
+pub const SUPPORTED_SCAN_PIXELS_PER_INCH: [PixelUnit; 4] = [100, 150, SCAN_PIXELS_PER_INCH, 300];
 
 /// Scanned margins for the front and back of the ballot card in inches.
 /// Include 5mm margins by default to create room for an imprinting ID.
@@ -859,17 +1167,45 @@ impl PaperInfo {
         }
     }
 
-    /// Returns info for all supported scanned paper sizes.
-    pub const fn scanned() -> [Self; 7] {
-        [
-            Self::scanned_letter(),
-            Self::scanned_legal(),
-            Self::scanned_custom17(),
-            Self::scanned_custom18(),
-            Self::scanned_custom19(),
-            Self::scanned_custom20(),
-            Self::scanned_custom22(),
-        ]
+    pub const fn scanned_a4() -> Self {
+        Self::scanned_with_size(PaperSize::A4)
+    }
+
+    /// Returns info for a scanned ballot card of any size.
+    pub const fn scanned_with_size(size: PaperSize) -> Self {
+        Self {
+            size,
+            margins: BALLOT_CARD_SCAN_MARGINS,
+            pixels_per_inch: SCAN_PIXELS_PER_INCH,
+        }
+    }
+
+    /// Returns info for all standard scanned paper sizes.
+    pub fn scanned() -> [Self; PaperSize::STANDARD.len()] {
+        PaperSize::STANDARD.map(Self::scanned_with_size)
+    }
+
+    /// Returns info for each of `paper_sizes` scanned at each of
+    /// `pixels_per_inch`. Paper widths at the supported densities are far
+    /// enough apart that at most one density matches a given image.
+    #[must_use]
+    pub fn scanned_at(paper_sizes: &[PaperSize], pixels_per_inch: &[PixelUnit]) -> Vec<Self> {
+        pixels_per_inch
+            .iter()
+            .flat_map(|&pixels_per_inch| {
+                paper_sizes.iter().map(move |&size| {
+                    Self::scanned_with_size(size).with_pixels_per_inch(pixels_per_inch)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns a copy of this paper info at a different scan density.
+    pub const fn with_pixels_per_inch(self, pixels_per_inch: PixelUnit) -> Self {
+        Self {
+            pixels_per_inch,
+            ..self
+        }
     }
 
     pub fn compute_geometry(&self) -> Geometry {
@@ -886,12 +1222,14 @@ impl PaperInfo {
             content_area_height.pixels(pixels_per_inch).round() as PixelUnit,
         );
         // Corresponds to the NH Accuvote ballot grid, which we also use in VX
-        // ballots. This formula is also defined in libs/hmpb.
+        // ballots. This formula is also defined in libs/hmpb. Sizes that
+        // aren't a whole number of grid units (e.g. A4) get as many timing
+        // marks as fit, with the spacing stretched to fill the content area.
         let columns_per_inch = 4.0;
         let rows_per_inch = 4.0;
         let grid_size = Size {
-            width: (columns_per_inch * canvas_size.width.get()) as GridUnit,
-            height: (rows_per_inch * canvas_size.height.get()) as GridUnit - 3,
+            width: (columns_per_inch * canvas_size.width.get()).floor() as GridUnit,
+            height: (rows_per_inch * canvas_size.height.get()).floor() as GridUnit - 3,
         };
 
         let timing_mark_vertical_spacing = (content_area_height
@@ -970,17 +1308,185 @@ pub fn get_matching_paper_info_for_image_size(
 /// build-time bug, not a runtime condition.
 #[must_use]
 pub fn ballot_scan_bubble_image() -> &'static GrayImage {
-    static TEMPLATE: LazyLock<GrayImage> = LazyLock::new(|| {
+    embedded_bubble_template()
+}
+
+fn embedded_bubble_template() -> &'static Arc<GrayImage> {
+    static TEMPLATE: LazyLock<Arc<GrayImage>> = LazyLock::new(|| {
         let bubble_image_bytes = include_bytes!("../../data/bubble_scan.png");
         let inner = io::Cursor::new(bubble_image_bytes);
         let image = image::load(inner, image::ImageFormat::Png)
             .map(|image| image.to_luma8())
             .expect("decoding embedded bubble_scan.png always succeeds");
-        bleed(&threshold(&image, otsu_level(&image)), BLACK)
+        Arc::new(binarize_bubble_image(&image))
     });
     &TEMPLATE
 }
 
+fn binarize_bubble_image(image: &GrayImage) -> GrayImage {
+    bleed(&threshold(image, otsu_level(image)), BLACK)
+}
+
+/// Width of the outline of a bubble drawn by [`BubbleTemplate::Drawn`]. Like
+/// the bled outline of the embedded template, this is thicker than the printed
+/// outline so that scanner blur around it doesn't count as a mark.
+const DRAWN_BUBBLE_OUTLINE_INCHES: f32 = 0.015;
+
+/// The shape of a bubble drawn from its dimensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BubbleShape {
+    /// An ellipse touching all four sides.
+    Oval,
+
+    /// A rectangle whose short sides are semicircles, like the bubbles on our
+    /// ballots.
+    Rounded,
+
+    /// A rectangle with square corners.
+    Rectangle,
+}
+
+/// What a blank bubble looks like, used both to locate bubbles in the scan
+/// and to score how much of them has been filled in. Parsed from `scanned`
+/// for the embedded template, or `{shape}-{width}x{height}` (in inches, e.g.
+/// `oval-0.25x0.17`, `rounded-0.2x0.14` or `rectangle-0.3x0.15`) for a bubble
+/// drawn to size.
+#[derive(Debug, Clone, DeserializeFromStr)]
+pub enum BubbleTemplate {
+    /// A binarized image of a blank bubble at [`SCAN_PIXELS_PER_INCH`],
+    /// resized to match the density of each scan.
+    Image(Arc<GrayImage>),
+
+    /// The outline of a bubble with the given outer dimensions, drawn at the
+    /// density of each scan.
+    Drawn {
+        shape: BubbleShape,
+        size: Size<Inch>,
+    },
+}
+
+impl Default for BubbleTemplate {
+    /// The template cropped from a scan of a blank bubble on our ballots.
+    fn default() -> Self {
+        Self::Image(Arc::clone(embedded_bubble_template()))
+    }
+}
+
+impl BubbleTemplate {
+    /// Builds a template from an image of a blank bubble scanned at
+    /// [`SCAN_PIXELS_PER_INCH`], cropped closely around its outline.
+    #[must_use]
+    pub fn from_image(image: &GrayImage) -> Self {
+        Self::Image(Arc::new(binarize_bubble_image(image)))
+    }
+
+    /// Gets the template image to match against scans at `pixels_per_inch`.
+    #[must_use]
+    pub fn at_pixels_per_inch(&self, pixels_per_inch: PixelUnit) -> Cow<'_, GrayImage> {
+        match self {
+            Self::Image(image) => scale_bubble_template(image, pixels_per_inch),
+            Self::Drawn { shape, size } => {
+                Cow::Owned(draw_bubble_template(*shape, *size, pixels_per_inch))
+            }
+        }
+    }
+}
+
+impl FromStr for BubbleTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "scanned" {
+            return Ok(Self::default());
+        }
+
+        let invalid = || format!("Unexpected bubble template setting: {s}");
+        let (shape, dimensions) = s.split_once('-').ok_or_else(invalid)?;
+        let shape = match shape {
+            "oval" => BubbleShape::Oval,
+            "rounded" => BubbleShape::Rounded,
+            "rectangle" => BubbleShape::Rectangle,
+            _ => return Err(invalid()),
+        };
+        let (width, height) = dimensions.split_once('x').ok_or_else(invalid)?;
+        let parse_inches = |value: &str| match value.parse::<f32>() {
+            Ok(inches) if inches.is_finite() && inches > 0.0 => Ok(Inch::new(inches)),
+            _ => Err(invalid()),
+        };
+
+        Ok(Self::Drawn {
+            shape,
+            size: Size {
+                width: parse_inches(width)?,
+                height: parse_inches(height)?,
+            },
+        })
+    }
+}
+
+/// Draws the outline of a blank bubble of the given outer `size` at
+/// `pixels_per_inch`, filling the whole template image.
+fn draw_bubble_template(
+    shape: BubbleShape,
+    size: Size<Inch>,
+    pixels_per_inch: PixelUnit,
+) -> GrayImage {
+    let width = size.width.pixels(pixels_per_inch).round().max(1.0);
+    let height = size.height.pixels(pixels_per_inch).round().max(1.0);
+    let outline = (DRAWN_BUBBLE_OUTLINE_INCHES * pixels_per_inch as f32).max(1.0);
+    let (half_width, half_height) = (width / 2.0, height / 2.0);
+
+    // Whether the point (`dx`, `dy`) from the center is within the shape
+    // shrunk by `inset` on every side.
+    let within = |dx: f32, dy: f32, inset: f32| {
+        let (a, b) = (half_width - inset, half_height - inset);
+        if a <= 0.0 || b <= 0.0 {
+            return false;
+        }
+
+        let (dx, dy) = (dx.abs(), dy.abs());
+        match shape {
+            BubbleShape::Oval => (dx / a).powi(2) + (dy / b).powi(2) <= 1.0,
+            BubbleShape::Rounded => {
+                let radius = a.min(b);
+                // Distance past the centers of the corner arcs.
+                let (past_x, past_y) = ((dx - (a - radius)).max(0.0), (dy - (b - radius)).max(0.0));
+                dx <= a && dy <= b && past_x.hypot(past_y) <= radius
+            }
+            BubbleShape::Rectangle => dx <= a && dy <= b,
+        }
+    };
+
+    GrayImage::from_fn(width as u32, height as u32, |x, y| {
+        let dx = x as f32 + 0.5 - half_width;
+        let dy = y as f32 + 0.5 - half_height;
+        if within(dx, dy, 0.0) && !within(dx, dy, outline) {
+            BLACK
+        } else {
+            WHITE
+        }
+    })
+}
+
+/// Scales a bubble template defined at [`SCAN_PIXELS_PER_INCH`] to match
+/// images scanned at `pixels_per_inch`. The template is binarized again after
+/// resizing so that it stays purely black and white.
+#[must_use]
+pub fn scale_bubble_template(
+    bubble_template: &GrayImage,
+    pixels_per_inch: PixelUnit,
+) -> Cow<'_, GrayImage> {
+    if pixels_per_inch == SCAN_PIXELS_PER_INCH {
+        return Cow::Borrowed(bubble_template);
+    }
+
+    let scale = pixels_per_inch as f32 / SCAN_PIXELS_PER_INCH as f32;
+    let width = ((bubble_template.width() as f32 * scale).round() as u32).max(1);
+    let height = ((bubble_template.height() as f32 * scale).round() as u32).max(1);
+    let resized = resize(bubble_template, width, height, FilterType::Triangle);
+    Cow::Owned(threshold(&resized, u8::MAX / 2))
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -1022,6 +1528,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_scanned_ballot_card_geometry_at_other_densities() {
+        let paper_infos =
+            PaperInfo::scanned_at(&PaperSize::STANDARD, &SUPPORTED_SCAN_PIXELS_PER_INCH);
+        assert_eq!(
+            get_matching_paper_info_for_image_size((848, 1100), &paper_infos),
+            Some(PaperInfo::scanned_letter().with_pixels_per_inch(100))
+        );
+        assert_eq!(
+            get_matching_paper_info_for_image_size((1272, 2100), &paper_infos),
+            Some(PaperInfo::scanned_legal().with_pixels_per_inch(150))
+        );
+        assert_eq!(
+            get_matching_paper_info_for_image_size((1696, 2200), &paper_infos),
+            Some(PaperInfo::scanned_letter())
+        );
+        assert_eq!(
+            get_matching_paper_info_for_image_size((2544, 3300), &paper_infos),
+            Some(PaperInfo::scanned_letter().with_pixels_per_inch(300))
+        );
+        assert_eq!(
+            get_matching_paper_info_for_image_size((1272, 2100), &PaperInfo::scanned()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_scale_bubble_template() {
+        let template = ballot_scan_bubble_image();
+        assert!(matches!(
+            scale_bubble_template(template, SCAN_PIXELS_PER_INCH),
+            Cow::Borrowed(_)
+        ));
+
+        let scaled = scale_bubble_template(template, 100);
+        assert_eq!(
+            scaled.dimensions(),
+            (template.width().div_ceil(2), template.height().div_ceil(2))
+        );
+        assert!(scaled
+            .pixels()
+            .all(|pixel| pixel.0 == [0] || pixel.0 == [255]));
+    }
+
     #[test]
     fn test_compute_geometry() {
         let allowed_spacing_error = Inch::new(0.06);
@@ -1050,6 +1600,166 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_compute_geometry_a4_and_custom_sizes() {
+        assert_eq!(
+            PaperInfo::scanned_a4().compute_geometry().grid_size,
+            Size {
+                width: 33,
+                height: 43
+            },
+        );
+
+        let custom_8_5_x_16 =
+            PaperInfo::scanned_with_size("custom-8.5x16".parse::<PaperSize>().unwrap());
+        assert_eq!(
+            custom_8_5_x_16.compute_geometry().grid_size,
+            Size {
+                width: 34,
+                height: 61
+            },
+        );
+
+        let custom_11_x_17 =
+            PaperInfo::scanned_with_size("custom-11x17".parse::<PaperSize>().unwrap());
+        assert_eq!(
+            custom_11_x_17.compute_geometry().grid_size,
+            Size {
+                width: 44,
+                height: 65
+            },
+        );
+    }
+
+    #[test]
+    fn test_get_scanned_ballot_card_geometry_a4_and_custom_sizes() {
+        // A4 is only a candidate when asked for, since it would otherwise
+        // match letter ballots stretched by the scanner.
+        assert_eq!(
+            get_matching_paper_info_for_image_size((1700, 2310), &PaperInfo::scanned()),
+            Some(PaperInfo::scanned_letter())
+        );
+        let mut paper_sizes = PaperSize::STANDARD.to_vec();
+        paper_sizes.push(PaperSize::A4);
+        let paper_infos = PaperInfo::scanned_at(&paper_sizes, &[SCAN_PIXELS_PER_INCH]);
+        assert_eq!(
+            get_matching_paper_info_for_image_size((1654, 2339), &paper_infos),
+            Some(PaperInfo::scanned_a4())
+        );
+
+        // Without the custom size, an 8.5x16 ballot is closest to legal.
+        let custom_8_5_x_16 = "custom-8.5x16".parse::<PaperSize>().unwrap();
+        assert_eq!(
+            get_matching_paper_info_for_image_size((1696, 3200), &PaperInfo::scanned()),
+            Some(PaperInfo::scanned_legal())
+        );
+        let mut paper_sizes = PaperSize::STANDARD.to_vec();
+        paper_sizes.push(custom_8_5_x_16);
+        assert_eq!(
+            get_matching_paper_info_for_image_size(
+                (1696, 3200),
+                &PaperInfo::scanned_at(&paper_sizes, &[SCAN_PIXELS_PER_INCH])
+            ),
+            Some(PaperInfo::scanned_with_size(custom_8_5_x_16))
+        );
+    }
+
+    #[test]
+    fn test_bubble_template_from_str() {
+        assert!(matches!(
+            "scanned".parse::<BubbleTemplate>().unwrap(),
+            BubbleTemplate::Image(image) if Arc::ptr_eq(&image, embedded_bubble_template())
+        ));
+        assert!(matches!(
+            "oval-0.25x0.17".parse::<BubbleTemplate>().unwrap(),
+            BubbleTemplate::Drawn { shape: BubbleShape::Oval, size }
+                if size == Size { width: Inch::new(0.25), height: Inch::new(0.17) }
+        ));
+        assert!(matches!(
+            "rectangle-0.3x0.15".parse::<BubbleTemplate>().unwrap(),
+            BubbleTemplate::Drawn {
+                shape: BubbleShape::Rectangle,
+                ..
+            }
+        ));
+        assert!(matches!(
+            "rounded-0.2x0.14".parse::<BubbleTemplate>().unwrap(),
+            BubbleTemplate::Drawn {
+                shape: BubbleShape::Rounded,
+                ..
+            }
+        ));
+        for invalid in [
+            "",
+            "oval",
+            "oval-0.25",
+            "circle-0.2x0.2",
+            "oval-0x0.2",
+            "oval-ax0.2",
+        ] {
+            assert_eq!(
+                invalid.parse::<BubbleTemplate>().unwrap_err(),
+                format!("Unexpected bubble template setting: {invalid}")
+            );
+        }
+    }
+
+    #[test]
+    fn test_drawn_bubble_template() {
+        let template: BubbleTemplate = "oval-0.2x0.13".parse().unwrap();
+        let at_200 = template.at_pixels_per_inch(200);
+        assert_eq!(at_200.dimensions(), (40, 26));
+        assert_eq!(template.at_pixels_per_inch(100).dimensions(), (20, 13));
+        assert!(at_200
+            .pixels()
+            .all(|pixel| pixel.0 == [0] || pixel.0 == [255]));
+
+        // The outline runs through the middle of each side, leaving the inside
+        // of the bubble blank.
+        assert_eq!(*at_200.get_pixel(0, 13), BLACK);
+        assert_eq!(*at_200.get_pixel(39, 13), BLACK);
+        assert_eq!(*at_200.get_pixel(20, 0), BLACK);
+        assert_eq!(*at_200.get_pixel(20, 25), BLACK);
+        assert_eq!(*at_200.get_pixel(20, 13), WHITE);
+
+        // Only a rectangle's outline reaches into the corners.
+        for (shape, corner_is_outline) in [("oval", false), ("rounded", false), ("rectangle", true)]
+        {
+            let template: BubbleTemplate = format!("{shape}-0.2x0.13").parse().unwrap();
+            let image = template.at_pixels_per_inch(200);
+            assert_eq!(*image.get_pixel(0, 13), BLACK, "shape: {shape}");
+            assert_eq!(
+                *image.get_pixel(0, 0) == BLACK,
+                corner_is_outline,
+                "shape: {shape}"
+            );
+        }
+
+        // A rounded bubble's long sides are straight, unlike an oval's.
+        let rounded: BubbleTemplate = "rounded-0.2x0.13".parse().unwrap();
+        assert_eq!(*rounded.at_pixels_per_inch(200).get_pixel(13, 0), BLACK);
+        assert_eq!(*at_200.get_pixel(13, 0), WHITE);
+    }
+
+    #[test]
+    fn test_bubble_template_from_image() {
+        // A faint gray outline on off-white paper is binarized and thickened
+        // the same way as the embedded template.
+        let image = GrayImage::from_fn(10, 8, |x, y| {
+            if x == 0 || y == 0 || x == 9 || y == 7 {
+                image::Luma([90])
+            } else {
+                image::Luma([210])
+            }
+        });
+        let template = BubbleTemplate::from_image(&image);
+        let template = template.at_pixels_per_inch(SCAN_PIXELS_PER_INCH);
+        assert_eq!(template.dimensions(), (10, 8));
+        assert_eq!(*template.get_pixel(0, 0), BLACK);
+        assert_eq!(*template.get_pixel(1, 1), BLACK);
+        assert_eq!(*template.get_pixel(5, 4), WHITE);
+    }
+
     #[test]
     fn test_load_bubble_template() {
         let _ = ballot_scan_bubble_image();
@@ -0,0 +1,283 @@
+//! Recommends mark thresholds from the fill scores of a corpus of scanned
+//! bubbles.
+//!
+//! On any given election and paper stock, the fill scores of blank bubbles
+//! cluster near zero and those of filled bubbles cluster near the template's
+//! blank-paper fraction (see [`ScoredBubbleMark::fill_score`]). [`calibrate`]
+//! separates the two clusters with Otsu's method, fits a normal distribution
+//! to each, and places the thresholds between them. The corpus is unlabeled,
+//! so the error rates it reports are estimated from the fitted distributions
+//! rather than counted.
+//!
+//! [`ScoredBubbleMark::fill_score`]: crate::scoring::ScoredBubbleMark::fill_score
+
+use serde::Serialize;
+
+use crate::{image_utils::otsu_threshold_index, scoring::UnitIntervalScore};
+
+/// Number of histogram bins used to find the boundary between blank and
+/// marked bubbles, i.e. a resolution of 0.1% fill.
+pub const HISTOGRAM_BINS: usize = 1000;
+
+/// How many standard deviations above the mean blank score the marginal
+/// threshold is placed, so that few blank bubbles are flagged.
+const MARGINAL_STANDARD_DEVIATIONS: f32 = 3.0;
+
+/// Counts `scores` into [`HISTOGRAM_BINS`] equal bins spanning 0% to 100%.
+#[must_use]
+pub fn fill_score_histogram(scores: &[UnitIntervalScore]) -> Vec<u32> {
+    let mut histogram = vec![0; HISTOGRAM_BINS];
+    for score in scores {
+        histogram[bin_index(*score)] += 1;
+    }
+    histogram
+}
+
+fn bin_index(UnitIntervalScore(score): UnitIntervalScore) -> usize {
+    ((score.clamp(0.0, 1.0) * HISTOGRAM_BINS as f32) as usize).min(HISTOGRAM_BINS - 1)
+}
+
+/// A normal distribution fit to the fill scores of one class of bubbles.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreDistribution {
+    pub count: usize,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub std_dev: f32,
+}
+
+impl ScoreDistribution {
+    fn fit(scores: impl Iterator<Item = f32> + Clone) -> Option<Self> {
+        let count = scores.clone().count();
+        if count == 0 {
+            return None;
+        }
+        let min = scores.clone().fold(f32::INFINITY, f32::min);
+        let max = scores.clone().fold(f32::NEG_INFINITY, f32::max);
+        let mean = scores.clone().sum::<f32>() / count as f32;
+        let variance = scores.map(|score| (score - mean).powi(2)).sum::<f32>() / count as f32;
+        Some(Self {
+            count,
+            min,
+            max,
+            mean,
+            std_dev: variance.sqrt(),
+        })
+    }
+
+    /// Estimates the fraction of this class scoring below `score`.
+    #[must_use]
+    pub fn fraction_below(&self, score: f32) -> f32 {
+        if self.std_dev == 0.0 {
+            return if self.mean < score { 1.0 } else { 0.0 };
+        }
+        normal_cdf((score - self.mean) / self.std_dev)
+    }
+
+    /// Estimates the fraction of this class scoring at or above `score`.
+    #[must_use]
+    pub fn fraction_at_or_above(&self, score: f32) -> f32 {
+        1.0 - self.fraction_below(score)
+    }
+}
+
+/// Mark thresholds recommended for a corpus of fill scores, along with the
+/// distributions they were derived from and how often they are expected to
+/// be wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Calibration {
+    /// Scores of the bubbles below the boundary found by Otsu's method.
+    pub blank: ScoreDistribution,
+
+    /// Scores of the bubbles at or above the boundary.
+    pub marked: ScoreDistribution,
+
+    /// Where the densities of the fitted blank and marked distributions
+    /// cross, i.e. where a score is equally likely to be either.
+    pub definite: f32,
+
+    /// [`MARGINAL_STANDARD_DEVIATIONS`] above the mean blank score, but no
+    /// higher than `definite`.
+    pub marginal: f32,
+
+    /// Fraction of the fitted blank distribution at or above `definite`, i.e.
+    /// a model estimate of how many blank bubbles would count as votes, not a
+    /// count of labeled bubbles.
+    pub estimated_false_positive_rate: f32,
+
+    /// Fraction of the fitted marked distribution below `definite`, i.e. a
+    /// model estimate of how many marked bubbles would not count as votes.
+    pub estimated_false_negative_rate: f32,
+
+    /// How many bubbles in the blank class score at or above `definite`, i.e.
+    /// would count as votes despite being separated out as blank. These are
+    /// worth a look before adopting the recommendation.
+    pub blank_at_or_above_definite: usize,
+}
+
+/// Recommends thresholds for `scores`, or returns `None` if they don't fall
+/// into two classes, e.g. because there are no marked bubbles in the corpus.
+#[must_use]
+pub fn calibrate(scores: &[UnitIntervalScore]) -> Option<Calibration> {
+    let histogram = fill_score_histogram(scores);
+    let boundary_index = otsu_threshold_index(&histogram) + 1;
+
+    let scores = scores.iter().map(|score| (*score, score.0));
+    let blank_scores = scores
+        .clone()
+        .filter(|(score, _)| bin_index(*score) < boundary_index)
+        .map(|(_, score)| score);
+    let blank = ScoreDistribution::fit(blank_scores.clone())?;
+    let marked = ScoreDistribution::fit(
+        scores
+            .filter(|(score, _)| bin_index(*score) >= boundary_index)
+            .map(|(_, score)| score),
+    )?;
+    let definite = density_crossing(&blank, &marked);
+    let marginal = (blank.mean + MARGINAL_STANDARD_DEVIATIONS * blank.std_dev).min(definite);
+
+    Some(Calibration {
+        blank,
+        marked,
+        definite,
+        marginal,
+        estimated_false_positive_rate: blank.fraction_at_or_above(definite),
+        estimated_false_negative_rate: marked.fraction_below(definite),
+        blank_at_or_above_definite: blank_scores.filter(|score| *score >= definite).count(),
+    })
+}
+
+/// Finds the score between the means of `blank` and `marked` where their
+/// normal densities are equal. Each density is normalized on its own rather
+/// than weighted by class size, so a corpus that is mostly blank bubbles
+/// doesn't push the threshold toward the marked ones. Unlike the midpoint of
+/// the gap between the classes, this depends on every score in each class
+/// rather than only the extremes, so a single stray score in the gap moves it
+/// much less.
+fn density_crossing(blank: &ScoreDistribution, marked: &ScoreDistribution) -> f32 {
+    let (mean_b, sd_b) = (f64::from(blank.mean), f64::from(blank.std_dev));
+    let (mean_m, sd_m) = (f64::from(marked.mean), f64::from(marked.std_dev));
+    if sd_b == 0.0 || sd_m == 0.0 {
+        // A class with no spread has no density to cross, so fall back to the
+        // middle of the gap between the classes.
+        return f32::midpoint(blank.max, marked.min);
+    }
+
+    // Equating the log densities gives a x^2 + b x + c = 0.
+    let (var_b, var_m) = (sd_b * sd_b, sd_m * sd_m);
+    let a = 1.0 / var_b - 1.0 / var_m;
+    let b = 2.0 * (mean_m / var_m - mean_b / var_b);
+    let c = mean_b * mean_b / var_b - mean_m * mean_m / var_m + 2.0 * (sd_b / sd_m).ln();
+    let crossing = if a.abs() < f64::EPSILON * (1.0 / var_b + 1.0 / var_m) {
+        -c / b
+    } else {
+        let discriminant = (b * b - 4.0 * a * c).max(0.0).sqrt();
+        let roots = [
+            (-b + discriminant) / (2.0 * a),
+            (-b - discriminant) / (2.0 * a),
+        ];
+        roots
+            .into_iter()
+            .find(|root| (mean_b..=mean_m).contains(root))
+            .unwrap_or(f64::midpoint(mean_b, mean_m))
+    };
+    crossing.clamp(mean_b, mean_m) as f32
+}
+
+/// The standard normal cumulative distribution function.
+fn normal_cdf(z: f32) -> f32 {
+    0.5 * (1.0 + erf(z / std::f32::consts::SQRT_2))
+}
+
+/// The error function, to within 1.5e-7 (Abramowitz and Stegun 7.1.26).
+fn erf(x: f32) -> f32 {
+    const P: f32 = 0.327_591_1;
+    const A: [f32; 5] = [
+        0.254_829_6,
+        -0.284_496_74,
+        1.421_413_8,
+        -1.453_152_1,
+        1.061_405_4,
+    ];
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1.0 / (1.0 + P * x);
+    let polynomial = A.iter().rev().fold(0.0, |sum, a| sum * t + a) * t;
+    sign * (1.0 - polynomial * (-x * x).exp())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn scores(values: &[f32]) -> Vec<UnitIntervalScore> {
+        values.iter().copied().map(UnitIntervalScore).collect()
+    }
+
+    #[test]
+    fn test_fill_score_histogram() {
+        let histogram = fill_score_histogram(&scores(&[0.0, 0.0005, 0.0015, 0.5, 1.0]));
+        assert_eq!(histogram.len(), HISTOGRAM_BINS);
+        assert_eq!(histogram[0], 2);
+        assert_eq!(histogram[1], 1);
+        assert_eq!(histogram[500], 1);
+        assert_eq!(histogram[HISTOGRAM_BINS - 1], 1);
+        assert_eq!(histogram.iter().sum::<u32>(), 5);
+    }
+
+    #[test]
+    fn test_normal_cdf() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-6);
+        assert!((normal_cdf(1.0) - 0.841_344_7).abs() < 1e-6);
+        assert!((normal_cdf(-2.0) - 0.022_750_1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_calibrate() {
+        // Mostly blank bubbles around 1%, with marked ones around 55%.
+        let blank = (0..200).map(|i| 0.005 + (i % 10) as f32 * 0.001);
+        let marked = (0..20).map(|i| 0.5 + (i % 10) as f32 * 0.01);
+        let calibration = calibrate(&scores(&blank.chain(marked).collect::<Vec<_>>())).unwrap();
+
+        assert_eq!(calibration.blank.count, 200);
+        assert_eq!(calibration.marked.count, 20);
+        assert!((calibration.blank.mean - 0.0095).abs() < 1e-4);
+        assert!((calibration.marked.mean - 0.545).abs() < 1e-4);
+        // The blank scores are ten times tighter than the marked ones, so the
+        // densities cross much closer to the blank scores.
+        assert!((calibration.definite - 0.0585).abs() < 1e-3);
+        assert!(calibration.marginal > calibration.blank.mean);
+        assert!(calibration.marginal <= calibration.definite);
+        assert!(calibration.estimated_false_positive_rate < 0.01);
+        assert!(calibration.estimated_false_negative_rate < 0.01);
+        assert_eq!(calibration.blank_at_or_above_definite, 0);
+    }
+
+    #[test]
+    fn test_calibrate_outlier_in_gap() {
+        // One smudged blank bubble at 25% would drag the middle of the gap up
+        // to 37.5%; the fitted densities still cross well below it, though
+        // below the smudge too, so it is flagged for review.
+        let blank = (0..199)
+            .map(|i| 0.005 + (i % 10) as f32 * 0.001)
+            .chain([0.25]);
+        let marked = (0..20).map(|i| 0.5 + (i % 10) as f32 * 0.01);
+        let calibration = calibrate(&scores(&blank.chain(marked).collect::<Vec<_>>())).unwrap();
+
+        assert_eq!(calibration.blank.count, 200);
+        assert!((calibration.blank.max - 0.25).abs() < f32::EPSILON);
+        assert!(calibration.definite > calibration.blank.mean);
+        assert!(calibration.definite < f32::midpoint(0.25, calibration.marked.min));
+        assert!((calibration.definite - 0.211).abs() < 1e-3);
+        assert_eq!(calibration.blank_at_or_above_definite, 1);
+    }
+
+    #[test]
+    fn test_calibrate_single_class() {
+        assert_eq!(calibrate(&scores(&[0.01; 10])), None);
+        assert_eq!(calibrate(&[]), None);
+    }
+}
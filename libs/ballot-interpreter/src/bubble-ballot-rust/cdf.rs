@@ -0,0 +1,866 @@
+//! Builds NIST SP 1500-103 cast vote records from interpretation results.
+//!
+//! These follow `buildCastVoteRecord` in
+//! `libs/backend/src/cast_vote_records/build_cast_vote_record.ts`: bubble
+//! ballots get an "original" snapshot with every scored bubble and an
+//! "interpreted" snapshot with contest rules applied, while summary ballots
+//! only get an "original" snapshot since the BMD already applied contest rules.
+//! Option positions follow the candidate order of the ballot's style, as
+//! `getOptionPosition` in `libs/utils` does.
+
+use types_rs::ballot_card::BallotType;
+use types_rs::bmd::cvr::CastVoteRecord;
+use types_rs::bmd::votes::{CandidateVote, ContestVote};
+use types_rs::cdf::cvr::{
+    AllocationStatus, ContestSelectionStatus, ContestStatus, Cvr, CvrContest, CvrContestObjectType,
+    CvrContestSelection, CvrContestSelectionObjectType, CvrObjectType, CvrSnapshot,
+    CvrSnapshotObjectType, CvrStatus, CvrType, CvrWriteIn, CvrWriteInObjectType, Hash,
+    HashObjectType, HashType, ImageData, ImageDataObjectType, IndicationStatus, PositionStatus,
+    SelectionPosition, SelectionPositionObjectType,
+};
+use types_rs::election::{
+    BallotStyle, BallotStyleId, Candidate, Contest, ContestId, Election, GridPosition,
+    MarkThresholds, OptionId,
+};
+
+use crate::interpret::InterpretedBallotCard;
+use crate::scoring::ScoredBubbleMark;
+use crate::votes::{ContestVotes, MarkStatus};
+
+/// A reference to a scanned ballot image to include in a CVR.
+#[derive(Debug, Clone)]
+pub struct CvrImage {
+    /// Path to the image, relative to the CVR.
+    pub path: String,
+
+    /// Hex-encoded SHA-256 hash of the image file.
+    pub sha256: Option<String>,
+}
+
+/// Identifying information for a CVR that does not come from the ballot.
+#[derive(Debug, Clone)]
+pub struct CvrMetadata {
+    pub election_id: String,
+    pub creating_device_id: String,
+    pub batch_id: String,
+    pub batch_sequence_id: Option<u32>,
+
+    /// Unique ID for the CVR, used as the prefix for snapshot IDs.
+    pub cvr_id: String,
+
+    pub front_image: Option<CvrImage>,
+    pub back_image: Option<CvrImage>,
+}
+
+/// Builds a CVR for a bubble ballot card. `mark_thresholds` should be the
+/// thresholds used to interpret `card`.
+#[must_use]
+pub fn bubble_ballot_cvr(
+    election: &Election,
+    card: &InterpretedBallotCard,
+    mark_thresholds: &MarkThresholds,
+    metadata: &CvrMetadata,
+) -> Cvr {
+    let front_metadata = &card.front.metadata;
+    let ballot_style = find_ballot_style(election, front_metadata.ballot_style_id());
+    let sheet_number = front_metadata
+        .page_number()
+        .get()
+        .max(card.back.metadata.page_number().get())
+        / 2;
+
+    let front_write_in_image = metadata.front_image.as_ref().map(image_data);
+    let back_write_in_image = metadata.back_image.as_ref().map(image_data);
+    let write_in_image = |contest_id: &ContestId, option_id: &OptionId| {
+        let is_on_back = card.back.marks.iter().any(|(position, _)| {
+            position.contest_id() == *contest_id && position.option_id() == *option_id
+        });
+        if is_on_back {
+            back_write_in_image.clone()
+        } else {
+            front_write_in_image.clone()
+        }
+    };
+
+    let interpreted_snapshot = CvrSnapshot {
+        id: format!("{}-interpreted", metadata.cvr_id),
+        object_type: CvrSnapshotObjectType::CvrSnapshot,
+        cvr_contest: card
+            .votes
+            .contests
+            .iter()
+            .filter_map(|contest_votes| {
+                let contest = find_contest(election, &contest_votes.contest_id)?;
+                Some(interpreted_cvr_contest(
+                    ballot_style,
+                    contest,
+                    contest_votes,
+                    &write_in_image,
+                ))
+            })
+            .collect(),
+        other_status: front_metadata.ballot_type().map(ballot_type_other_status),
+        status: Some(vec![CvrStatus::Other]),
+        r#type: CvrType::Interpreted,
+    };
+
+    let original_snapshot = original_snapshot(
+        election,
+        ballot_style,
+        &metadata.cvr_id,
+        card.front.marks.iter().chain(&card.back.marks),
+        mark_thresholds,
+        front_metadata.ballot_type(),
+    );
+
+    Cvr {
+        object_type: CvrObjectType::Cvr,
+        ballot_audit_id: front_metadata
+            .bubble_ballot_metadata()
+            .and_then(|metadata| metadata.ballot_audit_id.clone()),
+        ballot_image: ballot_image(metadata),
+        ballot_sheet_id: Some(sheet_number.to_string()),
+        ballot_style_id: front_metadata.ballot_style_id().to_string(),
+        // Only the QR code says which precinct a ballot whose style was
+        // inferred is for.
+        ballot_style_unit_id: front_metadata
+            .precinct_id()
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+        batch_id: metadata.batch_id.clone(),
+        batch_sequence_id: metadata.batch_sequence_id,
+        current_snapshot_id: interpreted_snapshot.id.clone(),
+        cvr_snapshot: vec![interpreted_snapshot, original_snapshot],
+        creating_device_id: metadata.creating_device_id.clone(),
+        election_id: metadata.election_id.clone(),
+        party_ids: ballot_party_ids(ballot_style),
+        unique_id: metadata.cvr_id.clone(),
+    }
+}
+
+/// Builds a CVR for a page of a BMD summary ballot.
+#[must_use]
+pub fn summary_ballot_cvr(
+    election: &Election,
+    cast_vote_record: &CastVoteRecord,
+    metadata: &CvrMetadata,
+) -> Cvr {
+    let ballot_style = find_ballot_style(election, &cast_vote_record.ballot_style_id);
+    let snapshot = CvrSnapshot {
+        id: format!("{}-original", metadata.cvr_id),
+        object_type: CvrSnapshotObjectType::CvrSnapshot,
+        cvr_contest: cast_vote_record
+            .contest_ids
+            .iter()
+            .filter_map(|contest_id| {
+                let contest = find_contest(election, contest_id)?;
+                Some(summary_ballot_cvr_contest(
+                    ballot_style,
+                    contest,
+                    cast_vote_record.votes.get(contest_id),
+                ))
+            })
+            .collect(),
+        other_status: Some(ballot_type_other_status(cast_vote_record.ballot_type)),
+        status: Some(vec![CvrStatus::Other]),
+        r#type: CvrType::Original,
+    };
+
+    Cvr {
+        object_type: CvrObjectType::Cvr,
+        ballot_audit_id: Some(cast_vote_record.ballot_audit_id.to_string()),
+        ballot_image: ballot_image(metadata),
+        ballot_sheet_id: Some(cast_vote_record.page_number.get().to_string()),
+        ballot_style_id: cast_vote_record.ballot_style_id.to_string(),
+        ballot_style_unit_id: cast_vote_record.precinct_id.to_string(),
+        batch_id: metadata.batch_id.clone(),
+        batch_sequence_id: metadata.batch_sequence_id,
+        current_snapshot_id: snapshot.id.clone(),
+        cvr_snapshot: vec![snapshot],
+        creating_device_id: metadata.creating_device_id.clone(),
+        election_id: metadata.election_id.clone(),
+        party_ids: ballot_party_ids(ballot_style),
+        unique_id: metadata.cvr_id.clone(),
+    }
+}
+
+/// Builds the "original" snapshot, which has one selection per scored bubble
+/// with its fill score as the mark metric and no contest rules applied.
+fn original_snapshot<'a>(
+    election: &Election,
+    ballot_style: Option<&BallotStyle>,
+    cvr_id: &str,
+    marks: impl Iterator<Item = &'a (GridPosition, Option<ScoredBubbleMark>)>,
+    mark_thresholds: &MarkThresholds,
+    ballot_type: Option<BallotType>,
+) -> CvrSnapshot {
+    let mut cvr_contests: Vec<CvrContest> = vec![];
+
+    for (position, scored_bubble_mark) in marks {
+        let contest_id = position.contest_id();
+        let option_id = position.option_id();
+        let has_indication = match MarkStatus::for_scored_bubble_mark(
+            scored_bubble_mark.as_ref(),
+            mark_thresholds,
+        ) {
+            MarkStatus::Marked => IndicationStatus::Yes,
+            MarkStatus::Marginal => IndicationStatus::Unknown,
+            MarkStatus::Unmarked => IndicationStatus::No,
+        };
+        let selection = CvrContestSelection {
+            object_type: CvrContestSelectionObjectType::CvrContestSelection,
+            contest_selection_id: option_id.to_string(),
+            option_position: find_contest(election, &contest_id)
+                .and_then(|contest| option_position(ballot_style, contest, &option_id)),
+            selection_position: vec![SelectionPosition {
+                mark_metric_value: scored_bubble_mark
+                    .as_ref()
+                    .map(|mark| vec![format_mark_metric(mark.fill_score.0)]),
+                rank: position.rank(),
+                ..selection_position(has_indication)
+            }],
+            status: None,
+        };
+
+        match cvr_contests
+            .iter_mut()
+            .find(|cvr_contest| cvr_contest.contest_id == contest_id.to_string())
+        {
+            // each rank of a ranked-choice option is a position of one selection
+            Some(cvr_contest) if position.rank().is_some() => {
+                match cvr_contest
+                    .cvr_contest_selection
+                    .iter_mut()
+                    .find(|existing| existing.contest_selection_id == option_id.to_string())
+                {
+                    Some(existing) => existing
+                        .selection_position
+                        .extend(selection.selection_position),
+                    None => cvr_contest.cvr_contest_selection.push(selection),
+                }
+            }
+            Some(cvr_contest) => cvr_contest.cvr_contest_selection.push(selection),
+            None => cvr_contests.push(CvrContest {
+                object_type: CvrContestObjectType::CvrContest,
+                cvr_contest_selection: vec![selection],
+                contest_id: contest_id.to_string(),
+                overvotes: None,
+                status: None,
+                undervotes: None,
+                write_ins: None,
+            }),
+        }
+    }
+
+    CvrSnapshot {
+        id: format!("{cvr_id}-original"),
+        object_type: CvrSnapshotObjectType::CvrSnapshot,
+        cvr_contest: cvr_contests,
+        other_status: ballot_type.map(ballot_type_other_status),
+        status: Some(vec![CvrStatus::Other]),
+        r#type: CvrType::Original,
+    }
+}
+
+/// Builds a contest for the "interpreted" snapshot of a bubble ballot from its
+/// derived votes. Votes added by straight-party expansion are marked as
+/// generated by contest rules, and ranked-choice votes record their rank.
+fn interpreted_cvr_contest(
+    ballot_style: Option<&BallotStyle>,
+    contest: &Contest,
+    contest_votes: &ContestVotes,
+    write_in_image: &impl Fn(&ContestId, &OptionId) -> Option<ImageData>,
+) -> CvrContest {
+    let votes_allowed = contest.votes_allowed();
+    let is_overvote = contest_votes.option_ids.len() as u32 > votes_allowed;
+
+    let selections = contest_votes
+        .option_ids
+        .iter()
+        .map(|option_id| {
+            let is_generated = contest_votes.straight_party_option_ids.contains(option_id);
+            let is_write_in = is_write_in(contest, option_id);
+
+            let mut statuses = vec![];
+            if is_overvote {
+                statuses.push(ContestSelectionStatus::InvalidatedRules);
+            }
+            if is_write_in {
+                statuses.push(ContestSelectionStatus::NeedsAdjudication);
+            }
+            if is_generated {
+                statuses.push(ContestSelectionStatus::GeneratedRules);
+            }
+
+            CvrContestSelection {
+                object_type: CvrContestSelectionObjectType::CvrContestSelection,
+                contest_selection_id: option_id.to_string(),
+                option_position: option_position(ballot_style, contest, option_id),
+                selection_position: vec![SelectionPosition {
+                    cvr_write_in: is_write_in.then(|| CvrWriteIn {
+                        object_type: CvrWriteInObjectType::CvrWriteIn,
+                        text: None,
+                        write_in_image: write_in_image(contest.id(), option_id),
+                    }),
+                    is_allocable: Some(if is_overvote {
+                        AllocationStatus::No
+                    } else if is_write_in {
+                        AllocationStatus::Unknown
+                    } else {
+                        AllocationStatus::Yes
+                    }),
+                    is_generated: is_generated.then_some(true),
+                    rank: ranked_choice_rank(contest_votes, option_id),
+                    status: if is_overvote {
+                        Some(vec![PositionStatus::InvalidatedRules])
+                    } else if is_generated {
+                        Some(vec![PositionStatus::GeneratedRules])
+                    } else {
+                        None
+                    },
+                    ..selection_position(if is_generated {
+                        IndicationStatus::No
+                    } else {
+                        IndicationStatus::Yes
+                    })
+                }],
+                status: (!statuses.is_empty()).then_some(statuses),
+            }
+        })
+        .collect();
+
+    cvr_contest(contest, selections)
+}
+
+/// Finds the rank an option was counted at in a ranked-choice contest: the
+/// first rank at which it is the only option marked. Skipped and overvoted
+/// ranks are not counted, so this may be later than its place in
+/// `option_ids`.
+fn ranked_choice_rank(contest_votes: &ContestVotes, option_id: &OptionId) -> Option<u32> {
+    contest_votes.ranks.as_ref().and_then(|ranks| {
+        (1..).zip(ranks).find_map(|(rank, rank_option_ids)| {
+            (rank_option_ids.as_slice() == std::slice::from_ref(option_id)).then_some(rank)
+        })
+    })
+}
+
+/// Builds a contest for a summary ballot from its decoded votes. Write-in
+/// selections are numbered in the order they appear, matching the IDs used for
+/// write-ins on bubble ballots.
+fn summary_ballot_cvr_contest(
+    ballot_style: Option<&BallotStyle>,
+    contest: &Contest,
+    vote: Option<&ContestVote>,
+) -> CvrContest {
+    let votes_allowed = contest.votes_allowed();
+    let is_ranked = matches!(vote, Some(ContestVote::RankedChoice(_)));
+    let selected: Vec<(OptionId, Option<String>)> = match vote {
+        None => vec![],
+        Some(
+            ContestVote::Candidate(candidate_votes) | ContestVote::RankedChoice(candidate_votes),
+        ) => {
+            let mut write_in_index = 0;
+            candidate_votes
+                .iter()
+                .map(|candidate_vote| match candidate_vote {
+                    CandidateVote::NamedCandidate { candidate_id } => (candidate_id.clone(), None),
+                    CandidateVote::WriteInCandidate { name, .. } => {
+                        let option_id = OptionId::from(format!("write-in-{write_in_index}"));
+                        write_in_index += 1;
+                        (option_id, Some(name.to_string()))
+                    }
+                })
+                .collect()
+        }
+        Some(ContestVote::YesNo(option_ids)) => option_ids
+            .iter()
+            .map(|option_id| (option_id.clone(), None))
+            .collect(),
+        Some(ContestVote::StraightParty(party_ids)) => party_ids
+            .iter()
+            .map(|party_id| (OptionId::from(party_id.to_string()), None))
+            .collect(),
+    };
+    let is_overvote = selected.len() as u32 > votes_allowed;
+
+    // Summary ballots list rankings first choice first with no gaps or
+    // repeats, so an option's place in the list is its rank.
+    let selections = selected
+        .into_iter()
+        .zip(1..)
+        .map(|((option_id, write_in_name), rank)| {
+            let mut statuses = vec![];
+            if is_overvote {
+                statuses.push(ContestSelectionStatus::InvalidatedRules);
+            }
+            if write_in_name.is_some() {
+                statuses.push(ContestSelectionStatus::NeedsAdjudication);
+            }
+
+            CvrContestSelection {
+                object_type: CvrContestSelectionObjectType::CvrContestSelection,
+                contest_selection_id: option_id.to_string(),
+                option_position: option_position(ballot_style, contest, &option_id),
+                selection_position: vec![SelectionPosition {
+                    is_allocable: Some(if is_overvote {
+                        AllocationStatus::No
+                    } else if write_in_name.is_some() {
+                        AllocationStatus::Unknown
+                    } else {
+                        AllocationStatus::Yes
+                    }),
+                    rank: is_ranked.then_some(rank),
+                    status: is_overvote.then(|| vec![PositionStatus::InvalidatedRules]),
+                    cvr_write_in: write_in_name.map(|name| CvrWriteIn {
+                        object_type: CvrWriteInObjectType::CvrWriteIn,
+                        text: Some(name),
+                        write_in_image: None,
+                    }),
+                    ..selection_position(IndicationStatus::Yes)
+                }],
+                status: (!statuses.is_empty()).then_some(statuses),
+            }
+        })
+        .collect();
+
+    cvr_contest(contest, selections)
+}
+
+/// Wraps `selections` in a contest, computing overvotes and undervotes per
+/// VVSG 2.0 1.1.5-E.2.
+fn cvr_contest(contest: &Contest, selections: Vec<CvrContestSelection>) -> CvrContest {
+    let votes_allowed = contest.votes_allowed();
+    let vote_count = selections.len() as u32;
+    let overvotes = if vote_count > votes_allowed {
+        votes_allowed
+    } else {
+        0
+    };
+    let undervotes = votes_allowed.saturating_sub(vote_count);
+
+    let mut statuses = vec![];
+    if vote_count == 0 {
+        statuses.push(ContestStatus::NotIndicated);
+    }
+    if undervotes > 0 {
+        statuses.push(ContestStatus::Undervoted);
+    }
+    if overvotes > 0 {
+        statuses.extend([ContestStatus::Overvoted, ContestStatus::InvalidatedRules]);
+    }
+
+    let write_ins = match contest {
+        Contest::Candidate(_) | Contest::RankedChoice(_) => Some(
+            selections
+                .iter()
+                .filter(|selection| {
+                    selection
+                        .selection_position
+                        .iter()
+                        .any(|position| position.cvr_write_in.is_some())
+                })
+                .count() as u32,
+        ),
+        Contest::YesNo(_) | Contest::StraightParty(_) => None,
+    };
+
+    CvrContest {
+        object_type: CvrContestObjectType::CvrContest,
+        cvr_contest_selection: selections,
+        contest_id: contest.id().to_string(),
+        overvotes: Some(overvotes),
+        status: (!statuses.is_empty()).then_some(statuses),
+        undervotes: Some(undervotes),
+        write_ins,
+    }
+}
+
+fn selection_position(has_indication: IndicationStatus) -> SelectionPosition {
+    SelectionPosition {
+        object_type: SelectionPositionObjectType::SelectionPosition,
+        cvr_write_in: None,
+        has_indication,
+        is_allocable: None,
+        is_generated: None,
+        mark_metric_value: None,
+        number_votes: 1,
+        rank: None,
+        status: None,
+    }
+}
+
+fn find_ballot_style<'a>(
+    election: &'a Election,
+    ballot_style_id: &BallotStyleId,
+) -> Option<&'a BallotStyle> {
+    election
+        .ballot_styles
+        .iter()
+        .find(|ballot_style| ballot_style.id == *ballot_style_id)
+}
+
+fn find_contest<'a>(election: &'a Election, contest_id: &ContestId) -> Option<&'a Contest> {
+    election
+        .contests
+        .iter()
+        .find(|contest| contest.id() == contest_id)
+}
+
+fn is_write_in(contest: &Contest, option_id: &OptionId) -> bool {
+    match candidates(contest) {
+        Some((candidates, allow_write_ins)) => allow_write_ins
+            && !candidates.iter().any(
+                |candidate| matches!(candidate, Candidate::Named(named) if named.id == *option_id),
+            ),
+        None => false,
+    }
+}
+
+/// Gets the candidates of a candidate or ranked-choice contest and whether it
+/// allows write-ins.
+fn candidates(contest: &Contest) -> Option<(&[Candidate], bool)> {
+    match contest {
+        Contest::Candidate(candidate_contest) => Some((
+            &candidate_contest.candidates,
+            candidate_contest.allow_write_ins,
+        )),
+        Contest::RankedChoice(ranked_choice_contest) => Some((
+            &ranked_choice_contest.candidates,
+            ranked_choice_contest.allow_write_ins,
+        )),
+        Contest::YesNo(_) | Contest::StraightParty(_) => None,
+    }
+}
+
+/// Finds the position of an option within its contest as it appears on the
+/// ballot, per VVSG 2.0 1.1.5-C.2. Candidates are in the order `ballot_style`
+/// lists them, if it does, and a candidate listed once per endorsing party is
+/// at its first listing. Write-ins come after every listing.
+fn option_position(
+    ballot_style: Option<&BallotStyle>,
+    contest: &Contest,
+    option_id: &OptionId,
+) -> Option<u32> {
+    let position = match contest {
+        Contest::Candidate(_) | Contest::RankedChoice(_) => {
+            let (candidates, _) = candidates(contest)?;
+            let candidate_ids: Vec<&OptionId> = match ballot_style
+                .and_then(|ballot_style| ballot_style.ordered_candidates_by_contest.as_ref())
+                .and_then(|ordered_candidates| ordered_candidates.get(contest.id()))
+            {
+                Some(ordered_candidates) => ordered_candidates
+                    .iter()
+                    .map(|ordered_candidate| &ordered_candidate.id)
+                    .collect(),
+                None => candidates.iter().map(Candidate::id).collect(),
+            };
+            candidate_ids
+                .iter()
+                .position(|candidate_id| *candidate_id == option_id)
+                .or_else(|| {
+                    let write_in_index: usize = option_id
+                        .to_string()
+                        .strip_prefix("write-in-")?
+                        .parse()
+                        .ok()?;
+                    Some(candidate_ids.len() + write_in_index)
+                })
+        }
+        Contest::YesNo(yes_no_contest) => yes_no_contest
+            .options
+            .iter()
+            .position(|option| option.id == *option_id),
+        Contest::StraightParty(straight_party_contest) => straight_party_contest
+            .option_ids
+            .iter()
+            .position(|party_id| party_id.to_string() == option_id.to_string()),
+    }?;
+    u32::try_from(position).ok()
+}
+
+fn ballot_party_ids(ballot_style: Option<&BallotStyle>) -> Option<Vec<String>> {
+    ballot_style
+        .and_then(|ballot_style| ballot_style.party_id.as_ref())
+        .map(|party_id| vec![party_id.to_string()])
+}
+
+/// Records the ballot type in the snapshot's `OtherStatus`, matching
+/// `buildCVRSnapshotBallotTypeMetadata` in `libs/utils`.
+fn ballot_type_other_status(ballot_type: BallotType) -> String {
+    serde_json::json!({ "ballotType": ballot_type }).to_string()
+}
+
+/// Formats a fill score as a mark metric, truncated to two decimal places.
+fn format_mark_metric(fill_score: f32) -> String {
+    ((fill_score * 100.0).floor() / 100.0).to_string()
+}
+
+fn ballot_image(metadata: &CvrMetadata) -> Option<Vec<ImageData>> {
+    let images: Vec<ImageData> = [&metadata.front_image, &metadata.back_image]
+        .into_iter()
+        .flatten()
+        .map(image_data)
+        .collect();
+    (!images.is_empty()).then_some(images)
+}
+
+fn image_data(image: &CvrImage) -> ImageData {
+    ImageData {
+        object_type: ImageDataObjectType::ImageData,
+        hash: image.sha256.as_ref().map(|sha256| Hash {
+            object_type: HashObjectType::Hash,
+            r#type: HashType::Sha256,
+            value: sha256.clone(),
+        }),
+        location: Some(format!("file:{}", image.path)),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::collections::HashMap;
+
+    use types_rs::bmd::cvr::PageNumber;
+    use types_rs::bmd::encoding::BallotAuditId;
+    use types_rs::bmd::write_in_name::WriteInName;
+    use types_rs::election::{BallotStyleId, PrecinctId};
+
+    use super::*;
+
+    fn election() -> Election {
+        serde_json::from_value(serde_json::json!({
+            "title": "Test",
+            "ballotStyles": [
+                {
+                    "id": "rotated",
+                    "groupId": "rotated",
+                    "precincts": ["precinct"],
+                    "districts": ["district"],
+                    "orderedCandidatesByContest": {
+                        "mayor": [
+                            { "id": "bob", "partyIds": ["red"] },
+                            { "id": "alice" },
+                            { "id": "bob", "partyIds": ["blue"] }
+                        ]
+                    }
+                }
+            ],
+            "precincts": [],
+            "contests": [
+                {
+                    "type": "candidate",
+                    "id": "mayor",
+                    "districtId": "district",
+                    "title": "Mayor",
+                    "seats": 1,
+                    "allowWriteIns": true,
+                    "candidates": [
+                        { "id": "alice", "name": "Alice" },
+                        { "id": "bob", "name": "Bob" }
+                    ]
+                },
+                {
+                    "type": "yesno",
+                    "id": "measure",
+                    "districtId": "district",
+                    "title": "Measure",
+                    "description": "",
+                    "options": [
+                        { "id": "yes", "label": "Yes" },
+                        { "id": "no", "label": "No" }
+                    ]
+                },
+                {
+                    "type": "ranked-choice",
+                    "id": "council",
+                    "districtId": "district",
+                    "title": "Council",
+                    "maxRankings": 3,
+                    "allowWriteIns": false,
+                    "candidates": [
+                        { "id": "carol", "name": "Carol" },
+                        { "id": "dave", "name": "Dave" },
+                        { "id": "erin", "name": "Erin" }
+                    ]
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn metadata() -> CvrMetadata {
+        CvrMetadata {
+            election_id: "election".to_owned(),
+            creating_device_id: "scanner".to_owned(),
+            batch_id: "batch".to_owned(),
+            batch_sequence_id: Some(3),
+            cvr_id: "cvr".to_owned(),
+            front_image: Some(CvrImage {
+                path: "cvr-front.jpg".to_owned(),
+                sha256: Some("abcd".to_owned()),
+            }),
+            back_image: None,
+        }
+    }
+
+    #[test]
+    fn test_format_mark_metric() {
+        assert_eq!(format_mark_metric(0.0), "0");
+        assert_eq!(format_mark_metric(0.5), "0.5");
+        assert_eq!(format_mark_metric(0.079), "0.07");
+    }
+
+    #[test]
+    fn test_option_position() {
+        let election = election();
+        let mayor = &election.contests[0];
+        let option_id = |id: &str| OptionId::from(id.to_owned());
+        assert_eq!(option_position(None, mayor, &option_id("bob")), Some(1));
+        assert_eq!(
+            option_position(None, mayor, &option_id("write-in-0")),
+            Some(2)
+        );
+        assert_eq!(option_position(None, mayor, &option_id("carol")), None);
+
+        // Bob is listed once per endorsing party, ahead of Alice.
+        let rotated = find_ballot_style(&election, &BallotStyleId::from("rotated".to_owned()));
+        assert!(rotated.is_some());
+        assert_eq!(option_position(rotated, mayor, &option_id("bob")), Some(0));
+        assert_eq!(
+            option_position(rotated, mayor, &option_id("alice")),
+            Some(1)
+        );
+        assert_eq!(
+            option_position(rotated, mayor, &option_id("write-in-0")),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_summary_ballot_cvr() {
+        let election = election();
+        let cast_vote_record = CastVoteRecord {
+            ballot_hash: [0; 10],
+            ballot_style_id: BallotStyleId::from("1".to_owned()),
+            precinct_id: PrecinctId::from("precinct".to_owned()),
+            page_number: PageNumber::new(1).unwrap(),
+            total_pages: PageNumber::new(1).unwrap(),
+            is_test_mode: true,
+            ballot_type: BallotType::Precinct,
+            ballot_audit_id: BallotAuditId::new("audit-id").unwrap(),
+            contest_ids: vec![
+                ContestId::from("mayor".to_owned()),
+                ContestId::from("measure".to_owned()),
+            ],
+            votes: HashMap::from([(
+                ContestId::from("mayor".to_owned()),
+                ContestVote::Candidate(vec![CandidateVote::WriteInCandidate {
+                    candidate_id: OptionId::from("write-in-abc".to_owned()),
+                    name: WriteInName::new("ABC").unwrap(),
+                }]),
+            )]),
+        };
+
+        let cvr = summary_ballot_cvr(&election, &cast_vote_record, &metadata());
+        let json = serde_json::to_value(&cvr).unwrap();
+
+        assert_eq!(json["@type"], "CVR.CVR");
+        assert_eq!(json["BallotAuditId"], "audit-id");
+        assert_eq!(json["BatchSequenceId"], 3);
+        assert_eq!(json["CurrentSnapshotId"], "cvr-original");
+        assert_eq!(json["BallotImage"][0]["Location"], "file:cvr-front.jpg");
+        assert_eq!(json["BallotImage"][0]["Hash"]["Type"], "sha-256");
+
+        let snapshot = &json["CVRSnapshot"][0];
+        assert_eq!(snapshot["Type"], "original");
+        assert_eq!(snapshot["OtherStatus"], r#"{"ballotType":"precinct"}"#);
+
+        let mayor = &snapshot["CVRContest"][0];
+        assert_eq!(mayor["WriteIns"], 1);
+        assert_eq!(mayor["Undervotes"], 0);
+        let selection = &mayor["CVRContestSelection"][0];
+        assert_eq!(selection["ContestSelectionId"], "write-in-0");
+        assert_eq!(selection["OptionPosition"], 2);
+        assert_eq!(
+            selection["SelectionPosition"][0]["CVRWriteIn"]["Text"],
+            "ABC"
+        );
+        assert_eq!(selection["SelectionPosition"][0]["IsAllocable"], "unknown");
+
+        let measure = &snapshot["CVRContest"][1];
+        assert_eq!(measure["Undervotes"], 1);
+        assert_eq!(
+            measure["Status"],
+            serde_json::json!(["not-indicated", "undervoted"])
+        );
+        assert_eq!(measure["CVRContestSelection"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_summary_ballot_cvr_ranked_choice() {
+        let election = election();
+        let cast_vote_record = CastVoteRecord {
+            ballot_hash: [0; 10],
+            ballot_style_id: BallotStyleId::from("1".to_owned()),
+            precinct_id: PrecinctId::from("precinct".to_owned()),
+            page_number: PageNumber::new(1).unwrap(),
+            total_pages: PageNumber::new(1).unwrap(),
+            is_test_mode: true,
+            ballot_type: BallotType::Precinct,
+            ballot_audit_id: BallotAuditId::new("audit-id").unwrap(),
+            contest_ids: vec![ContestId::from("council".to_owned())],
+            votes: HashMap::from([(
+                ContestId::from("council".to_owned()),
+                ContestVote::RankedChoice(vec![
+                    CandidateVote::NamedCandidate {
+                        candidate_id: OptionId::from("erin".to_owned()),
+                    },
+                    CandidateVote::NamedCandidate {
+                        candidate_id: OptionId::from("carol".to_owned()),
+                    },
+                ]),
+            )]),
+        };
+
+        let cvr = summary_ballot_cvr(&election, &cast_vote_record, &metadata());
+        let json = serde_json::to_value(&cvr).unwrap();
+
+        let council = &json["CVRSnapshot"][0]["CVRContest"][0];
+        assert_eq!(council["Undervotes"], 1);
+        assert_eq!(council["WriteIns"], 0);
+        let selections = &council["CVRContestSelection"];
+        assert_eq!(selections[0]["ContestSelectionId"], "erin");
+        assert_eq!(selections[0]["OptionPosition"], 2);
+        assert_eq!(selections[0]["SelectionPosition"][0]["Rank"], 1);
+        assert_eq!(selections[1]["ContestSelectionId"], "carol");
+        assert_eq!(selections[1]["SelectionPosition"][0]["Rank"], 2);
+    }
+
+    #[test]
+    fn test_interpreted_cvr_contest_ranked_choice_skips_overvoted_ranks() {
+        let election = election();
+        let council = &election.contests[2];
+        let option_id = |id: &str| OptionId::from(id.to_owned());
+        let contest_votes = ContestVotes {
+            contest_id: council.id().clone(),
+            option_ids: vec![option_id("dave"), option_id("carol")],
+            adjudication_reasons: vec![],
+            ranks: Some(vec![
+                vec![option_id("dave")],
+                vec![option_id("carol"), option_id("erin")],
+                vec![option_id("carol")],
+            ]),
+            straight_party_option_ids: vec![],
+        };
+
+        let cvr_contest = interpreted_cvr_contest(None, council, &contest_votes, &|_, _| None);
+        let json = serde_json::to_value(&cvr_contest).unwrap();
+
+        let selections = &json["CVRContestSelection"];
+        assert_eq!(selections[0]["ContestSelectionId"], "dave");
+        assert_eq!(selections[0]["SelectionPosition"][0]["Rank"], 1);
+        assert_eq!(selections[1]["ContestSelectionId"], "carol");
+        assert_eq!(selections[1]["SelectionPosition"][0]["Rank"], 3);
+    }
+}
@@ -0,0 +1,228 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use itertools::Itertools;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Serialize, Serializer};
+use types_rs::ballot_card::BallotSide;
+use types_rs::election::{ContestId, GridLayout, GridPosition};
+use types_rs::geometry::{PixelUnit, Quadrilateral, SubGridRect, SubGridUnit};
+
+use crate::ballot_card::{BallotImage, Geometry, SCAN_PIXELS_PER_INCH};
+use crate::image_utils::{binarize_and_encode_png, warp_quadrilateral_to_image};
+use crate::timing_marks::TimingMarks;
+
+/// A perspective-corrected image of an area of a ballot page. The image is
+/// binarized the same way as the normalized ballot image and always has the
+/// size the area would have in a straight 200 DPI scan, regardless of the
+/// actual scan's resolution, skew, or stretch.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AreaCrop {
+    /// Where the area is in the ballot image.
+    pub shape: Quadrilateral,
+    pub width: PixelUnit,
+    pub height: PixelUnit,
+    /// PNG bytes of the cropped image. Serialized as base64.
+    #[serde(serialize_with = "serialize_png_as_base64")]
+    pub png: image::ImageResult<Vec<u8>>,
+}
+
+/// A crop of the area where a voter would write in a candidate's name.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteInAreaCrop {
+    pub grid_position: GridPosition,
+    #[serde(flatten)]
+    pub crop: AreaCrop,
+}
+
+/// A crop of a contest, approximated as the union of its options' bounds.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContestAreaCrop {
+    pub contest_id: ContestId,
+    #[serde(flatten)]
+    pub crop: AreaCrop,
+}
+
+/// Crops of the write-in areas and contests on a ballot page.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AreaCrops {
+    pub write_ins: Vec<WriteInAreaCrop>,
+    pub contests: Vec<ContestAreaCrop>,
+}
+
+fn serialize_png_as_base64<S: Serializer>(
+    png: &image::ImageResult<Vec<u8>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match png {
+        Ok(bytes) => serializer.serialize_str(&STANDARD.encode(bytes)),
+        Err(err) => Err(serde::ser::Error::custom(format!(
+            "unable to encode area crop: {err}"
+        ))),
+    }
+}
+
+/// Crops the write-in areas and contests on the given side of a sheet. Areas
+/// that fall outside the timing mark grid are skipped.
+pub(crate) fn crop_areas(
+    ballot_image: &BallotImage,
+    timing_marks: &TimingMarks,
+    grid_layout: &GridLayout,
+    sheet_number: u32,
+    side: BallotSide,
+) -> AreaCrops {
+    let grid_positions = grid_layout
+        .grid_positions
+        .iter()
+        .filter(|grid_position| {
+            grid_position.sheet_number() == sheet_number && grid_position.location().side == side
+        })
+        .collect_vec();
+
+    let write_ins = grid_positions
+        .iter()
+        .filter_map(|grid_position| match grid_position {
+            GridPosition::WriteIn { write_in_area, .. } => Some((*grid_position, *write_in_area)),
+            GridPosition::Option { .. } => None,
+        })
+        .collect_vec()
+        .into_par_iter()
+        .filter_map(|(grid_position, write_in_area)| {
+            Some(WriteInAreaCrop {
+                grid_position: grid_position.clone(),
+                crop: crop_area(ballot_image, timing_marks, write_in_area)?,
+            })
+        })
+        .collect();
+
+    let contests = grid_positions
+        .iter()
+        .map(|grid_position| grid_position.contest_id())
+        .unique()
+        .filter_map(|contest_id| {
+            grid_positions
+                .iter()
+                .filter(|grid_position| grid_position.contest_id() == contest_id)
+                .map(|grid_position| grid_position.bounds())
+                .map(|bounds| SubGridRect {
+                    x: bounds.column,
+                    y: bounds.row,
+                    width: bounds.width,
+                    height: bounds.height,
+                })
+                .reduce(union)
+                .map(|area| (contest_id, area))
+        })
+        .collect_vec()
+        .into_par_iter()
+        .filter_map(|(contest_id, area)| {
+            Some(ContestAreaCrop {
+                contest_id,
+                crop: crop_area(ballot_image, timing_marks, area)?,
+            })
+        })
+        .collect();
+
+    AreaCrops {
+        write_ins,
+        contests,
+    }
+}
+
+fn crop_area(
+    ballot_image: &BallotImage,
+    timing_marks: &TimingMarks,
+    area: SubGridRect,
+) -> Option<AreaCrop> {
+    let shape = timing_marks.quadrilateral_for_area(area)?;
+    let (width, height) = crop_size(&timing_marks.geometry, area.width, area.height);
+    let image = warp_quadrilateral_to_image(ballot_image.image(), &shape, width, height);
+    Some(AreaCrop {
+        shape,
+        width,
+        height,
+        png: binarize_and_encode_png(&image, ballot_image.threshold()),
+    })
+}
+
+/// The size in pixels of a crop of an area with the given size in grid units,
+/// as if scanned at [`SCAN_PIXELS_PER_INCH`].
+fn crop_size(
+    geometry: &Geometry,
+    width: SubGridUnit,
+    height: SubGridUnit,
+) -> (PixelUnit, PixelUnit) {
+    let scale = SCAN_PIXELS_PER_INCH as f32 / geometry.pixels_per_inch as f32;
+    let to_pixels = |grid_units: SubGridUnit, pixels_per_grid_unit: f32| {
+        ((grid_units * pixels_per_grid_unit * scale).round() as PixelUnit).max(1)
+    };
+    (
+        to_pixels(
+            width,
+            geometry.horizontal_timing_mark_center_to_center_pixel_distance(),
+        ),
+        to_pixels(
+            height,
+            geometry.vertical_timing_mark_center_to_center_pixel_distance(),
+        ),
+    )
+}
+
+fn union(a: SubGridRect, b: SubGridRect) -> SubGridRect {
+    let left = a.x.min(b.x);
+    let top = a.y.min(b.y);
+    let right = (a.x + a.width).max(b.x + b.width);
+    let bottom = (a.y + a.height).max(b.y + b.height);
+    SubGridRect {
+        x: left,
+        y: top,
+        width: right - left,
+        height: bottom - top,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ballot_card::PaperInfo;
+
+    #[test]
+    fn test_crop_size_is_independent_of_scan_resolution() {
+        let geometry = PaperInfo::scanned_letter().compute_geometry();
+        assert_eq!(crop_size(&geometry, 10.0, 1.5), (480, 80));
+
+        for pixels_per_inch in [100, 150, 300] {
+            let geometry = PaperInfo::scanned_letter()
+                .with_pixels_per_inch(pixels_per_inch)
+                .compute_geometry();
+            assert_eq!(crop_size(&geometry, 10.0, 1.5), (480, 80));
+        }
+    }
+
+    #[test]
+    fn test_union() {
+        let a = SubGridRect {
+            x: 1.0,
+            y: 2.0,
+            width: 3.0,
+            height: 1.0,
+        };
+        let b = SubGridRect {
+            x: 2.0,
+            y: 4.0,
+            width: 5.0,
+            height: 0.5,
+        };
+        assert_eq!(
+            union(a, b),
+            SubGridRect {
+                x: 1.0,
+                y: 2.0,
+                width: 6.0,
+                height: 2.5,
+            }
+        );
+    }
+}
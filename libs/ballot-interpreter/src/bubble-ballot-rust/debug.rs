@@ -19,6 +19,7 @@ use crate::ballot_card::{BallotImage, Geometry};
 
 use crate::image_utils::{dark_rainbow, rainbow, VerticalStreak};
 use crate::layout::InterpretedContestLayout;
+use crate::line_artifacts::LineArtifact;
 use crate::scoring::{BubbleRegion, UnitIntervalScore};
 use crate::timing_marks::scoring::CandidateTimingMark;
 use crate::timing_marks::{Corner, TimingMarks};
@@ -28,6 +29,7 @@ use crate::{
     },
     qr_code::Detected,
     scoring::{ScoredBubbleMark, ScoredPositionAreas},
+    stray_marks::ContestStrayMarks,
 };
 
 const TOP_COLOR: Rgb<u8> = GREEN;
@@ -81,6 +83,22 @@ pub fn draw_qr_code_debug_image_mut(
                 fg,
                 bg,
             );
+            if let Some(attempt) = qr_code.attempts().last() {
+                draw_text_with_background_mut(
+                    canvas,
+                    &format!(
+                        "Enhancement: {:?} (attempt {})",
+                        attempt.enhancement,
+                        qr_code.attempts().len()
+                    ),
+                    0,
+                    60,
+                    scale,
+                    &font,
+                    fg,
+                    bg,
+                );
+            }
         }
         None => {
             draw_text_mut(
@@ -271,7 +289,11 @@ pub fn draw_timing_mark_debug_image_mut(
         let center = mark.rect().center();
         let text = format!("{i}");
         let (_, text_height) = text_size(scale, font, text.as_str());
-        draw_filled_rect_mut(canvas, *mark.rect(), LEFT_COLOR);
+        if timing_marks.inferred_marks.left.contains(&i) {
+            draw_hollow_rect_mut(canvas, *mark.rect(), LEFT_COLOR);
+        } else {
+            draw_filled_rect_mut(canvas, *mark.rect(), LEFT_COLOR);
+        }
         draw_text_mut(
             canvas,
             DARK_RED,
@@ -288,7 +310,11 @@ pub fn draw_timing_mark_debug_image_mut(
         let center = mark.rect().center();
         let text = format!("{i}");
         let (text_width, text_height) = text_size(scale, font, text.as_str());
-        draw_filled_rect_mut(canvas, *mark.rect(), RIGHT_COLOR);
+        if timing_marks.inferred_marks.right.contains(&i) {
+            draw_hollow_rect_mut(canvas, *mark.rect(), RIGHT_COLOR);
+        } else {
+            draw_filled_rect_mut(canvas, *mark.rect(), RIGHT_COLOR);
+        }
         draw_text_mut(
             canvas,
             DARK_CYAN,
@@ -302,13 +328,35 @@ pub fn draw_timing_mark_debug_image_mut(
         );
     }
 
-    draw_filled_rect_mut(canvas, *timing_marks.top_left_mark.rect(), CORNER_COLOR);
-
-    draw_filled_rect_mut(canvas, *timing_marks.top_right_mark.rect(), CORNER_COLOR);
+    for mark in &timing_marks.border_marks.top {
+        draw_filled_rect_mut(canvas, *mark.rect(), TOP_COLOR);
+    }
 
-    draw_filled_rect_mut(canvas, *timing_marks.bottom_left_mark.rect(), CORNER_COLOR);
+    for mark in &timing_marks.border_marks.bottom {
+        draw_filled_rect_mut(canvas, *mark.rect(), BOTTOM_COLOR);
+    }
 
-    draw_filled_rect_mut(canvas, *timing_marks.bottom_right_mark.rect(), CORNER_COLOR);
+    for (corner, mark) in [
+        (Corner::TopLeft, &timing_marks.top_left_mark),
+        (Corner::TopRight, &timing_marks.top_right_mark),
+        (Corner::BottomLeft, &timing_marks.bottom_left_mark),
+        (Corner::BottomRight, &timing_marks.bottom_right_mark),
+    ] {
+        if timing_marks.inferred_corner == Some(corner) {
+            draw_hollow_rect_mut(canvas, *mark.rect(), CORNER_COLOR);
+            draw_text_mut(
+                canvas,
+                CORNER_COLOR,
+                mark.rect().left(),
+                mark.rect().bottom() + 5,
+                scale,
+                font,
+                "inferred",
+            );
+        } else {
+            draw_filled_rect_mut(canvas, *mark.rect(), CORNER_COLOR);
+        }
+    }
 
     draw_cross_mut(
         canvas,
@@ -438,7 +486,34 @@ pub fn draw_timing_mark_debug_image_mut(
                 )
                 .as_str(),
             ),
+            (
+                TOP_COLOR,
+                format!(
+                    "Top ({}/{} found)",
+                    timing_marks.border_marks.top.len(),
+                    geometry.grid_size.width,
+                )
+                .as_str(),
+            ),
+            (
+                BOTTOM_COLOR,
+                format!(
+                    "Bottom ({}/{} found)",
+                    timing_marks.border_marks.bottom.len(),
+                    geometry.grid_size.width,
+                )
+                .as_str(),
+            ),
             (CORNER_COLOR, "Corners (4/4)"),
+            (
+                WHITE_RGB,
+                format!(
+                    "Warp residuals: {:.2}px RMS, {:.2}px max",
+                    timing_marks.warp.residuals().rms,
+                    timing_marks.warp.residuals().max,
+                )
+                .as_str(),
+            ),
         ],
         Point::new(140, 140),
     );
@@ -831,6 +906,49 @@ pub fn draw_contest_layouts_debug_image_mut(
     }
 }
 
+pub fn draw_stray_marks_debug_image_mut(
+    canvas: &mut RgbImage,
+    contest_layouts: &[InterpretedContestLayout],
+    contest_stray_marks: &[ContestStrayMarks],
+) {
+    draw_legend(
+        canvas,
+        &[
+            (DARK_BLUE, "Contest Bounds"),
+            (ORANGE, "Stray Mark (possible voter intent)"),
+        ],
+        Point::new(10, 10),
+    );
+
+    for contest_layout in contest_layouts {
+        draw_hollow_rect_mut(canvas, contest_layout.bounds, DARK_BLUE);
+    }
+
+    for stray_mark in contest_stray_marks
+        .iter()
+        .flat_map(|contest| &contest.marks)
+    {
+        draw_hollow_rect_mut(canvas, stray_mark.bounds, ORANGE);
+    }
+}
+
+pub fn draw_line_artifacts_debug_image_mut(canvas: &mut RgbImage, artifacts: &[LineArtifact]) {
+    draw_legend(
+        canvas,
+        &[(ORANGE, "Line Artifact (fold or streak)")],
+        Point::new(10, 10),
+    );
+
+    for artifact in artifacts {
+        draw_line_segment_mut(
+            canvas,
+            (artifact.start.x, artifact.start.y),
+            (artifact.end.x, artifact.end.y),
+            ORANGE,
+        );
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn draw_text_with_background_mut(
     canvas: &mut RgbImage,
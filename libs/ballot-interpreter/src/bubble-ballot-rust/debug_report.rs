@@ -0,0 +1,535 @@
+//! Builds a single self-contained HTML page describing the interpretation of
+//! a ballot card, as an alternative to the separate debug images written by
+//! [`ImageDebugWriter`].
+//!
+//! Each side is shown with the elements the interpreter found drawn over it
+//! in layers that can be turned on and off: timing marks, bubbles, contest
+//! layouts, write-in areas and the QR code. Hovering over a bubble shows its
+//! scores and bounds. Below the images are the mark thresholds, the QR code
+//! detection attempts the interpreter made for each side, and the result or
+//! error as JSON.
+
+use std::fmt::Write;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{GrayImage, ImageFormat};
+use types_rs::{
+    election::MarkThresholds,
+    geometry::{Quadrilateral, Rect},
+    pair::Pair,
+};
+
+use crate::{
+    interpret::{
+        Error, InterpretedBallotCard, InterpretedBallotPage, Result, SIDE_A_LABEL, SIDE_B_LABEL,
+    },
+    qr_code::{self, DetectionAttempt},
+    scoring::ScoredBubbleMark,
+};
+
+/// Overlay layers, by CSS class and label.
+const LAYERS: [(&str, &str); 5] = [
+    ("timing-marks", "Timing marks"),
+    ("bubbles", "Bubbles"),
+    ("contests", "Contest layouts"),
+    ("write-ins", "Write-in areas"),
+    ("qr-code", "QR code"),
+];
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 1em; }
+.pages { display: flex; gap: 1em; }
+.page { flex: 1; }
+.page svg { width: 100%; height: auto; border: 1px solid #ccc; }
+.layer rect, .layer polygon { fill: none; stroke-width: 3; }
+.timing-marks rect { stroke: #0a0; }
+.timing-marks rect.inferred { stroke: #f0f; }
+.bubbles rect { fill: rgba(0, 0, 0, 0); stroke: #0a0; }
+.bubbles rect.marginal { stroke: #f80; }
+.bubbles rect.marked { stroke: #d00; }
+.bubbles rect.expected { stroke: #888; stroke-dasharray: 4; }
+.contests rect { stroke: #00c; }
+.write-ins polygon { stroke: #0cc; }
+.qr-code rect { stroke: #c0c; }
+.qr-code rect.area { stroke-dasharray: 8; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 0.2em 0.5em; text-align: left; }
+pre { background: #f4f4f4; padding: 1em; overflow: auto; }
+";
+
+/// Renders the report for a ballot card given the images that were
+/// interpreted, the thresholds used to decide which bubbles are marked, and
+/// the result of [`crate::interpret::ballot_card`].
+///
+/// When interpretation succeeds, each side is shown as normalized by the
+/// interpreter so that everything it found lines up with the image.
+/// Otherwise, `side_images` are shown as given, and only the QR code searches
+/// of a side whose QR code couldn't be read are known.
+#[must_use]
+pub fn ballot_card_report(
+    side_images: Pair<&GrayImage>,
+    mark_thresholds: &MarkThresholds,
+    result: &Result<InterpretedBallotCard>,
+) -> String {
+    let pages = match result {
+        Ok(card) => Pair::new(Some(&card.front), Some(&card.back)),
+        Err(_) => Pair::default(),
+    };
+    let sides = Pair::new(SIDE_A_LABEL, SIDE_B_LABEL)
+        .zip(side_images)
+        .zip(pages)
+        .map(|((label, side_image), page)| {
+            let image = page
+                .and_then(|page| page.encoded_normalized_image.as_ref().ok())
+                .and_then(|png| image::load_from_memory_with_format(png, ImageFormat::Png).ok())
+                .map_or_else(|| side_image.clone(), image::DynamicImage::into_luma8);
+            let qr_code_searches = match (page, result) {
+                (Some(page), _) => page.qr_code_searches.as_slice(),
+                (
+                    None,
+                    Err(Error::InvalidQrCodeMetadata {
+                        label: error_label,
+                        qr_code_searches,
+                        ..
+                    }),
+                ) if error_label == label => qr_code_searches.as_slice(),
+                (None, _) => &[],
+            };
+            (label, image, page, qr_code_searches)
+        });
+    let sides: [_; 2] = <(_, _)>::from(sides).into();
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Ballot card interpretation</title>\n<style>");
+    html.push_str(STYLE);
+    for (class, _) in LAYERS {
+        let _ = writeln!(
+            html,
+            "#layer-{class}:not(:checked) ~ .pages .{class} {{ display: none; }}"
+        );
+    }
+    html.push_str("</style>\n</head>\n<body>\n");
+
+    let _ = writeln!(
+        html,
+        "<h1>Ballot card interpretation: {}</h1>",
+        match result {
+            Ok(_) => "ok".to_owned(),
+            Err(error) => escape(&format!("error: {error}")),
+        }
+    );
+
+    for (class, label) in LAYERS {
+        let _ = writeln!(
+            html,
+            "<input type=\"checkbox\" id=\"layer-{class}\" checked> \
+             <label for=\"layer-{class}\">{label}</label>"
+        );
+    }
+
+    html.push_str("<div class=\"pages\">\n");
+    for (label, image, page, qr_code_searches) in &sides {
+        let _ = writeln!(html, "<div class=\"page\">\n<h2>{}</h2>", escape(label));
+        write_page_svg(&mut html, image, *page, qr_code_searches, mark_thresholds);
+        html.push_str("</div>\n");
+    }
+    html.push_str("</div>\n");
+
+    let _ = writeln!(
+        html,
+        "<h2>Mark thresholds</h2>\n<table>\n\
+         <tr><th>Definite</th><td>{definite:.2}%</td></tr>\n\
+         <tr><th>Marginal</th><td>{marginal:.2}%</td></tr>\n</table>",
+        definite = mark_thresholds.definite * 100.0,
+        marginal = mark_thresholds.marginal * 100.0,
+    );
+
+    html.push_str("<h2>QR code detection</h2>\n");
+    for (label, _, _, qr_code_searches) in &sides {
+        let _ = writeln!(html, "<h3>{}</h3>", escape(label));
+        write_qr_code_searches(&mut html, qr_code_searches);
+    }
+
+    let json = match result {
+        Ok(card) => serde_json::to_string_pretty(card),
+        Err(error) => serde_json::to_string_pretty(error),
+    }
+    .unwrap_or_else(|error| format!("unable to serialize: {error}"));
+    let _ = writeln!(
+        html,
+        "<h2>{}</h2>\n<pre>{}</pre>",
+        if result.is_ok() { "Result" } else { "Error" },
+        escape(&json)
+    );
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn write_page_svg(
+    html: &mut String,
+    image: &GrayImage,
+    page: Option<&InterpretedBallotPage>,
+    qr_code_searches: &[qr_code::Result],
+    mark_thresholds: &MarkThresholds,
+) {
+    let (width, height) = image.dimensions();
+    let mut png = std::io::Cursor::new(Vec::new());
+    let data = match image.write_to(&mut png, ImageFormat::Png) {
+        Ok(()) => STANDARD.encode(png.into_inner()),
+        Err(_) => String::new(),
+    };
+    let _ = writeln!(
+        html,
+        "<svg viewBox=\"0 0 {width} {height}\" xmlns=\"http://www.w3.org/2000/svg\">\n\
+         <image width=\"{width}\" height=\"{height}\" href=\"data:image/png;base64,{data}\"/>"
+    );
+
+    if let Some(page) = page {
+        let timing_marks = &page.timing_marks;
+        let border_marks = &timing_marks.border_marks;
+        html.push_str("<g class=\"layer timing-marks\">\n");
+        for (marks, inferred) in [
+            (&border_marks.left, &timing_marks.inferred_marks.left),
+            (&border_marks.right, &timing_marks.inferred_marks.right),
+            (&border_marks.top, &vec![]),
+            (&border_marks.bottom, &vec![]),
+        ] {
+            for (index, mark) in marks.iter().enumerate() {
+                let class = if inferred.contains(&index) {
+                    "inferred"
+                } else {
+                    ""
+                };
+                write_rect(html, mark.rect(), class, None);
+            }
+        }
+        html.push_str("</g>\n");
+
+        html.push_str("<g class=\"layer bubbles\">\n");
+        for (grid_position, mark) in &page.marks {
+            let Some(mark) = mark else {
+                continue;
+            };
+            let class = if mark.fill_score.0 >= mark_thresholds.definite {
+                "marked"
+            } else if mark.fill_score.0 >= mark_thresholds.marginal {
+                "marginal"
+            } else {
+                ""
+            };
+            let tooltip = bubble_tooltip(
+                &grid_position.contest_id().to_string(),
+                &grid_position.option_id().to_string(),
+                mark,
+            );
+            write_rect(html, &mark.expected_bounds, "expected", None);
+            write_rect(html, &mark.matched_bounds, class, Some(&tooltip));
+        }
+        html.push_str("</g>\n");
+
+        html.push_str("<g class=\"layer contests\">\n");
+        for contest in &page.contest_layouts {
+            write_rect(
+                html,
+                &contest.bounds,
+                "",
+                Some(&format!("Contest {}", contest.contest_id)),
+            );
+        }
+        html.push_str("</g>\n");
+
+        html.push_str("<g class=\"layer write-ins\">\n");
+        for area in &page.write_ins {
+            write_polygon(
+                html,
+                &area.shape,
+                &format!(
+                    "{} / {}: {}",
+                    area.grid_position.contest_id(),
+                    area.grid_position.option_id(),
+                    area.score
+                ),
+            );
+        }
+        html.push_str("</g>\n");
+    }
+
+    html.push_str("<g class=\"layer qr-code\">\n");
+    // The searches were made in the image as normalized, so they only line up
+    // with it when interpretation succeeded.
+    if page.is_some() {
+        write_qr_code_layer(html, qr_code_searches);
+    }
+    html.push_str("</g>\n</svg>\n");
+}
+
+fn write_qr_code_layer(html: &mut String, qr_code_searches: &[qr_code::Result]) {
+    for search in qr_code_searches {
+        let detection_areas = match search {
+            Ok(qr_code) => qr_code.detection_areas(),
+            Err(error) => error.detection_areas(),
+        };
+        for area in detection_areas {
+            write_rect(html, area, "area", Some("QR code detection area"));
+        }
+        if let Ok(qr_code) = search {
+            write_rect(
+                html,
+                &qr_code.bounds(),
+                "",
+                Some(&format!(
+                    "QR code ({:?}, {:?})",
+                    qr_code.detector(),
+                    qr_code.orientation()
+                )),
+            );
+        }
+    }
+}
+
+fn bubble_tooltip(contest_id: &str, option_id: &str, mark: &ScoredBubbleMark) -> String {
+    let bounds = |rect: &Rect| {
+        format!(
+            "({}, {}) {}×{}",
+            rect.left(),
+            rect.top(),
+            rect.width(),
+            rect.height()
+        )
+    };
+    format!(
+        "{contest_id} / {option_id}\n\
+         grid: ({:.2}, {:.2})\n\
+         fill score: {}\n\
+         match score: {}\n\
+         classification: {:?}\n\
+         expected bounds: {}\n\
+         matched bounds: {}",
+        mark.location.column,
+        mark.location.row,
+        mark.fill_score,
+        mark.match_score,
+        mark.classification,
+        bounds(&mark.expected_bounds),
+        bounds(&mark.matched_bounds),
+    )
+}
+
+fn write_qr_code_searches(html: &mut String, qr_code_searches: &[qr_code::Result]) {
+    if qr_code_searches.is_empty() {
+        html.push_str("<p>No QR code searches were recorded.</p>\n");
+    }
+    for search in qr_code_searches {
+        let attempts = match search {
+            Ok(detected) => detected.attempts(),
+            Err(error) => error.attempts(),
+        };
+        if attempts.is_empty() {
+            if let Err(error) = search {
+                let _ = writeln!(html, "<p>{}</p>", escape(&error.to_string()));
+            }
+        } else {
+            write_qr_code_attempts(html, attempts);
+        }
+    }
+}
+
+fn write_qr_code_attempts(html: &mut String, attempts: &[DetectionAttempt]) {
+    html.push_str("<table>\n<tr><th>Enhancement</th><th>Result</th></tr>\n");
+    for attempt in attempts {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td></tr>",
+            attempt.enhancement.map_or_else(
+                || "none".to_owned(),
+                |enhancement| format!("{enhancement:?}")
+            ),
+            escape(attempt.error.as_deref().unwrap_or("decoded")),
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn write_rect(html: &mut String, rect: &Rect, class: &str, title: Option<&str>) {
+    let _ = write!(
+        html,
+        "<rect class=\"{class}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\">",
+        rect.left(),
+        rect.top(),
+        rect.width(),
+        rect.height()
+    );
+    if let Some(title) = title {
+        let _ = write!(html, "<title>{}</title>", escape(title));
+    }
+    html.push_str("</rect>\n");
+}
+
+fn write_polygon(html: &mut String, shape: &Quadrilateral, title: &str) {
+    let _ = writeln!(
+        html,
+        "<polygon points=\"{},{} {},{} {},{} {},{}\"><title>{}</title></polygon>",
+        shape.top_left.x,
+        shape.top_left.y,
+        shape.top_right.x,
+        shape.top_right.y,
+        shape.bottom_right.x,
+        shape.bottom_right.y,
+        shape.bottom_left.x,
+        shape.bottom_left.y,
+        escape(title)
+    );
+}
+
+/// Escapes text for use in HTML element content or attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use image::Luma;
+
+    use crate::{interpret::Error, votes::DEFAULT_MARK_THRESHOLDS};
+
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & Jerry</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_report_for_error() {
+        let image = GrayImage::from_pixel(100, 120, Luma([255]));
+        let report = ballot_card_report(
+            Pair::new(&image, &image),
+            &DEFAULT_MARK_THRESHOLDS,
+            &Err(Error::MissingTimingMarks {
+                reason: "<none>".to_owned(),
+            }),
+        );
+
+        assert_eq!(report.matches("<svg viewBox=\"0 0 100 120\"").count(), 2);
+        assert!(report.contains("error: missing timing marks: &lt;none&gt;"));
+        assert!(report.contains("&quot;type&quot;: &quot;missingTimingMarks&quot;"));
+        assert_eq!(
+            report
+                .matches("<p>No QR code searches were recorded.</p>")
+                .count(),
+            2
+        );
+        assert!(!report.contains("<g class=\"layer bubbles\">"));
+    }
+
+    #[test]
+    fn test_report_for_unreadable_qr_code() {
+        let image = GrayImage::from_pixel(800, 1000, Luma([255]));
+        let search = qr_code::detect_with_strategy(
+            &image,
+            qr_code::SearchStrategy::BubbleCorners,
+            &crate::debug::ImageDebugWriter::disabled(),
+        );
+        assert!(search.is_err());
+        let report = ballot_card_report(
+            Pair::new(&image, &image),
+            &DEFAULT_MARK_THRESHOLDS,
+            &Err(Error::InvalidQrCodeMetadata {
+                label: SIDE_B_LABEL.to_owned(),
+                message: "no QR code".to_owned(),
+                qr_code_searches: vec![search],
+            }),
+        );
+
+        assert!(report.contains("<tr><td>none</td><td>no QR code detected"));
+        assert!(report.contains("<tr><td>AdaptiveThreshold</td><td>no QR code detected"));
+        assert_eq!(
+            report
+                .matches("<p>No QR code searches were recorded.</p>")
+                .count(),
+            1
+        );
+        // The searched areas don't line up with an image that wasn't
+        // normalized, so they aren't drawn.
+        assert!(!report.contains("<rect class=\"area\""));
+    }
+
+    #[cfg(feature = "synthetic")]
+    #[test]
+    fn test_report_for_interpreted_card() {
+        use std::path::PathBuf;
+
+        use sha2::{Digest, Sha256};
+        use types_rs::{
+            ballot_card::BallotType,
+            bubble_ballot::PartialBallotHash,
+            election::{BallotStyleId, Election, PrecinctId},
+        };
+
+        use crate::{interpret, synthetic};
+
+        let bytes = std::fs::read(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../hmpb/fixtures/vx-general-election/letter-en/election.json"),
+        )
+        .unwrap();
+        let election: Election = serde_json::from_slice(&bytes).unwrap();
+        let mut ballot_hash = PartialBallotHash::default();
+        let len = ballot_hash.len();
+        ballot_hash.copy_from_slice(&Sha256::digest(&bytes)[..len]);
+
+        let (front, back) = synthetic::render_ballot_card(
+            &synthetic::BallotCard {
+                election: &election,
+                ballot_style_id: BallotStyleId::from("12".to_owned()),
+                precinct_id: PrecinctId::from("23".to_owned()),
+                sheet_number: 1,
+                ballot_hash,
+                is_test_mode: false,
+                ballot_type: BallotType::Precinct,
+            },
+            |grid_position| grid_position.option_id().to_string() == "barchi-hallaren",
+            &synthetic::Options::default(),
+        )
+        .unwrap()
+        .into();
+        let result = interpret::ballot_card(
+            front.clone(),
+            back.clone(),
+            &interpret::Options::new(election.clone(), ballot_hash),
+        );
+        assert!(result.is_ok());
+
+        let report =
+            ballot_card_report(Pair::new(&front, &back), &DEFAULT_MARK_THRESHOLDS, &result);
+
+        assert!(report.contains("<h1>Ballot card interpretation: ok</h1>"));
+        assert_eq!(report.matches("<rect class=\"marked\"").count(), 1);
+        assert!(report.contains("<title>president / barchi-hallaren\n"));
+        assert!(report.contains("<tr><th>Definite</th><td>7.00%</td></tr>"));
+        assert_eq!(
+            report
+                .matches("<tr><td>none</td><td>decoded</td></tr>")
+                .count(),
+            2
+        );
+        assert_eq!(report.matches("<title>QR code (").count(), 2);
+        assert!(report.contains("<h2>Result</h2>"));
+    }
+}
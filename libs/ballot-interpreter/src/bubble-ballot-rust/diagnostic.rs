@@ -5,7 +5,7 @@ use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use types_rs::geometry::Rect;
 
 use crate::{
-    ballot_card::{ballot_scan_bubble_image, BallotImage},
+    ballot_card::{BallotImage, BubbleTemplate, SCAN_PIXELS_PER_INCH},
     debug::draw_diagnostic_cells,
     image_utils::{count_pixels, threshold, BLACK},
 };
@@ -74,8 +74,15 @@ fn inspect_cells(ballot_image: &BallotImage, cells: &[Rect]) -> (Vec<Rect>, Vec<
     (passed_cells, failed_cells)
 }
 
-pub fn blank_paper(img: GrayImage, debug_path: Option<PathBuf>) -> bool {
-    let bubble_img = ballot_scan_bubble_image();
+/// Checks that `img` is a scan of blank paper by looking for ink in cells the
+/// size of `bubble_template`, so that any smudge or streak large enough to be
+/// mistaken for a mark on the ballots being scanned fails the check.
+pub fn blank_paper(
+    img: GrayImage,
+    bubble_template: &BubbleTemplate,
+    debug_path: Option<PathBuf>,
+) -> bool {
+    let bubble_img = bubble_template.at_pixels_per_inch(SCAN_PIXELS_PER_INCH);
     let cell_width = bubble_img.width();
     let cell_height = bubble_img.height();
 
@@ -134,7 +141,11 @@ mod test {
                         .ok()
                         .map(DynamicImage::into_luma8)
                         .unwrap();
-                    assert_eq!(blank_paper(img, None), $expected, "image path: {path:?}");
+                    assert_eq!(
+                        blank_paper(img, &BubbleTemplate::default(), None),
+                        $expected,
+                        "image path: {path:?}"
+                    );
                 }
             }
         };
@@ -170,6 +181,6 @@ mod test {
             .ok()
             .map(DynamicImage::into_luma8)
             .unwrap();
-        assert!(!blank_paper(img, None));
+        assert!(!blank_paper(img, &BubbleTemplate::default(), None));
     }
 }
@@ -12,6 +12,7 @@ use crate::ballot_card::BallotImage;
 use crate::{debug, scoring::UnitIntervalScore};
 
 pub const BLACK: Luma<u8> = Luma([0]);
+pub const WHITE: Luma<u8> = Luma([255]);
 pub const WHITE_RGB: Rgb<u8> = Rgb([255, 255, 255]);
 pub const RED: Rgb<u8> = Rgb([255, 0, 0]);
 pub const DARK_RED: Rgb<u8> = Rgb([127, 0, 0]);
@@ -192,6 +193,104 @@ pub(crate) fn crop_to_image(
     GrayImage::from_vec(width, height, out).expect("buffer length matches dimensions")
 }
 
+/// Resamples the area of `image` inside `shape` into a new image of the given
+/// size, undoing any rotation, skew, or perspective so the shape's corners
+/// become the corners of the new image. Uses bilinear interpolation, and
+/// treats anything outside `image` as white.
+pub fn warp_quadrilateral_to_image(
+    image: &GrayImage,
+    shape: &Quadrilateral,
+    width: PixelUnit,
+    height: PixelUnit,
+) -> GrayImage {
+    let projection = SquareToQuadrilateral::new(shape);
+    let sample = |x: f32, y: f32| -> f32 {
+        let pixel = |x: i64, y: i64| -> f32 {
+            if x < 0 || y < 0 || x >= i64::from(image.width()) || y >= i64::from(image.height()) {
+                f32::from(u8::MAX)
+            } else {
+                f32::from(image.get_pixel(x as u32, y as u32)[0])
+            }
+        };
+        // Pixel `(x, y)` covers `[x, x + 1)`, so its center is at `x + 0.5`.
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let top = pixel(x0, y0) * (1.0 - fx) + pixel(x0 + 1, y0) * fx;
+        let bottom = pixel(x0, y0 + 1) * (1.0 - fx) + pixel(x0 + 1, y0 + 1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    };
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let u = (x as f32 + 0.5) / width as f32;
+        let v = (y as f32 + 0.5) / height as f32;
+        let (source_x, source_y) = projection.map(u, v);
+        Luma([sample(source_x, source_y)
+            .round()
+            .clamp(0.0, f32::from(u8::MAX)) as u8])
+    })
+}
+
+/// The projective transform taking the unit square to a quadrilateral, with
+/// `(0, 0)`, `(1, 0)`, `(1, 1)`, and `(0, 1)` mapping to the top left, top
+/// right, bottom right, and bottom left corners respectively.
+///
+/// See Heckbert, "Fundamentals of Texture Mapping and Image Warping" (1989),
+/// section 2.2.3.
+struct SquareToQuadrilateral {
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    e: f32,
+    f: f32,
+    g: f32,
+    h: f32,
+}
+
+impl SquareToQuadrilateral {
+    fn new(shape: &Quadrilateral) -> Self {
+        let [(x0, y0), (x1, y1), (x2, y2), (x3, y3)] = [
+            shape.top_left,
+            shape.top_right,
+            shape.bottom_right,
+            shape.bottom_left,
+        ]
+        .map(|point| (point.x, point.y));
+
+        let (sx, sy) = (x0 - x1 + x2 - x3, y0 - y1 + y2 - y3);
+        let (dx1, dy1, dx2, dy2) = (x1 - x2, y1 - y2, x3 - x2, y3 - y2);
+        let det = dx1 * dy2 - dx2 * dy1;
+
+        let (g, h) = if (sx == 0.0 && sy == 0.0) || det == 0.0 {
+            // A parallelogram (or degenerate shape), so the transform is affine.
+            (0.0, 0.0)
+        } else {
+            ((sx * dy2 - dx2 * sy) / det, (dx1 * sy - sx * dy1) / det)
+        };
+
+        Self {
+            a: x1 - x0 + g * x1,
+            b: x3 - x0 + h * x3,
+            c: x0,
+            d: y1 - y0 + g * y1,
+            e: y3 - y0 + h * y3,
+            f: y0,
+            g,
+            h,
+        }
+    }
+
+    fn map(&self, u: f32, v: f32) -> (f32, f32) {
+        let w = self.g * u + self.h * v + 1.0;
+        (
+            (self.a * u + self.b * v + self.c) / w,
+            (self.d * u + self.e * v + self.f) / w,
+        )
+    }
+}
+
 /// Finds the inset of a scanned document in an image such that each side of the
 /// inset has more than `min_ratio_above_threshold` of its pixels above the
 /// given threshold.
@@ -485,8 +584,13 @@ pub(crate) fn histogram(pixels: &[u8]) -> [u32; 256] {
 
 /// Computes Otsu's threshold for a grayscale image.
 pub(crate) fn otsu_level(image: &GrayImage) -> u8 {
-    let hist = histogram(image.as_raw());
-    let total = f64::from(image.width()) * f64::from(image.height());
+    otsu_threshold_index(&histogram(image.as_raw())) as u8
+}
+
+/// Computes Otsu's threshold for a histogram of any number of bins, i.e. the
+/// index of the last bin in the lower class.
+pub(crate) fn otsu_threshold_index(hist: &[u32]) -> usize {
+    let total: f64 = hist.iter().map(|&c| f64::from(c)).sum();
     let sum: f64 = hist
         .iter()
         .enumerate()
@@ -495,7 +599,7 @@ pub(crate) fn otsu_level(image: &GrayImage) -> u8 {
     let mut sum_b = 0.0f64;
     let mut w_b = 0.0f64;
     let mut max_var = 0.0f64;
-    let mut threshold = 0u8;
+    let mut threshold = 0;
     for (t, &count) in hist.iter().enumerate() {
         w_b += f64::from(count);
         if w_b == 0.0 {
@@ -511,7 +615,7 @@ pub(crate) fn otsu_level(image: &GrayImage) -> u8 {
         let var = w_b * w_f * (m_b - m_f).powi(2);
         if var > max_var {
             max_var = var;
-            threshold = t as u8;
+            threshold = t;
         }
     }
     threshold
@@ -757,4 +861,63 @@ mod test {
             })
         );
     }
+
+    fn quadrilateral(
+        top_left: (f32, f32),
+        top_right: (f32, f32),
+        bottom_left: (f32, f32),
+        bottom_right: (f32, f32),
+    ) -> Quadrilateral {
+        let point = |(x, y)| types_rs::geometry::Point::new(x, y);
+        Quadrilateral {
+            top_left: point(top_left),
+            top_right: point(top_right),
+            bottom_left: point(bottom_left),
+            bottom_right: point(bottom_right),
+        }
+    }
+
+    #[test]
+    fn test_warp_quadrilateral_to_image_axis_aligned() {
+        let image = GrayImage::from_fn(40, 30, |x, y| Luma([(x * 5 + y) as u8]));
+        let warped = warp_quadrilateral_to_image(
+            &image,
+            &quadrilateral((10.0, 5.0), (30.0, 5.0), (10.0, 25.0), (30.0, 25.0)),
+            20,
+            20,
+        );
+        assert_eq!(warped, crop_to_image(&image, 10, 5, 20, 20));
+    }
+
+    #[test]
+    fn test_warp_quadrilateral_to_image_rotated() {
+        // A shape whose top edge runs down the right side of the area, as if
+        // the page were rotated 90° clockwise, so the warped image is the area
+        // rotated back counterclockwise.
+        let image = GrayImage::from_fn(40, 30, |x, y| Luma([(x * 5 + y) as u8]));
+        let warped = warp_quadrilateral_to_image(
+            &image,
+            &quadrilateral((30.0, 5.0), (30.0, 25.0), (10.0, 5.0), (10.0, 25.0)),
+            20,
+            20,
+        );
+        assert_eq!(
+            warped,
+            image::imageops::rotate270(&crop_to_image(&image, 10, 5, 20, 20))
+        );
+    }
+
+    #[test]
+    fn test_warp_quadrilateral_to_image_outside_is_white() {
+        let image = GrayImage::new(10, 10);
+        let warped = warp_quadrilateral_to_image(
+            &image,
+            &quadrilateral((10.0, 0.0), (20.0, 0.0), (10.0, 10.0), (20.0, 10.0)),
+            10,
+            10,
+        );
+        // The first column samples halfway between the image's last column
+        // and the white beyond it.
+        assert!(warped.pixels().skip(1).step_by(10).all(|p| p[0] == u8::MAX));
+    }
 }
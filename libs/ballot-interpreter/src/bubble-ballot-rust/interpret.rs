@@ -4,31 +4,44 @@ use std::fmt::Display;
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use image::GrayImage;
+use image::{DynamicImage, GrayImage, Luma};
 use serde::Serialize;
 use serde_with::DeserializeFromStr;
-use types_rs::ballot_card::BallotSide;
+use types_rs::ballot_card::{BallotSide, PaperSize};
 use types_rs::bubble_ballot::{self, Metadata, MetadataMismatch, PartialBallotHash};
-use types_rs::election::{ContestId, Election};
+use types_rs::election::{ContestId, Election, GridLayout, MarkThresholds};
 use types_rs::geometry::PixelPosition;
 use types_rs::geometry::{PixelUnit, Size, SubGridUnit};
 use types_rs::pair::Pair;
 
-use crate::ballot_card::ballot_scan_bubble_image;
 use crate::ballot_card::BallotCard;
 use crate::ballot_card::BallotPage;
+use crate::ballot_card::BubbleTemplate;
 use crate::ballot_card::Geometry;
 use crate::ballot_card::Orientation;
 use crate::ballot_card::PaperInfo;
+use crate::ballot_card::SCAN_PIXELS_PER_INCH;
+use crate::ballot_card::SUPPORTED_SCAN_PIXELS_PER_INCH;
+use crate::crops::AreaCrops;
 use crate::debug::draw_timing_mark_debug_image_mut;
 use crate::image_utils::binarize_and_encode_png;
+use crate::image_utils::detect_vertical_streaks;
 use crate::image_utils::Inset;
+use crate::image_utils::VerticalStreak;
 use crate::layout::InterpretedContestLayout;
+use crate::qr_code;
+use crate::quality::QualityReport;
 use crate::scoring::ScoredBubbleMarks;
 use crate::scoring::ScoredPositionAreas;
 use crate::scoring::UnitIntervalScore;
+use crate::stray_marks::ContestStrayMarks;
+use crate::style_inference::{infer_ballot_style, infer_page_ballot_style, InferredBallotStyle};
+use crate::timing_marks::metadata::TimingMarkMetadata;
 use crate::timing_marks::TimingMarks;
 use crate::timing_marks::{self, BallotPageMetadata, DefaultForGeometry};
+use crate::votes::{
+    derive_votes, flag_possible_voter_intent, BallotCardVotes, DEFAULT_MARK_THRESHOLDS,
+};
 
 /// Default maximum cumulative width of vertical streaks in pixels.
 /// This value must match `DEFAULT_MAX_CUMULATIVE_STREAK_WIDTH` in `libs/types/src/system_settings.ts`
@@ -46,23 +59,37 @@ pub struct Options {
     /// callers slice the full 32-byte election hash before constructing
     /// `Options`.
     pub expected_ballot_hash: PartialBallotHash,
-    pub bubble_template: &'static GrayImage,
     pub debug_side_a_base: Option<PathBuf>,
     pub debug_side_b_base: Option<PathBuf>,
-    pub write_in_scoring: WriteInScoring,
-    pub vertical_streak_detection: VerticalStreakDetection,
-    pub minimum_detected_scale: Option<UnitIntervalScore>,
-    pub max_cumulative_streak_width: PixelUnit,
-    pub retry_streak_width_threshold: PixelUnit,
-    pub metadata_source: MetadataSource,
+    /// How to interpret the card, as for a [`ScanInterpreter`].
+    pub scan: ScanInterpreterOptions,
 }
 
-#[derive(Debug, Clone)]
+impl Options {
+    /// Options for interpreting ballots of `election` the way
+    /// [`ScanInterpreterOptions::default`] does, without debug images.
+    #[must_use]
+    pub fn new(election: Election, expected_ballot_hash: PartialBallotHash) -> Self {
+        Self {
+            election,
+            expected_ballot_hash,
+            debug_side_a_base: None,
+            debug_side_b_base: None,
+            scan: ScanInterpreterOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, DeserializeFromStr)]
 pub enum MetadataSource {
-    /// Detect and decode the QR codes printed on the ballot. This is the only
-    /// option available in production.
+    /// Detect and decode the QR codes printed on the ballot.
+    #[default]
     QrCode,
 
+    /// Decode the metadata `AccuVote` ballots, which have no QR code, encode in
+    /// their bottom row of timing marks. See [`timing_marks::metadata`].
+    TimingMarks,
+
     /// Use the provided metadata directly, skipping QR code detection and
     /// decoding entirely. This is only meant for tests that use field-captured
     /// fixtures whose QR codes predate the current metadata encoding and can
@@ -71,58 +98,228 @@ pub enum MetadataSource {
     Provided(Pair<(Metadata, Orientation)>),
 }
 
-#[derive(Debug, Clone, Copy, DeserializeFromStr, PartialEq, Default)]
-pub enum VerticalStreakDetection {
-    #[default]
+impl Display for MetadataSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::QrCode => write!(f, "qr-code"),
+            Self::TimingMarks => write!(f, "timing-marks"),
+            #[cfg(test)]
+            Self::Provided(_) => write!(f, "provided"),
+        }
+    }
+}
+
+impl FromStr for MetadataSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "qr-code" => Ok(Self::QrCode),
+            "timing-marks" => Ok(Self::TimingMarks),
+            _ => Err(format!("Unexpected metadata source: {s}")),
+        }
+    }
+}
+
+/// Whether an optional step of interpretation is turned on. Written as
+/// `enabled` or `disabled` on the command line.
+#[derive(Debug, Clone, Copy, DeserializeFromStr, PartialEq, Eq)]
+pub enum Toggle {
     Enabled,
     Disabled,
 }
 
-impl Display for VerticalStreakDetection {
+impl Toggle {
+    #[must_use]
+    pub const fn is_enabled(self) -> bool {
+        matches!(self, Self::Enabled)
+    }
+}
+
+impl From<bool> for Toggle {
+    fn from(enabled: bool) -> Self {
+        if enabled {
+            Self::Enabled
+        } else {
+            Self::Disabled
+        }
+    }
+}
+
+impl Display for Toggle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Enabled => write!(f, "enabled",),
+            Self::Enabled => write!(f, "enabled"),
             Self::Disabled => write!(f, "disabled"),
         }
     }
 }
 
-impl FromStr for VerticalStreakDetection {
+impl FromStr for Toggle {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "enabled" => Ok(Self::Enabled),
             "disabled" => Ok(Self::Disabled),
-            _ => Err(format!("Unexpected vertical streak detection setting: {s}")),
+            _ => Err(format!(
+                "Unexpected setting: {s} (expected enabled or disabled)"
+            )),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, DeserializeFromStr, PartialEq)]
-pub enum WriteInScoring {
-    Enabled,
-    Disabled,
+/// Determines which scan densities ballot images may have.
+#[derive(Debug, Clone, Copy, DeserializeFromStr, PartialEq, Eq, Default)]
+pub enum ScanResolution {
+    /// Infer the density from the image dimensions, accepting any of
+    /// [`SUPPORTED_SCAN_PIXELS_PER_INCH`].
+    #[default]
+    Detect,
+
+    /// Only accept images scanned at this many pixels per inch.
+    PixelsPerInch(PixelUnit),
+}
+
+impl ScanResolution {
+    /// Gets the paper infos that images of any of `paper_sizes` may match.
+    #[must_use]
+    pub fn paper_infos(self, paper_sizes: &[PaperSize]) -> Vec<PaperInfo> {
+        match self {
+            Self::Detect => PaperInfo::scanned_at(paper_sizes, &SUPPORTED_SCAN_PIXELS_PER_INCH),
+            Self::PixelsPerInch(pixels_per_inch) => {
+                PaperInfo::scanned_at(paper_sizes, &[pixels_per_inch])
+            }
+        }
+    }
 }
 
-impl Display for WriteInScoring {
+impl Display for ScanResolution {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Enabled => write!(f, "enabled"),
-            Self::Disabled => write!(f, "disabled"),
+            Self::Detect => write!(f, "detect"),
+            Self::PixelsPerInch(pixels_per_inch) => write!(f, "{pixels_per_inch}"),
+        }
+    }
+}
+
+impl FromStr for ScanResolution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "detect" {
+            return Ok(Self::Detect);
+        }
+
+        match s.parse::<PixelUnit>() {
+            Ok(pixels_per_inch) if SUPPORTED_SCAN_PIXELS_PER_INCH.contains(&pixels_per_inch) => {
+                Ok(Self::PixelsPerInch(pixels_per_inch))
+            }
+            _ => Err(format!("Unexpected scan resolution setting: {s}")),
+        }
+    }
+}
+
+/// How to turn a color scan into the grayscale image that timing marks are
+/// found in and bubbles are scored against. Picking a single channel lets
+/// colored ink drop out or stand out: red and pink ink is nearly as bright as
+/// the paper in the red channel and darkest in the green channel.
+#[derive(Debug, Clone, Copy, DeserializeFromStr, PartialEq, Default)]
+pub enum ChannelMix {
+    /// Perceived brightness, as a grayscale scanner would record it.
+    #[default]
+    Luma,
+
+    /// Only the red channel, dropping out red and pink ink.
+    Red,
+
+    /// Only the green channel.
+    Green,
+
+    /// Only the blue channel.
+    Blue,
+
+    /// A weighted sum of the red, green and blue channels. The weights are
+    /// scaled to add up to 1 when applied. Use [`ChannelMix::weighted`] to
+    /// build one from untrusted weights.
+    Weighted { red: f32, green: f32, blue: f32 },
+}
+
+impl ChannelMix {
+    /// Builds a weighted mix, or returns `None` unless every weight is
+    /// non-negative and their sum is positive and finite, since the weights
+    /// are divided by their sum.
+    #[must_use]
+    pub fn weighted(red: f32, green: f32, blue: f32) -> Option<Self> {
+        let total = red + green + blue;
+        ([red, green, blue].iter().all(|weight| *weight >= 0.0) && total.is_finite() && total > 0.0)
+            .then_some(Self::Weighted { red, green, blue })
+    }
+
+    /// Converts `image` to grayscale by mixing its channels. Grayscale images
+    /// are returned as is.
+    #[must_use]
+    pub fn to_gray(self, image: DynamicImage) -> GrayImage {
+        if let DynamicImage::ImageLuma8(image) = image {
+            return image;
+        }
+
+        let [red, green, blue] = match self {
+            Self::Luma => return image.into_luma8(),
+            Self::Red => [1.0, 0.0, 0.0],
+            Self::Green => [0.0, 1.0, 0.0],
+            Self::Blue => [0.0, 0.0, 1.0],
+            Self::Weighted { red, green, blue } => {
+                if Self::weighted(red, green, blue).is_none() {
+                    // Dividing by a zero or infinite sum would turn every
+                    // pixel black, so fall back to luma.
+                    return image.into_luma8();
+                }
+                let total = red + green + blue;
+                [red / total, green / total, blue / total]
+            }
+        };
+        let image = image.into_rgb8();
+        GrayImage::from_fn(image.width(), image.height(), |x, y| {
+            let [r, g, b] = image.get_pixel(x, y).0.map(f32::from);
+            Luma([blue.mul_add(b, red.mul_add(r, green * g)).round() as u8])
+        })
+    }
+}
+
+impl Display for ChannelMix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Luma => write!(f, "luma"),
+            Self::Red => write!(f, "red"),
+            Self::Green => write!(f, "green"),
+            Self::Blue => write!(f, "blue"),
+            Self::Weighted { red, green, blue } => write!(f, "{red},{green},{blue}"),
         }
     }
 }
 
-impl FromStr for WriteInScoring {
+impl FromStr for ChannelMix {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "enabled" => Ok(Self::Enabled),
-            "disabled" => Ok(Self::Disabled),
-            _ => Err(format!("Unexpected write-in scoring setting: {s}")),
+            "luma" => return Ok(Self::Luma),
+            "red" => return Ok(Self::Red),
+            "green" => return Ok(Self::Green),
+            "blue" => return Ok(Self::Blue),
+            _ => {}
+        }
+
+        let weights = s
+            .split(',')
+            .map(|weight| weight.trim().parse::<f32>())
+            .collect::<Result<Vec<_>, _>>();
+        match weights.as_deref() {
+            Ok(&[red, green, blue]) => Self::weighted(red, green, blue),
+            _ => None,
         }
+        .ok_or_else(|| format!("Unexpected channel mix setting: {s}"))
     }
 }
 
@@ -139,6 +336,20 @@ pub struct InterpretedBallotPage {
     #[serde(skip_serializing)]
     pub encoded_normalized_image: image::ImageResult<Vec<u8>>,
     pub contest_layouts: Vec<InterpretedContestLayout>,
+    /// Image-health measurements for this page, e.g. skew and stretch.
+    pub quality: QualityReport,
+    /// Crops of the write-in areas and contests on this page, if
+    /// [`ScanInterpreterOptions::area_cropping`] is enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crops: Option<AreaCrops>,
+    /// Contests on this page with ink outside their bubbles and write-in
+    /// areas, if [`ScanInterpreterOptions::stray_mark_detection`] is enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stray_marks: Option<Vec<ContestStrayMarks>>,
+    /// Every search made for this page's QR code, in order, for debugging.
+    /// Empty if its metadata didn't come from a QR code.
+    #[serde(skip)]
+    pub qr_code_searches: Vec<qr_code::Result>,
 }
 
 impl std::fmt::Debug for InterpretedBallotPage {
@@ -149,6 +360,9 @@ impl std::fmt::Debug for InterpretedBallotPage {
             .field("marks", &self.marks)
             .field("write_ins", &self.write_ins)
             .field("contest_layouts", &self.contest_layouts)
+            .field("quality", &self.quality)
+            .field("crops", &self.crops)
+            .field("stray_marks", &self.stray_marks)
             .finish_non_exhaustive()
     }
 }
@@ -157,6 +371,30 @@ impl std::fmt::Debug for InterpretedBallotPage {
 pub struct InterpretedBallotCard {
     pub front: InterpretedBallotPage,
     pub back: InterpretedBallotPage,
+    /// Votes and adjudication reasons derived from both pages' marks using
+    /// [`ScanInterpreterOptions::mark_thresholds`].
+    pub votes: BallotCardVotes,
+    /// How the ballot style was identified, if it had to be inferred because
+    /// neither QR code could be read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inferred_ballot_style: Option<InferredBallotStyle>,
+}
+
+/// The result of interpreting a single-sided ballot card, i.e. one for which
+/// only one side was scanned. The side that wasn't scanned is treated as blank.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterpretedSimplexBallotCard {
+    pub page: InterpretedBallotPage,
+    /// Which side of the sheet `page` is, according to its QR code metadata.
+    pub side: BallotSide,
+    /// Votes and adjudication reasons derived from `page`'s marks, with every
+    /// bubble on the other side of the sheet counted as unmarked.
+    pub votes: BallotCardVotes,
+    /// How the ballot style was identified, if it had to be inferred because
+    /// the QR code couldn't be read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inferred_ballot_style: Option<InferredBallotStyle>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -174,7 +412,16 @@ pub enum Error {
     BorderInsetNotFound { label: String },
 
     #[error("invalid QR code metadata for {label}: {message}")]
-    InvalidQrCodeMetadata { label: String, message: String },
+    InvalidQrCodeMetadata {
+        label: String,
+        message: String,
+        /// Every search made for the page's QR code, for debugging.
+        #[serde(skip)]
+        qr_code_searches: Vec<qr_code::Result>,
+    },
+
+    #[error("invalid timing mark metadata for {label}: {message}")]
+    InvalidTimingMarkMetadata { label: String, message: String },
 
     #[error(
         "mismatched ballot card geometries: {SIDE_A_LABEL}: {side_a:?}, {SIDE_B_LABEL}: {side_b:?}"
@@ -247,6 +494,16 @@ pub enum Error {
         x_coordinates: Vec<PixelPosition>,
     },
 
+    #[error(
+        "fold lines or streaks cross bubbles on {label} in {} contest(s)",
+        contest_ids.len()
+    )]
+    #[serde(rename_all = "camelCase")]
+    LineArtifactsDetected {
+        label: String,
+        contest_ids: Vec<ContestId>,
+    },
+
     #[error("invalid election: {message}")]
     InvalidElection { message: String },
 }
@@ -267,9 +524,14 @@ impl Error {
                 | Self::MissingGridLayout { .. }
                 | Self::CouldNotComputeLayout { .. }
                 | Self::GridPositionOutsideTimingMarkGrid { .. }
+                // Line artifacts are only checked against bubbles that were
+                // located using the decoded grid layout.
+                | Self::LineArtifactsDetected { .. }
                 // InvalidScale is only reachable after find_timing_marks()
                 // succeeds, which requires bubble-ballot-specific timing marks.
                 | Self::InvalidScale { .. }
+                // As is reading metadata from the timing marks.
+                | Self::InvalidTimingMarkMetadata { .. }
         )
     }
 }
@@ -279,15 +541,75 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 pub const SIDE_A_LABEL: &str = "side A";
 pub const SIDE_B_LABEL: &str = "side B";
 
+/// How a [`ScanInterpreter`] interprets ballots, apart from the election and
+/// ballot hash. The defaults match those of the TypeScript interpreter.
+#[derive(Debug, Clone)]
+pub struct ScanInterpreterOptions {
+    pub write_in_scoring: Toggle,
+    pub vertical_streak_detection: Toggle,
+    pub minimum_detected_scale: Option<UnitIntervalScore>,
+    /// Widest the vertical streaks on a page may be in total, in pixels of a
+    /// [`SCAN_PIXELS_PER_INCH`] scan. Scaled to the density of each page.
+    pub max_cumulative_streak_width: PixelUnit,
+    /// Like [`Self::max_cumulative_streak_width`], but for when the timing
+    /// marks can't be found.
+    pub retry_streak_width_threshold: PixelUnit,
+    /// Thresholds used to turn bubble fill scores into votes. Defaults to
+    /// [`DEFAULT_MARK_THRESHOLDS`], though callers typically take these from
+    /// system settings, falling back to
+    /// [`election_mark_thresholds`](crate::votes::election_mark_thresholds).
+    pub mark_thresholds: MarkThresholds,
+    /// Density of the images to interpret. The bubble template is scaled to
+    /// match whatever density the images turn out to be.
+    pub scan_resolution: ScanResolution,
+    /// Whether to produce perspective-corrected crops of each write-in area
+    /// and contest along with each interpreted page.
+    pub area_cropping: Toggle,
+    /// Whether to look for ink within contests that is not on a bubble or in
+    /// a write-in area, flagging those contests for adjudication.
+    pub stray_mark_detection: Toggle,
+    /// Whether to look for horizontal and angled lines left by folds or
+    /// streaks, rejecting the card if any of them cross a bubble.
+    pub line_artifact_detection: Toggle,
+    /// How to combine the channels of color images into the grayscale image
+    /// that is thresholded. Has no effect on grayscale images.
+    pub channel_mix: ChannelMix,
+    /// What a blank bubble looks like, scaled to the density of each scan
+    /// and used to locate and score every bubble. Elections whose ballots use
+    /// bubbles of a different size or shape than ours need their own.
+    pub bubble_template: BubbleTemplate,
+    /// Whether to identify the ballot style and sheet of a card from where
+    /// its bubbles are printed when neither of its QR codes can be read. Cards
+    /// identified this way are held for adjudication.
+    pub ballot_style_inference: Toggle,
+    pub metadata_source: MetadataSource,
+}
+
+impl Default for ScanInterpreterOptions {
+    fn default() -> Self {
+        Self {
+            write_in_scoring: Toggle::Disabled,
+            vertical_streak_detection: Toggle::Enabled,
+            minimum_detected_scale: None,
+            max_cumulative_streak_width: DEFAULT_MAX_CUMULATIVE_STREAK_WIDTH,
+            retry_streak_width_threshold: DEFAULT_RETRY_STREAK_WIDTH_THRESHOLD,
+            mark_thresholds: DEFAULT_MARK_THRESHOLDS,
+            scan_resolution: ScanResolution::default(),
+            area_cropping: Toggle::Disabled,
+            stray_mark_detection: Toggle::Disabled,
+            line_artifact_detection: Toggle::Disabled,
+            channel_mix: ChannelMix::default(),
+            bubble_template: BubbleTemplate::default(),
+            ballot_style_inference: Toggle::Disabled,
+            metadata_source: MetadataSource::default(),
+        }
+    }
+}
+
 pub struct ScanInterpreter {
     election: Election,
     expected_ballot_hash: PartialBallotHash,
-    write_in_scoring: WriteInScoring,
-    vertical_streak_detection: VerticalStreakDetection,
-    bubble_template_image: &'static GrayImage,
-    minimum_detected_scale: Option<UnitIntervalScore>,
-    max_cumulative_streak_width: PixelUnit,
-    retry_streak_width_threshold: PixelUnit,
+    options: ScanInterpreterOptions,
 }
 
 impl ScanInterpreter {
@@ -297,28 +619,20 @@ impl ScanInterpreter {
     /// [`PARTIAL_BALLOT_HASH_BYTE_LENGTH`] bytes (see
     /// [`bubble_ballot::PartialBallotHash`]).
     #[must_use]
-    pub fn new(
+    pub const fn new(
         election: Election,
         expected_ballot_hash: PartialBallotHash,
-        write_in_scoring: WriteInScoring,
-        vertical_streak_detection: VerticalStreakDetection,
-        minimum_detected_scale: Option<UnitIntervalScore>,
-        max_cumulative_streak_width: PixelUnit,
-        retry_streak_width_threshold: PixelUnit,
+        options: ScanInterpreterOptions,
     ) -> Self {
         Self {
             election,
             expected_ballot_hash,
-            write_in_scoring,
-            vertical_streak_detection,
-            bubble_template_image: ballot_scan_bubble_image(),
-            minimum_detected_scale,
-            max_cumulative_streak_width,
-            retry_streak_width_threshold,
+            options,
         }
     }
 
-    /// Interprets a pair of ballot card images.
+    /// Interprets a pair of ballot card images, which may be grayscale or
+    /// color.
     ///
     /// # Errors
     ///
@@ -326,43 +640,64 @@ impl ScanInterpreter {
     #[allow(clippy::result_large_err)]
     pub fn interpret<P: Into<Option<PathBuf>>>(
         &self,
-        side_a_image: GrayImage,
-        side_b_image: GrayImage,
+        side_a_image: impl Into<DynamicImage>,
+        side_b_image: impl Into<DynamicImage>,
         debug_side_a_base: P,
         debug_side_b_base: P,
     ) -> Result<InterpretedBallotCard> {
-        let options = Options {
+        let options = self.options(debug_side_a_base.into(), debug_side_b_base.into());
+        ballot_card(side_a_image, side_b_image, &options)
+    }
+
+    /// Interprets a single ballot page image as a single-sided ballot card.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image could not be interpreted.
+    #[allow(clippy::result_large_err)]
+    pub fn interpret_simplex<P: Into<Option<PathBuf>>>(
+        &self,
+        side_image: impl Into<DynamicImage>,
+        debug_side_base: P,
+    ) -> Result<InterpretedSimplexBallotCard> {
+        let options = self.options(debug_side_base.into(), None);
+        ballot_page(side_image, &options)
+    }
+
+    fn options(
+        &self,
+        debug_side_a_base: Option<PathBuf>,
+        debug_side_b_base: Option<PathBuf>,
+    ) -> Options {
+        Options {
             election: self.election.clone(),
             expected_ballot_hash: self.expected_ballot_hash,
-            bubble_template: self.bubble_template_image,
-            debug_side_a_base: debug_side_a_base.into(),
-            debug_side_b_base: debug_side_b_base.into(),
-            write_in_scoring: self.write_in_scoring,
-            vertical_streak_detection: self.vertical_streak_detection,
-            minimum_detected_scale: self.minimum_detected_scale,
-            max_cumulative_streak_width: self.max_cumulative_streak_width,
-            retry_streak_width_threshold: self.retry_streak_width_threshold,
-            metadata_source: MetadataSource::QrCode,
-        };
-        ballot_card(side_a_image, side_b_image, &options)
+            debug_side_a_base,
+            debug_side_b_base,
+            scan: self.options.clone(),
+        }
     }
 }
 
-type ScoringPairs = (
-    Pair<ScoredBubbleMarks>,
-    Pair<Vec<InterpretedContestLayout>>,
-    Pair<ScoredPositionAreas>,
+type PageScoring = (
+    ScoredBubbleMarks,
+    Vec<InterpretedContestLayout>,
+    ScoredPositionAreas,
+    Option<AreaCrops>,
+    QualityReport,
+    Option<Vec<ContestStrayMarks>>,
 );
 
-/// Interpret a ballot card image.
+/// Interpret a ballot card image. Color images are converted to grayscale
+/// using [`ScanInterpreterOptions::channel_mix`] before anything else is done with them.
 ///
 /// # Errors
 ///
 /// Returns an error if the ballot card could not be interpreted.
 #[allow(clippy::too_many_lines, clippy::result_large_err)]
 pub fn ballot_card(
-    side_a_image: GrayImage,
-    side_b_image: GrayImage,
+    side_a_image: impl Into<DynamicImage>,
+    side_b_image: impl Into<DynamicImage>,
     options: &Options,
 ) -> Result<InterpretedBallotCard> {
     // v4.1+ stores ballot geometry as `ballotPositions` on each ballot style;
@@ -373,228 +708,633 @@ pub fn ballot_card(
             message: "election has no ballot positions".to_owned(),
         });
     }
+    let paper_infos = options
+        .scan
+        .scan_resolution
+        .paper_infos(&options.election.ballot_paper_sizes());
     let mut ballot_card = Pair::new(
         (
             SIDE_A_LABEL,
-            side_a_image,
+            side_a_image.into(),
             options.debug_side_a_base.clone(),
         ),
         (
             SIDE_B_LABEL,
-            side_b_image,
+            side_b_image.into(),
             options.debug_side_b_base.clone(),
         ),
     )
     .par_map(|(label, image, debug_base)| {
-        BallotPage::from_image(label, image, &PaperInfo::scanned(), debug_base)
+        let image = options.scan.channel_mix.to_gray(image);
+        BallotPage::from_image(label, image, &paper_infos, debug_base)
     })
     .into_result()?
     .join(BallotCard::from_pages)?;
 
-    let mut detected_vertical_streaks = match options.vertical_streak_detection {
-        VerticalStreakDetection::Enabled => {
-            let streaks = ballot_card.detect_vertical_streaks();
-            ballot_card.reject_disallowed_vertical_streaks(
-                &streaks,
-                options.max_cumulative_streak_width,
-            )?;
-            streaks
-        }
-        VerticalStreakDetection::Disabled => Pair::default(),
-    };
+    let mut detected_vertical_streaks = ballot_card
+        .as_pair()
+        .par_map(|ballot_page| detect_page_vertical_streaks(ballot_page, options))
+        .into_result()?;
 
     // Run timing mark detection and QR code detection in parallel since they
     // are independent operations on the same ballot images.
-    let (timing_marks_result, decoded_qr_codes_result) = rayon::join(
+    let (timing_marks_result, decoded_qr_codes) = rayon::join(
         || {
-            ballot_card.find_timing_marks(&timing_marks::Options::default_for_geometry(
-                ballot_card.geometry(),
-            ))
+            ballot_card
+                .as_pair()
+                .zip(&detected_vertical_streaks)
+                .par_map(|(ballot_page, detected_vertical_streaks)| {
+                    find_page_timing_marks(ballot_page, detected_vertical_streaks, options)
+                })
+                .into_result()
         },
-        || match &options.metadata_source {
-            MetadataSource::QrCode => {
-                ballot_card.decode_ballot_barcodes(&options.election, &options.expected_ballot_hash)
-            }
+        || match &options.scan.metadata_source {
+            MetadataSource::QrCode => Some(ballot_card.as_pair().par_map(|ballot_page| {
+                ballot_page.decode_ballot_barcode(
+                    &options.election,
+                    &options.expected_ballot_hash,
+                    None,
+                )
+            })),
+            // This needs the timing marks, so it has to wait for them.
+            MetadataSource::TimingMarks => None,
             #[cfg(test)]
-            MetadataSource::Provided(metadata) => Ok(metadata.clone()),
+            MetadataSource::Provided(metadata) => Some(metadata.clone().map(Ok)),
         },
     );
-
-    let mut timing_marks = match timing_marks_result {
-        Ok(marks) => marks,
-        Err(Error::MissingTimingMarks { reason }) => {
-            // If timing marks couldn't be found, retry streak detection with a lower threshold
-            // to differentiate between truly unreadable ballots and ballots with minor streaks.
-            if matches!(
-                options.vertical_streak_detection,
-                VerticalStreakDetection::Enabled
-            ) {
-                // Check if streaks are detected with the retry threshold
-                ballot_card.reject_disallowed_vertical_streaks(
-                    &detected_vertical_streaks,
-                    options.retry_streak_width_threshold,
-                )?;
-            }
-            // If no streaks detected with retry threshold, return the original error
-            return Err(Error::MissingTimingMarks { reason });
+    let mut timing_marks = timing_marks_result?;
+
+    // Now that we know where the timing mark grid is, take one last look for
+    // any QR code we couldn't find anywhere in the corners of its page. The
+    // other page keeps what it already decoded.
+    let decoded_qr_codes_result = decoded_qr_codes
+        .map(|decoded_qr_codes| {
+            let decoded_qr_codes = ballot_card
+                .as_pair()
+                .zip(&timing_marks)
+                .zip(decoded_qr_codes)
+                .par_map(|((ballot_page, timing_marks), decoded)| match decoded {
+                    Err(Error::InvalidQrCodeMetadata { .. }) => ballot_page.decode_ballot_barcode(
+                        &options.election,
+                        &options.expected_ballot_hash,
+                        Some(timing_marks),
+                    ),
+                    decoded => decoded,
+                });
+            BallotCard::combine_decoded_ballot_barcodes(
+                decoded_qr_codes,
+                &options.expected_ballot_hash,
+            )
+        })
+        .transpose();
+
+    let bubble_template = options
+        .scan
+        .bubble_template
+        .at_pixels_per_inch(ballot_card.geometry().pixels_per_inch);
+
+    let mut inferred_ballot_style = None;
+    let mut page_metadata = match decoded_qr_codes_result {
+        Ok(Some(decoded_qr_codes)) => decoded_qr_codes
+            .map(|(metadata, orientation)| (BallotPageMetadata::QrCode(metadata), orientation)),
+        // Read the metadata from the timing marks themselves, now that we know
+        // where they are.
+        Ok(None) => ballot_card.decode_timing_mark_metadata(&options.election, &timing_marks)?,
+        // If neither QR code can be read, the bubbles themselves may still say
+        // which ballot style and sheet this is.
+        Err(error @ Error::InvalidQrCodeMetadata { .. })
+            if options.scan.ballot_style_inference.is_enabled() =>
+        {
+            let (metadata, inferred) = infer_ballot_style(
+                &options.election,
+                &grid_layouts,
+                ballot_card
+                    .as_pair()
+                    .zip(&timing_marks)
+                    .map(|(ballot_page, timing_marks)| (ballot_page.ballot_image(), timing_marks)),
+                &bubble_template,
+            )
+            .ok_or(error)?;
+            inferred_ballot_style = Some(inferred);
+            metadata
         }
-        Err(e) => return Err(e),
+        Err(error) => return Err(error),
     };
 
-    if let Some(minimum_detected_scale) = options.minimum_detected_scale {
-        ballot_card.check_minimum_scale(&timing_marks, minimum_detected_scale)?;
-    }
-
-    let mut decoded_qr_codes = decoded_qr_codes_result?;
-
     // If the pages are reversed, i.e. fed in bottom-first, we need to rotate
     // them so they're right-side up.
     ballot_card
         .as_pair_mut()
         .zip(&mut timing_marks)
         .zip(&mut detected_vertical_streaks)
-        .zip(&decoded_qr_codes)
+        .zip(&page_metadata)
         .map(
             |(((ballot_page, timing_marks), detected_vertical_streaks), (_, orientation))| {
-                // Handle rotating the image and our timing marks if necessary.
-                if matches!(orientation, Orientation::PortraitReversed) {
-                    timing_marks.rotate180(ballot_page.dimensions().into());
-                    ballot_page.rotate180();
-                    // TODO: add a test that fails if this is removed
-                    for streak in detected_vertical_streaks.iter_mut() {
-                        streak.rotate180(ballot_page.width());
-                    }
-                }
-
-                ballot_page.debug().write(
-                    "complete_timing_marks_after_orientation_correction",
-                    |canvas| {
-                        draw_timing_mark_debug_image_mut(
-                            canvas,
-                            ballot_page.geometry(),
-                            timing_marks,
-                        );
-                    },
+                orient_page(
+                    ballot_page,
+                    timing_marks,
+                    detected_vertical_streaks,
+                    *orientation,
                 );
             },
         );
 
     // If what we've been calling the front is actually the back, swap them.
-    if decoded_qr_codes.first().0.page_number.is_back() {
+    if page_metadata.first().0.page_number().is_back() {
         ballot_card.swap_pages();
-        decoded_qr_codes.swap();
+        page_metadata.swap();
         timing_marks.swap();
         detected_vertical_streaks.swap();
     }
 
-    let ballot_style_id = decoded_qr_codes.first().0.ballot_style_id.clone();
+    let page_metadata = page_metadata.map(|(metadata, _)| metadata);
     let Some(grid_layout) = grid_layouts
         .iter()
-        .find(|layout| layout.ballot_style_id == ballot_style_id)
+        .find(|layout| layout.ballot_style_id == *page_metadata.first().ballot_style_id())
     else {
-        return Err(Error::MissingGridLayout {
-            front: BallotPageMetadata::QrCode(decoded_qr_codes.first().0.clone()),
-            back: BallotPageMetadata::QrCode(decoded_qr_codes.second().0.clone()),
-        });
+        let (front, back) = page_metadata.into();
+        return Err(Error::MissingGridLayout { front, back });
     };
 
-    let sheet_number = u32::from(decoded_qr_codes.first().0.page_number.sheet_number().get());
-
-    // Run scoring and image normalization+encoding in parallel. The PNG
-    // encoding is CPU-heavy and overlaps well with bubble-mark scoring.
-    let (scoring_result, encoded_images) = rayon::join(
-        || -> Result<ScoringPairs> {
-            let scored_bubble_marks = ballot_card.score_bubble_marks(
-                &timing_marks,
-                options.bubble_template,
-                grid_layout,
-                &detected_vertical_streaks,
-                sheet_number,
-            )?;
-
-            let contest_layouts =
-                ballot_card.build_page_layout(&timing_marks, grid_layout, sheet_number)?;
-
-            let write_in_area_scores = match options.write_in_scoring {
-                WriteInScoring::Enabled => {
-                    ballot_card.score_write_in_areas(&timing_marks, grid_layout, sheet_number)
-                }
-                WriteInScoring::Disabled => Pair::default(),
-            };
+    let sheet_number = u32::from(page_metadata.first().page_number().sheet_number().get());
 
-            Ok((scored_bubble_marks, contest_layouts, write_in_area_scores))
-        },
-        || {
-            ballot_card.as_pair().par_map(|ballot_page| {
-                binarize_and_encode_png(
-                    ballot_page.ballot_image().image(),
-                    ballot_page.ballot_image().threshold(),
+    ballot_card
+        .as_pair()
+        .zip(timing_marks)
+        .zip(page_metadata)
+        .zip(&detected_vertical_streaks)
+        .zip((BallotSide::Front, BallotSide::Back))
+        .par_map(
+            |((((ballot_page, timing_marks), metadata), detected_vertical_streaks), side)| {
+                interpret_page(
+                    ballot_page,
+                    timing_marks,
+                    metadata,
+                    detected_vertical_streaks,
+                    grid_layout,
+                    &bubble_template,
+                    sheet_number,
+                    side,
+                    options,
                 )
+            },
+        )
+        .into_result()?
+        .join(|front, back| {
+            let mut votes = derive_votes(
+                &options.election,
+                [&front.marks, &back.marks],
+                &options.scan.mark_thresholds,
+            )?;
+            flag_possible_voter_intent(
+                &mut votes,
+                [&front.stray_marks, &back.stray_marks]
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|contest_stray_marks| &contest_stray_marks.contest_id),
+            );
+            votes.is_ballot_style_inferred = inferred_ballot_style.is_some();
+            Ok(InterpretedBallotCard {
+                front,
+                back,
+                votes,
+                inferred_ballot_style: inferred_ballot_style.clone(),
             })
+        })
+}
+
+/// Interpret a single ballot page image as a single-sided ballot card. The
+/// page's QR code metadata is validated on its own rather than against the
+/// other side, and every bubble on the other side of the sheet is treated as
+/// unmarked. Uses [`Options::debug_side_a_base`] for debug images.
+///
+/// # Errors
+///
+/// Returns an error if the ballot page could not be interpreted.
+#[allow(clippy::too_many_lines, clippy::result_large_err)]
+pub fn ballot_page(
+    side_image: impl Into<DynamicImage>,
+    options: &Options,
+) -> Result<InterpretedSimplexBallotCard> {
+    let grid_layouts = options.election.grid_layouts();
+    if grid_layouts.is_empty() {
+        return Err(Error::InvalidElection {
+            message: "election has no ballot positions".to_owned(),
+        });
+    }
+    let mut ballot_page = BallotPage::from_image(
+        SIDE_A_LABEL,
+        options.scan.channel_mix.to_gray(side_image.into()),
+        &options
+            .scan
+            .scan_resolution
+            .paper_infos(&options.election.ballot_paper_sizes()),
+        options.debug_side_a_base.clone(),
+    )?;
+
+    let mut detected_vertical_streaks = detect_page_vertical_streaks(&ballot_page, options)?;
+
+    let (timing_marks_result, decoded_qr_code_result) = rayon::join(
+        || find_page_timing_marks(&ballot_page, &detected_vertical_streaks, options),
+        || match &options.scan.metadata_source {
+            MetadataSource::QrCode => ballot_page
+                .decode_ballot_barcode(&options.election, &options.expected_ballot_hash, None)
+                .map(Some),
+            MetadataSource::TimingMarks => Ok(None),
+            #[cfg(test)]
+            MetadataSource::Provided(metadata) => Ok(Some(metadata.first().clone())),
         },
     );
+    let mut timing_marks = timing_marks_result?;
+
+    let bubble_template = options
+        .scan
+        .bubble_template
+        .at_pixels_per_inch(ballot_page.geometry().pixels_per_inch);
+    let (metadata, orientation, inferred_ballot_style) = decode_page_metadata(
+        &ballot_page,
+        &timing_marks,
+        decoded_qr_code_result,
+        &grid_layouts,
+        &bubble_template,
+        options,
+    )?;
+
+    orient_page(
+        &mut ballot_page,
+        &mut timing_marks,
+        &mut detected_vertical_streaks,
+        orientation,
+    );
+
+    let side = if metadata.page_number().is_back() {
+        BallotSide::Back
+    } else {
+        BallotSide::Front
+    };
 
-    let (scored_bubble_marks, contest_layouts, write_in_area_scores) = scoring_result?;
+    let Some(grid_layout) = grid_layouts
+        .iter()
+        .find(|layout| layout.ballot_style_id == *metadata.ballot_style_id())
+    else {
+        let other_metadata = metadata.for_opposite_page();
+        let (front, back) = match side {
+            BallotSide::Front => (metadata, other_metadata),
+            BallotSide::Back => (other_metadata, metadata),
+        };
+        return Err(Error::MissingGridLayout { front, back });
+    };
+
+    let sheet_number = u32::from(metadata.page_number().sheet_number().get());
 
-    Pair::from((
+    let page = interpret_page(
+        &ballot_page,
         timing_marks,
-        decoded_qr_codes,
-        scored_bubble_marks,
-        write_in_area_scores,
-        encoded_images,
-        contest_layouts,
-    ))
-    .map(
-        |(
-            timing_marks,
-            (metadata, _),
-            marks,
-            write_ins,
-            encoded_normalized_image,
-            contest_layouts,
-        )| {
-            InterpretedBallotPage {
-                timing_marks,
-                metadata: BallotPageMetadata::QrCode(metadata),
-                marks,
-                write_ins,
-                encoded_normalized_image,
-                contest_layouts,
-            }
-        },
-    )
-    .join(|front, back| Ok(InterpretedBallotCard { front, back }))
-}
+        metadata,
+        &detected_vertical_streaks,
+        grid_layout,
+        &bubble_template,
+        sheet_number,
+        side,
+        options,
+    )?;
+
+    // The side that wasn't scanned is blank, so all of its bubbles are unmarked.
+    let blank_marks: ScoredBubbleMarks = grid_layout
+        .grid_positions
+        .iter()
+        .filter(|grid_position| {
+            grid_position.sheet_number() == sheet_number && grid_position.location().side != side
+        })
+        .map(|grid_position| (grid_position.clone(), None))
+        .collect();
+
+    let mut votes = derive_votes(
+        &options.election,
+        [&page.marks, &blank_marks],
+        &options.scan.mark_thresholds,
+    )?;
+    flag_possible_voter_intent(
+        &mut votes,
+        page.stray_marks
+            .iter()
+            .flatten()
+            .map(|contest_stray_marks| &contest_stray_marks.contest_id),
+    );
 
-#[cfg(test)]
-#[allow(clippy::similar_names, clippy::unwrap_used)]
-mod test {
-    use std::path::{Path, PathBuf};
+    votes.is_ballot_style_inferred = inferred_ballot_style.is_some();
 
-    use image::{imageops::FilterType, DynamicImage, GenericImage, Luma, Rgb};
-    use itertools::Itertools;
-    use sha2::{Digest, Sha256};
-    use types_rs::{
-        ballot_card::{BallotType, PageNumber},
-        bubble_ballot::PartialBallotHash,
-        election::{BallotStyleId, ContestId, OptionId, PrecinctId},
-        geometry::{PixelPosition, Rect},
-    };
+    Ok(InterpretedSimplexBallotCard {
+        page,
+        side,
+        votes,
+        inferred_ballot_style,
+    })
+}
 
-    use crate::{
-        ballot_card::ballot_scan_bubble_image,
-        debug::{monospace_font, ImageDebugWriter},
-        draw_utils::draw_text_mut,
-        qr_code,
-        scoring::{self, UnitIntervalScore},
-        timing_marks::{self, DefaultForGeometry, TimingMarks},
+/// Settles on the metadata for a single page given the result of looking for
+/// its QR code before its timing marks were found. See [`ballot_card`] for why
+/// the QR code is searched for again here, and for when the ballot style is
+/// inferred instead. `AccuVote` metadata is read from the timing marks instead
+/// when the QR code wasn't looked for.
+///
+/// # Errors
+///
+/// Fails if no metadata can be found, or if the page is the back of an
+/// `AccuVote` card, which doesn't say which ballot it is.
+#[allow(clippy::result_large_err)]
+fn decode_page_metadata(
+    ballot_page: &BallotPage,
+    timing_marks: &TimingMarks,
+    decoded_qr_code_result: Result<Option<(Metadata, Orientation)>>,
+    grid_layouts: &[GridLayout],
+    bubble_template: &GrayImage,
+    options: &Options,
+) -> Result<(BallotPageMetadata, Orientation, Option<InferredBallotStyle>)> {
+    let decoded_qr_code_result = match decoded_qr_code_result {
+        Err(Error::InvalidQrCodeMetadata { .. })
+            if matches!(options.scan.metadata_source, MetadataSource::QrCode) =>
+        {
+            ballot_page
+                .decode_ballot_barcode(
+                    &options.election,
+                    &options.expected_ballot_hash,
+                    Some(timing_marks),
+                )
+                .map(Some)
+        }
+        result => result,
     };
 
-    use super::*;
-
-    /// Loads a ballot page image from disk as grayscale.
-    fn load_ballot_page_image(image_path: &Path) -> GrayImage {
-        image::open(image_path).unwrap().into_luma8()
+    match decoded_qr_code_result {
+        Ok(Some((metadata, orientation))) => {
+            Ok((BallotPageMetadata::QrCode(metadata), orientation, None))
+        }
+        Ok(None) => {
+            let (decoded, orientation) = ballot_page.decode_timing_mark_metadata(timing_marks)?;
+            // Only the front of an AccuVote card says which ballot it is.
+            let TimingMarkMetadata::Front(front) = decoded else {
+                return Err(Error::InvalidTimingMarkMetadata {
+                    label: ballot_page.label().to_owned(),
+                    message: "the back of a card doesn't identify its ballot style".to_owned(),
+                });
+            };
+            let metadata = front
+                .map_to_election(&options.election)
+                .map_err(|message| Error::InvalidTimingMarkMetadata {
+                    label: ballot_page.label().to_owned(),
+                    message,
+                })?;
+            Ok((
+                BallotPageMetadata::TimingMarks {
+                    metadata,
+                    timing_mark_metadata: decoded,
+                },
+                orientation,
+                None,
+            ))
+        }
+        // As for a card, the bubbles may say which ballot style and sheet this
+        // is when the QR code can't be read.
+        Err(error @ Error::InvalidQrCodeMetadata { .. })
+            if options.scan.ballot_style_inference.is_enabled() =>
+        {
+            let ((metadata, orientation), inferred) = infer_page_ballot_style(
+                &options.election,
+                grid_layouts,
+                (ballot_page.ballot_image(), timing_marks),
+                bubble_template,
+            )
+            .ok_or(error)?;
+            Ok((metadata, orientation, Some(inferred)))
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Scales a streak width given in pixels of a [`SCAN_PIXELS_PER_INCH`] scan to
+/// a scan at `pixels_per_inch`, rounding up so that a limit of a pixel or more
+/// never becomes zero.
+const fn scale_streak_width(width: PixelUnit, pixels_per_inch: PixelUnit) -> PixelUnit {
+    (width * pixels_per_inch).div_ceil(SCAN_PIXELS_PER_INCH)
+}
+
+/// Looks for vertical streaks on a page if
+/// [`ScanInterpreterOptions::vertical_streak_detection`] is enabled.
+///
+/// # Errors
+///
+/// Fails if the streaks are wider in total than
+/// [`ScanInterpreterOptions::max_cumulative_streak_width`].
+#[allow(clippy::result_large_err)]
+fn detect_page_vertical_streaks(
+    ballot_page: &BallotPage,
+    options: &Options,
+) -> Result<Vec<VerticalStreak>> {
+    match options.scan.vertical_streak_detection {
+        Toggle::Enabled => {
+            let streaks = detect_vertical_streaks(ballot_page.ballot_image());
+            ballot_page.reject_vertical_streaks_above_cumulative_threshold(
+                &streaks,
+                scale_streak_width(
+                    options.scan.max_cumulative_streak_width,
+                    ballot_page.geometry().pixels_per_inch,
+                ),
+            )?;
+            Ok(streaks)
+        }
+        Toggle::Disabled => Ok(Vec::new()),
+    }
+}
+
+/// Finds the timing marks on a page and checks their scale against
+/// [`ScanInterpreterOptions::minimum_detected_scale`].
+///
+/// # Errors
+///
+/// Fails if the timing marks can't be found or are too small. When they can't
+/// be found, streaks narrower than the usual limit are reported instead if
+/// they're wider than [`ScanInterpreterOptions::retry_streak_width_threshold`].
+#[allow(clippy::result_large_err)]
+fn find_page_timing_marks(
+    ballot_page: &BallotPage,
+    detected_vertical_streaks: &[VerticalStreak],
+    options: &Options,
+) -> Result<TimingMarks> {
+    let timing_marks = match ballot_page.find_timing_marks(
+        &timing_marks::Options::default_for_geometry(ballot_page.geometry()),
+    ) {
+        Ok(marks) => marks,
+        Err(Error::MissingTimingMarks { reason }) => {
+            // If timing marks couldn't be found, retry streak detection with a lower threshold
+            // to differentiate between truly unreadable ballots and ballots with minor streaks.
+            if matches!(options.scan.vertical_streak_detection, Toggle::Enabled) {
+                ballot_page.reject_vertical_streaks_above_cumulative_threshold(
+                    detected_vertical_streaks,
+                    scale_streak_width(
+                        options.scan.retry_streak_width_threshold,
+                        ballot_page.geometry().pixels_per_inch,
+                    ),
+                )?;
+            }
+            // If no streaks detected with retry threshold, return the original error
+            return Err(Error::MissingTimingMarks { reason });
+        }
+        Err(e) => return Err(e),
+    };
+
+    if let Some(minimum_detected_scale) = options.scan.minimum_detected_scale {
+        ballot_page.check_minimum_scale(&timing_marks, minimum_detected_scale)?;
+    }
+    Ok(timing_marks)
+}
+
+/// Turns a page right side up if it was scanned upside down, along with the
+/// timing marks and vertical streaks found on it.
+fn orient_page(
+    ballot_page: &mut BallotPage,
+    timing_marks: &mut TimingMarks,
+    detected_vertical_streaks: &mut [VerticalStreak],
+    orientation: Orientation,
+) {
+    if matches!(orientation, Orientation::PortraitReversed) {
+        timing_marks.rotate180(ballot_page.dimensions().into());
+        ballot_page.rotate180();
+        // TODO: add a test that fails if this is removed
+        for streak in detected_vertical_streaks.iter_mut() {
+            streak.rotate180(ballot_page.width());
+        }
+    }
+
+    ballot_page.debug().write(
+        "complete_timing_marks_after_orientation_correction",
+        |canvas| {
+            draw_timing_mark_debug_image_mut(canvas, ballot_page.geometry(), timing_marks);
+        },
+    );
+}
+
+/// Scores everything [`Options`] asks for on a right-side-up page, which is
+/// `side` of sheet `sheet_number`, and encodes its normalized image in
+/// parallel since the PNG encoding is CPU-heavy and overlaps well with
+/// scoring. Used for each page by both [`ballot_card`] and [`ballot_page`].
+#[allow(clippy::too_many_arguments, clippy::result_large_err)]
+fn interpret_page(
+    ballot_page: &BallotPage,
+    timing_marks: TimingMarks,
+    metadata: BallotPageMetadata,
+    detected_vertical_streaks: &[VerticalStreak],
+    grid_layout: &GridLayout,
+    bubble_template: &GrayImage,
+    sheet_number: u32,
+    side: BallotSide,
+    options: &Options,
+) -> Result<InterpretedBallotPage> {
+    let (scoring_result, encoded_normalized_image) = rayon::join(
+        || -> Result<PageScoring> {
+            let marks = ballot_page.score_bubble_marks(
+                &timing_marks,
+                bubble_template,
+                grid_layout,
+                detected_vertical_streaks,
+                sheet_number,
+                side,
+            )?;
+
+            if options.scan.line_artifact_detection.is_enabled() {
+                ballot_page.reject_line_artifacts_through_bubbles(&timing_marks, &marks)?;
+            }
+
+            let contest_layouts =
+                ballot_page.build_page_layout(&timing_marks, grid_layout, sheet_number, side)?;
+
+            let write_ins = match options.scan.write_in_scoring {
+                Toggle::Enabled => {
+                    ballot_page.score_write_in_areas(&timing_marks, grid_layout, sheet_number, side)
+                }
+                Toggle::Disabled => ScoredPositionAreas::default(),
+            };
+
+            let crops = match options.scan.area_cropping {
+                Toggle::Enabled => {
+                    Some(ballot_page.crop_areas(&timing_marks, grid_layout, sheet_number, side))
+                }
+                Toggle::Disabled => None,
+            };
+
+            let quality = ballot_page.quality_report(&timing_marks);
+
+            let stray_marks = match options.scan.stray_mark_detection {
+                Toggle::Enabled => {
+                    Some(ballot_page.detect_stray_marks(&timing_marks, &contest_layouts, &marks))
+                }
+                Toggle::Disabled => None,
+            };
+
+            Ok((
+                marks,
+                contest_layouts,
+                write_ins,
+                crops,
+                quality,
+                stray_marks,
+            ))
+        },
+        || {
+            binarize_and_encode_png(
+                ballot_page.ballot_image().image(),
+                ballot_page.ballot_image().threshold(),
+            )
+        },
+    );
+
+    let (marks, contest_layouts, write_ins, crops, quality, stray_marks) = scoring_result?;
+    Ok(InterpretedBallotPage {
+        timing_marks,
+        metadata,
+        marks,
+        write_ins,
+        encoded_normalized_image,
+        contest_layouts,
+        quality,
+        crops,
+        stray_marks,
+        qr_code_searches: ballot_page.qr_code_searches(),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::similar_names, clippy::unwrap_used)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use image::{imageops::FilterType, DynamicImage, GenericImage, Luma, Rgb};
+    use itertools::Itertools;
+    use sha2::{Digest, Sha256};
+    use types_rs::{
+        ballot_card::{BallotType, PageNumber},
+        bubble_ballot::PartialBallotHash,
+        cdf::cvr::{CvrType, IndicationStatus},
+        election::{BallotStyleId, ContestId, OptionId, PrecinctId},
+        geometry::{PixelPosition, Rect},
+    };
+
+    use crate::{
+        cdf,
+        debug::{monospace_font, ImageDebugWriter},
+        draw_utils::draw_text_mut,
+        qr_code,
+        scoring::{self, MarkClassification, UnitIntervalScore},
+        timing_marks::{self, DefaultForGeometry, TimingMarks},
+        votes::AdjudicationReasonInfo,
+    };
+
+    use super::*;
+
+    /// Loads a ballot page image from disk as grayscale.
+    fn load_ballot_page_image(image_path: &Path) -> GrayImage {
+        image::open(image_path).unwrap().into_luma8()
     }
 
     /// Loads images for both sides of a ballot card and returns them.
@@ -630,116 +1370,845 @@ mod test {
         ))
     }
 
-    fn load_ballot_card_fixture(
-        fixture_name: &str,
-        (side_a_name, side_b_name): (&str, &str),
-        (precinct_id, ballot_style_id): (&str, &str),
-        is_test_mode: bool,
-    ) -> (GrayImage, GrayImage, Options) {
-        let fixture_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/fixtures");
-        let election_path = fixture_path.join(fixture_name).join("election.json");
-        let (election, expected_ballot_hash) = load_election_and_ballot_hash(&election_path);
-        let bubble_template = ballot_scan_bubble_image();
-        let side_a_path = fixture_path.join(fixture_name).join(side_a_name);
-        let side_b_path = fixture_path.join(fixture_name).join(side_b_name);
-        let (side_a_image, side_b_image) = load_ballot_card_images(&side_a_path, &side_b_path);
-        let options = Options {
-            debug_side_a_base: None,
-            debug_side_b_base: None,
-            bubble_template,
-            election,
-            expected_ballot_hash,
-            write_in_scoring: WriteInScoring::Enabled,
-            vertical_streak_detection: VerticalStreakDetection::default(),
-            minimum_detected_scale: None,
-            max_cumulative_streak_width: 5,
-            retry_streak_width_threshold: 1,
-            metadata_source: provided_metadata(Metadata {
-                ballot_hash: expected_ballot_hash,
-                precinct_id: PrecinctId::from(precinct_id.to_owned()),
-                ballot_style_id: BallotStyleId::from(ballot_style_id.to_owned()),
-                page_number: PageNumber::new_unchecked(1),
-                is_test_mode,
-                ballot_type: BallotType::Precinct,
-                ballot_audit_id: None,
-            }),
-        };
-        (side_a_image, side_b_image, options)
+    fn load_ballot_card_fixture(
+        fixture_name: &str,
+        (side_a_name, side_b_name): (&str, &str),
+        (precinct_id, ballot_style_id): (&str, &str),
+        is_test_mode: bool,
+    ) -> (GrayImage, GrayImage, Options) {
+        let fixture_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/fixtures");
+        let election_path = fixture_path.join(fixture_name).join("election.json");
+        let (election, expected_ballot_hash) = load_election_and_ballot_hash(&election_path);
+        let side_a_path = fixture_path.join(fixture_name).join(side_a_name);
+        let side_b_path = fixture_path.join(fixture_name).join(side_b_name);
+        let (side_a_image, side_b_image) = load_ballot_card_images(&side_a_path, &side_b_path);
+        let options = Options {
+            scan: ScanInterpreterOptions {
+                write_in_scoring: Toggle::Enabled,
+                metadata_source: provided_metadata(Metadata {
+                    ballot_hash: expected_ballot_hash,
+                    precinct_id: PrecinctId::from(precinct_id.to_owned()),
+                    ballot_style_id: BallotStyleId::from(ballot_style_id.to_owned()),
+                    page_number: PageNumber::new_unchecked(1),
+                    is_test_mode,
+                    ballot_type: BallotType::Precinct,
+                    ballot_audit_id: None,
+                }),
+                ..ScanInterpreterOptions::default()
+            },
+            ..Options::new(election, expected_ballot_hash)
+        };
+        (side_a_image, side_b_image, options)
+    }
+
+    fn load_hmpb_fixture(
+        fixture_name: &str,
+        starting_page_number: usize,
+    ) -> (GrayImage, GrayImage, Options) {
+        let fixture_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../hmpb/fixtures/")
+            .join(fixture_name);
+        let election_path = fixture_path.join("election.json");
+        let (election, expected_ballot_hash) = load_election_and_ballot_hash(&election_path);
+
+        let side_a_path = fixture_path.join(format!("blank-ballot-p{starting_page_number}.jpg"));
+        let side_b_path =
+            fixture_path.join(format!("blank-ballot-p{}.jpg", starting_page_number + 1));
+        let (side_a_image, side_b_image) = load_ballot_card_images(&side_a_path, &side_b_path);
+        let options = Options {
+            scan: ScanInterpreterOptions {
+                write_in_scoring: Toggle::Enabled,
+                ..ScanInterpreterOptions::default()
+            },
+            ..Options::new(election, expected_ballot_hash)
+        };
+        (side_a_image, side_b_image, options)
+    }
+
+    /// Removes more consecutive marks from the middle of the left and right
+    /// borders than can be inferred, leaving the timing marks unreadable.
+    fn deface_ballot_by_removing_side_timing_marks(image: &mut GrayImage, marks: &TimingMarks) {
+        let count = timing_marks::Options::default_for_geometry(&marks.geometry)
+            .border
+            .max_consecutive_inferred_timing_marks
+            + 1;
+        let start = marks.border_marks.left.len() / 2;
+        remove_side_timing_marks(image, marks, start..start + count);
+    }
+
+    /// Whites out the left and right border marks at `indexes`.
+    fn remove_side_timing_marks(
+        image: &mut GrayImage,
+        marks: &TimingMarks,
+        indexes: std::ops::Range<usize>,
+    ) {
+        const PADDING: u32 = 10;
+        let image_rect = Rect::new(0, 0, image.width(), image.height());
+        let left_marks = &marks.border_marks.left[indexes.clone()];
+        let right_marks = &marks.border_marks.right[indexes];
+
+        for mark_to_deface in left_marks.iter().chain(right_marks) {
+            let rect = mark_to_deface.rect();
+            let rect = Rect::new(
+                rect.left() - PADDING as i32,
+                rect.top() - PADDING as i32,
+                rect.width() + 20,
+                rect.height() + PADDING * 2,
+            )
+            .intersect(&image_rect)
+            .unwrap();
+            for x in rect.left()..rect.right() {
+                for y in rect.top()..rect.bottom() {
+                    image.put_pixel(x as u32, y as u32, Luma([255]));
+                }
+            }
+        }
+    }
+
+    /// The options whose bubbles are clearly filled in on `page`.
+    fn marked_option_ids(page: &InterpretedBallotPage) -> Vec<OptionId> {
+        page.marks
+            .iter()
+            .filter(|(_, scored_bubble)| {
+                scored_bubble
+                    .as_ref()
+                    .is_some_and(|bubble| bubble.fill_score > UnitIntervalScore(0.1))
+            })
+            .map(|(grid_position, _)| grid_position.option_id())
+            .collect_vec()
+    }
+
+    fn is_binary_image(image: &GrayImage) -> bool {
+        image
+            .as_raw()
+            .iter()
+            .all(|&pixel| pixel == 0 || pixel == 255)
+    }
+
+    #[test]
+    fn test_interpret_returns_binarized_images() {
+        let (side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let card = ballot_card(side_a_image, side_b_image, &options).unwrap();
+        for page in [&card.front, &card.back] {
+            let encoded = page.encoded_normalized_image.as_ref().unwrap();
+            let decoded = image::load_from_memory(encoded).unwrap().into_luma8();
+            assert!(is_binary_image(&decoded));
+        }
+    }
+
+    #[test]
+    fn test_interpret_crops_write_in_areas_and_contests() {
+        let (side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 3);
+        let card = ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap();
+        assert!(card.front.crops.is_none());
+        assert!(card.back.crops.is_none());
+
+        let options = Options {
+            scan: ScanInterpreterOptions {
+                area_cropping: Toggle::Enabled,
+                ..options.scan
+            },
+            ..options
+        };
+        let card = ballot_card(side_a_image, side_b_image, &options).unwrap();
+        let mut write_in_crop_count = 0;
+        for page in [&card.front, &card.back] {
+            let crops = page.crops.as_ref().unwrap();
+            let contest_ids = page
+                .marks
+                .iter()
+                .map(|(grid_position, _)| grid_position.contest_id())
+                .unique()
+                .collect_vec();
+            assert_eq!(
+                crops
+                    .contests
+                    .iter()
+                    .map(|crop| crop.contest_id.clone())
+                    .collect_vec(),
+                contest_ids
+            );
+            write_in_crop_count += crops.write_ins.len();
+
+            for crop in crops
+                .write_ins
+                .iter()
+                .map(|write_in| &write_in.crop)
+                .chain(crops.contests.iter().map(|contest| &contest.crop))
+            {
+                let decoded = image::load_from_memory(crop.png.as_ref().unwrap())
+                    .unwrap()
+                    .into_luma8();
+                assert_eq!(decoded.dimensions(), (crop.width, crop.height));
+                assert!(is_binary_image(&decoded));
+            }
+        }
+        assert_eq!(
+            write_in_crop_count,
+            card.front.write_ins.len() + card.back.write_ins.len()
+        );
+        assert!(write_in_crop_count > 0);
+
+        let json = serde_json::to_value(&card.front).unwrap();
+        assert!(json["crops"]["contests"][0]["png"].is_string());
+    }
+
+    #[test]
+    fn test_interpret_classifies_blank_bubbles() {
+        let (side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let card = ballot_card(side_a_image, side_b_image, &options).unwrap();
+        for (grid_position, mark) in card.front.marks.iter().chain(&card.back.marks) {
+            let mark = mark.as_ref().unwrap();
+            assert_eq!(
+                mark.classification,
+                MarkClassification::Blank,
+                "{grid_position:?}: {mark:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_interpret_quality_report() {
+        let (side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let card = ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap();
+
+        for quality in [card.front.quality, card.back.quality] {
+            assert!(quality.skew_degrees.abs() < 1.0, "{quality:?}");
+            assert!(
+                (quality.horizontal_stretch - 1.0).abs() < 0.02,
+                "{quality:?}"
+            );
+            assert!((quality.vertical_stretch - 1.0).abs() < 0.02, "{quality:?}");
+            assert!(quality.contrast.0 > 0.5, "{quality:?}");
+            assert!(
+                (quality.ink_luminosity..quality.paper_luminosity).contains(&quality.threshold),
+                "{quality:?}"
+            );
+        }
+
+        // blurring the image should lower the focus estimate
+        let blurred_card = ballot_card(
+            image::imageops::blur(&side_a_image, 1.0),
+            image::imageops::blur(&side_b_image, 1.0),
+            &options,
+        )
+        .unwrap();
+        for (sharp, blurred) in [
+            (card.front.quality, blurred_card.front.quality),
+            (card.back.quality, blurred_card.back.quality),
+        ] {
+            assert!(
+                blurred.focus.0 < sharp.focus.0 * 0.75,
+                "sharp: {sharp:?}, blurred: {blurred:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_interpret_quality_report_measures_skew() {
+        let (side_a_image, side_b_image, options) = load_ballot_card_fixture(
+            "vxqa-2024-10",
+            ("skew-front.png", "skew-back.png"),
+            ("yxrf8bdlu2zz", "1_en"),
+            false,
+        );
+        let card = ballot_card(side_a_image, side_b_image, &options).unwrap();
+        let skew = card.front.quality.skew_degrees;
+        assert!(skew.abs() > 0.5, "skew: {skew}");
+    }
+
+    /// Draws a pen stroke through `points` by stamping a dark disc of
+    /// `radius` along each segment.
+    fn draw_pen_stroke(image: &mut GrayImage, points: &[(f32, f32)], radius: f32) {
+        for (&(x0, y0), &(x1, y1)) in points.iter().tuple_windows() {
+            let steps = (x1 - x0).hypot(y1 - y0).ceil().max(1.0) as u32;
+            for step in 0..=steps {
+                let t = step as f32 / steps as f32;
+                let (cx, cy) = (x0 + (x1 - x0) * t, y0 + (y1 - y0) * t);
+                for y in (cy - radius).floor() as u32..=(cy + radius).ceil() as u32 {
+                    for x in (cx - radius).floor() as u32..=(cx + radius).ceil() as u32 {
+                        if (x as f32 - cx).hypot(y as f32 - cy) <= radius {
+                            image.put_pixel(x, y, Luma([0]));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_interpret_detects_stray_marks() {
+        let (mut side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let options = Options {
+            scan: ScanInterpreterOptions {
+                stray_mark_detection: Toggle::Enabled,
+                ..options.scan
+            },
+            ..options
+        };
+
+        let card = ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap();
+        assert_eq!(card.front.stray_marks, Some(vec![]));
+        assert_eq!(card.back.stray_marks, Some(vec![]));
+        assert!(card
+            .votes
+            .adjudication_reasons()
+            .iter()
+            .all(|reason| !matches!(reason, AdjudicationReasonInfo::PossibleVoterIntent { .. })));
+
+        // Circle the first candidate's name in the first contest and draw an X
+        // over the second candidate's name in the second contest, leaving the
+        // bubbles untouched.
+        let layouts = &card.front.contest_layouts;
+        let circled = layouts[0].options[0].bounds;
+        let center = circled.center();
+        let (rx, ry) = (circled.width() as f32 * 0.4, circled.height() as f32 * 0.45);
+        let ellipse: Vec<_> = (0..=72)
+            .map(|i| {
+                let angle = (i as f32 * 5.0).to_radians();
+                (
+                    center.x + 30.0 + rx * angle.cos(),
+                    center.y + ry * angle.sin(),
+                )
+            })
+            .collect();
+        draw_pen_stroke(&mut side_a_image, &ellipse, 1.5);
+
+        let crossed = layouts[1].options[1].bounds;
+        let (x, y) = (crossed.left() as f32 + 150.0, crossed.top() as f32 + 20.0);
+        draw_pen_stroke(&mut side_a_image, &[(x, y), (x + 60.0, y + 60.0)], 1.5);
+        draw_pen_stroke(&mut side_a_image, &[(x + 60.0, y), (x, y + 60.0)], 1.5);
+
+        let card = ballot_card(side_a_image, side_b_image, &options).unwrap();
+        let flagged_contest_ids = card
+            .front
+            .stray_marks
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|contest_stray_marks| &contest_stray_marks.contest_id)
+            .collect_vec();
+        assert_eq!(
+            flagged_contest_ids,
+            vec![&layouts[0].contest_id, &layouts[1].contest_id]
+        );
+        assert_eq!(card.back.stray_marks, Some(vec![]));
+
+        assert!(card.votes.is_blank_ballot);
+        let possible_voter_intent_contest_ids = card
+            .votes
+            .adjudication_reasons()
+            .into_iter()
+            .filter_map(|reason| match reason {
+                AdjudicationReasonInfo::PossibleVoterIntent { contest_id } => Some(contest_id),
+                _ => None,
+            })
+            .collect_vec();
+        assert_eq!(
+            possible_voter_intent_contest_ids,
+            vec![layouts[0].contest_id.clone(), layouts[1].contest_id.clone()]
+        );
+    }
+
+    #[test]
+    fn test_interpret_rejects_line_artifacts_through_bubbles() {
+        let (side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let options = Options {
+            scan: ScanInterpreterOptions {
+                line_artifact_detection: Toggle::Enabled,
+                ..options.scan
+            },
+            ..options
+        };
+
+        let card = ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap();
+        let (grid_position, bubble) = card
+            .front
+            .marks
+            .iter()
+            .find_map(|(grid_position, scored_bubble)| {
+                Some((grid_position, scored_bubble.as_ref()?))
+            })
+            .unwrap();
+        let center = bubble.expected_bounds.center();
+        let width = side_a_image.width() as f32;
+
+        // A fold across the whole sheet through the first bubble.
+        let mut folded_image = side_a_image.clone();
+        draw_pen_stroke(
+            &mut folded_image,
+            &[(2.0, center.y), (width - 3.0, center.y)],
+            1.0,
+        );
+        let _debug_image = DebugImage::write(
+            "debug__test_interpret_rejects_line_artifacts_through_bubbles__fold.png",
+            &folded_image,
+        );
+        match ballot_card(folded_image.clone(), side_b_image.clone(), &options) {
+            Err(Error::LineArtifactsDetected { label, contest_ids }) => {
+                assert_eq!(label, "side A");
+                assert!(contest_ids.contains(&grid_position.contest_id()));
+            }
+            result => panic!("expected LineArtifactsDetected, got {result:?}"),
+        }
+
+        // A diagonal streak through the same bubble.
+        let mut streaked_image = side_a_image;
+        draw_pen_stroke(
+            &mut streaked_image,
+            &[
+                (center.x - 0.05 * width, center.y - 0.015 * width),
+                (center.x + 0.6 * width, center.y + 0.18 * width),
+            ],
+            1.0,
+        );
+        let _debug_image = DebugImage::write(
+            "debug__test_interpret_rejects_line_artifacts_through_bubbles__streak.png",
+            &streaked_image,
+        );
+        match ballot_card(streaked_image, side_b_image.clone(), &options) {
+            Err(Error::LineArtifactsDetected { label, contest_ids }) => {
+                assert_eq!(label, "side A");
+                assert!(contest_ids.contains(&grid_position.contest_id()));
+            }
+            result => panic!("expected LineArtifactsDetected, got {result:?}"),
+        }
+
+        // Detection is opt-in.
+        let options = Options {
+            scan: ScanInterpreterOptions {
+                line_artifact_detection: Toggle::Disabled,
+                ..options.scan
+            },
+            ..options
+        };
+        ballot_card(folded_image, side_b_image, &options).unwrap();
+    }
+
+    #[test]
+    fn test_interpret_derives_votes_for_blank_ballot() {
+        let (side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let card = ballot_card(side_a_image, side_b_image, &options).unwrap();
+
+        assert!(card.votes.is_blank_ballot);
+        assert!(!card.votes.contests.is_empty());
+        for contest in &card.votes.contests {
+            assert!(contest.option_ids.is_empty());
+            assert!(matches!(
+                contest.adjudication_reasons.as_slice(),
+                [AdjudicationReasonInfo::Undervote { .. }]
+            ));
+        }
+        assert_eq!(
+            card.votes.adjudication_reasons().last(),
+            Some(&AdjudicationReasonInfo::BlankBallot)
+        );
+    }
+
+    #[test]
+    fn test_interpret_blank_ballot_cdf_cvr() {
+        let (side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let card = ballot_card(side_a_image, side_b_image, &options).unwrap();
+        let cvr = cdf::bubble_ballot_cvr(
+            &options.election,
+            &card,
+            &options.scan.mark_thresholds,
+            &cdf::CvrMetadata {
+                election_id: "election".to_owned(),
+                creating_device_id: "scanner".to_owned(),
+                batch_id: "batch".to_owned(),
+                batch_sequence_id: None,
+                cvr_id: "cvr".to_owned(),
+                front_image: None,
+                back_image: None,
+            },
+        );
+
+        assert_eq!(cvr.ballot_sheet_id.as_deref(), Some("1"));
+        assert_eq!(cvr.current_snapshot_id, "cvr-interpreted");
+
+        let [interpreted, original] = cvr.cvr_snapshot.as_slice() else {
+            panic!("expected interpreted and original snapshots");
+        };
+        assert_eq!(interpreted.r#type, CvrType::Interpreted);
+        assert_eq!(interpreted.cvr_contest.len(), card.votes.contests.len());
+        for contest in &interpreted.cvr_contest {
+            assert!(contest.cvr_contest_selection.is_empty());
+            assert_eq!(contest.overvotes, Some(0));
+        }
+
+        assert_eq!(original.r#type, CvrType::Original);
+        let original_selections = original
+            .cvr_contest
+            .iter()
+            .flat_map(|contest| &contest.cvr_contest_selection)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            original_selections.len(),
+            card.front.marks.len() + card.back.marks.len()
+        );
+        for selection in original_selections {
+            let [position] = selection.selection_position.as_slice() else {
+                panic!("expected one selection position");
+            };
+            assert_eq!(position.has_indication, IndicationStatus::No);
+            assert!(position.mark_metric_value.is_some());
+        }
+    }
+
+    #[test]
+    fn test_interpret_simplex_front_matches_duplex() {
+        let (side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let card = ballot_card(side_a_image.clone(), side_b_image, &options).unwrap();
+        let simplex = ballot_page(side_a_image, &options).unwrap();
+
+        assert_eq!(simplex.side, BallotSide::Front);
+        assert_eq!(simplex.page.marks.len(), card.front.marks.len());
+        assert_eq!(
+            simplex.page.contest_layouts.len(),
+            card.front.contest_layouts.len()
+        );
+
+        // Contests on the missing back are present and treated as blank.
+        assert_eq!(simplex.votes, card.votes);
+    }
+
+    #[test]
+    fn test_interpret_simplex_back_only() {
+        let (_, side_b_image, options) = load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let simplex = ballot_page(side_b_image, &options).unwrap();
+
+        assert_eq!(simplex.side, BallotSide::Back);
+        assert!(simplex.page.metadata.page_number().is_back());
+        assert!(simplex.votes.is_blank_ballot);
+    }
+
+    #[test]
+    fn test_interpret_simplex_upside_down() {
+        let (side_a_image, _, options) = load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let simplex = ballot_page(image::imageops::rotate180(&side_a_image), &options).unwrap();
+
+        assert_eq!(simplex.side, BallotSide::Front);
+        assert!(simplex.votes.is_blank_ballot);
+    }
+
+    #[test]
+    fn test_interpret_simplex_rejects_unexpected_ballot_hash() {
+        let (side_a_image, _, mut options) = load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let actual_ballot_hash = options.expected_ballot_hash;
+        let bogus = actual_ballot_hash.map(|b| !b);
+        options.expected_ballot_hash = bogus;
+
+        match ballot_page(side_a_image, &options) {
+            Err(Error::InvalidBallotHash { expected, actual }) => {
+                assert_eq!(expected, bogus);
+                assert_eq!(actual, actual_ballot_hash);
+            }
+            Err(err) => panic!("unexpected error: {err:?}"),
+            Ok(_) => panic!("interpretation unexpectedly succeeded"),
+        }
+    }
+
+    #[test]
+    fn test_grid_warp_follows_non_uniform_horizontal_stretch() {
+        const AMPLITUDE: f32 = 8.0;
+
+        let (side_a_image, _, options) = load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let width = side_a_image.width() as f32;
+        let shift = |x: f32| AMPLITUDE * (std::f32::consts::PI * x / width).sin();
+
+        // Stretch the middle of the page sideways while leaving the left and
+        // right edges in place, as if the paper had buckled while feeding.
+        let stretched_image =
+            GrayImage::from_fn(side_a_image.width(), side_a_image.height(), |x, y| {
+                let source_x = (x as f32 - shift(x as f32)).round().clamp(0.0, width - 1.0);
+                *side_a_image.get_pixel(source_x as u32, y)
+            });
+
+        let original = ballot_page(side_a_image, &options)
+            .unwrap()
+            .page
+            .timing_marks;
+        let stretched = ballot_page(stretched_image, &options)
+            .unwrap()
+            .page
+            .timing_marks;
+
+        let grid_size = stretched.geometry.grid_size;
+        assert_eq!(stretched.border_marks.top.len(), grid_size.width as usize);
+        assert_eq!(
+            stretched.border_marks.bottom.len(),
+            grid_size.width as usize
+        );
+        let residuals = stretched.warp.residuals();
+        assert_eq!(
+            residuals.mark_count,
+            2 * (grid_size.width + grid_size.height - 2) as usize
+        );
+        assert!(residuals.rms < 1.0, "{residuals:?}");
+
+        let mut unwarped = stretched.clone();
+        unwarped.warp = timing_marks::warp::GridWarp::default();
+
+        for (column, row) in [(16.0, 20.0), (8.0, 10.0), (25.0, 30.0)] {
+            let original_point = original.point_for_location(column, row).unwrap();
+            let expected_x = original_point.x + shift(original_point.x);
+            let warped_error =
+                (stretched.point_for_location(column, row).unwrap().x - expected_x).abs();
+            let unwarped_error =
+                (unwarped.point_for_location(column, row).unwrap().x - expected_x).abs();
+            assert!(
+                warped_error < unwarped_error / 2.0,
+                "({column}, {row}): warped error {warped_error}, unwarped error {unwarped_error}"
+            );
+        }
+    }
+
+    fn resample_to_pixels_per_inch(image: &GrayImage, pixels_per_inch: u32) -> GrayImage {
+        image::imageops::resize(
+            image,
+            image.width() * pixels_per_inch / SCAN_PIXELS_PER_INCH,
+            image.height() * pixels_per_inch / SCAN_PIXELS_PER_INCH,
+            FilterType::Triangle,
+        )
+    }
+
+    #[test]
+    fn test_interpret_at_supported_scan_resolutions() {
+        let (mut side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let card = ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap();
+
+        // Fill in the first bubble on the front so there's a vote to find.
+        let (filled_position, Some(filled_mark)) = &card.front.marks[0] else {
+            panic!("expected the first bubble to be scored");
+        };
+        let bounds = filled_mark.matched_bounds;
+        for y in bounds.top()..=bounds.bottom() {
+            for x in bounds.left()..=bounds.right() {
+                side_a_image.put_pixel(x as u32, y as u32, Luma([0]));
+            }
+        }
+
+        for pixels_per_inch in SUPPORTED_SCAN_PIXELS_PER_INCH {
+            let card = ballot_card(
+                resample_to_pixels_per_inch(&side_a_image, pixels_per_inch),
+                resample_to_pixels_per_inch(&side_b_image, pixels_per_inch),
+                &options,
+            )
+            .unwrap();
+
+            assert_eq!(
+                card.front.timing_marks.geometry.pixels_per_inch,
+                pixels_per_inch
+            );
+            let contest_votes = card
+                .votes
+                .contests
+                .iter()
+                .find(|contest| contest.contest_id == filled_position.contest_id())
+                .unwrap();
+            assert_eq!(
+                contest_votes.option_ids,
+                vec![filled_position.option_id()],
+                "at {pixels_per_inch} pixels per inch"
+            );
+            assert_eq!(
+                card.votes
+                    .contests
+                    .iter()
+                    .map(|contest| contest.option_ids.len())
+                    .sum::<usize>(),
+                1,
+                "at {pixels_per_inch} pixels per inch"
+            );
+        }
+    }
+
+    #[test]
+    fn test_streak_widths_scale_with_scan_resolution() {
+        assert_eq!(scale_streak_width(5, SCAN_PIXELS_PER_INCH), 5);
+        assert_eq!(scale_streak_width(5, 300), 8);
+        assert_eq!(scale_streak_width(5, 150), 4);
+        assert_eq!(scale_streak_width(5, 100), 3);
+        assert_eq!(scale_streak_width(1, 100), 1);
+        assert_eq!(scale_streak_width(0, 300), 0);
     }
 
-    fn load_hmpb_fixture(
-        fixture_name: &str,
-        starting_page_number: usize,
-    ) -> (GrayImage, GrayImage, Options) {
-        let fixture_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .join("../hmpb/fixtures/")
-            .join(fixture_name);
-        let election_path = fixture_path.join("election.json");
-        let (election, expected_ballot_hash) = load_election_and_ballot_hash(&election_path);
+    #[test]
+    fn test_fixed_scan_resolution_rejects_other_densities() {
+        let (side_a_image, side_b_image, mut options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        options.scan.scan_resolution = ScanResolution::PixelsPerInch(SCAN_PIXELS_PER_INCH);
 
-        let bubble_template = ballot_scan_bubble_image();
-        let side_a_path = fixture_path.join(format!("blank-ballot-p{starting_page_number}.jpg"));
-        let side_b_path =
-            fixture_path.join(format!("blank-ballot-p{}.jpg", starting_page_number + 1));
-        let (side_a_image, side_b_image) = load_ballot_card_images(&side_a_path, &side_b_path);
-        let options = Options {
-            debug_side_a_base: None,
-            debug_side_b_base: None,
-            bubble_template,
-            election,
-            expected_ballot_hash,
-            write_in_scoring: WriteInScoring::Enabled,
-            vertical_streak_detection: VerticalStreakDetection::default(),
-            minimum_detected_scale: None,
-            max_cumulative_streak_width: 5,
-            retry_streak_width_threshold: 1,
-            metadata_source: MetadataSource::QrCode,
-        };
-        (side_a_image, side_b_image, options)
+        assert!(matches!(
+            ballot_card(
+                resample_to_pixels_per_inch(&side_a_image, 150),
+                resample_to_pixels_per_inch(&side_b_image, 150),
+                &options,
+            ),
+            Err(Error::UnexpectedDimensions { .. })
+        ));
     }
 
-    fn deface_ballot_by_removing_side_timing_marks(image: &mut GrayImage, marks: &TimingMarks) {
-        const PADDING: u32 = 10;
-        let image_rect = Rect::new(0, 0, image.width(), image.height());
-        let left_marks = &marks.border_marks.left;
-        let right_marks = &marks.border_marks.right;
-        let left_side_mark_to_deface = left_marks[left_marks.len() / 2];
-        let right_side_mark_to_deface = right_marks[right_marks.len() / 2];
+    #[test]
+    fn test_scan_resolution_from_str() {
+        assert_eq!("detect".parse(), Ok(ScanResolution::Detect));
+        assert_eq!("150".parse(), Ok(ScanResolution::PixelsPerInch(150)));
+        assert!("123".parse::<ScanResolution>().is_err());
+    }
 
-        for mark_to_deface in [left_side_mark_to_deface, right_side_mark_to_deface] {
-            let rect = mark_to_deface.rect();
-            let rect = Rect::new(
-                rect.left() - PADDING as i32,
-                rect.top() - PADDING as i32,
-                rect.width() + 20,
-                rect.height() + PADDING * 2,
-            )
-            .intersect(&image_rect)
-            .unwrap();
-            for x in rect.left()..rect.right() {
-                for y in rect.top()..rect.bottom() {
-                    image.put_pixel(x as u32, y as u32, Luma([255]));
-                }
+    #[test]
+    fn test_toggle_from_str() {
+        assert_eq!("enabled".parse(), Ok(Toggle::Enabled));
+        assert_eq!("disabled".parse(), Ok(Toggle::Disabled));
+        assert!("on".parse::<Toggle>().is_err());
+        assert_eq!(Toggle::from(true).to_string(), "enabled");
+        assert!(!Toggle::from(false).is_enabled());
+    }
+
+    #[test]
+    fn test_channel_mix_from_str() {
+        assert_eq!("luma".parse(), Ok(ChannelMix::Luma));
+        assert_eq!("red".parse(), Ok(ChannelMix::Red));
+        assert_eq!(
+            "0.5, 0.5, 0".parse(),
+            Ok(ChannelMix::Weighted {
+                red: 0.5,
+                green: 0.5,
+                blue: 0.0,
+            })
+        );
+        assert!("0,0,0".parse::<ChannelMix>().is_err());
+        assert!("3e38,3e38,1".parse::<ChannelMix>().is_err());
+        assert!("inf,0,0".parse::<ChannelMix>().is_err());
+        assert!(serde_json::from_str::<ChannelMix>(r#""0,0,0""#).is_err());
+        assert!("1,-1,1".parse::<ChannelMix>().is_err());
+        assert!("1,1".parse::<ChannelMix>().is_err());
+        assert!("cyan".parse::<ChannelMix>().is_err());
+
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(2, 2, Rgb([200, 100, 50])));
+        assert_eq!(
+            ChannelMix::Weighted {
+                red: 0.0,
+                green: 0.0,
+                blue: 0.0,
             }
+            .to_gray(image.clone()),
+            image.into_luma8()
+        );
+
+        for channel_mix in ["luma", "green", "0.25,0.5,0.25"] {
+            assert_eq!(
+                channel_mix.parse::<ChannelMix>().unwrap().to_string(),
+                channel_mix
+            );
         }
     }
 
-    fn is_binary_image(image: &GrayImage) -> bool {
-        image
-            .as_raw()
+    #[test]
+    fn test_channel_mix_drops_out_red_ink() {
+        let (side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let card = ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap();
+        let (grid_position, bubble) = card
+            .front
+            .marks
             .iter()
-            .all(|&pixel| pixel == 0 || pixel == 255)
+            .find_map(|(grid_position, scored_bubble)| {
+                Some((grid_position, scored_bubble.as_ref()?))
+            })
+            .unwrap();
+
+        // Fill in the first bubble with red ink on a color copy of the scan.
+        let mut color_image = DynamicImage::ImageLuma8(side_a_image).into_rgb8();
+        let bounds = bubble.expected_bounds;
+        for x in bounds.left() + 3..bounds.right() - 3 {
+            for y in bounds.top() + 3..bounds.bottom() - 3 {
+                color_image.put_pixel(x as u32, y as u32, Rgb([230, 30, 40]));
+            }
+        }
+
+        for (channel_mix, expected_marked_option_ids) in [
+            (ChannelMix::Luma, vec![grid_position.option_id()]),
+            (ChannelMix::Green, vec![grid_position.option_id()]),
+            (ChannelMix::Red, vec![]),
+        ] {
+            let options = Options {
+                scan: ScanInterpreterOptions {
+                    channel_mix,
+                    ..options.scan.clone()
+                },
+                ..options.clone()
+            };
+            let card = ballot_card(color_image.clone(), side_b_image.clone(), &options).unwrap();
+            assert_eq!(
+                marked_option_ids(&card.front),
+                expected_marked_option_ids,
+                "channel mix: {channel_mix}"
+            );
+        }
     }
 
     #[test]
-    fn test_interpret_returns_binarized_images() {
-        let (side_a_image, side_b_image, options) =
+    fn test_drawn_bubble_template_scores_like_scanned_template() {
+        let (mut side_a_image, side_b_image, options) =
             load_hmpb_fixture("vx-general-election/letter-en", 1);
-        let card = ballot_card(side_a_image, side_b_image, &options).unwrap();
-        for page in [&card.front, &card.back] {
-            let encoded = page.encoded_normalized_image.as_ref().unwrap();
-            let decoded = image::load_from_memory(encoded).unwrap().into_luma8();
-            assert!(is_binary_image(&decoded));
+        let card = ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap();
+        let (grid_position, bubble) = card
+            .front
+            .marks
+            .iter()
+            .find_map(|(grid_position, scored_bubble)| {
+                Some((grid_position, scored_bubble.as_ref()?))
+            })
+            .unwrap();
+
+        let bounds = bubble.expected_bounds;
+        for x in bounds.left() + 3..bounds.right() - 3 {
+            for y in bounds.top() + 3..bounds.bottom() - 3 {
+                side_a_image.put_pixel(x as u32, y as u32, Luma([0]));
+            }
+        }
+
+        let scanned = ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap();
+        let drawn = ballot_card(
+            side_a_image,
+            side_b_image,
+            &Options {
+                scan: ScanInterpreterOptions {
+                    bubble_template: "rounded-0.2x0.14".parse().unwrap(),
+                    ..options.scan
+                },
+                ..options
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            marked_option_ids(&scanned.front),
+            vec![grid_position.option_id()]
+        );
+        assert_eq!(
+            marked_option_ids(&drawn.front),
+            marked_option_ids(&scanned.front)
+        );
+        for ((_, scanned_bubble), (_, drawn_bubble)) in
+            scanned.front.marks.iter().zip(&drawn.front.marks)
+        {
+            let scanned_bounds = scanned_bubble.as_ref().unwrap().matched_bounds;
+            let drawn_bounds = drawn_bubble.as_ref().unwrap().matched_bounds;
+            let center_distance = scanned_bounds.center().distance_to(&drawn_bounds.center());
+            assert!(center_distance <= 2.0, "center distance: {center_distance}");
         }
     }
 
@@ -777,6 +2246,36 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_decode_ballot_barcode_near_timing_marks() {
+        let (side_a_image, _, options) = load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let mut upside_down_image = side_a_image.clone();
+        image::imageops::rotate180_in_place(&mut upside_down_image);
+
+        let decode = |image: GrayImage| {
+            let ballot_page =
+                BallotPage::from_image(SIDE_A_LABEL, image, &PaperInfo::scanned(), None).unwrap();
+            let timing_marks = ballot_page
+                .find_timing_marks(&timing_marks::Options::default_for_geometry(
+                    ballot_page.geometry(),
+                ))
+                .unwrap();
+            ballot_page
+                .decode_ballot_barcode(
+                    &options.election,
+                    &options.expected_ballot_hash,
+                    Some(&timing_marks),
+                )
+                .unwrap()
+        };
+
+        let (metadata, orientation) = decode(side_a_image);
+        assert_eq!(orientation, Orientation::Portrait);
+        let (upside_down_metadata, upside_down_orientation) = decode(upside_down_image);
+        assert_eq!(upside_down_orientation, Orientation::PortraitReversed);
+        assert_eq!(upside_down_metadata, metadata);
+    }
+
     #[test]
     fn test_inferred_missing_metadata_from_one_side() {
         let (mut side_a_image, side_b_image, options) =
@@ -799,6 +2298,281 @@ mod test {
         ballot_card(side_a_image, side_b_image, &options).unwrap();
     }
 
+    /// Whites out the QR code on a ballot page image.
+    fn white_out_qr_code(image: &mut GrayImage) {
+        let qr_code_bounds = qr_code::detect_with_strategy(
+            image,
+            qr_code::SearchStrategy::BubbleCorners,
+            &ImageDebugWriter::disabled(),
+        )
+        .unwrap()
+        .bounds();
+        for y in qr_code_bounds.top() - 5..qr_code_bounds.bottom() + 5 {
+            for x in qr_code_bounds.left() - 5..qr_code_bounds.right() + 5 {
+                image.put_pixel(x as u32, y as u32, Luma([255]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_infer_ballot_style_when_both_qr_codes_are_unreadable() {
+        let (mut side_a_image, mut side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 5);
+        white_out_qr_code(&mut side_a_image);
+        white_out_qr_code(&mut side_b_image);
+
+        let error = ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap_err();
+        assert!(
+            matches!(error, Error::InvalidQrCodeMetadata { .. }),
+            "unexpected error: {error:?}"
+        );
+
+        let card = ballot_card(
+            side_a_image,
+            side_b_image,
+            &Options {
+                scan: ScanInterpreterOptions {
+                    ballot_style_inference: Toggle::Enabled,
+                    ..options.scan
+                },
+                ..options
+            },
+        )
+        .unwrap();
+        let inferred_ballot_style = card.inferred_ballot_style.unwrap();
+        assert_eq!(
+            inferred_ballot_style.ballot_style_id,
+            BallotStyleId::from("12".to_owned())
+        );
+        assert_eq!(inferred_ballot_style.sheet_number, 3);
+        assert!(matches!(
+            card.front.metadata,
+            BallotPageMetadata::Inferred { page_number, .. } if page_number.get() == 5
+        ));
+        assert!(matches!(
+            card.back.metadata,
+            BallotPageMetadata::Inferred { page_number, .. } if page_number.get() == 6
+        ));
+        assert!(card.front.metadata.precinct_id().is_none());
+        assert!(card.front.metadata.bubble_ballot_metadata().is_none());
+        let json = serde_json::to_value(&card.front.metadata).unwrap();
+        assert_eq!(json["source"], "inferred");
+        assert_eq!(json["ballotStyleId"], "12");
+        // Only the QR code says any of these, so they aren't made up.
+        for field in ["ballotHash", "isTestMode", "ballotType", "precinctId"] {
+            assert!(json.get(field).is_none(), "{field} in {json}");
+        }
+        assert!(card
+            .votes
+            .adjudication_reasons()
+            .contains(&AdjudicationReasonInfo::InferredBallotStyle));
+    }
+
+    #[test]
+    fn test_infer_ballot_style_of_reversed_upside_down_card() {
+        let (mut side_a_image, mut side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 3);
+        white_out_qr_code(&mut side_a_image);
+        white_out_qr_code(&mut side_b_image);
+        image::imageops::rotate180_in_place(&mut side_a_image);
+        image::imageops::rotate180_in_place(&mut side_b_image);
+
+        let card = ballot_card(
+            side_b_image,
+            side_a_image,
+            &Options {
+                scan: ScanInterpreterOptions {
+                    ballot_style_inference: Toggle::Enabled,
+                    ..options.scan
+                },
+                ..options
+            },
+        )
+        .unwrap();
+        assert_eq!(card.inferred_ballot_style.unwrap().sheet_number, 2);
+        assert_eq!(card.front.metadata.page_number().get(), 3);
+        assert!(card
+            .front
+            .marks
+            .iter()
+            .all(|(_, mark)| mark.as_ref().unwrap().fill_score.0 < 0.05));
+    }
+
+    #[test]
+    fn test_infer_ballot_style_of_simplex_page_without_qr_code() {
+        let (mut side_a_image, _, options) = load_hmpb_fixture("vx-general-election/letter-en", 5);
+        white_out_qr_code(&mut side_a_image);
+
+        let error = ballot_page(side_a_image.clone(), &options).unwrap_err();
+        assert!(
+            matches!(error, Error::InvalidQrCodeMetadata { .. }),
+            "unexpected error: {error:?}"
+        );
+
+        let simplex = ballot_page(
+            side_a_image,
+            &Options {
+                scan: ScanInterpreterOptions {
+                    ballot_style_inference: Toggle::Enabled,
+                    ..options.scan
+                },
+                ..options
+            },
+        )
+        .unwrap();
+        let inferred_ballot_style = simplex.inferred_ballot_style.unwrap();
+        assert_eq!(
+            inferred_ballot_style.ballot_style_id,
+            BallotStyleId::from("12".to_owned())
+        );
+        assert_eq!(inferred_ballot_style.sheet_number, 3);
+        assert_eq!(simplex.side, BallotSide::Front);
+        assert!(matches!(
+            simplex.page.metadata,
+            BallotPageMetadata::Inferred { page_number, .. } if page_number.get() == 5
+        ));
+        assert!(simplex.votes.is_ballot_style_inferred);
+        assert!(simplex
+            .votes
+            .adjudication_reasons()
+            .contains(&AdjudicationReasonInfo::InferredBallotStyle));
+    }
+
+    /// Replaces the QR code on a page with `metadata` encoded in its bottom
+    /// row of timing marks, the way `AccuVote` ballots are printed. Returns the
+    /// page as cropped for interpretation.
+    fn print_timing_mark_metadata(mut image: GrayImage, metadata: TimingMarkMetadata) -> GrayImage {
+        white_out_qr_code(&mut image);
+        let ballot_page =
+            BallotPage::from_image(SIDE_A_LABEL, image, &PaperInfo::scanned(), None).unwrap();
+        let geometry = ballot_page.geometry();
+        let timing_marks = ballot_page
+            .find_timing_marks(&timing_marks::Options::default_for_geometry(geometry))
+            .unwrap();
+        let mut image = ballot_page.ballot_image().image().clone();
+        let last_row = (geometry.grid_size.height - 1) as SubGridUnit;
+        let half_width = geometry.timing_mark_width_pixels() / 2.0;
+        let half_height = geometry.timing_mark_height_pixels() / 2.0;
+        for (index, bit) in metadata.encode().unwrap().into_iter().enumerate() {
+            let column = (geometry.grid_size.width as usize - 2 - index) as SubGridUnit;
+            let center = timing_marks.point_for_location(column, last_row).unwrap();
+            // Erase a little more than the mark so that no edge of it is left.
+            let margin = if bit { 0.0 } else { 3.0 };
+            for y in
+                (center.y - half_height - margin) as u32..(center.y + half_height + margin) as u32
+            {
+                for x in
+                    (center.x - half_width - margin) as u32..(center.x + half_width + margin) as u32
+                {
+                    image.put_pixel(x, y, Luma([if bit { 0 } else { 255 }]));
+                }
+            }
+        }
+        image
+    }
+
+    fn accuvote_front(card_number: u16) -> TimingMarkMetadata {
+        TimingMarkMetadata::Front(timing_marks::metadata::FrontMetadata {
+            mod_4_checksum: 0,
+            batch_or_precinct_number: 23,
+            card_number,
+            sequence_number: 0,
+        })
+    }
+
+    const ACCUVOTE_BACK: TimingMarkMetadata =
+        TimingMarkMetadata::Back(timing_marks::metadata::BackMetadata {
+            election_day: 3,
+            election_month: 11,
+            election_year: 26,
+            election_type: 'G',
+        });
+
+    #[test]
+    fn test_interpret_timing_mark_metadata() {
+        let (side_a_image, side_b_image, mut options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        options.scan.metadata_source = MetadataSource::TimingMarks;
+        let front_image = print_timing_mark_metadata(side_a_image, accuvote_front(12));
+        let back_image = print_timing_mark_metadata(side_b_image, ACCUVOTE_BACK);
+
+        // Fed upside down and back first.
+        let mut reversed_front_image = front_image.clone();
+        let mut reversed_back_image = back_image.clone();
+        image::imageops::rotate180_in_place(&mut reversed_front_image);
+        image::imageops::rotate180_in_place(&mut reversed_back_image);
+
+        for (side_a_image, side_b_image) in [
+            (front_image, back_image),
+            (reversed_back_image, reversed_front_image),
+        ] {
+            let card = ballot_card(side_a_image, side_b_image, &options).unwrap();
+            let BallotPageMetadata::TimingMarks {
+                metadata: front_metadata,
+                timing_mark_metadata: front_timing_mark_metadata,
+            } = &card.front.metadata
+            else {
+                panic!("unexpected front metadata: {:?}", card.front.metadata);
+            };
+            assert_eq!(
+                front_metadata.ballot_style_id,
+                BallotStyleId::from("12".to_owned())
+            );
+            assert_eq!(
+                front_metadata.precinct_id,
+                PrecinctId::from("23".to_owned())
+            );
+            assert_eq!(front_metadata.page_number, PageNumber::new(1).unwrap());
+            assert!(matches!(
+                front_timing_mark_metadata,
+                TimingMarkMetadata::Front(front) if front.card_number == 12
+            ));
+            let json = serde_json::to_value(&card.front.metadata).unwrap();
+            assert_eq!(json["source"], "timing-marks");
+            assert_eq!(json["ballotStyleId"], "12");
+            // The card doesn't say any of these, so they aren't made up.
+            for field in ["ballotHash", "isTestMode", "ballotType"] {
+                assert!(json.get(field).is_none(), "{field} in {json}");
+            }
+            assert!(card.front.metadata.bubble_ballot_metadata().is_none());
+            assert_eq!(json["timingMarkMetadata"]["side"], "front");
+            assert_eq!(json["timingMarkMetadata"]["cardNumber"], 12);
+            assert!(matches!(
+                &card.back.metadata,
+                BallotPageMetadata::TimingMarks {
+                    metadata,
+                    timing_mark_metadata: ACCUVOTE_BACK,
+                } if metadata.page_number == PageNumber::new(2).unwrap()
+            ));
+            assert!(card
+                .front
+                .marks
+                .iter()
+                .all(|(_, mark)| mark.as_ref().unwrap().fill_score.0 < 0.05));
+        }
+    }
+
+    #[test]
+    fn test_timing_mark_metadata_for_unknown_ballot_style() {
+        let (side_a_image, side_b_image, mut options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        options.scan.metadata_source = MetadataSource::TimingMarks;
+        let error = ballot_card(
+            print_timing_mark_metadata(side_a_image, accuvote_front(99)),
+            print_timing_mark_metadata(side_b_image, ACCUVOTE_BACK),
+            &options,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(
+                &error,
+                Error::InvalidTimingMarkMetadata { message, .. }
+                    if message == "no ballot style with ID 99"
+            ),
+            "unexpected error: {error:?}"
+        );
+    }
+
     #[test]
     fn test_vertical_streaks_not_through_bubbles() {
         let (mut side_a_image, mut side_b_image, options) =
@@ -912,7 +2686,10 @@ mod test {
             side_a_image,
             side_b_image,
             &Options {
-                vertical_streak_detection: VerticalStreakDetection::Disabled,
+                scan: ScanInterpreterOptions {
+                    vertical_streak_detection: Toggle::Disabled,
+                    ..options.scan
+                },
                 ..options
             },
         )
@@ -982,7 +2759,7 @@ mod test {
         // version of the election than the one in `hmpb/fixtures/...` and their
         // QR codes predate the current metadata encoding, so we supply the
         // metadata directly instead of decoding it.
-        options.metadata_source = provided_metadata(Metadata {
+        options.scan.metadata_source = provided_metadata(Metadata {
             ballot_hash: options.expected_ballot_hash,
             precinct_id: PrecinctId::from("23".to_owned()),
             ballot_style_id: BallotStyleId::from("12".to_owned()),
@@ -1031,7 +2808,7 @@ mod test {
         match ballot_card(side_a_image.clone(), side_b_image, &options) {
             Err(Error::MissingTimingMarks { reason, .. }) => assert_eq!(
                 reason,
-                "Unable to find mark along Left border at index 20; no marks close enough?"
+                "Unable to find mark along Left border at index 23; no marks close enough?"
             ),
             Err(err) => {
                 panic!("unexpected error: {err:?}");
@@ -1064,13 +2841,107 @@ mod test {
         match ballot_card(side_a_image, side_b_image, &options) {
             Err(Error::MissingTimingMarks { reason, .. }) => assert_eq!(
                 reason,
-                "Unable to find mark along Left border at index 20; no marks close enough?"
+                "Unable to find mark along Left border at index 23; no marks close enough?"
             ),
             Err(err) => panic!("unexpected error: {err:?}"),
             Ok(_) => panic!("interpretation unexpectedly succeeded"),
         }
     }
 
+    #[test]
+    fn test_missing_side_timing_marks_are_inferred() {
+        let (mut side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let expected = ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap();
+        let expected_timing_marks = &expected.front.timing_marks;
+        assert!(expected_timing_marks.inferred_marks.is_empty());
+
+        remove_side_timing_marks(&mut side_a_image, expected_timing_marks, 10..12);
+
+        let interpretation = ballot_card(side_a_image, side_b_image, &options).unwrap();
+        let timing_marks = &interpretation.front.timing_marks;
+        assert_eq!(timing_marks.inferred_marks.left, vec![10, 11]);
+        assert_eq!(timing_marks.inferred_marks.right, vec![10, 11]);
+
+        for (inferred, original) in [
+            (
+                &timing_marks.border_marks.left,
+                &expected_timing_marks.border_marks.left,
+            ),
+            (
+                &timing_marks.border_marks.right,
+                &expected_timing_marks.border_marks.right,
+            ),
+        ] {
+            for index in 10..12 {
+                let distance = inferred[index]
+                    .rect()
+                    .center()
+                    .distance_to(&original[index].rect().center());
+                assert!(
+                    distance < 2.0,
+                    "inferred mark {index} is {distance}px from the original"
+                );
+            }
+        }
+
+        assert_eq!(
+            marked_option_ids(&interpretation.front),
+            marked_option_ids(&expected.front)
+        );
+    }
+
+    #[test]
+    fn test_dog_eared_corner_is_inferred() {
+        let (mut side_a_image, side_b_image, options) =
+            load_hmpb_fixture("vx-general-election/letter-en", 1);
+        let expected = ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap();
+        let expected_timing_marks = &expected.front.timing_marks;
+        assert_eq!(expected_timing_marks.inferred_corner, None);
+
+        // Fold the bottom right corner over, hiding the corner mark and the
+        // two marks next to it along each border.
+        let geometry = &expected_timing_marks.geometry;
+        let corner = expected_timing_marks.bottom_right_corner;
+        let fold_size = 2.5
+            * geometry
+                .vertical_timing_mark_center_to_center_pixel_distance()
+                .max(geometry.horizontal_timing_mark_center_to_center_pixel_distance());
+        for (x, y, pixel) in side_a_image.enumerate_pixels_mut() {
+            if (x as f32 - corner.x) + (y as f32 - corner.y) > -fold_size {
+                *pixel = Luma([255]);
+            }
+        }
+
+        let _debug_image = DebugImage::write(
+            "debug__test_dog_eared_corner_is_inferred.png",
+            &side_a_image,
+        );
+
+        let interpretation = ballot_card(side_a_image, side_b_image, &options).unwrap();
+        let timing_marks = &interpretation.front.timing_marks;
+        assert_eq!(
+            timing_marks.inferred_corner,
+            Some(timing_marks::Corner::BottomRight)
+        );
+        let last = timing_marks.border_marks.right.len() - 1;
+        assert_eq!(timing_marks.inferred_marks.right.last(), Some(&last));
+        assert!(timing_marks.inferred_marks.left.is_empty());
+
+        let distance = timing_marks
+            .bottom_right_corner
+            .distance_to(&expected_timing_marks.bottom_right_corner);
+        assert!(
+            distance < 3.0,
+            "inferred corner is {distance}px from the original"
+        );
+
+        assert_eq!(
+            marked_option_ids(&interpretation.front),
+            marked_option_ids(&expected.front)
+        );
+    }
+
     #[test]
     fn test_imprinting_over_timing_marks() {
         let (side_a_image, side_b_image, options) = load_ballot_card_fixture(
@@ -1339,7 +3210,7 @@ mod test {
 
         let (mut side_a_image, side_b_image, mut options) =
             load_hmpb_fixture("vx-general-election/letter-en", 1);
-        options.max_cumulative_streak_width = 20;
+        options.scan.max_cumulative_streak_width = 20;
 
         let clean_interpretation =
             ballot_card(side_a_image.clone(), side_b_image.clone(), &options)
@@ -1365,7 +3236,11 @@ mod test {
         // can attract a nearby bubble's matched bounds toward the streak,
         // and we want clear of both the bubble template's max search
         // distance AND the bubble template's full width on either side.
-        let bubble_w = options.bubble_template.width() as i32;
+        let bubble_w = options
+            .scan
+            .bubble_template
+            .at_pixels_per_inch(SCAN_PIXELS_PER_INCH)
+            .width() as i32;
         let exclude_radius =
             STREAK_HALF_WIDTH + scoring::DEFAULT_MAXIMUM_SEARCH_DISTANCE as i32 + bubble_w;
         let line_x = (1..n_cols - 1)
@@ -1526,7 +3401,10 @@ mod test {
         // Set a minimum scale of 98.5%.
         let minimum_detected_scale = UnitIntervalScore(0.985);
         let options = Options {
-            minimum_detected_scale: Some(minimum_detected_scale),
+            scan: ScanInterpreterOptions {
+                minimum_detected_scale: Some(minimum_detected_scale),
+                ..options.scan
+            },
             ..options
         };
 
@@ -1589,8 +3467,8 @@ mod test {
         // but fail the retry threshold. Looking at the actual fixture, it has thin streaks
         // that are around 2-3px wide. We'll set normal threshold high enough to let it pass
         // initially, but retry threshold low enough to catch it.
-        options.max_cumulative_streak_width = 10; // Allow streaks up to 10px during normal check
-        options.retry_streak_width_threshold = 1; // But catch anything >1px during retry
+        options.scan.max_cumulative_streak_width = 10; // Allow streaks up to 10px during normal check
+        options.scan.retry_streak_width_threshold = 1; // But catch anything >1px during retry
 
         // The streaked image doesn't have valid timing marks, so timing mark detection
         // will fail, triggering the retry logic with the lower threshold.
@@ -1648,8 +3526,8 @@ mod test {
         // Don't add any streaks - the ballot should be truly unreadable
 
         // Set thresholds: normal=5px, retry=1px
-        options.max_cumulative_streak_width = 5;
-        options.retry_streak_width_threshold = 1;
+        options.scan.max_cumulative_streak_width = 5;
+        options.scan.retry_streak_width_threshold = 1;
 
         // Interpret should fail with MissingTimingMarks (no streaks found)
         let error = super::ballot_card(side_a_image, side_b_image, &options).unwrap_err();
@@ -1676,8 +3554,8 @@ mod test {
         let (_, side_b_image, mut options) = load_hmpb_fixture("vx-general-election/letter-en", 1);
 
         // Set thresholds so that streaks are caught by the normal threshold
-        options.max_cumulative_streak_width = 1; // Very strict - catch any streaks immediately
-        options.retry_streak_width_threshold = 1;
+        options.scan.max_cumulative_streak_width = 1; // Very strict - catch any streaks immediately
+        options.scan.retry_streak_width_threshold = 1;
 
         // Interpret should fail with VerticalStreaksDetected immediately
         // (before timing mark detection even runs)
@@ -1706,8 +3584,8 @@ mod test {
 
         // Test with retry threshold = 2px (should detect streaks during retry)
         // Set normal threshold high enough to not catch initially
-        options.max_cumulative_streak_width = 100; // High enough to not trigger initially
-        options.retry_streak_width_threshold = 2;
+        options.scan.max_cumulative_streak_width = 100; // High enough to not trigger initially
+        options.scan.retry_streak_width_threshold = 2;
 
         let error =
             super::ballot_card(side_a_image.clone(), side_b_image.clone(), &options).unwrap_err();
@@ -1720,8 +3598,8 @@ mod test {
 
         // Test with retry threshold = 200px (should NOT detect streaks)
         // The actual streaks in the image are around 23px cumulative, so 200px should pass
-        options.max_cumulative_streak_width = 200;
-        options.retry_streak_width_threshold = 200;
+        options.scan.max_cumulative_streak_width = 200;
+        options.scan.retry_streak_width_threshold = 200;
         let error = super::ballot_card(side_a_image, side_b_image, &options).unwrap_err();
         match error {
             Error::MissingTimingMarks { .. } => {
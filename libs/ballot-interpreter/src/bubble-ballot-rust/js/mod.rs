@@ -10,18 +10,23 @@ use napi::bindgen_prelude::Buffer;
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 use std::io::Cursor;
+use types_rs::ballot_card::PaperSize;
 use types_rs::bmd::cvr::CastVoteRecord;
 use types_rs::bubble_ballot::{PartialBallotHash, PARTIAL_BALLOT_HASH_BYTE_LENGTH};
 use types_rs::coding;
-use types_rs::election::Election;
+use types_rs::election::{Election, MarkThresholds, PrecinctId};
 
-use crate::ballot_card::{ballot_scan_bubble_image, BallotPage, PaperInfo};
+use crate::ballot_card::{
+    BallotPage, BubbleTemplate, SCAN_PIXELS_PER_INCH, SUPPORTED_SCAN_PIXELS_PER_INCH,
+};
 use crate::interpret::{
-    self, ballot_card, InterpretedBallotCard, MetadataSource, Options, VerticalStreakDetection,
-    WriteInScoring,
+    self, ballot_card, ChannelMix, InterpretedBallotCard, MetadataSource, Options,
+    ScanInterpreterOptions, ScanResolution,
 };
 use crate::scoring::UnitIntervalScore;
+use crate::summary_ballot::{self, interpret_summary_ballot, InterpretedSummaryBallot};
 use crate::timing_marks::{self, DefaultForGeometry, TimingMarks};
+use crate::votes::election_mark_thresholds;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -36,9 +41,81 @@ struct JsInterpretOptions {
     debug_base_path_side_b: Option<String>,
     minimum_detected_scale: Option<f64>,
     score_write_ins: Option<bool>,
+    /// Whether to include crops of each write-in area and contest with each
+    /// interpreted page.
+    crop_areas: Option<bool>,
+    /// Whether to flag contests with ink outside their bubbles and write-in
+    /// areas as possible voter intent.
+    detect_stray_marks: Option<bool>,
+    /// Whether to reject ballots with fold lines or non-vertical streaks
+    /// crossing bubbles.
+    detect_line_artifacts: Option<bool>,
+    /// Whether to identify the ballot style from where bubbles are printed
+    /// when neither QR code can be read, holding the card for adjudication.
+    infer_ballot_style: Option<bool>,
     disable_vertical_streak_detection: Option<bool>,
     max_cumulative_streak_width: u32,
     retry_streak_width_threshold: u32,
+    /// Thresholds for deriving votes from bubble scores, usually from system
+    /// settings. Falls back to the election's thresholds, then to the
+    /// defaults.
+    mark_thresholds: Option<MarkThresholds>,
+    /// Density of the images, if known. When omitted only images scanned at
+    /// [`SCAN_PIXELS_PER_INCH`] are accepted.
+    scan_pixels_per_inch: Option<u32>,
+    /// How to combine the channels of color images before thresholding, e.g.
+    /// "red" to drop out red ink. Defaults to luma.
+    channel_mix: Option<ChannelMix>,
+    /// The bubble to look for, e.g. "oval-0.25x0.17" for larger ovals than
+    /// our ballots use. Defaults to the scanned bubble from our ballots.
+    bubble_template: Option<BubbleTemplate>,
+    /// Where to read ballot metadata from: "qr-code" (the default), or
+    /// "timing-marks" for `AccuVote` ballots, which have no QR code.
+    metadata_source: Option<MetadataSource>,
+}
+
+impl JsInterpretOptions {
+    fn minimum_detected_scale(&self) -> Result<Option<UnitIntervalScore>, napi::Error> {
+        match self.minimum_detected_scale {
+            Some(minimum_detected_scale)
+                if minimum_detected_scale > f64::from(f32::MAX)
+                    || minimum_detected_scale < f64::from(f32::MIN) =>
+            {
+                Err(napi::Error::from_reason("Invalid minimum detected scale"))
+            }
+            Some(minimum_detected_scale) => {
+                Ok(Some(UnitIntervalScore(minimum_detected_scale as f32)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn scan_resolution(&self) -> Result<ScanResolution, napi::Error> {
+        match self.scan_pixels_per_inch {
+            Some(pixels_per_inch) if !SUPPORTED_SCAN_PIXELS_PER_INCH.contains(&pixels_per_inch) => {
+                Err(napi::Error::from_reason(format!(
+                    "Unsupported scan resolution: {pixels_per_inch} pixels per inch"
+                )))
+            }
+            Some(pixels_per_inch) => Ok(ScanResolution::PixelsPerInch(pixels_per_inch)),
+            // Other densities are only accepted when asked for.
+            None => Ok(ScanResolution::PixelsPerInch(SCAN_PIXELS_PER_INCH)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsInterpretSummaryBallotOptions {
+    /// Expected ballot hash as a hex string, as for `JsInterpretOptions`.
+    expected_ballot_hash: String,
+    /// Whether the scanner is in test mode.
+    is_test_mode: bool,
+    /// The precincts the scanner is configured for.
+    valid_precinct_ids: Vec<PrecinctId>,
+    /// How to combine the channels of color images before looking for the QR
+    /// code. Defaults to luma.
+    channel_mix: Option<ChannelMix>,
 }
 
 /// Decodes a hex ballot hash string into a [`PartialBallotHash`]. Accepts
@@ -79,50 +156,55 @@ enum JsInterpretResult {
     Err(Box<JsInterpretErr>),
 }
 
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value")]
+enum JsInterpretSummaryBallotResult {
+    #[serde(rename = "ok")]
+    Ok(Box<InterpretedSummaryBallot>),
+    #[serde(rename = "err")]
+    Err(summary_ballot::Error),
+}
+
 fn interpret(
     election: Election,
-    side_a_image: GrayImage,
-    side_b_image: GrayImage,
+    side_a_image: DynamicImage,
+    side_b_image: DynamicImage,
     options: JsInterpretOptions,
 ) -> Result<JsInterpretResult, napi::Error> {
-    let minimum_detected_scale = match options.minimum_detected_scale {
-        Some(minimum_detected_scale)
-            if minimum_detected_scale > f64::from(f32::MAX)
-                || minimum_detected_scale < f64::from(f32::MIN) =>
-        {
-            return Err(napi::Error::from_reason("Invalid minimum detected scale"));
-        }
-        Some(minimum_detected_scale) => Some(UnitIntervalScore(minimum_detected_scale as f32)),
-        None => None,
-    };
-
+    let minimum_detected_scale = options.minimum_detected_scale()?;
     let expected_ballot_hash = decode_partial_ballot_hash(&options.expected_ballot_hash)?;
+    let scan_resolution = options.scan_resolution()?;
+    let mark_thresholds = options
+        .mark_thresholds
+        .unwrap_or_else(|| election_mark_thresholds(&election));
 
-    let bubble_template = ballot_scan_bubble_image();
     let interpret_result = ballot_card(
         side_a_image,
         side_b_image,
         &Options {
             election,
             expected_ballot_hash,
-            bubble_template,
             debug_side_a_base: options.debug_base_path_side_a.map(PathBuf::from),
             debug_side_b_base: options.debug_base_path_side_b.map(PathBuf::from),
-            write_in_scoring: if options.score_write_ins.unwrap_or(false) {
-                WriteInScoring::Enabled
-            } else {
-                WriteInScoring::Disabled
+            scan: ScanInterpreterOptions {
+                write_in_scoring: options.score_write_ins.unwrap_or(false).into(),
+                vertical_streak_detection: (!options
+                    .disable_vertical_streak_detection
+                    .unwrap_or(false))
+                .into(),
+                minimum_detected_scale,
+                max_cumulative_streak_width: options.max_cumulative_streak_width,
+                retry_streak_width_threshold: options.retry_streak_width_threshold,
+                mark_thresholds,
+                scan_resolution,
+                area_cropping: options.crop_areas.unwrap_or(false).into(),
+                stray_mark_detection: options.detect_stray_marks.unwrap_or(false).into(),
+                line_artifact_detection: options.detect_line_artifacts.unwrap_or(false).into(),
+                channel_mix: options.channel_mix.unwrap_or_default(),
+                bubble_template: options.bubble_template.unwrap_or_default(),
+                ballot_style_inference: options.infer_ballot_style.unwrap_or(false).into(),
+                metadata_source: options.metadata_source.unwrap_or_default(),
             },
-            vertical_streak_detection: if options.disable_vertical_streak_detection.unwrap_or(false)
-            {
-                VerticalStreakDetection::Disabled
-            } else {
-                VerticalStreakDetection::Enabled
-            },
-            minimum_detected_scale,
-            max_cumulative_streak_width: options.max_cumulative_streak_width,
-            retry_streak_width_threshold: options.retry_streak_width_threshold,
-            metadata_source: MetadataSource::QrCode,
         },
     );
 
@@ -219,8 +301,8 @@ pub async fn interpret_paths(
 
     // Decode images in parallel with rayon (CPU-bound)
     let (side_a_image, side_b_image) = match rayon::join(
-        || image::load_from_memory(&side_a_bytes).map(DynamicImage::into_luma8),
-        || image::load_from_memory(&side_b_bytes).map(DynamicImage::into_luma8),
+        || image::load_from_memory(&side_a_bytes),
+        || image::load_from_memory(&side_b_bytes),
     ) {
         (Err(err), _) | (_, Err(err)) => {
             return Err(napi::Error::from_reason(err.to_string()));
@@ -232,6 +314,56 @@ pub async fn interpret_paths(
     to_json(&result)
 }
 
+#[napi(
+    ts_args_type = "election: Election, sideAImagePath: string, sideBImagePath: string, options: BridgeInterpretSummaryBallotOptions",
+    ts_return_type = "Promise<BridgeInterpretSummaryBallotResult>"
+)]
+pub async fn interpret_summary_ballot_paths(
+    election: serde_json::Value,
+    side_a_image_path: String,
+    side_b_image_path: String,
+    options: serde_json::Value,
+) -> napi::Result<serde_json::Value> {
+    let election: Election = from_json(election)?;
+    let options: JsInterpretSummaryBallotOptions = from_json(options)?;
+    let channel_mix = options.channel_mix.unwrap_or_default();
+
+    let (side_a_bytes, side_b_bytes) = tokio::try_join!(
+        tokio::fs::read(&side_a_image_path),
+        tokio::fs::read(&side_b_image_path),
+    )
+    .map_err(|err| napi::Error::from_reason(err.to_string()))?;
+
+    let (side_a_image, side_b_image) = match rayon::join(
+        || image::load_from_memory(&side_a_bytes),
+        || image::load_from_memory(&side_b_bytes),
+    ) {
+        (Err(err), _) | (_, Err(err)) => {
+            return Err(napi::Error::from_reason(err.to_string()));
+        }
+        (Ok(side_a_image), Ok(side_b_image)) => (
+            channel_mix.to_gray(side_a_image),
+            channel_mix.to_gray(side_b_image),
+        ),
+    };
+
+    let result = match interpret_summary_ballot(
+        &side_a_image,
+        &side_b_image,
+        &summary_ballot::Options {
+            election,
+            expected_ballot_hash: decode_partial_ballot_hash(&options.expected_ballot_hash)?,
+            is_test_mode: options.is_test_mode,
+            valid_precinct_ids: options.valid_precinct_ids,
+        },
+    ) {
+        Ok(interpreted) => JsInterpretSummaryBallotResult::Ok(Box::new(interpreted)),
+        // Don't throw `summary_ballot::Error`, for better structured & typed handling.
+        Err(err) => JsInterpretSummaryBallotResult::Err(err),
+    };
+    to_json(&result)
+}
+
 // unused_async: napi-rs requires `async fn` to return a Promise in JS.
 #[allow(clippy::too_many_arguments, clippy::unused_async)]
 #[napi(
@@ -257,8 +389,8 @@ pub async fn interpret_images(
     let side_b_h = as_u32(side_b_image_height)?;
 
     let (side_a_image, side_b_image) = match rayon::join(
-        || gray_image(side_a_w, side_a_h, side_a_image_data.to_vec()),
-        || gray_image(side_b_w, side_b_h, side_b_image_data.to_vec()),
+        || scan_image(side_a_w, side_a_h, side_a_image_data.to_vec()),
+        || scan_image(side_b_w, side_b_h, side_b_image_data.to_vec()),
     ) {
         (Err(err), _) | (_, Err(err)) => return Err(err),
         (Ok(side_a_image), Ok(side_b_image)) => (side_a_image, side_b_image),
@@ -273,10 +405,15 @@ fn find_timing_mark_grid_inner(
     label: &str,
     debug_path: Option<PathBuf>,
 ) -> Result<TimingMarks, napi::Error> {
-    let ballot_page = BallotPage::from_image(label, image, &PaperInfo::scanned(), debug_path)
-        .map_err(|err| {
-            napi::Error::from_reason(format!("Unable to prepare ballot page image: {err}"))
-        })?;
+    let ballot_page = BallotPage::from_image(
+        label,
+        image,
+        &ScanResolution::PixelsPerInch(SCAN_PIXELS_PER_INCH).paper_infos(&PaperSize::STANDARD),
+        debug_path,
+    )
+    .map_err(|err| {
+        napi::Error::from_reason(format!("Unable to prepare ballot page image: {err}"))
+    })?;
 
     let find_timing_marks_result = ballot_page.find_timing_marks(
         &timing_marks::Options::default_for_geometry(ballot_page.geometry()),
@@ -332,6 +469,12 @@ pub async fn find_timing_mark_grid_from_image(
 }
 
 fn gray_image(width: u32, height: u32, data: Vec<u8>) -> Result<GrayImage, napi::Error> {
+    scan_image(width, height, data).map(DynamicImage::into_luma8)
+}
+
+/// Wraps raw image data from JS, which is either one byte per pixel
+/// (grayscale) or four (RGBA), keeping color images in color.
+fn scan_image(width: u32, height: u32, data: Vec<u8>) -> Result<DynamicImage, napi::Error> {
     let len = data.len();
     let pixel_count = (width as usize)
         .checked_mul(height as usize)
@@ -342,18 +485,20 @@ fn gray_image(width: u32, height: u32, data: Vec<u8>) -> Result<GrayImage, napi:
         })?;
 
     if len == pixel_count {
-        GrayImage::from_vec(width, height, data).ok_or_else(|| {
-            napi::Error::from_reason(format!(
-                "Could not construct GrayImage: width={width} height={height} buffer length={len}",
-            ))
-        })
+        GrayImage::from_vec(width, height, data)
+            .map(DynamicImage::ImageLuma8)
+            .ok_or_else(|| {
+                napi::Error::from_reason(format!(
+                    "Could not construct GrayImage: width={width} height={height} buffer length={len}",
+                ))
+            })
     } else if Some(len) == pixel_count.checked_mul(4) {
         let rgba = RgbaImage::from_vec(width, height, data).ok_or_else(|| {
             napi::Error::from_reason(format!(
                 "Could not construct RgbaImage: width={width} height={height} buffer length={len}",
             ))
         })?;
-        Ok(DynamicImage::ImageRgba8(rgba).into_luma8())
+        Ok(DynamicImage::ImageRgba8(rgba))
     } else {
         Err(napi::Error::from_reason(format!(
             "Unexpected buffer length for image: width={width} height={height} buffer length={len}"
@@ -387,6 +532,7 @@ pub async fn run_blank_paper_diagnostic_from_path(
         .map_err(|err| napi::Error::from_reason(err.to_string()))?;
     Ok(crate::diagnostic::blank_paper(
         image,
+        &BubbleTemplate::default(),
         debug_path.map(PathBuf::from),
     ))
 }
@@ -410,6 +556,7 @@ pub async fn run_blank_paper_diagnostic_from_image(
     )?;
     Ok(crate::diagnostic::blank_paper(
         image,
+        &BubbleTemplate::default(),
         debug_path.map(PathBuf::from),
     ))
 }
@@ -535,6 +682,19 @@ mod test {
         }
     }
 
+    #[test]
+    fn scan_image_keeps_rgba_data_in_color() {
+        let red_pixel = [255u8, 0, 0, 255];
+        let data = red_pixel.repeat(4);
+        let image = scan_image(2, 2, data).unwrap();
+        assert!(matches!(image, DynamicImage::ImageRgba8(_)));
+        assert_eq!(
+            ChannelMix::Red.to_gray(image.clone()).get_pixel(0, 0).0,
+            [255]
+        );
+        assert_eq!(ChannelMix::Green.to_gray(image).get_pixel(0, 0).0, [0]);
+    }
+
     #[test]
     fn gray_image_rejects_mismatched_buffer() {
         let pixel_count = SCAN_WIDTH as usize * SCAN_HEIGHT_LETTER as usize;
@@ -4,15 +4,27 @@
 #![allow(clippy::cast_possible_wrap)]
 
 pub mod ballot_card;
+pub mod calibration;
+pub mod cdf;
+pub mod crops;
 pub mod debug;
+pub mod debug_report;
 mod diagnostic;
 mod draw_utils;
 mod image_utils;
 pub mod interpret;
 mod js;
 mod layout;
+pub mod line_artifacts;
 pub mod qr_code;
+pub mod quality;
 pub mod scoring;
+pub mod stray_marks;
+pub mod style_inference;
+pub mod summary_ballot;
+#[cfg(feature = "synthetic")]
+pub mod synthetic;
 pub mod timing_marks;
+pub mod votes;
 
 // Anything marked with `#[napi]` is exported to JavaScript.
@@ -0,0 +1,679 @@
+use itertools::Itertools;
+use serde::Serialize;
+use types_rs::election::ContestId;
+use types_rs::geometry::{PixelPosition, Point, Rect, Segment, SubPixelUnit};
+
+use crate::ballot_card::{BallotImage, Geometry};
+use crate::debug;
+use crate::scoring::{ScoredBubbleMarks, UnitIntervalScore};
+use crate::timing_marks::TimingMarks;
+
+/// Lines are searched for at angles within this many degrees of horizontal.
+/// Steeper lines are left to vertical streak detection.
+const MAX_LINE_ANGLE_DEGREES: f32 = 45.0;
+
+/// Lines that stray less than this far from the timing mark grid's rows over
+/// their whole length look just like printed rules, or could be following
+/// along one, so they are only reported if they run all the way from the left
+/// timing mark column to the right one, which printed content never does.
+const MIN_ANGLED_LINE_DRIFT_INCHES: f32 = 0.1;
+
+/// Lines that stray further than [`MIN_ANGLED_LINE_DRIFT_INCHES`] from the
+/// grid's rows can't be printed content, so they are reported if they are at
+/// least this long.
+const MIN_ANGLED_LINE_LENGTH_INCHES: f32 = 2.0;
+
+/// The longest run of white a line may have and still count as one line.
+/// Matches the gap allowed in vertical streaks, i.e. 15px at 200 DPI.
+const MAX_LINE_GAP_INCHES: f32 = 0.075;
+
+/// How far from its center a line's ink may be and still count as part of
+/// it, so that slightly wavy or skewed creases are followed.
+const LINE_HALF_THICKNESS_INCHES: f32 = 0.01;
+
+/// How far beyond [`LINE_HALF_THICKNESS_INCHES`] to look for white on either
+/// side of a line.
+const LINE_CLEARANCE_INCHES: f32 = 0.03;
+
+/// The fraction of a line's length that must be ink.
+const MIN_LINE_COVERAGE: UnitIntervalScore = UnitIntervalScore(0.8);
+
+/// The fraction of a line's length that may have ink at
+/// [`LINE_CLEARANCE_INCHES`] on its whiter side. More than this means the
+/// "line" is actually running through a larger dark or shaded area, such as
+/// a filled header bar.
+const MAX_LINE_SIDE_INK: UnitIntervalScore = UnitIntervalScore(0.25);
+
+/// Lines whose angles differ by less than this are considered duplicates if
+/// they are also close together.
+const SAME_LINE_ANGLE_TOLERANCE_DEGREES: f32 = 1.0;
+
+/// The coarse search counts ink in square cells of this many pixels.
+const HOUGH_CELL_SIZE: usize = 4;
+
+/// The angular resolution of the coarse search.
+const HOUGH_ANGLE_STEP_DEGREES: f32 = 0.5;
+
+/// The resolution of the coarse search across lines.
+const HOUGH_INTERCEPT_BIN_PIXELS: f32 = 8.0;
+
+/// The angular resolution used to refine each line found by the coarse
+/// search.
+const REFINE_ANGLE_STEP_DEGREES: f32 = 0.1;
+
+/// A long, straight, thin line that is not part of the printed ballot, e.g. a
+/// crease shadow from a fold or a line left by a scanner roller.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineArtifact {
+    pub start: Point<SubPixelUnit>,
+    pub end: Point<SubPixelUnit>,
+
+    /// The fraction of the line's length that is ink.
+    pub coverage: UnitIntervalScore,
+}
+
+impl LineArtifact {
+    pub const fn segment(&self) -> Segment {
+        Segment::new(self.start, self.end)
+    }
+
+    /// The angle of the line in degrees, positive when it slopes downward to
+    /// the right.
+    #[must_use]
+    pub fn angle_degrees(&self) -> f32 {
+        (self.end.y - self.start.y)
+            .atan2(self.end.x - self.start.x)
+            .to_degrees()
+    }
+}
+
+/// Finds line artifacts within the timing mark grid that cross any of
+/// `crossing`, at angles of up to 45° from horizontal. Lines that follow the
+/// grid's rows must cross it from one timing mark column to the other, while
+/// angled lines need only be long.
+pub(crate) fn detect_line_artifacts(
+    ballot_image: &BallotImage,
+    geometry: &Geometry,
+    timing_marks: &TimingMarks,
+    crossing: &[Rect],
+) -> Vec<LineArtifact> {
+    let pixels_per_inch = geometry.pixels_per_inch as f32;
+    let Some(ink) = GridInk::new(ballot_image, timing_marks) else {
+        return vec![];
+    };
+    let clearance =
+        ((LINE_HALF_THICKNESS_INCHES + LINE_CLEARANCE_INCHES) * pixels_per_inch).round() as i32;
+    let tracer = LineTracer {
+        ink: &ink,
+        row_angles: row_angles(timing_marks),
+        half_thickness: (LINE_HALF_THICKNESS_INCHES * pixels_per_inch).round() as i32,
+        clearance,
+        max_gap: (MAX_LINE_GAP_INCHES * pixels_per_inch).round() as i32,
+        min_angled_drift: MIN_ANGLED_LINE_DRIFT_INCHES * pixels_per_inch,
+        min_angled_length: MIN_ANGLED_LINE_LENGTH_INCHES * pixels_per_inch,
+    };
+
+    let mut artifacts = ink
+        .coarse_line_candidates(&tracer, clearance as usize / HOUGH_CELL_SIZE, crossing)
+        .into_iter()
+        .flat_map(|(angle, intercept)| {
+            let (angle, intercept) = ink.refine_line(angle, intercept);
+            tracer.trace(angle, intercept)
+        })
+        .collect_vec();
+
+    // A thick line is found several times over, once for each nearby angle and
+    // offset that fits within it. Keep the longest of each.
+    artifacts.sort_by(|a, b| b.segment().length().total_cmp(&a.segment().length()));
+    let mut distinct: Vec<LineArtifact> = vec![];
+    for artifact in artifacts {
+        if !distinct
+            .iter()
+            .any(|kept| same_line(kept, &artifact, clearance as f32 * 2.0))
+        {
+            distinct.push(artifact);
+        }
+    }
+
+    ballot_image.debug().write("line_artifacts", |canvas| {
+        debug::draw_line_artifacts_debug_image_mut(canvas, &distinct);
+    });
+
+    distinct
+}
+
+/// The contests with a bubble whose expected bounds are crossed by any of
+/// `artifacts`, in the order they appear in `marks`.
+pub(crate) fn contests_crossed_by_line_artifacts(
+    artifacts: &[LineArtifact],
+    marks: &ScoredBubbleMarks,
+) -> Vec<ContestId> {
+    marks
+        .iter()
+        .filter_map(|(grid_position, scored_bubble_mark)| {
+            let bounds = scored_bubble_mark.as_ref()?.expected_bounds;
+            artifacts
+                .iter()
+                .any(|artifact| bounds.intersects_line(&artifact.segment()))
+                .then(|| grid_position.contest_id())
+        })
+        .unique()
+        .collect()
+}
+
+/// Whether `b` lies along `a`, i.e. at nearly the same angle and with its
+/// midpoint within `max_distance` of the line through `a`.
+fn same_line(a: &LineArtifact, b: &LineArtifact, max_distance: SubPixelUnit) -> bool {
+    if (a.angle_degrees() - b.angle_degrees()).abs() > SAME_LINE_ANGLE_TOLERANCE_DEGREES {
+        return false;
+    }
+    let (dx, dy) = (a.end.x - a.start.x, a.end.y - a.start.y);
+    let midpoint = Point::new(b.start.x.midpoint(b.end.x), b.start.y.midpoint(b.end.y));
+    let distance =
+        (dx * (midpoint.y - a.start.y) - dy * (midpoint.x - a.start.x)).abs() / dx.hypot(dy);
+    distance <= max_distance
+}
+
+/// The y coordinate and angle of each row of the timing mark grid, measured
+/// from the left border mark to the right one. Warped scans have rows at
+/// noticeably different angles, so lines are compared against the row
+/// nearest to them rather than the grid as a whole.
+fn row_angles(timing_marks: &TimingMarks) -> Vec<(SubPixelUnit, f32)> {
+    timing_marks
+        .border_marks
+        .left
+        .iter()
+        .zip(&timing_marks.border_marks.right)
+        .map(|(left, right)| {
+            let (left, right) = (left.rect().center(), right.rect().center());
+            (
+                left.y.midpoint(right.y),
+                (right.y - left.y).atan2(right.x - left.x),
+            )
+        })
+        .collect()
+}
+
+/// The ink within the timing mark grid, between the centers of the left and
+/// right timing mark columns and between the top and bottom timing mark rows.
+/// Coordinates are relative to the top left of that area.
+struct GridInk {
+    dark: Vec<bool>,
+    left: u32,
+    top: u32,
+    width: usize,
+    height: usize,
+}
+
+impl GridInk {
+    fn new(ballot_image: &BallotImage, timing_marks: &TimingMarks) -> Option<Self> {
+        let image = ballot_image.image();
+        let left = timing_marks
+            .top_left_corner
+            .x
+            .min(timing_marks.bottom_left_corner.x)
+            .max(0.0) as u32;
+        let right = (timing_marks
+            .top_right_corner
+            .x
+            .max(timing_marks.bottom_right_corner.x) as u32)
+            .min(image.width() - 1);
+        let top = timing_marks
+            .top_left_mark
+            .rect()
+            .bottom()
+            .max(timing_marks.top_right_mark.rect().bottom())
+            .max(0) as u32
+            + 1;
+        let bottom = (timing_marks
+            .bottom_left_mark
+            .rect()
+            .top()
+            .min(timing_marks.bottom_right_mark.rect().top())
+            .max(1) as u32
+            - 1)
+        .min(image.height() - 1);
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        let threshold = ballot_image.threshold();
+        let dark = image
+            .rows()
+            .skip(top as usize)
+            .take((bottom - top + 1) as usize)
+            .flat_map(|row| {
+                row.skip(left as usize)
+                    .take((right - left + 1) as usize)
+                    .map(|pixel| pixel.0[0] <= threshold)
+            })
+            .collect();
+        Some(Self {
+            dark,
+            left,
+            top,
+            width: (right - left + 1) as usize,
+            height: (bottom - top + 1) as usize,
+        })
+    }
+
+    fn is_dark(&self, x: i32, y: i32) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as usize) < self.width
+            && (y as usize) < self.height
+            && self.dark[y as usize * self.width + x as usize]
+    }
+
+    /// The lowest intercept a line within the searched angles can have.
+    fn min_intercept(&self) -> f32 {
+        -(self.width as f32) * MAX_LINE_ANGLE_DEGREES.to_radians().tan()
+    }
+
+    /// The cells of [`HOUGH_CELL_SIZE`] pixels that contain ink but could be
+    /// part of a thin line, i.e. that don't have ink in the cells
+    /// `clearance_cells` above and below them both. This leaves out the
+    /// insides of shaded areas and dense text, which would otherwise make the
+    /// coarse search find lines everywhere.
+    fn thin_ink_cells(&self, clearance_cells: usize) -> InkCells {
+        let wide = self.width.div_ceil(HOUGH_CELL_SIZE);
+        let high = self.height.div_ceil(HOUGH_CELL_SIZE);
+        let mut cells = vec![false; wide * high];
+        for (y, row) in self.dark.chunks_exact(self.width).enumerate() {
+            let cell_row = &mut cells[y / HOUGH_CELL_SIZE * wide..];
+            for (x, _) in row.iter().enumerate().filter(|(_, dark)| **dark) {
+                cell_row[x / HOUGH_CELL_SIZE] = true;
+            }
+        }
+        let ink_cells = InkCells { wide, high, cells };
+
+        let clearance_cells = clearance_cells as i32;
+        let thin = (0..ink_cells.high as i32)
+            .cartesian_product(0..ink_cells.wide as i32)
+            .map(|(row, column)| {
+                ink_cells.is_set(row, column)
+                    && !(ink_cells.is_set(row - clearance_cells, column)
+                        && ink_cells.is_set(row + clearance_cells, column))
+            })
+            .collect();
+        InkCells {
+            cells: thin,
+            ..ink_cells
+        }
+    }
+
+    /// Finds the (angle, intercept) pairs of lines through any of `crossing`
+    /// with enough ink along them to possibly be a line artifact, where the
+    /// intercept is the line's y coordinate at x = 0. Uses a Hough transform
+    /// over the cells from [`GridInk::thin_ink_cells`].
+    fn coarse_line_candidates(
+        &self,
+        tracer: &LineTracer,
+        clearance_cells: usize,
+        crossing: &[Rect],
+    ) -> Vec<(f32, f32)> {
+        let cells = self.thin_ink_cells(clearance_cells);
+        let centers = cells.centers();
+        let angle_count = (2.0 * MAX_LINE_ANGLE_DEGREES / HOUGH_ANGLE_STEP_DEGREES) as usize + 1;
+        let angles = (0..angle_count)
+            .map(|i| (i as f32).mul_add(HOUGH_ANGLE_STEP_DEGREES, -MAX_LINE_ANGLE_DEGREES))
+            .map(f32::to_radians)
+            .collect_vec();
+        let min_intercept = self.min_intercept();
+        let bin_count = ((self.height as f32 - 2.0 * min_intercept) / HOUGH_INTERCEPT_BIN_PIXELS)
+            .ceil() as usize
+            + 1;
+
+        let mut votes = vec![0u32; angle_count * bin_count];
+        for (row, angle) in votes.chunks_exact_mut(bin_count).zip(&angles) {
+            let slope = angle.tan();
+            for (x, y) in &centers {
+                let intercept = slope.mul_add(-x, *y);
+                let bin = ((intercept - min_intercept) / HOUGH_INTERCEPT_BIN_PIXELS) as usize;
+                row[bin.min(bin_count - 1)] += 1;
+            }
+        }
+
+        // A line's cells may fall into two neighboring bins, so look at the
+        // votes for each pair of bins, i.e. for bands two bins wide.
+        let band_count = bin_count - 1;
+        let band_votes = |angle_index: usize, band: usize| {
+            votes[angle_index * bin_count + band] + votes[angle_index * bin_count + band + 1]
+        };
+        let mut candidates = vec![];
+        for (angle_index, angle) in angles.iter().enumerate() {
+            let slope = angle.tan();
+            let crosses = self.bands_crossing(slope, crossing, band_count);
+            let min_votes = MIN_LINE_COVERAGE.0 * tracer.min_length_at(*angle) * angle.cos()
+                / HOUGH_CELL_SIZE as f32;
+            for (band, _) in crosses.iter().enumerate().filter(|(_, crosses)| **crosses) {
+                let count = band_votes(angle_index, band);
+                if (count as f32) < min_votes {
+                    continue;
+                }
+                // Break ties toward lower indexes so that a plateau of equal
+                // votes yields one candidate rather than all of them.
+                let is_local_maximum = (angle_index.saturating_sub(1)..=angle_index + 1)
+                    .filter(|i| *i < angle_count)
+                    .cartesian_product(band.saturating_sub(1)..=band + 1)
+                    .filter(|(_, b)| *b < band_count)
+                    .all(|(i, b)| {
+                        let other = band_votes(i, b);
+                        other < count || (other == count && (i, b) >= (angle_index, band))
+                    });
+                let intercept =
+                    ((band + 1) as f32).mul_add(HOUGH_INTERCEPT_BIN_PIXELS, min_intercept);
+                if is_local_maximum
+                    && cells.has_run_along(
+                        slope,
+                        intercept,
+                        tracer.min_length_at(*angle) * angle.cos(),
+                        tracer.max_gap as f32,
+                    )
+                {
+                    candidates.push((*angle, intercept));
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Which of the `band_count` Hough bands, each two bins wide, contain lines
+    /// with the given slope that pass through any of `rects`.
+    fn bands_crossing(&self, slope: f32, rects: &[Rect], band_count: usize) -> Vec<bool> {
+        let min_intercept = self.min_intercept();
+        let bin_of = |intercept: f32| {
+            ((intercept - min_intercept) / HOUGH_INTERCEPT_BIN_PIXELS).max(0.0) as usize
+        };
+        let mut crosses = vec![false; band_count];
+        for rect in rects {
+            let (min, max) = [
+                (rect.left(), rect.top()),
+                (rect.right(), rect.top()),
+                (rect.left(), rect.bottom()),
+                (rect.right(), rect.bottom()),
+            ]
+            .into_iter()
+            .map(|(x, y)| {
+                slope.mul_add(
+                    -(x - self.left as PixelPosition) as f32,
+                    (y - self.top as PixelPosition) as f32,
+                )
+            })
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+            // Band `b` holds intercepts in bins `b` and `b + 1`.
+            let first = bin_of(min).saturating_sub(1);
+            let last = bin_of(max).min(band_count - 1);
+            if first <= last {
+                crosses[first..=last].fill(true);
+            }
+        }
+        crosses
+    }
+
+    /// Finds the angle and intercept near a coarse candidate with the most
+    /// ink directly along it.
+    fn refine_line(&self, angle: f32, intercept: f32) -> (f32, f32) {
+        let angle_steps = (HOUGH_ANGLE_STEP_DEGREES / REFINE_ANGLE_STEP_DEGREES).round() as i32;
+        let intercept_steps = HOUGH_INTERCEPT_BIN_PIXELS as i32;
+        (-angle_steps..=angle_steps)
+            .map(|step| angle + (step as f32 * REFINE_ANGLE_STEP_DEGREES).to_radians())
+            .filter(|angle| angle.abs() <= MAX_LINE_ANGLE_DEGREES.to_radians())
+            .flat_map(|angle| {
+                let slope = angle.tan();
+                let mut counts = vec![0u32; (2 * intercept_steps + 1) as usize];
+                for x in (0..self.width as i32).step_by(HOUGH_CELL_SIZE) {
+                    let y = slope.mul_add(x as f32, intercept).round() as i32;
+                    for (offset, count) in (-intercept_steps..).zip(counts.iter_mut()) {
+                        *count += u32::from(self.is_dark(x, y + offset));
+                    }
+                }
+                (-intercept_steps..)
+                    .zip(counts)
+                    .map(move |(offset, count)| (angle, intercept + offset as f32, count))
+            })
+            .max_by_key(|(_, _, count)| *count)
+            .map_or((angle, intercept), |(angle, intercept, _)| {
+                (angle, intercept)
+            })
+    }
+}
+
+/// Cells of [`HOUGH_CELL_SIZE`] pixels within [`GridInk`], each of which is
+/// either set or not.
+struct InkCells {
+    wide: usize,
+    high: usize,
+    cells: Vec<bool>,
+}
+
+impl InkCells {
+    fn is_set(&self, row: i32, column: i32) -> bool {
+        row >= 0
+            && column >= 0
+            && (row as usize) < self.high
+            && (column as usize) < self.wide
+            && self.cells[row as usize * self.wide + column as usize]
+    }
+
+    /// The centers of the set cells in [`GridInk`] coordinates.
+    fn centers(&self) -> Vec<(f32, f32)> {
+        let half_cell = HOUGH_CELL_SIZE as f32 / 2.0;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, set)| **set)
+            .map(|(index, _)| {
+                (
+                    ((index % self.wide) * HOUGH_CELL_SIZE) as f32 + half_cell,
+                    ((index / self.wide) * HOUGH_CELL_SIZE) as f32 + half_cell,
+                )
+            })
+            .collect()
+    }
+
+    /// Whether the band of two Hough bins centered on the line with the given
+    /// slope and intercept has set cells across at least `min_width` pixels,
+    /// with gaps of no more than `max_gap` pixels and [`MIN_LINE_COVERAGE`]
+    /// overall. This is a quick check of coarse candidates to avoid refining
+    /// the many that cross dense text.
+    fn has_run_along(&self, slope: f32, intercept: f32, min_width: f32, max_gap: f32) -> bool {
+        let half_band = (HOUGH_INTERCEPT_BIN_PIXELS / HOUGH_CELL_SIZE as f32) as i32;
+        let max_gap_cells = (max_gap / HOUGH_CELL_SIZE as f32).ceil() as i32;
+        let min_width_cells = min_width / HOUGH_CELL_SIZE as f32;
+        let mut run: Option<(i32, i32, u32)> = None;
+        for column in 0..=self.wide as i32 + max_gap_cells {
+            let x = (column as f32 + 0.5) * HOUGH_CELL_SIZE as f32;
+            let row = (slope.mul_add(x, intercept) / HOUGH_CELL_SIZE as f32).floor() as i32;
+            if (-half_band..=half_band).any(|offset| self.is_set(row + offset, column)) {
+                let (start, _, count) = run.unwrap_or((column, column, 0));
+                run = Some((start, column, count + 1));
+            } else if let Some((start, end, count)) = run {
+                if column - end > max_gap_cells {
+                    let width = (end - start + 1) as f32;
+                    if width >= min_width_cells && count as f32 / width >= MIN_LINE_COVERAGE.0 {
+                        return true;
+                    }
+                    run = None;
+                }
+            }
+        }
+        false
+    }
+}
+
+/// A stretch of a traced line from `start` to `end`, inclusive, of which
+/// `on_line` pixels have ink.
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    start: i32,
+    end: i32,
+    on_line: u32,
+}
+
+struct LineTracer<'a> {
+    ink: &'a GridInk,
+    row_angles: Vec<(SubPixelUnit, f32)>,
+    half_thickness: i32,
+    clearance: i32,
+    max_gap: i32,
+    min_angled_drift: f32,
+    min_angled_length: f32,
+}
+
+impl LineTracer<'_> {
+    /// Follows the line with the given angle and intercept across the grid,
+    /// splitting it wherever it has a gap wider than `max_gap`, and returns
+    /// the pieces that are line artifacts.
+    fn trace(&self, angle: f32, intercept: f32) -> Vec<LineArtifact> {
+        let slope = angle.tan();
+        let last_x = self.ink.width as i32 - 1;
+        let y_at = |x: i32| slope.mul_add(x as f32, intercept).round() as i32;
+
+        let mut artifacts = vec![];
+        let mut run: Option<Run> = None;
+        for x in 0..=last_x + self.max_gap + 1 {
+            let y = y_at(x);
+            let in_bounds = x <= last_x && y >= 0 && y < self.ink.height as i32;
+            let on_line = in_bounds
+                && (-self.half_thickness..=self.half_thickness)
+                    .any(|dy| self.ink.is_dark(x, y + dy));
+
+            if on_line {
+                let current = run.unwrap_or(Run {
+                    start: x,
+                    end: x,
+                    on_line: 0,
+                });
+                run = Some(Run {
+                    end: x,
+                    on_line: current.on_line + 1,
+                    ..current
+                });
+            } else if let Some(current) = run {
+                if !in_bounds || x - current.end > self.max_gap {
+                    artifacts.extend(self.artifact_from_run(&current, angle, y_at));
+                    run = None;
+                }
+            }
+        }
+        artifacts
+    }
+
+    /// The line artifact for `run`, if it is long and thin enough to be one.
+    fn artifact_from_run(
+        &self,
+        run: &Run,
+        angle: f32,
+        y_at: impl Fn(i32) -> i32,
+    ) -> Option<LineArtifact> {
+        let span = (run.end - run.start + 1) as f32;
+        let coverage = UnitIntervalScore(run.on_line as f32 / span);
+        let (above, below) = (run.start..=run.end).fold((0, 0), |(above, below), x| {
+            let y = y_at(x);
+            (
+                above + u32::from(self.ink.is_dark(x, y - self.clearance)),
+                below + u32::from(self.ink.is_dark(x, y + self.clearance)),
+            )
+        });
+        let side_ink = UnitIntervalScore(above.min(below) as f32 / span);
+        if coverage < MIN_LINE_COVERAGE || side_ink > MAX_LINE_SIDE_INK {
+            return None;
+        }
+
+        let start = self.image_point(run.start, y_at(run.start));
+        let end = self.image_point(run.end, y_at(run.end));
+        let length = span / angle.cos();
+        let drift = length
+            * (angle - self.row_angle_near(start.y.midpoint(end.y)))
+                .sin()
+                .abs();
+        let long_enough = if drift < self.min_angled_drift {
+            run.start <= self.max_gap && run.end >= self.ink.width as i32 - 1 - self.max_gap
+        } else {
+            length >= self.min_angled_length
+        };
+        long_enough.then_some(LineArtifact {
+            start,
+            end,
+            coverage,
+        })
+    }
+
+    /// The shortest a line at `angle` could be and still be a line artifact
+    /// somewhere in the grid.
+    fn min_length_at(&self, angle: f32) -> f32 {
+        let (min_row_angle, max_row_angle) = self
+            .row_angles
+            .iter()
+            .map(|(_, row_angle)| *row_angle)
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+        let angle_from_rows = (min_row_angle - angle).max(angle - max_row_angle).max(0.0);
+        let full_width = self.ink.width as f32 / angle.cos();
+        if full_width * angle_from_rows.sin() < self.min_angled_drift {
+            full_width
+        } else {
+            (self.min_angled_drift / angle_from_rows.sin()).max(self.min_angled_length)
+        }
+    }
+
+    /// The angle of the timing mark grid row nearest to `y`.
+    fn row_angle_near(&self, y: SubPixelUnit) -> f32 {
+        self.row_angles
+            .iter()
+            .min_by(|(a, _), (b, _)| (a - y).abs().total_cmp(&(b - y).abs()))
+            .map_or(0.0, |(_, angle)| *angle)
+    }
+
+    fn image_point(&self, x: i32, y: i32) -> Point<SubPixelUnit> {
+        Point::new(
+            (self.ink.left as PixelPosition + x) as SubPixelUnit,
+            (self.ink.top as PixelPosition + y) as SubPixelUnit,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn artifact(start: (f32, f32), end: (f32, f32)) -> LineArtifact {
+        LineArtifact {
+            start: Point::new(start.0, start.1),
+            end: Point::new(end.0, end.1),
+            coverage: UnitIntervalScore(1.0),
+        }
+    }
+
+    #[test]
+    fn test_same_line() {
+        let line = artifact((0.0, 100.0), (1000.0, 110.0));
+        assert!(same_line(
+            &line,
+            &artifact((200.0, 104.0), (800.0, 110.0)),
+            10.0
+        ));
+        assert!(!same_line(
+            &line,
+            &artifact((200.0, 140.0), (800.0, 146.0)),
+            10.0
+        ));
+        assert!(!same_line(
+            &line,
+            &artifact((0.0, 100.0), (1000.0, 400.0)),
+            10.0
+        ));
+    }
+
+    #[test]
+    fn test_angle_degrees() {
+        assert!(artifact((0.0, 0.0), (100.0, 0.0)).angle_degrees().abs() < 1e-4);
+        assert!((artifact((0.0, 0.0), (100.0, 100.0)).angle_degrees() - 45.0).abs() < 1e-4);
+        assert!((artifact((0.0, 100.0), (100.0, 0.0)).angle_degrees() + 45.0).abs() < 1e-4);
+    }
+}
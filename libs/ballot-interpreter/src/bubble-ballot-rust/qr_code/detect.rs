@@ -2,6 +2,7 @@ use std::cell::OnceCell;
 
 use base64::{engine::general_purpose::STANDARD, Engine as _};
 use image::GrayImage;
+use itertools::Itertools;
 use serde::Serialize;
 use types_rs::{
     bmd, bubble_ballot,
@@ -12,9 +13,15 @@ use crate::{
     ballot_card::Orientation,
     debug::{self, ImageDebugWriter},
     image_utils::crop_to_image,
+    timing_marks::TimingMarks,
 };
 
-use super::{rqrr, zedbar};
+use super::{enhance::Enhancement, rqrr, zedbar};
+
+/// Size of the square inside each corner of the timing mark grid that is
+/// searched for the QR code once the grid has been found. Ballot QR codes are
+/// about 0.6" square and sit about 0.25" inside the bottom left corner.
+const GRID_CORNER_SEARCH_INCHES: f32 = 1.25;
 
 /// An area in a ballot image to be searched for QR codes.
 pub struct DetectionArea<'a> {
@@ -76,6 +83,19 @@ impl<'a> DetectionArea<'a> {
             )
         })
     }
+
+    /// Gets a copy of this area whose image has been cleaned up by
+    /// `enhancement`. Detectors report positions in the enhanced image, which
+    /// [`Enhancement::unmap_bounds`] maps back.
+    fn enhanced(&self, enhancement: Enhancement) -> Self {
+        Self {
+            source: self.source,
+            origin: self.origin,
+            size: self.size,
+            orientation: self.orientation,
+            cropped: OnceCell::from(enhancement.apply(self.image())),
+        }
+    }
 }
 
 /// Strategy for where to search for QR codes in an image.
@@ -145,6 +165,46 @@ pub fn get_broad_detection_areas(img: &GrayImage) -> Vec<DetectionArea<'_>> {
     ]
 }
 
+/// Gets detection areas for where the QR code should be given the timing mark
+/// grid found in `img`: inside its bottom left corner for a right-side up
+/// ballot, or inside its top right corner for an upside down one. These are
+/// much smaller than the [`SearchStrategy::BubbleCorners`] areas, leaving
+/// less for the detectors to be confused by.
+pub fn get_timing_mark_grid_detection_areas<'a>(
+    img: &'a GrayImage,
+    timing_marks: &TimingMarks,
+) -> Vec<DetectionArea<'a>> {
+    let (width, height) = img.dimensions();
+    let extent = (GRID_CORNER_SEARCH_INCHES * timing_marks.geometry.pixels_per_inch as f32) as u32;
+    let size = Size {
+        width: extent.min(width),
+        height: extent.min(height),
+    };
+    let clamp_origin = |x: f32, y: f32| {
+        Point::new(
+            (x.max(0.0) as u32).min(width - size.width),
+            (y.max(0.0) as u32).min(height - size.height),
+        )
+    };
+    let bottom_left = timing_marks.bottom_left_corner;
+    let top_right = timing_marks.top_right_corner;
+
+    vec![
+        DetectionArea::new(
+            img,
+            clamp_origin(bottom_left.x, bottom_left.y - extent as f32),
+            size,
+            Orientation::Portrait,
+        ),
+        DetectionArea::new(
+            img,
+            clamp_origin(top_right.x - extent as f32, top_right.y),
+            size,
+            Orientation::PortraitReversed,
+        ),
+    ]
+}
+
 /// Gets the detection areas for a given search strategy.
 pub fn get_detection_areas_for_strategy(
     img: &GrayImage,
@@ -197,13 +257,47 @@ fn is_vx_payload(bytes: &[u8]) -> bool {
     )
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
 #[must_use]
 pub enum Detector {
     Rqrr,
     Zedbar,
 }
 
+/// One pass of the QR code detectors over a set of detection areas.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionAttempt {
+    /// How the detection areas were cleaned up first, if at all.
+    pub enhancement: Option<Enhancement>,
+    pub detection_areas: Vec<Rect>,
+    /// Why nothing was decoded, or `None` if this attempt found the QR code.
+    pub error: Option<String>,
+}
+
+impl DetectionAttempt {
+    fn rotate180(&mut self, image_size: Size<u32>) {
+        rotate_rects180(&mut self.detection_areas, image_size);
+    }
+}
+
+/// Where `rect` ends up in an image of `image_size` rotated 180°.
+const fn rotate_rect180(rect: Rect, image_size: Size<u32>) -> Rect {
+    Rect::new(
+        image_size.width as i32 - 1 - rect.right(),
+        image_size.height as i32 - 1 - rect.bottom(),
+        rect.width(),
+        rect.height(),
+    )
+}
+
+fn rotate_rects180(rects: &mut [Rect], image_size: Size<u32>) {
+    for rect in rects {
+        *rect = rotate_rect180(*rect, image_size);
+    }
+}
+
 /// Information about a QR code found in an image.
 #[derive(Debug, Clone)]
 #[must_use]
@@ -213,6 +307,8 @@ pub struct Detected {
     bytes: Vec<u8>,
     bounds: Rect,
     orientation: Orientation,
+    area_index: usize,
+    attempts: Vec<DetectionAttempt>,
 }
 
 impl Detected {
@@ -229,6 +325,16 @@ impl Detected {
             bytes,
             bounds,
             orientation,
+            area_index: 0,
+            attempts: Vec::new(),
+        }
+    }
+
+    /// Records that the QR code was found in the detection area at `index`.
+    pub(super) fn in_area(self, index: usize) -> Self {
+        Self {
+            area_index: index,
+            ..self
         }
     }
 
@@ -259,11 +365,29 @@ impl Detected {
         &self.detection_areas
     }
 
+    /// Every pass of the detectors it took to find the QR code, ending with
+    /// the one that found it.
+    #[must_use]
+    pub fn attempts(&self) -> &[DetectionAttempt] {
+        &self.attempts
+    }
+
     /// Classifies the QR code payload by inspecting the 3-byte prelude.
     #[must_use]
     pub fn kind(&self) -> QrCodeKind {
         classify_qr_payload(&self.bytes)
     }
+
+    /// Moves everything this says about where the QR code was searched for
+    /// and found to match the image of `image_size` it was found in being
+    /// rotated 180°. The orientation is left as it was scanned.
+    pub fn rotate180(&mut self, image_size: Size<u32>) {
+        self.bounds = rotate_rect180(self.bounds, image_size);
+        rotate_rects180(&mut self.detection_areas, image_size);
+        for attempt in &mut self.attempts {
+            attempt.rotate180(image_size);
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, thiserror::Error)]
@@ -280,10 +404,17 @@ pub enum Error {
     },
     #[error("no QR code detected (searched {detection_areas:?})")]
     NoQrCodeDetected { detection_areas: Vec<Rect> },
+    #[error("{error} (after {} attempts)", attempts.len())]
+    AllAttemptsFailed {
+        /// Why the areas as scanned didn't yield a QR code.
+        error: Box<Error>,
+        attempts: Vec<DetectionAttempt>,
+    },
 }
 
 impl Error {
-    fn detection_areas(&self) -> &[Rect] {
+    /// The areas of the image that were searched for QR codes.
+    pub fn detection_areas(&self) -> &[Rect] {
         match self {
             Self::DecodeFailed {
                 detection_areas, ..
@@ -292,6 +423,41 @@ impl Error {
                 detection_areas, ..
             }
             | Self::NoQrCodeDetected { detection_areas } => detection_areas,
+            Self::AllAttemptsFailed { error, .. } => error.detection_areas(),
+        }
+    }
+
+    /// Every pass of the detectors that was made before giving up, if they
+    /// were retried.
+    #[must_use]
+    pub fn attempts(&self) -> &[DetectionAttempt] {
+        match self {
+            Self::AllAttemptsFailed { attempts, .. } => attempts,
+            Self::DecodeFailed { .. }
+            | Self::DetectFailed { .. }
+            | Self::NoQrCodeDetected { .. } => &[],
+        }
+    }
+
+    /// Like [`Detected::rotate180`], moves the searched areas to match the
+    /// image being rotated 180°.
+    pub fn rotate180(&mut self, image_size: Size<u32>) {
+        match self {
+            Self::DecodeFailed {
+                detection_areas, ..
+            }
+            | Self::DetectFailed {
+                detection_areas, ..
+            }
+            | Self::NoQrCodeDetected { detection_areas } => {
+                rotate_rects180(detection_areas, image_size);
+            }
+            Self::AllAttemptsFailed { error, attempts } => {
+                error.rotate180(image_size);
+                for attempt in attempts {
+                    attempt.rotate180(image_size);
+                }
+            }
         }
     }
 }
@@ -312,13 +478,99 @@ fn unwrap_qr_payload(detected: &Detected) -> Detected {
         _ => detected.bytes().to_vec(),
     };
 
-    Detected::new(
-        detected.detector(),
-        detected.detection_areas().to_vec(),
+    Detected {
         bytes,
-        detected.bounds(),
-        detected.orientation(),
-    )
+        ..detected.clone()
+    }
+}
+
+/// Tries each detector in turn over `areas`, returning the first QR code
+/// found.
+fn detect_in_areas(areas: &[DetectionArea<'_>]) -> Result {
+    // Try zedbar first because on representative ballot images it decodes
+    // more QR codes than rqrr and is faster per call. rqrr is kept as a
+    // fallback to catch the rare cases zedbar misses.
+    zedbar::detect_in_areas(areas).or_else(|_| rqrr::detect_in_areas(areas))
+}
+
+/// Searches `areas` for a QR code as scanned, then again after each of
+/// [`Enhancement::RETRIES`] until one is found. Every attempt is recorded in
+/// the result or the error. If none succeed, the error wraps the one from
+/// searching the areas as scanned.
+///
+/// Only `zedbar` searches the enhanced areas. It already finds more codes than
+/// `rqrr` does, and trying both on every enhancement would double what a page
+/// without a readable QR code costs.
+fn detect_with_retries(
+    areas: &[DetectionArea<'_>],
+    debug: &ImageDebugWriter,
+    name: &str,
+) -> Result {
+    let detection_areas = areas.iter().map(DetectionArea::bounds).collect_vec();
+    let mut attempts = Vec::new();
+    let mut first_error = None;
+    let mut found = None;
+
+    let enhancements = [None].into_iter().chain(Enhancement::RETRIES.map(Some));
+    for enhancement in enhancements {
+        let result = match enhancement {
+            None => detect_in_areas(areas),
+            Some(enhancement) => {
+                let enhanced = areas
+                    .iter()
+                    .map(|area| area.enhanced(enhancement))
+                    .collect_vec();
+                zedbar::detect_in_areas(&enhanced).map(|detected| {
+                    let area = &areas[detected.area_index];
+                    let origin = area.origin();
+                    let local = detected
+                        .bounds()
+                        .offset(-(origin.x as i32), -(origin.y as i32));
+                    let bounds = enhancement
+                        .unmap_bounds(local, area.image().dimensions())
+                        .offset(origin.x as i32, origin.y as i32);
+                    Detected { bounds, ..detected }
+                })
+            }
+        };
+
+        attempts.push(DetectionAttempt {
+            enhancement,
+            detection_areas: detection_areas.clone(),
+            error: result.as_ref().err().map(ToString::to_string),
+        });
+
+        match result {
+            Ok(detected) => {
+                found = Some(detected);
+                break;
+            }
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+
+    let detect_result = match found {
+        Some(detected) => Ok(Detected {
+            attempts,
+            ..detected
+        }),
+        None => Err(Error::AllAttemptsFailed {
+            error: Box::new(first_error.unwrap_or(Error::NoQrCodeDetected { detection_areas })),
+            attempts,
+        }),
+    };
+
+    let searched_areas = match detect_result {
+        Ok(ref qr_code) => qr_code.detection_areas(),
+        Err(ref e) => e.detection_areas(),
+    };
+    debug.write(name, |canvas| {
+        debug::draw_qr_code_debug_image_mut(canvas, detect_result.as_ref().ok(), searched_areas);
+    });
+
+    detect_result.map(|detected| unwrap_qr_payload(&detected))
 }
 
 /// Detect a QR code using the specified search strategy.
@@ -335,22 +587,23 @@ pub fn detect_with_strategy(
     // scanning the whole image: both decoders scale poorly with image area,
     // and the cost of producing the crops themselves is negligible.
     let areas = get_detection_areas_for_strategy(img, strategy);
+    detect_with_retries(&areas, debug, "qr_code")
+}
 
-    // Try zedbar first because on representative ballot images it decodes
-    // more QR codes than rqrr and is faster per call. rqrr is kept as a
-    // fallback to catch the rare cases zedbar misses.
-    let zedbar_result = zedbar::detect_in_areas(&areas);
-    let detect_result = zedbar_result.or_else(|_| rqrr::detect_in_areas(&areas));
-    let detection_areas = match detect_result {
-        Ok(ref qr_code) => qr_code.detection_areas().to_vec(),
-        Err(ref e) => e.detection_areas().to_vec(),
-    };
-
-    debug.write("qr_code", |canvas| {
-        debug::draw_qr_code_debug_image_mut(canvas, detect_result.as_ref().ok(), &detection_areas);
-    });
-
-    detect_result.map(|detected| unwrap_qr_payload(&detected))
+/// Detect a QR code where it should be given the timing mark grid found in
+/// `img`. This is a last resort for when [`detect_with_strategy`] failed, e.g.
+/// because something else in the ballot corner confused the detectors.
+///
+/// # Errors
+///
+/// Returns an `Err` if no QR codes are detected.
+pub fn detect_near_timing_marks(
+    img: &GrayImage,
+    timing_marks: &TimingMarks,
+    debug: &ImageDebugWriter,
+) -> Result {
+    let areas = get_timing_mark_grid_detection_areas(img, timing_marks);
+    detect_with_retries(&areas, debug, "qr_code_near_timing_marks")
 }
 
 #[cfg(test)]
@@ -358,6 +611,7 @@ pub fn detect_with_strategy(
 mod test {
     use std::path::PathBuf;
 
+    use image::Luma;
     use proptest::proptest;
     use types_rs::geometry::Rect;
 
@@ -416,6 +670,24 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_rotate_detected_180() {
+        let mut detected = Detected::new(
+            Detector::Zedbar,
+            vec![Rect::new(0, 10, 4, 5)],
+            vec![],
+            Rect::new(1, 12, 2, 3),
+            Orientation::PortraitReversed,
+        );
+        detected.rotate180(Size {
+            width: 10,
+            height: 20,
+        });
+        assert_eq!(detected.detection_areas(), [Rect::new(6, 5, 4, 5)]);
+        assert_eq!(detected.bounds(), Rect::new(7, 5, 2, 3));
+        assert_eq!(detected.orientation(), Orientation::PortraitReversed);
+    }
+
     #[test]
     fn test_unwrap_base64_wrapped_payload() {
         let raw = [0x56, 0x53, 0x01, 0xab, 0xcd];
@@ -559,4 +831,139 @@ mod test {
         )
         .expect("bottom QR code should be detected");
     }
+
+    /// Loads a ballot page and punches a regular pattern of light specks into
+    /// the dark modules of its QR code, as a worn-out print head might.
+    /// Returns the page along with the QR code as found before the specks.
+    fn speckled_qr_code_image() -> (GrayImage, Detected) {
+        let fixture_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test/fixtures");
+        let image_path = fixture_path.join("all-bubble-ballot/blank-front.jpg");
+        let mut image = image::open(image_path).unwrap().into_luma8();
+        let expected = detect_with_strategy(
+            &image,
+            SearchStrategy::BubbleCorners,
+            &ImageDebugWriter::disabled(),
+        )
+        .unwrap();
+        assert_eq!(expected.attempts().len(), 1);
+
+        let qr_bounds = expected.bounds();
+        for y in qr_bounds.top() as u32..=qr_bounds.bottom() as u32 {
+            for x in qr_bounds.left() as u32..=qr_bounds.right() as u32 {
+                if image.get_pixel(x, y).0[0] < 128 && (x * 7 + y * 13) % 5 == 0 {
+                    image.put_pixel(x, y, Luma([u8::MAX]));
+                }
+            }
+        }
+        (image, expected)
+    }
+
+    #[test]
+    fn test_detect_qr_code_with_specks() {
+        let (image, expected) = speckled_qr_code_image();
+        let qr_bounds = expected.bounds();
+
+        let areas = get_hmpb_detection_areas(&image);
+        assert!(detect_in_areas(&areas).is_err());
+
+        let qr_code = detect_with_strategy(
+            &image,
+            SearchStrategy::BubbleCorners,
+            &ImageDebugWriter::disabled(),
+        )
+        .unwrap();
+        assert_eq!(qr_code.bytes(), expected.bytes());
+        assert_eq!(qr_code.orientation(), Orientation::Portrait);
+        assert_eq!(
+            qr_code
+                .attempts()
+                .iter()
+                .map(|attempt| (attempt.enhancement, attempt.error.is_some()))
+                .collect_vec(),
+            vec![
+                (None, true),
+                (Some(Enhancement::AdaptiveThreshold), true),
+                (Some(Enhancement::Close), false),
+            ]
+        );
+        let overlap = qr_code.bounds().intersect(&qr_bounds).unwrap();
+        assert!(overlap.width() >= qr_bounds.width() - 4);
+        assert!(overlap.height() >= qr_bounds.height() - 4);
+    }
+
+    /// Only the area that the QR code was decoded in says where it is, even
+    /// if an earlier area is for the same orientation.
+    #[test]
+    fn test_detect_qr_code_with_specks_in_later_area() {
+        let (image, expected) = speckled_qr_code_image();
+        let qr_bounds = expected.bounds();
+        let (width, height) = image.dimensions();
+        let size = Size {
+            width: width / 4,
+            height: width / 4,
+        };
+        let areas = [
+            DetectionArea::new(
+                &image,
+                Point::new(width / 2, 0),
+                size,
+                Orientation::Portrait,
+            ),
+            DetectionArea::new(
+                &image,
+                Point::new(0, height - size.height),
+                size,
+                Orientation::Portrait,
+            ),
+        ];
+
+        let qr_code =
+            detect_with_retries(&areas, &ImageDebugWriter::disabled(), "qr_code").unwrap();
+        assert!(qr_code.attempts().len() > 1);
+        let overlap = qr_code.bounds().intersect(&qr_bounds).unwrap();
+        assert!(overlap.width() >= qr_bounds.width() - 4);
+        assert!(overlap.height() >= qr_bounds.height() - 4);
+    }
+
+    #[test]
+    fn test_detect_qr_code_failure_records_attempts() {
+        let image = GrayImage::from_pixel(800, 1000, Luma([u8::MAX]));
+        let error = detect_with_strategy(
+            &image,
+            SearchStrategy::BubbleCorners,
+            &ImageDebugWriter::disabled(),
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(
+                error,
+                Error::AllAttemptsFailed { ref error, .. }
+                    if matches!(**error, Error::NoQrCodeDetected { .. })
+            ),
+            "unexpected error: {error:?}"
+        );
+        assert_eq!(
+            error
+                .attempts()
+                .iter()
+                .map(|attempt| attempt.enhancement)
+                .collect_vec(),
+            [None]
+                .into_iter()
+                .chain(Enhancement::RETRIES.map(Some))
+                .collect_vec()
+        );
+        assert!(error
+            .attempts()
+            .iter()
+            .all(|attempt| attempt.error.is_some()));
+        assert_eq!(
+            error.detection_areas(),
+            get_hmpb_detection_areas(&image)
+                .iter()
+                .map(DetectionArea::bounds)
+                .collect_vec()
+        );
+    }
 }
@@ -0,0 +1,239 @@
+use image::{
+    imageops::{resize, FilterType},
+    GrayImage, Luma,
+};
+use serde::Serialize;
+use types_rs::geometry::{Point, Rect};
+
+use crate::image_utils::{otsu_level, threshold};
+
+/// How far a pixel must be below the mean of its neighborhood to be treated as
+/// ink by [`Enhancement::AdaptiveThreshold`]. Keeps paper noise in otherwise
+/// blank areas from turning into specks.
+const ADAPTIVE_THRESHOLD_OFFSET: i32 = 8;
+
+/// A way of cleaning up a detection area before trying again to decode a QR
+/// code that could not be decoded as scanned.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Enhancement {
+    /// Compares each pixel to the mean of its neighborhood rather than one
+    /// threshold for the whole area, for faint or unevenly lit codes.
+    AdaptiveThreshold,
+
+    /// Fills light specks and scratches in dark modules by growing and then
+    /// shrinking the dark areas of the binarized image.
+    Close,
+
+    /// Enlarges the area so that each module spans more pixels, for small or
+    /// blurry codes.
+    Upscale { factor: u32 },
+
+    /// Rotates the area about its center to undo a small skew.
+    Rotate { degrees: f32 },
+}
+
+impl Enhancement {
+    /// Enhancements to try, in order, once the QR code can't be decoded as
+    /// scanned. Cheaper and less invasive ones come first.
+    pub const RETRIES: [Self; 7] = [
+        Self::AdaptiveThreshold,
+        Self::Close,
+        Self::Upscale { factor: 2 },
+        Self::Rotate { degrees: -2.0 },
+        Self::Rotate { degrees: 2.0 },
+        Self::Rotate { degrees: -4.0 },
+        Self::Rotate { degrees: 4.0 },
+    ];
+
+    /// Applies the enhancement to `image`.
+    #[must_use]
+    pub fn apply(self, image: &GrayImage) -> GrayImage {
+        match self {
+            Self::AdaptiveThreshold => adaptive_threshold(image),
+            Self::Close => close_dark_areas(&threshold(image, otsu_level(image))),
+            Self::Upscale { factor } => resize(
+                image,
+                image.width() * factor,
+                image.height() * factor,
+                FilterType::CatmullRom,
+            ),
+            Self::Rotate { degrees } => rotate_about_center(image, degrees),
+        }
+    }
+
+    /// Maps the bounds of a QR code found in an image enhanced from one of
+    /// the given size back to the bounds in the original image.
+    pub fn unmap_bounds(self, bounds: Rect, (width, height): (u32, u32)) -> Rect {
+        match self {
+            Self::AdaptiveThreshold | Self::Close => bounds,
+            Self::Upscale { factor } => Rect::new(
+                bounds.left() / factor as i32,
+                bounds.top() / factor as i32,
+                bounds.width().div_ceil(factor),
+                bounds.height().div_ceil(factor),
+            ),
+            Self::Rotate { degrees } => {
+                let center = Point::new(width as f32 / 2.0, height as f32 / 2.0);
+                let corners = [
+                    (bounds.left(), bounds.top()),
+                    (bounds.right(), bounds.top()),
+                    (bounds.left(), bounds.bottom()),
+                    (bounds.right(), bounds.bottom()),
+                ]
+                .map(|(x, y)| rotate_point(Point::new(x as f32, y as f32), center, -degrees));
+                let left = corners.iter().map(|p| p.x).fold(f32::INFINITY, f32::min);
+                let top = corners.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+                let right = corners
+                    .iter()
+                    .map(|p| p.x)
+                    .fold(f32::NEG_INFINITY, f32::max);
+                let bottom = corners
+                    .iter()
+                    .map(|p| p.y)
+                    .fold(f32::NEG_INFINITY, f32::max);
+                Rect::from_points(
+                    Point::new(left.round() as i32, top.round() as i32),
+                    Point::new(right.round() as i32, bottom.round() as i32),
+                )
+            }
+        }
+    }
+}
+
+/// Rotates `point` about `center` by `degrees`, clockwise in image
+/// coordinates.
+fn rotate_point(point: Point<f32>, center: Point<f32>, degrees: f32) -> Point<f32> {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (dx, dy) = (point.x - center.x, point.y - center.y);
+    Point::new(
+        center.x + dx * cos - dy * sin,
+        center.y + dx * sin + dy * cos,
+    )
+}
+
+/// Binarizes `image` by comparing each pixel to the mean of the square around
+/// it, computed from a summed-area table.
+fn adaptive_threshold(image: &GrayImage) -> GrayImage {
+    let (width, height) = image.dimensions();
+    let radius = (width.min(height) / 16).max(4);
+    let stride = width as usize + 1;
+    let mut sums = vec![0u64; stride * (height as usize + 1)];
+    for (y, row) in image.rows().enumerate() {
+        let mut row_sum = 0u64;
+        for (x, pixel) in row.enumerate() {
+            row_sum += u64::from(pixel.0[0]);
+            sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row_sum;
+        }
+    }
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let left = x.saturating_sub(radius) as usize;
+        let top = y.saturating_sub(radius) as usize;
+        let right = (x + radius + 1).min(width) as usize;
+        let bottom = (y + radius + 1).min(height) as usize;
+        let sum = sums[bottom * stride + right] + sums[top * stride + left]
+            - sums[top * stride + right]
+            - sums[bottom * stride + left];
+        let mean = (sum / ((right - left) * (bottom - top)) as u64) as i32;
+        let value = i32::from(image.get_pixel(x, y).0[0]);
+        Luma([if value < mean - ADAPTIVE_THRESHOLD_OFFSET {
+            0
+        } else {
+            u8::MAX
+        }])
+    })
+}
+
+/// Applies `pick` to each pixel's 3×3 neighborhood.
+fn filter_3x3(image: &GrayImage, pick: impl Fn(u8, u8) -> u8) -> GrayImage {
+    let (width, height) = image.dimensions();
+    GrayImage::from_fn(width, height, |x, y| {
+        let mut value = image.get_pixel(x, y).0[0];
+        for ny in y.saturating_sub(1)..(y + 2).min(height) {
+            for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                value = pick(value, image.get_pixel(nx, ny).0[0]);
+            }
+        }
+        Luma([value])
+    })
+}
+
+/// Morphological closing of the dark areas of a binarized image.
+fn close_dark_areas(image: &GrayImage) -> GrayImage {
+    filter_3x3(&filter_3x3(image, u8::min), u8::max)
+}
+
+/// Rotates `image` by `degrees` about its center, keeping its size and
+/// filling in the corners with white.
+fn rotate_about_center(image: &GrayImage, degrees: f32) -> GrayImage {
+    let (width, height) = image.dimensions();
+    let center = Point::new(width as f32 / 2.0, height as f32 / 2.0);
+    GrayImage::from_fn(width, height, |x, y| {
+        let source = rotate_point(Point::new(x as f32 + 0.5, y as f32 + 0.5), center, -degrees);
+        let (sx, sy) = (source.x.floor(), source.y.floor());
+        if sx < 0.0 || sy < 0.0 || sx >= width as f32 || sy >= height as f32 {
+            Luma([u8::MAX])
+        } else {
+            *image.get_pixel(sx as u32, sy as u32)
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_threshold_evens_out_lighting() {
+        // Faint dark squares on paper that gets darker from left to right,
+        // so that the squares on the left are lighter than the paper on the
+        // right.
+        let image = GrayImage::from_fn(64, 64, |x, y| {
+            let paper = 220 - x as u8;
+            Luma([if (x / 4 + y / 4) % 2 == 0 {
+                paper - 30
+            } else {
+                paper
+            }])
+        });
+        let enhanced = Enhancement::AdaptiveThreshold.apply(&image);
+        assert_eq!(enhanced.get_pixel(1, 1).0, [0]);
+        assert_eq!(enhanced.get_pixel(5, 1).0, [255]);
+        assert_eq!(enhanced.get_pixel(57, 1).0, [0]);
+        assert_eq!(enhanced.get_pixel(61, 1).0, [255]);
+    }
+
+    #[test]
+    fn test_close_fills_specks() {
+        let mut image = GrayImage::from_pixel(16, 16, Luma([255]));
+        for y in 4..12 {
+            for x in 4..12 {
+                image.put_pixel(x, y, Luma([0]));
+            }
+        }
+        image.put_pixel(7, 7, Luma([255]));
+        let enhanced = Enhancement::Close.apply(&image);
+        assert_eq!(enhanced.get_pixel(7, 7).0, [0]);
+        assert_eq!(enhanced.get_pixel(4, 4).0, [0]);
+        assert_eq!(enhanced.get_pixel(2, 2).0, [255]);
+    }
+
+    #[test]
+    fn test_unmap_bounds() {
+        let bounds = Rect::new(20, 30, 40, 50);
+        assert_eq!(
+            Enhancement::Upscale { factor: 2 }.unmap_bounds(bounds, (200, 200)),
+            Rect::new(10, 15, 20, 25)
+        );
+        assert_eq!(Enhancement::Close.unmap_bounds(bounds, (100, 100)), bounds);
+
+        // Rotating the image and then unmapping bounds found in it should
+        // roughly cover the same area as before.
+        let rotation = Enhancement::Rotate { degrees: 4.0 };
+        let centered = Rect::new(40, 40, 20, 20);
+        let unmapped = rotation.unmap_bounds(centered, (100, 100));
+        assert!(unmapped.left() <= 40 && unmapped.right() >= 59);
+        assert!(unmapped.width() <= 24 && unmapped.height() <= 24);
+    }
+}
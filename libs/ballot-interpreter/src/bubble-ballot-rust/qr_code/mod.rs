@@ -1,5 +1,10 @@
 mod detect;
+mod enhance;
 mod rqrr;
 mod zedbar;
 
-pub use detect::{classify_qr_payload, detect_with_strategy, Detected, QrCodeKind, SearchStrategy};
+pub use detect::{
+    classify_qr_payload, detect_near_timing_marks, detect_with_strategy, Detected,
+    DetectionAttempt, Error, QrCodeKind, Result, SearchStrategy,
+};
+pub use enhance::Enhancement;
@@ -7,7 +7,7 @@ use super::detect::{Detected, DetectionArea, Detector, Error, Result};
 /// areas.
 pub fn detect_in_areas(detection_areas: &[DetectionArea<'_>]) -> Result {
     let detection_area_rects = detection_areas.iter().map(DetectionArea::bounds).collect();
-    for area in detection_areas {
+    for (index, area) in detection_areas.iter().enumerate() {
         let mut prepared_img = PreparedImage::prepare(area.image().clone());
         if let Some(grid) = prepared_img.detect_grids().first() {
             let mut bytes = Vec::new();
@@ -18,7 +18,8 @@ pub fn detect_in_areas(detection_areas: &[DetectionArea<'_>]) -> Result {
                     bytes,
                     get_original_bounds_rqrr(area.origin(), grid),
                     area.orientation(),
-                )),
+                )
+                .in_area(index)),
                 Err(e) => Err(Error::DecodeFailed {
                     detection_areas: detection_area_rects,
                     message: e.to_string(),
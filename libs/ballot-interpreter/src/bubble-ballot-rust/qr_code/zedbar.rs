@@ -8,7 +8,7 @@ use super::detect::{Detected, DetectionArea, Detector, Error, Result};
 /// detection areas.
 pub fn detect_in_areas(detection_areas: &[DetectionArea<'_>]) -> Result {
     let detection_area_rects = detection_areas.iter().map(DetectionArea::bounds).collect();
-    for area in detection_areas {
+    for (index, area) in detection_areas.iter().enumerate() {
         match scan_image_for_qr_codes(area.image()) {
             Ok(qr_codes) => {
                 if let Some((data, bounds)) = qr_codes.into_iter().next() {
@@ -19,7 +19,8 @@ pub fn detect_in_areas(detection_areas: &[DetectionArea<'_>]) -> Result {
                         data,
                         bounds,
                         area.orientation(),
-                    ));
+                    )
+                    .in_area(index));
                 }
             }
             Err(e) => {
@@ -0,0 +1,371 @@
+use image::GrayImage;
+use itertools::Itertools;
+use serde::Serialize;
+use types_rs::geometry::{PixelPosition, Point, Rect, SubPixelUnit};
+
+use crate::ballot_card::{BallotImage, Geometry};
+use crate::scoring::UnitIntervalScore;
+use crate::timing_marks::scoring::CandidateTimingMark;
+use crate::timing_marks::{Corner, TimingMarks};
+
+/// Image-health measurements for a scanned ballot page, taken from its timing
+/// marks. None of these affect interpretation; they are reported so that a
+/// scanner drifting out of spec can be noticed from routine ballots.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityReport {
+    /// Rotation of the timing mark grid relative to the image axes, averaged
+    /// over all four borders. Positive values are clockwise.
+    pub skew_degrees: f32,
+
+    /// Pixel density measured between the left and right border marks.
+    pub horizontal_pixels_per_inch: SubPixelUnit,
+
+    /// Pixel density measured between the top and bottom border marks.
+    pub vertical_pixels_per_inch: SubPixelUnit,
+
+    /// Measured horizontal density relative to the expected density, e.g.
+    /// `1.02` for an image 2% wider than it should be.
+    pub horizontal_stretch: f32,
+
+    /// Measured vertical density relative to the expected density. Feed
+    /// problems usually show up here since they stretch along the scan
+    /// direction.
+    pub vertical_stretch: f32,
+
+    /// Median luminosity of the paper, i.e. pixels above the threshold.
+    pub paper_luminosity: u8,
+
+    /// Median luminosity of the inside of the timing marks.
+    pub ink_luminosity: u8,
+
+    /// The difference between paper and ink luminosity as a fraction of the
+    /// full luminosity range.
+    pub contrast: UnitIntervalScore,
+
+    /// How sharp the timing mark edges are: the steepest step in luminosity
+    /// across each edge as a fraction of the paper/ink difference, averaged
+    /// over all edges. A perfectly focused edge goes from paper to ink in one
+    /// pixel and scores 1.0; blur spreads the step out and lowers the score.
+    pub focus: UnitIntervalScore,
+
+    /// The luminosity threshold used to separate ink from paper.
+    pub threshold: u8,
+}
+
+/// Measures the quality of a ballot page image using its timing marks.
+pub(crate) fn quality_report(
+    ballot_image: &BallotImage,
+    geometry: &Geometry,
+    timing_marks: &TimingMarks,
+) -> QualityReport {
+    let image = ballot_image.image();
+    let threshold = ballot_image.threshold();
+
+    let (horizontal_pixels_per_inch, vertical_pixels_per_inch) =
+        measured_pixels_per_inch(timing_marks, geometry);
+    let expected_pixels_per_inch = geometry.pixels_per_inch as SubPixelUnit;
+
+    let mark_rects = timing_mark_rects(timing_marks);
+    let paper_luminosity = paper_luminosity(image, threshold);
+    let ink_luminosity = ink_luminosity(image, &mark_rects).unwrap_or(0);
+    let contrast = f32::from(paper_luminosity.saturating_sub(ink_luminosity)) / 255.0;
+
+    QualityReport {
+        skew_degrees: skew_degrees(
+            timing_marks.top_left_corner,
+            timing_marks.top_right_corner,
+            timing_marks.bottom_left_corner,
+            timing_marks.bottom_right_corner,
+        ),
+        horizontal_pixels_per_inch,
+        vertical_pixels_per_inch,
+        horizontal_stretch: horizontal_pixels_per_inch / expected_pixels_per_inch,
+        vertical_stretch: vertical_pixels_per_inch / expected_pixels_per_inch,
+        paper_luminosity,
+        ink_luminosity,
+        contrast: UnitIntervalScore(contrast),
+        focus: UnitIntervalScore(edge_sharpness(
+            image,
+            &mark_rects,
+            paper_luminosity,
+            ink_luminosity,
+            edge_search_distance(geometry),
+        )),
+        threshold,
+    }
+}
+
+/// Computes the average rotation of the lines between the corner timing marks,
+/// in degrees clockwise.
+fn skew_degrees(
+    top_left: Point<SubPixelUnit>,
+    top_right: Point<SubPixelUnit>,
+    bottom_left: Point<SubPixelUnit>,
+    bottom_right: Point<SubPixelUnit>,
+) -> f32 {
+    // With y pointing down, a clockwise rotation moves the right end of a
+    // horizontal line down and the bottom end of a vertical line left.
+    let horizontal = |start: Point<SubPixelUnit>, end: Point<SubPixelUnit>| {
+        (end.y - start.y).atan2(end.x - start.x)
+    };
+    let vertical = |start: Point<SubPixelUnit>, end: Point<SubPixelUnit>| {
+        (start.x - end.x).atan2(end.y - start.y)
+    };
+
+    let radians = (horizontal(top_left, top_right)
+        + horizontal(bottom_left, bottom_right)
+        + vertical(top_left, bottom_left)
+        + vertical(top_right, bottom_right))
+        / 4.0;
+    radians.to_degrees()
+}
+
+/// Measures the pixel density along each axis by comparing the distances
+/// between the corner timing marks to the distances the geometry expects.
+fn measured_pixels_per_inch(
+    timing_marks: &TimingMarks,
+    geometry: &Geometry,
+) -> (SubPixelUnit, SubPixelUnit) {
+    let ppi = geometry.pixels_per_inch as SubPixelUnit;
+    let expected_width_inches = geometry.left_to_right_center_to_center_pixel_distance() / ppi;
+    let expected_height_inches = geometry.top_to_bottom_center_to_center_pixel_distance() / ppi;
+
+    let width = timing_marks
+        .top_left_corner
+        .distance_to(&timing_marks.top_right_corner)
+        .midpoint(
+            timing_marks
+                .bottom_left_corner
+                .distance_to(&timing_marks.bottom_right_corner),
+        );
+    let height = timing_marks
+        .top_left_corner
+        .distance_to(&timing_marks.bottom_left_corner)
+        .midpoint(
+            timing_marks
+                .top_right_corner
+                .distance_to(&timing_marks.bottom_right_corner),
+        );
+
+    (
+        width / expected_width_inches,
+        height / expected_height_inches,
+    )
+}
+
+/// All of the timing marks found on the page, including the corners. Marks
+/// that were inferred rather than found have no ink to measure.
+fn timing_mark_rects(timing_marks: &TimingMarks) -> Vec<Rect> {
+    let border_marks = &timing_marks.border_marks;
+    let inferred_marks = &timing_marks.inferred_marks;
+    [
+        (Corner::TopLeft, &timing_marks.top_left_mark),
+        (Corner::TopRight, &timing_marks.top_right_mark),
+        (Corner::BottomLeft, &timing_marks.bottom_left_mark),
+        (Corner::BottomRight, &timing_marks.bottom_right_mark),
+    ]
+    .into_iter()
+    .filter(|(corner, _)| timing_marks.inferred_corner != Some(*corner))
+    .map(|(_, mark)| mark)
+    .chain(found(&border_marks.left, &inferred_marks.left))
+    .chain(found(&border_marks.right, &inferred_marks.right))
+    .chain(&border_marks.top)
+    .chain(&border_marks.bottom)
+    .map(|mark| *mark.rect())
+    .collect()
+}
+
+/// The marks in `marks` whose index is not in `inferred`.
+fn found<'a>(
+    marks: &'a [CandidateTimingMark],
+    inferred: &'a [usize],
+) -> impl Iterator<Item = &'a CandidateTimingMark> {
+    marks
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !inferred.contains(index))
+        .map(|(_, mark)| mark)
+}
+
+/// Finds the median luminosity of the pixels above `threshold`.
+fn paper_luminosity(image: &GrayImage, threshold: u8) -> u8 {
+    let mut histogram = [0u64; 256];
+    for pixel in image.pixels() {
+        histogram[pixel[0] as usize] += 1;
+    }
+    median_from_histogram(&histogram[threshold as usize + 1..]).map_or(u8::MAX, |index| {
+        index.saturating_add(threshold).saturating_add(1)
+    })
+}
+
+/// Finds the median luminosity of the inside of the given timing marks, leaving
+/// out a one pixel margin where the edges may be blurred.
+fn ink_luminosity(image: &GrayImage, mark_rects: &[Rect]) -> Option<u8> {
+    let mut histogram = [0u64; 256];
+    for rect in mark_rects {
+        for y in rect.top() + 1..rect.bottom() {
+            for x in rect.left() + 1..rect.right() {
+                if let Some(pixel) = get_pixel(image, x, y) {
+                    histogram[pixel as usize] += 1;
+                }
+            }
+        }
+    }
+    median_from_histogram(&histogram)
+}
+
+fn median_from_histogram(histogram: &[u64]) -> Option<u8> {
+    let total: u64 = histogram.iter().sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut seen = 0;
+    for (index, count) in histogram.iter().enumerate() {
+        seen += count;
+        if seen * 2 >= total {
+            return u8::try_from(index).ok();
+        }
+    }
+    None
+}
+
+/// How far to either side of a timing mark's detected edge to look for the
+/// actual edge.
+fn edge_search_distance(geometry: &Geometry) -> PixelPosition {
+    (geometry.pixels_per_inch as PixelPosition / 50).max(2)
+}
+
+/// Averages the steepest step in luminosity across the left and right edges
+/// (through the mark's middle row) and the top and bottom edges (through its
+/// middle column) of every mark, relative to the paper/ink difference.
+fn edge_sharpness(
+    image: &GrayImage,
+    mark_rects: &[Rect],
+    paper_luminosity: u8,
+    ink_luminosity: u8,
+    search_distance: PixelPosition,
+) -> f32 {
+    let range = f32::from(paper_luminosity.saturating_sub(ink_luminosity));
+    if range == 0.0 {
+        return 0.0;
+    }
+
+    let steepest_step = |points: &[(PixelPosition, PixelPosition)]| {
+        points
+            .iter()
+            .filter_map(|&(x, y)| get_pixel(image, x, y))
+            .tuple_windows()
+            .map(|(a, b)| a.abs_diff(b))
+            .max()
+            .map(|step| (f32::from(step) / range).min(1.0))
+    };
+
+    let mut total = 0.0;
+    let mut count = 0;
+    for rect in mark_rects {
+        let center = rect.center();
+        let (center_x, center_y) = (center.x as PixelPosition, center.y as PixelPosition);
+        let across = |edge: PixelPosition| edge - search_distance..=edge + search_distance;
+        let edges = [
+            across(rect.left()).map(|x| (x, center_y)).collect_vec(),
+            across(rect.right()).map(|x| (x, center_y)).collect_vec(),
+            across(rect.top()).map(|y| (center_x, y)).collect_vec(),
+            across(rect.bottom()).map(|y| (center_x, y)).collect_vec(),
+        ];
+        for edge in &edges {
+            if let Some(sharpness) = steepest_step(edge) {
+                total += sharpness;
+                count += 1;
+            }
+        }
+    }
+
+    if count == 0 {
+        0.0
+    } else {
+        total / count as f32
+    }
+}
+
+fn get_pixel(image: &GrayImage, x: PixelPosition, y: PixelPosition) -> Option<u8> {
+    let (x, y) = (u32::try_from(x).ok()?, u32::try_from(y).ok()?);
+    (x < image.width() && y < image.height()).then(|| image.get_pixel(x, y)[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{imageops::blur, Luma};
+
+    use super::*;
+
+    /// A white image with a single black timing mark.
+    fn image_with_mark(rect: &Rect) -> GrayImage {
+        GrayImage::from_fn(60, 40, |x, y| {
+            let (x, y) = (x as PixelPosition, y as PixelPosition);
+            if (rect.left()..=rect.right()).contains(&x)
+                && (rect.top()..=rect.bottom()).contains(&y)
+            {
+                Luma([20])
+            } else {
+                Luma([230])
+            }
+        })
+    }
+
+    #[test]
+    fn test_skew_degrees() {
+        let straight = skew_degrees(
+            Point::new(0.0, 0.0),
+            Point::new(100.0, 0.0),
+            Point::new(0.0, 200.0),
+            Point::new(100.0, 200.0),
+        );
+        assert!(straight.abs() < f32::EPSILON);
+
+        // rotate a 100x200 rectangle by 2° clockwise around the origin
+        let angle = 2f32.to_radians();
+        let rotate = |x: f32, y: f32| {
+            Point::new(
+                x * angle.cos() - y * angle.sin(),
+                x * angle.sin() + y * angle.cos(),
+            )
+        };
+        let skewed = skew_degrees(
+            rotate(0.0, 0.0),
+            rotate(100.0, 0.0),
+            rotate(0.0, 200.0),
+            rotate(100.0, 200.0),
+        );
+        assert!((skewed - 2.0).abs() < 0.001, "skew: {skewed}");
+    }
+
+    #[test]
+    fn test_luminosity() {
+        let rect = Rect::new(20, 15, 20, 8);
+        let image = image_with_mark(&rect);
+        assert_eq!(paper_luminosity(&image, 128), 230);
+        assert_eq!(ink_luminosity(&image, &[rect]), Some(20));
+        assert_eq!(ink_luminosity(&image, &[]), None);
+    }
+
+    #[test]
+    fn test_edge_sharpness_drops_with_blur() {
+        let rect = Rect::new(20, 15, 20, 8);
+        let image = image_with_mark(&rect);
+        let sharp = edge_sharpness(&image, &[rect], 230, 20, 4);
+        assert!((sharp - 1.0).abs() < f32::EPSILON, "sharp: {sharp}");
+
+        let blurred = blur(&image, 1.5);
+        let blurry = edge_sharpness(&blurred, &[rect], 230, 20, 4);
+        assert!(blurry < 0.5, "blurry: {blurry}");
+    }
+
+    #[test]
+    fn test_edge_sharpness_without_contrast() {
+        let rect = Rect::new(20, 15, 20, 8);
+        let image = image_with_mark(&rect);
+        assert!(edge_sharpness(&image, &[rect], 20, 20, 4).abs() < f32::EPSILON);
+    }
+}
@@ -10,7 +10,7 @@ use types_rs::ballot_card::BallotSide;
 use types_rs::election::{GridLayout, GridLocation, GridPosition, UnitIntervalValue};
 use types_rs::geometry::{PixelPosition, PixelUnit, Point, Quadrilateral, Rect, SubPixelUnit};
 
-use crate::ballot_card::BallotImage;
+use crate::ballot_card::{BallotImage, SCAN_PIXELS_PER_INCH};
 use crate::debug;
 use crate::image_utils::{count_pixels_in_shape, VerticalStreak};
 use crate::interpret::{Error, Result};
@@ -115,6 +115,14 @@ pub struct ScoredBubbleMark {
     /// The bounds of the bubble mark in the scanned source image that was
     /// determined to be the best match.
     pub matched_bounds: Rect,
+
+    /// Grayscale statistics for the template's blank-paper pixels at
+    /// `matched_bounds`. Unlike `fill_score`, these see ink that is lighter
+    /// than the image threshold, e.g. what is left after an erasure.
+    pub grayscale_stats: BubbleGrayscaleStats,
+
+    /// What kind of mark, if any, `grayscale_stats` suggests the voter made.
+    pub classification: MarkClassification,
 }
 
 impl Debug for ScoredBubbleMark {
@@ -125,10 +133,120 @@ impl Debug for ScoredBubbleMark {
             .field("fill_score", &self.fill_score)
             .field("expected_bounds", &self.expected_bounds)
             .field("matched_bounds", &self.matched_bounds)
+            .field("grayscale_stats", &self.grayscale_stats)
+            .field("classification", &self.classification)
             .finish_non_exhaustive()
     }
 }
 
+/// How much darker than the paper a pixel must be to count as a visible mark,
+/// as a fraction of the paper's brightness. Low enough to see graphite left
+/// behind by an eraser, high enough to ignore scanner noise and paper texture.
+pub const VISIBLE_MARK_DARKNESS: f32 = 0.15;
+
+/// Grayscale statistics for the blank-paper pixels of a bubble template placed
+/// over a scanned bubble, leaving out those right next to the outline. Darkness is measured relative to the lightest such
+/// pixel, which is taken to be the paper, so 0 is paper and 1 is black.
+/// Positions are relative to the center of the template and scaled so that the
+/// edges of the template are at a distance of 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BubbleGrayscaleStats {
+    /// Mean darkness of all blank-paper pixels.
+    pub mean_darkness: f32,
+
+    /// Variance of the darkness of all blank-paper pixels. Solid fills and
+    /// blank bubbles are both uniform; erasures and partial marks are not.
+    pub darkness_variance: f32,
+
+    /// Fraction of blank-paper pixels that are darker than the image
+    /// threshold. Roughly `fill_score` relative to the blank-paper area rather
+    /// than the whole template.
+    pub ink_coverage: f32,
+
+    /// Fraction of blank-paper pixels at least [`VISIBLE_MARK_DARKNESS`]
+    /// darker than the paper, whether or not they pass the image threshold.
+    pub visible_coverage: f32,
+
+    /// Mean darkness of the visible pixels, or 0 if there are none.
+    pub visible_mean_darkness: f32,
+
+    /// Distance from the center of the template to the darkness-weighted
+    /// centroid of the visible pixels.
+    pub ink_centroid_offset: f32,
+
+    /// Darkness-weighted root mean square distance of the visible pixels from
+    /// their centroid. Small for a dot, large for a fill or scribble.
+    pub ink_spread: f32,
+}
+
+/// What kind of mark a voter made in a bubble, judged from its
+/// [`BubbleGrayscaleStats`]. This is independent of the mark thresholds, which
+/// only look at `fill_score`; it tells adjudicators why a bubble's score is
+/// what it is, and an erased bubble is flagged as a marginal mark whatever its
+/// score.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MarkClassification {
+    /// No visible mark.
+    #[default]
+    Blank,
+
+    /// Most of the bubble is covered with dark ink.
+    Filled,
+
+    /// Much of the bubble is covered, but only faintly, as is left behind
+    /// when a filled bubble is erased.
+    Erased,
+
+    /// Some dark ink spread over part of the bubble, e.g. a check mark, a
+    /// partial fill, or a hesitation mark where the pen rested.
+    Partial,
+
+    /// A small, concentrated mark away from the center of the bubble, e.g. a
+    /// pen touching the paper on its way somewhere else.
+    StrayDot,
+}
+
+impl MarkClassification {
+    /// `ink_coverage` at or above which a bubble is filled.
+    const FILLED_MIN_INK_COVERAGE: f32 = 0.5;
+
+    /// `visible_coverage` below which a bubble is blank.
+    const BLANK_MAX_VISIBLE_COVERAGE: f32 = 0.01;
+
+    /// `visible_coverage` at or above which a faint mark is an erasure.
+    const ERASED_MIN_VISIBLE_COVERAGE: f32 = 0.2;
+
+    /// `ink_spread` below which a mark is a dot rather than a stroke or fill.
+    const DOT_MAX_SPREAD: f32 = 0.25;
+
+    /// `ink_centroid_offset` at or above which a dot is stray rather than a
+    /// hesitation mark inside the bubble.
+    const STRAY_DOT_MIN_CENTROID_OFFSET: f32 = 0.4;
+
+    #[must_use]
+    pub fn classify(stats: &BubbleGrayscaleStats) -> Self {
+        if stats.visible_coverage < Self::BLANK_MAX_VISIBLE_COVERAGE {
+            Self::Blank
+        } else if stats.ink_coverage >= Self::FILLED_MIN_INK_COVERAGE {
+            Self::Filled
+        } else if stats.visible_coverage >= Self::ERASED_MIN_VISIBLE_COVERAGE
+            && stats.ink_coverage < stats.visible_coverage / 2.0
+        {
+            Self::Erased
+        } else if stats.ink_spread < Self::DOT_MAX_SPREAD
+            && stats.ink_centroid_offset >= Self::STRAY_DOT_MIN_CENTROID_OFFSET
+        {
+            Self::StrayDot
+        } else {
+            Self::Partial
+        }
+    }
+}
+
+/// How far from its expected position to search for a bubble, in pixels at
+/// [`SCAN_PIXELS_PER_INCH`]. Scaled to the density of the scan being scored.
 pub const DEFAULT_MAXIMUM_SEARCH_DISTANCE: u32 = 7;
 
 pub type ScoredBubbleMarks = Vec<(GridPosition, Option<ScoredBubbleMark>)>;
@@ -144,6 +262,9 @@ pub(crate) fn score_bubble_marks_from_grid_layout(
     sheet_number: u32,
     side: BallotSide,
 ) -> Result<ScoredBubbleMarks> {
+    let maximum_search_distance = (DEFAULT_MAXIMUM_SEARCH_DISTANCE
+        * timing_marks.geometry.pixels_per_inch)
+        .div_ceil(SCAN_PIXELS_PER_INCH);
     let scored_bubbles = grid_layout
         .grid_positions
         .par_iter()
@@ -181,7 +302,7 @@ pub(crate) fn score_bubble_marks_from_grid_layout(
                 bubble_template,
                 expected_bubble_center,
                 &location,
-                DEFAULT_MAXIMUM_SEARCH_DISTANCE,
+                maximum_search_distance,
             );
 
             Some(Ok((grid_position.clone(), scored_bubble_mark)))
@@ -342,6 +463,98 @@ impl<'a> BubbleRegion<'a> {
         UnitIntervalScore(filled as f32 / (self.width * self.height) as f32)
     }
 
+    /// Computes grayscale statistics over the template's blank-paper pixels
+    /// away from the outline.
+    /// See [`BubbleGrayscaleStats`] for details.
+    pub fn grayscale_stats(&self) -> BubbleGrayscaleStats {
+        let mut paper = 0u8;
+        self.for_each_paper_value(|_, _, value| paper = paper.max(value));
+        if paper == 0 {
+            return BubbleGrayscaleStats::default();
+        }
+
+        let half_width = self.width as f32 / 2.0;
+        let half_height = self.height as f32 / 2.0;
+        let mut count = 0u32;
+        let mut ink_count = 0u32;
+        let mut visible_count = 0u32;
+        let (mut sum, mut sum_of_squares) = (0.0, 0.0);
+        let (mut visible_sum, mut weighted_x, mut weighted_y) = (0.0, 0.0, 0.0);
+        let mut weighted_squared_distance = 0.0;
+        self.for_each_paper_value(|px, py, value| {
+            let darkness = f32::from(paper - value.min(paper)) / f32::from(paper);
+            count += 1;
+            sum += darkness;
+            sum_of_squares += darkness * darkness;
+            if value <= self.threshold {
+                ink_count += 1;
+            }
+            if darkness >= VISIBLE_MARK_DARKNESS {
+                let x = (px as f32 + 0.5 - half_width) / half_width;
+                let y = (py as f32 + 0.5 - half_height) / half_height;
+                visible_count += 1;
+                visible_sum += darkness;
+                weighted_x += darkness * x;
+                weighted_y += darkness * y;
+                weighted_squared_distance += darkness * (x * x + y * y);
+            }
+        });
+
+        let count = count as f32;
+        let mean_darkness = sum / count;
+        let (ink_centroid_offset, ink_spread) = if visible_count == 0 {
+            (0.0, 0.0)
+        } else {
+            let centroid_x = weighted_x / visible_sum;
+            let centroid_y = weighted_y / visible_sum;
+            let centroid_offset = centroid_x.hypot(centroid_y);
+            (
+                centroid_offset,
+                (weighted_squared_distance / visible_sum - centroid_offset * centroid_offset)
+                    .max(0.0)
+                    .sqrt(),
+            )
+        };
+
+        BubbleGrayscaleStats {
+            mean_darkness,
+            darkness_variance: (sum_of_squares / count - mean_darkness * mean_darkness).max(0.0),
+            ink_coverage: ink_count as f32 / count,
+            visible_coverage: visible_count as f32 / count,
+            visible_mean_darkness: if visible_count == 0 {
+                0.0
+            } else {
+                visible_sum / visible_count as f32
+            },
+            ink_centroid_offset,
+            ink_spread,
+        }
+    }
+
+    /// Calls `f(px, py, value)` with the grayscale value of each source pixel
+    /// where the template is white and not next to the outline. The scanned
+    /// outline blurs into its neighbors, which would otherwise look like a
+    /// faint mark all the way around a blank bubble. Coordinates are relative
+    /// to the region origin.
+    fn for_each_paper_value(&self, mut f: impl FnMut(usize, usize, u8)) {
+        let is_template_white =
+            |px: usize, py: usize| self.template_pixels[py * self.width + px] == 255;
+        let mut img_row_start = self.region_y * self.image_stride + self.region_x;
+        for py in 0..self.height {
+            let img_row = &self.image_pixels[img_row_start..img_row_start + self.width];
+            for (px, &source_val) in img_row.iter().enumerate() {
+                let next_to_outline = (py.saturating_sub(1)..=py + 1).any(|ny| {
+                    (px.saturating_sub(1)..=px + 1)
+                        .any(|nx| nx < self.width && ny < self.height && !is_template_white(nx, ny))
+                });
+                if is_template_white(px, py) && !next_to_outline {
+                    f(px, py, source_val);
+                }
+            }
+            img_row_start += self.image_stride;
+        }
+    }
+
     /// Calls `f(px, py)` for each filled pixel (template white AND source dark).
     /// Coordinates are relative to the region origin.
     pub fn for_each_filled_pixel(&self, mut f: impl FnMut(usize, usize)) {
@@ -610,6 +823,7 @@ pub(crate) fn score_bubble_mark(
         threshold_val,
     );
     let fill_score = best_region.fill_score();
+    let grayscale_stats = best_region.grayscale_stats();
 
     Some(ScoredBubbleMark {
         location: *location,
@@ -617,6 +831,8 @@ pub(crate) fn score_bubble_mark(
         fill_score,
         expected_bounds,
         matched_bounds: best_match.bounds,
+        grayscale_stats,
+        classification: MarkClassification::classify(&grayscale_stats),
     })
 }
 
@@ -667,21 +883,7 @@ fn score_write_in_area(
         return None;
     };
 
-    let top_left_corner = timing_marks.point_for_location(write_in_area.x, write_in_area.y)?;
-    let top_right_corner =
-        timing_marks.point_for_location(write_in_area.x + write_in_area.width, write_in_area.y)?;
-    let bottom_left_corner =
-        timing_marks.point_for_location(write_in_area.x, write_in_area.y + write_in_area.height)?;
-    let bottom_right_corner = timing_marks.point_for_location(
-        write_in_area.x + write_in_area.width,
-        write_in_area.y + write_in_area.height,
-    )?;
-    let shape = Quadrilateral {
-        top_left: top_left_corner,
-        top_right: top_right_corner,
-        bottom_left: bottom_left_corner,
-        bottom_right: bottom_right_corner,
-    };
+    let shape = timing_marks.quadrilateral_for_area(write_in_area)?;
     let counted = count_pixels_in_shape(img, &shape);
     let score = UnitIntervalScore(counted.ratio());
 
@@ -872,6 +1074,109 @@ mod test {
         UnitIntervalScore(count_pixels(&match_diff, white).ratio())
     }
 
+    /// Draws the embedded bubble template onto blank paper and lets `mark`
+    /// darken pixels inside the bubble outline, given their position relative
+    /// to the center of the template (edges at ±1) and their current value.
+    fn synthetic_bubble(mark: impl Fn(f32, f32) -> Option<u8>) -> (BallotImage, GrayImage) {
+        let template = crate::ballot_card::ballot_scan_bubble_image().clone();
+        let (width, height) = template.dimensions();
+        let margin = 10;
+        let mut image = GrayImage::from_pixel(width + margin * 2, height + margin * 2, Luma([230]));
+        for (x, y, pixel) in template.enumerate_pixels() {
+            let u = (x as f32 + 0.5 - width as f32 / 2.0) / (width as f32 / 2.0);
+            let v = (y as f32 + 0.5 - height as f32 / 2.0) / (height as f32 / 2.0);
+            let value = if pixel.0[0] == 0 {
+                Some(40)
+            } else if u * u + v * v < 0.8 {
+                mark(u, v)
+            } else {
+                None
+            };
+            if let Some(value) = value {
+                image.put_pixel(x + margin, y + margin, Luma([value]));
+            }
+        }
+        (BallotImage::for_testing(image, 128), template)
+    }
+
+    fn classify_synthetic_bubble(mark: impl Fn(f32, f32) -> Option<u8>) -> ScoredBubbleMark {
+        let (ballot_image, template) = synthetic_bubble(mark);
+        let center = Point::new(
+            ballot_image.width() as f32 / 2.0,
+            ballot_image.height() as f32 / 2.0,
+        );
+        score_bubble_mark(
+            &ballot_image,
+            &template,
+            center,
+            &make_location(),
+            DEFAULT_MAXIMUM_SEARCH_DISTANCE,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn classifies_blank_bubble() {
+        let mark = classify_synthetic_bubble(|_, _| None);
+        assert_eq!(mark.classification, MarkClassification::Blank, "{mark:?}");
+        assert!(mark.grayscale_stats.mean_darkness < 0.01, "{mark:?}");
+    }
+
+    #[test]
+    fn classifies_filled_bubble() {
+        let mark = classify_synthetic_bubble(|_, _| Some(50));
+        assert_eq!(mark.classification, MarkClassification::Filled, "{mark:?}");
+        assert!(mark.grayscale_stats.ink_centroid_offset < 0.1, "{mark:?}");
+    }
+
+    #[test]
+    fn classifies_erased_bubble() {
+        // Graphite smeared across the bubble, lighter than the threshold, with
+        // a few specks the eraser missed.
+        let mark = classify_synthetic_bubble(|u, v| {
+            Some(if (u * 37.0 + v * 11.0).sin() > 0.95 {
+                100
+            } else {
+                175
+            })
+        });
+        assert_eq!(mark.classification, MarkClassification::Erased, "{mark:?}");
+
+        // The specks alone give it a fill score that could pass for a light
+        // mark, which is what the classification is there to explain.
+        assert!(mark.fill_score.0 > 0.03, "{mark:?}");
+        assert!(mark.grayscale_stats.visible_coverage > 0.5, "{mark:?}");
+    }
+
+    #[test]
+    fn classifies_check_mark_as_partial() {
+        let mark = classify_synthetic_bubble(|u, v| {
+            let on_short_stroke = u < 0.0 && (v - (u + 0.3)).abs() < 0.12;
+            let on_long_stroke = u >= -0.1 && (v - (0.4 - 1.2 * u)).abs() < 0.12;
+            (on_short_stroke || on_long_stroke).then_some(60)
+        });
+        assert_eq!(mark.classification, MarkClassification::Partial, "{mark:?}");
+    }
+
+    #[test]
+    fn classifies_centered_dot_as_hesitation() {
+        let mark = classify_synthetic_bubble(|u, v| (u * u + v * v < 0.03).then_some(60));
+        assert_eq!(mark.classification, MarkClassification::Partial, "{mark:?}");
+        assert!(mark.grayscale_stats.ink_spread < 0.25, "{mark:?}");
+    }
+
+    #[test]
+    fn classifies_off_center_dot_as_stray() {
+        let mark = classify_synthetic_bubble(|u, v| {
+            ((u - 0.6).powi(2) + (v + 0.3).powi(2) < 0.02).then_some(60)
+        });
+        assert_eq!(
+            mark.classification,
+            MarkClassification::StrayDot,
+            "{mark:?}"
+        );
+    }
+
     proptest! {
         #[test]
         fn score_bubble_mark_never_panics(
@@ -0,0 +1,364 @@
+use serde::Serialize;
+use types_rs::election::{ContestId, GridPosition};
+use types_rs::geometry::{PixelPosition, PixelUnit, Rect};
+
+use crate::ballot_card::{BallotImage, Geometry};
+use crate::debug;
+use crate::layout::InterpretedContestLayout;
+use crate::scoring::{ScoredBubbleMark, ScoredBubbleMarks};
+use crate::timing_marks::TimingMarks;
+
+/// Printed rules (contest borders, option dividers, write-in lines) are long
+/// and perfectly straight. Runs of ink at least this long are treated as
+/// printed and removed before looking for stray marks.
+const MIN_RULE_LENGTH_INCHES: f32 = 0.5;
+
+/// How far a rule may wander across its direction over its length, so that
+/// rules on a skewed scan are still found. This covers a rule 0.5" long
+/// skewed by about 2°.
+const RULE_SKEW_TOLERANCE_INCHES: f32 = 0.02;
+
+/// Ink blobs shorter than this are indistinguishable from printed text, the
+/// tallest of which (a bold capital with a descender) is under 0.2".
+const MIN_STRAY_MARK_HEIGHT_INCHES: f32 = 0.25;
+
+/// Ink blobs with fewer pixels than this square's area are ignored as noise.
+const MIN_STRAY_MARK_SIDE_INCHES: f32 = 0.05;
+
+/// Margin around bubbles and write-in areas within which ink is ignored, so
+/// that overflow from a filled bubble or a long write-in is not reported.
+const EXCLUSION_MARGIN_INCHES: f32 = 0.05;
+
+/// A blob of ink inside a contest that is not on a bubble or in a write-in
+/// area and is too large to be printed text, e.g. a circled name, a checkmark
+/// or an X next to a bubble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrayMark {
+    pub bounds: Rect,
+    pub pixel_count: u32,
+}
+
+/// The stray marks found in a contest. Any stray mark is a possible indication
+/// of voter intent that the bubbles alone don't capture.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(clippy::module_name_repetitions)]
+pub struct ContestStrayMarks {
+    pub contest_id: ContestId,
+    pub marks: Vec<StrayMark>,
+}
+
+/// Finds stray marks within each contest in `contest_layouts`. Printed content
+/// is ignored by first removing long straight rules and then only keeping
+/// blobs taller than a line of text. Contests without stray marks are omitted.
+pub(crate) fn detect_stray_marks(
+    ballot_image: &BallotImage,
+    geometry: &Geometry,
+    timing_marks: &TimingMarks,
+    contest_layouts: &[InterpretedContestLayout],
+    marks: &ScoredBubbleMarks,
+) -> Vec<ContestStrayMarks> {
+    let pixels_per_inch = geometry.pixels_per_inch as f32;
+    let inches = |value: f32| (value * pixels_per_inch).round() as PixelUnit;
+    let image = ballot_image.image();
+    let threshold = ballot_image.threshold();
+    let image_bounds = Rect::new(0, 0, image.width(), image.height());
+    let margin = inches(EXCLUSION_MARGIN_INCHES) as PixelPosition;
+    let min_side = inches(MIN_STRAY_MARK_SIDE_INCHES);
+
+    let contest_stray_marks: Vec<_> = contest_layouts
+        .iter()
+        .filter_map(|contest_layout| {
+            let region = contest_layout.bounds.intersect(&image_bounds)?;
+            let mut ink =
+                InkMask::from_region(region, |x, y| image.get_pixel(x, y).0[0] <= threshold);
+
+            for exclusion in marks
+                .iter()
+                .filter(|(grid_position, _)| {
+                    grid_position.contest_id() == contest_layout.contest_id
+                })
+                .filter_map(|(grid_position, scored_bubble_mark)| {
+                    excluded_area(timing_marks, grid_position, scored_bubble_mark.as_ref())
+                })
+            {
+                ink.clear_rect(&expand(exclusion, margin));
+            }
+
+            ink.remove_rules(
+                inches(MIN_RULE_LENGTH_INCHES),
+                inches(RULE_SKEW_TOLERANCE_INCHES),
+            );
+
+            let marks: Vec<_> = ink
+                .blobs()
+                .into_iter()
+                .filter(|blob| {
+                    blob.bounds.height() >= inches(MIN_STRAY_MARK_HEIGHT_INCHES)
+                        && blob.pixel_count >= min_side * min_side
+                })
+                .collect();
+
+            (!marks.is_empty()).then(|| ContestStrayMarks {
+                contest_id: contest_layout.contest_id.clone(),
+                marks,
+            })
+        })
+        .collect();
+
+    ballot_image.debug().write("stray_marks", |canvas| {
+        debug::draw_stray_marks_debug_image_mut(canvas, contest_layouts, &contest_stray_marks);
+    });
+
+    contest_stray_marks
+}
+
+/// The area of a bubble or write-in in which voter ink is expected.
+fn excluded_area(
+    timing_marks: &TimingMarks,
+    grid_position: &GridPosition,
+    scored_bubble_mark: Option<&ScoredBubbleMark>,
+) -> Option<Rect> {
+    let bubble = scored_bubble_mark.map(|mark| mark.expected_bounds.union(&mark.matched_bounds));
+    let write_in_area = match grid_position {
+        GridPosition::WriteIn { write_in_area, .. } => timing_marks
+            .quadrilateral_for_area(*write_in_area)
+            .map(|shape| shape.bounds()),
+        GridPosition::Option { .. } => None,
+    };
+
+    match (bubble, write_in_area) {
+        (Some(bubble), Some(write_in_area)) => Some(bubble.union(&write_in_area)),
+        (bubble, write_in_area) => bubble.or(write_in_area),
+    }
+}
+
+const fn expand(rect: Rect, margin: PixelPosition) -> Rect {
+    Rect::new(
+        rect.left() - margin,
+        rect.top() - margin,
+        rect.width() + 2 * margin as PixelUnit,
+        rect.height() + 2 * margin as PixelUnit,
+    )
+}
+
+/// Which pixels within a region of the ballot image are ink.
+struct InkMask {
+    region: Rect,
+    ink: Vec<bool>,
+}
+
+impl InkMask {
+    fn from_region(region: Rect, is_ink: impl Fn(u32, u32) -> bool) -> Self {
+        let mut ink = Vec::with_capacity((region.width() * region.height()) as usize);
+        for y in region.top()..=region.bottom() {
+            for x in region.left()..=region.right() {
+                ink.push(is_ink(x as u32, y as u32));
+            }
+        }
+        Self { region, ink }
+    }
+
+    const fn width(&self) -> usize {
+        self.region.width() as usize
+    }
+
+    const fn height(&self) -> usize {
+        self.region.height() as usize
+    }
+
+    /// Clears all ink within `rect`, given in image coordinates.
+    fn clear_rect(&mut self, rect: &Rect) {
+        let Some(rect) = rect.intersect(&self.region) else {
+            return;
+        };
+        let width = self.width();
+        for y in rect.top()..=rect.bottom() {
+            let row = (y - self.region.top()) as usize * width;
+            let left = (rect.left() - self.region.left()) as usize;
+            let right = (rect.right() - self.region.left()) as usize;
+            self.ink[row + left..=row + right].fill(false);
+        }
+    }
+
+    /// Removes every horizontal and vertical rule at least `min_length`
+    /// pixels long. So that slightly skewed rules are found, a run may wander
+    /// up to `tolerance` pixels across its direction. Rules are found before
+    /// any are removed so that a rule crossing another is removed completely.
+    fn remove_rules(&mut self, min_length: PixelUnit, tolerance: PixelUnit) {
+        let (width, height) = (self.width(), self.height());
+        let mut rules = vec![false; self.ink.len()];
+
+        self.mark_rules(
+            &mut rules,
+            (width, height),
+            |x, y| y * width + x,
+            min_length,
+            tolerance,
+        );
+        self.mark_rules(
+            &mut rules,
+            (height, width),
+            |y, x| y * width + x,
+            min_length,
+            tolerance,
+        );
+
+        for (ink, rule) in self.ink.iter_mut().zip(rules) {
+            *ink &= !rule;
+        }
+    }
+
+    /// Marks rules running along the first axis of `(along, across)`, where
+    /// `index` maps a position on each axis to an index into the mask.
+    fn mark_rules(
+        &self,
+        rules: &mut [bool],
+        (along, across): (usize, usize),
+        index: impl Fn(usize, usize) -> usize,
+        min_length: PixelUnit,
+        tolerance: PixelUnit,
+    ) {
+        let min_length = min_length.max(1) as usize;
+        let tolerance = tolerance as usize;
+
+        for line in 0..across {
+            let band = line.saturating_sub(tolerance)..=(line + tolerance).min(across - 1);
+            let mut start = 0;
+            for i in 0..=along {
+                if i < along && band.clone().any(|j| self.ink[index(i, j)]) {
+                    continue;
+                }
+                if i - start >= min_length {
+                    for j in start..i {
+                        rules[index(j, line)] = true;
+                    }
+                }
+                start = i + 1;
+            }
+        }
+    }
+
+    /// Finds all 8-connected blobs of ink.
+    fn blobs(mut self) -> Vec<StrayMark> {
+        let (width, height) = (self.width(), self.height());
+        let mut blobs = Vec::new();
+        let mut stack = Vec::new();
+
+        for start in 0..self.ink.len() {
+            if !self.ink[start] {
+                continue;
+            }
+
+            self.ink[start] = false;
+            stack.push(start);
+            let (mut min_x, mut min_y) = (width, height);
+            let (mut max_x, mut max_y) = (0, 0);
+            let mut pixel_count = 0;
+
+            while let Some(index) = stack.pop() {
+                let (x, y) = (index % width, index / width);
+                pixel_count += 1;
+                min_x = min_x.min(x);
+                max_x = max_x.max(x);
+                min_y = min_y.min(y);
+                max_y = max_y.max(y);
+
+                for ny in y.saturating_sub(1)..=(y + 1).min(height - 1) {
+                    for nx in x.saturating_sub(1)..=(x + 1).min(width - 1) {
+                        let neighbor = ny * width + nx;
+                        if self.ink[neighbor] {
+                            self.ink[neighbor] = false;
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+            }
+
+            blobs.push(StrayMark {
+                bounds: Rect::new(
+                    self.region.left() + min_x as PixelPosition,
+                    self.region.top() + min_y as PixelPosition,
+                    (max_x - min_x + 1) as PixelUnit,
+                    (max_y - min_y + 1) as PixelUnit,
+                ),
+                pixel_count,
+            });
+        }
+
+        blobs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mask(region: Rect, ink: &[(u32, u32)]) -> InkMask {
+        InkMask::from_region(region, |x, y| ink.contains(&(x, y)))
+    }
+
+    #[test]
+    fn test_blobs_are_eight_connected_in_image_coordinates() {
+        let region = Rect::new(10, 20, 20, 20);
+        let ink = [(12, 22), (13, 23), (14, 24), (25, 30), (25, 31)];
+        let blobs = mask(region, &ink).blobs();
+
+        assert_eq!(
+            blobs,
+            vec![
+                StrayMark {
+                    bounds: Rect::new(12, 22, 3, 3),
+                    pixel_count: 3,
+                },
+                StrayMark {
+                    bounds: Rect::new(25, 30, 1, 2),
+                    pixel_count: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clear_rect_is_clipped_to_region() {
+        let region = Rect::new(10, 10, 10, 10);
+        let ink = [(10, 10), (11, 11), (19, 19)];
+        let mut mask = mask(region, &ink);
+        mask.clear_rect(&Rect::new(0, 0, 12, 12));
+
+        assert_eq!(
+            mask.blobs(),
+            vec![StrayMark {
+                bounds: Rect::new(19, 19, 1, 1),
+                pixel_count: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_remove_rules_keeps_short_strokes() {
+        let region = Rect::new(0, 0, 100, 100);
+        // A horizontal rule that steps down a pixel every 20 pixels, as it
+        // would on a skewed scan, a vertical rule, and a short diagonal stroke.
+        let ink: Vec<_> = (0..100)
+            .map(|x| (x, 10 + x / 20))
+            .chain((0..100).map(|y| (90, y)))
+            .chain((0..20).map(|i| (40 + i, 40 + i)))
+            .collect();
+
+        let mut without_tolerance = mask(region, &ink);
+        without_tolerance.remove_rules(50, 0);
+        assert!(without_tolerance.blobs().len() > 1);
+
+        let mut with_tolerance = mask(region, &ink);
+        with_tolerance.remove_rules(50, 2);
+        assert_eq!(
+            with_tolerance.blobs(),
+            vec![StrayMark {
+                bounds: Rect::new(40, 40, 20, 20),
+                pixel_count: 20,
+            }]
+        );
+    }
+}
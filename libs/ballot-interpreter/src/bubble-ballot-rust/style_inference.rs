@@ -0,0 +1,384 @@
+//! Identifies the ballot style and sheet of a card whose QR codes can't be
+//! read from where bubbles are printed on it.
+//!
+//! Every ballot style's grid layout says where its bubbles are on each side of
+//! each sheet. A printed bubble matches the bubble template far better than
+//! blank paper does, whether or not it is filled in, so the layout that puts
+//! bubbles where the scan has them, and nowhere else, is the one the card was
+//! printed from.
+
+use std::collections::HashMap;
+
+use image::GrayImage;
+use itertools::Itertools;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use serde::Serialize;
+use types_rs::{
+    ballot_card::{BallotSide, PageNumber},
+    election::{BallotStyleId, Election, GridLayout, GridLocation, GridPosition, PrecinctId},
+    geometry::SubGridUnit,
+    pair::Pair,
+};
+
+use crate::{
+    ballot_card::{BallotImage, Orientation, SCAN_PIXELS_PER_INCH},
+    scoring::{score_bubble_mark, DEFAULT_MAXIMUM_SEARCH_DISTANCE},
+    timing_marks::{BallotPageMetadata, TimingMarks},
+};
+
+/// How much better the template must match where a layout puts bubbles than
+/// where it doesn't for the layout to be accepted. Blank paper still matches
+/// about two thirds of the template, so this is a sizeable difference.
+const MIN_LAYOUT_SCORE: f32 = 0.1;
+
+/// How much better the chosen layout must score than the next best one.
+/// Ballot styles that print bubbles in the same places on a sheet can't be
+/// told apart this way, and a card that could be either is rejected.
+const MIN_LAYOUT_SCORE_MARGIN: f32 = 0.02;
+
+/// The ballot style and sheet inferred for a card whose QR codes couldn't be
+/// read. Cards identified this way are held for adjudication.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InferredBallotStyle {
+    pub ballot_style_id: BallotStyleId,
+    pub sheet_number: u32,
+
+    /// Every precinct the ballot style is used in. Only the QR code says which
+    /// one the ballot was printed for, so the page metadata names none.
+    pub precinct_ids: Vec<PrecinctId>,
+
+    /// The mean bubble match score where the layout puts bubbles, less the
+    /// mean everywhere else any layout puts them.
+    pub score: f32,
+
+    /// The score of the next best layout, sheet, side and orientation.
+    pub runner_up_score: Option<f32>,
+}
+
+/// A grid location keyed by its exact coordinates.
+type LocationKey = (u32, u32);
+
+fn location_key(column: SubGridUnit, row: SubGridUnit) -> LocationKey {
+    (column.to_bits(), row.to_bits())
+}
+
+/// The bubble locations on one sheet of one ballot style.
+struct Candidate<'a> {
+    grid_layout: &'a GridLayout,
+    sheet_number: u32,
+    front: Vec<LocationKey>,
+    back: Vec<LocationKey>,
+}
+
+impl Candidate<'_> {
+    fn locations(&self, side: BallotSide) -> &[LocationKey] {
+        match side {
+            BallotSide::Front => &self.front,
+            BallotSide::Back => &self.back,
+        }
+    }
+}
+
+/// One way of reading the card: as a given candidate, with side A as the
+/// front or the back, and with every page either upright or upside down.
+struct Hypothesis<'a> {
+    candidate: &'a Candidate<'a>,
+    side_a: BallotSide,
+    orientation: Orientation,
+    score: f32,
+}
+
+/// Builds one candidate for every sheet of every grid layout.
+fn candidates(grid_layouts: &[GridLayout]) -> Vec<Candidate<'_>> {
+    grid_layouts
+        .iter()
+        .flat_map(|grid_layout| {
+            grid_layout
+                .grid_positions
+                .iter()
+                .map(GridPosition::sheet_number)
+                .unique()
+                .map(move |sheet_number| {
+                    let locations = |side| {
+                        grid_layout
+                            .grid_positions
+                            .iter()
+                            .filter(|position| position.sheet_number() == sheet_number)
+                            .map(GridPosition::location)
+                            .filter(|location| location.side == side)
+                            .map(|location| location_key(location.column, location.row))
+                            .unique()
+                            .collect()
+                    };
+                    Candidate {
+                        grid_layout,
+                        sheet_number,
+                        front: locations(BallotSide::Front),
+                        back: locations(BallotSide::Back),
+                    }
+                })
+        })
+        .collect()
+}
+
+/// Scores how well the bubble template matches at each of `locations` on a
+/// page read in `orientation`. Locations outside the timing mark grid are
+/// left out.
+fn match_scores(
+    ballot_image: &BallotImage,
+    timing_marks: &TimingMarks,
+    bubble_template: &GrayImage,
+    orientation: Orientation,
+    locations: &[LocationKey],
+) -> HashMap<LocationKey, f32> {
+    let maximum_search_distance = (DEFAULT_MAXIMUM_SEARCH_DISTANCE
+        * timing_marks.geometry.pixels_per_inch)
+        .div_ceil(SCAN_PIXELS_PER_INCH);
+    let grid_size = timing_marks.geometry.grid_size;
+    locations
+        .par_iter()
+        .filter_map(|&key| {
+            let (column, row) = (f32::from_bits(key.0), f32::from_bits(key.1));
+            // An upside down page has its grid turned around too.
+            let (column, row) = match orientation {
+                Orientation::Portrait => (column, row),
+                Orientation::PortraitReversed => (
+                    (grid_size.width - 1) as SubGridUnit - column,
+                    (grid_size.height - 1) as SubGridUnit - row,
+                ),
+            };
+            let center = timing_marks.point_for_location(column, row)?;
+            let scored = score_bubble_mark(
+                ballot_image,
+                bubble_template,
+                center,
+                &GridLocation::new(BallotSide::Front, column, row),
+                maximum_search_distance,
+            )?;
+            Some((key, scored.match_score.0))
+        })
+        .collect()
+}
+
+/// Scores every way of reading the card as one of `candidates`, given the
+/// match scores at `all_locations` on each page in each orientation. The
+/// pages are side A and, if it was scanned, side B.
+fn hypotheses<'a>(
+    candidates: &'a [Candidate<'a>],
+    all_locations: &[LocationKey],
+    scores: &[[HashMap<LocationKey, f32>; 2]],
+) -> Vec<Hypothesis<'a>> {
+    let mut hypotheses = Vec::new();
+    for candidate in candidates {
+        for side_a in [BallotSide::Front, BallotSide::Back] {
+            let side_b = match side_a {
+                BallotSide::Front => BallotSide::Back,
+                BallotSide::Back => BallotSide::Front,
+            };
+            for (orientation_index, orientation) in
+                [Orientation::Portrait, Orientation::PortraitReversed]
+                    .into_iter()
+                    .enumerate()
+            {
+                let (mut expected, mut elsewhere) = (Vec::new(), Vec::new());
+                for (page_scores, side) in scores.iter().zip([side_a, side_b]) {
+                    let page_scores = &page_scores[orientation_index];
+                    let bubbles = candidate.locations(side);
+                    for location in all_locations {
+                        let Some(&score) = page_scores.get(location) else {
+                            continue;
+                        };
+                        if bubbles.contains(location) {
+                            expected.push(score);
+                        } else {
+                            elsewhere.push(score);
+                        }
+                    }
+                }
+
+                if expected.is_empty() {
+                    continue;
+                }
+                let mean = |scores: &[f32]| scores.iter().sum::<f32>() / scores.len() as f32;
+                let elsewhere_mean = if elsewhere.is_empty() {
+                    0.0
+                } else {
+                    mean(&elsewhere)
+                };
+                hypotheses.push(Hypothesis {
+                    candidate,
+                    side_a,
+                    orientation,
+                    score: mean(&expected) - elsewhere_mean,
+                });
+            }
+        }
+    }
+
+    hypotheses
+}
+
+/// Identifies the ballot style and sheet of a card from where bubbles are
+/// printed on its pages, returning metadata for sides A and B along with the
+/// orientation of each. Returns `None` if no layout fits the card well enough
+/// or if more than one fits it about as well.
+///
+/// Nothing but the QR code says which precinct, mode or ballot type a ballot
+/// was printed for, so the metadata only has the ballot style and page
+/// number, and callers must hold the card for adjudication.
+#[must_use]
+pub fn infer_ballot_style(
+    election: &Election,
+    grid_layouts: &[GridLayout],
+    pages: Pair<(&BallotImage, &TimingMarks)>,
+    bubble_template: &GrayImage,
+) -> Option<(Pair<(BallotPageMetadata, Orientation)>, InferredBallotStyle)> {
+    let (side_a, side_b) = pages.into();
+    let (metadata, inferred) = infer(election, grid_layouts, &[side_a, side_b], bubble_template)?;
+    let [side_a, side_b] = <[_; 2]>::try_from(metadata).ok()?;
+    Some((Pair::new(side_a, side_b), inferred))
+}
+
+/// Identifies the ballot style and sheet of a single page scanned on its own,
+/// like [`infer_ballot_style`] does for a card.
+#[must_use]
+pub fn infer_page_ballot_style(
+    election: &Election,
+    grid_layouts: &[GridLayout],
+    page: (&BallotImage, &TimingMarks),
+    bubble_template: &GrayImage,
+) -> Option<((BallotPageMetadata, Orientation), InferredBallotStyle)> {
+    let (mut metadata, inferred) = infer(election, grid_layouts, &[page], bubble_template)?;
+    Some((metadata.pop()?, inferred))
+}
+
+/// Identifies the ballot style and sheet from `pages`, which are side A and,
+/// if it was scanned, side B, returning metadata for each.
+fn infer(
+    election: &Election,
+    grid_layouts: &[GridLayout],
+    pages: &[(&BallotImage, &TimingMarks)],
+    bubble_template: &GrayImage,
+) -> Option<(Vec<(BallotPageMetadata, Orientation)>, InferredBallotStyle)> {
+    let candidates = candidates(grid_layouts);
+    let all_locations = candidates
+        .iter()
+        .flat_map(|candidate| candidate.front.iter().chain(&candidate.back))
+        .copied()
+        .unique()
+        .collect_vec();
+
+    let scores = pages
+        .par_iter()
+        .map(|(ballot_image, timing_marks)| {
+            [Orientation::Portrait, Orientation::PortraitReversed].map(|orientation| {
+                match_scores(
+                    ballot_image,
+                    timing_marks,
+                    bubble_template,
+                    orientation,
+                    &all_locations,
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut hypotheses = hypotheses(&candidates, &all_locations, &scores);
+    hypotheses.sort_by(|a, b| b.score.total_cmp(&a.score));
+    let best = hypotheses.first()?;
+    let runner_up_score = hypotheses.get(1).map(|hypothesis| hypothesis.score);
+    if best.score < MIN_LAYOUT_SCORE
+        || runner_up_score.is_some_and(|score| best.score - score < MIN_LAYOUT_SCORE_MARGIN)
+    {
+        return None;
+    }
+
+    let ballot_style_id = &best.candidate.grid_layout.ballot_style_id;
+    let ballot_style = election
+        .ballot_styles
+        .iter()
+        .find(|ballot_style| &ballot_style.id == ballot_style_id)?;
+    let front_page_number =
+        PageNumber::new(u8::try_from(best.candidate.sheet_number * 2 - 1).ok()?)?;
+    let side_a_page_number = match best.side_a {
+        BallotSide::Front => front_page_number,
+        BallotSide::Back => front_page_number.opposite(),
+    };
+    let metadata = [side_a_page_number, side_a_page_number.opposite()]
+        .into_iter()
+        .take(pages.len())
+        .map(|page_number| {
+            (
+                BallotPageMetadata::Inferred {
+                    ballot_style_id: ballot_style_id.clone(),
+                    page_number,
+                },
+                best.orientation,
+            )
+        })
+        .collect();
+
+    Some((
+        metadata,
+        InferredBallotStyle {
+            ballot_style_id: ballot_style_id.clone(),
+            sheet_number: best.candidate.sheet_number,
+            precinct_ids: ballot_style.precincts.clone(),
+            score: best.score,
+            runner_up_score,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use types_rs::election::{ContestId, GridRect, OptionId};
+
+    use super::*;
+
+    fn grid_position(sheet_number: u32, side: BallotSide, column: f32, row: f32) -> GridPosition {
+        GridPosition::Option {
+            sheet_number,
+            side,
+            column,
+            row,
+            contest_id: ContestId::from("contest".to_owned()),
+            option_id: OptionId::from(format!("option-{column}-{row}")),
+            rank: None,
+            bounds: GridRect::default(),
+        }
+    }
+
+    #[test]
+    fn test_candidates_split_layouts_by_sheet_and_side() {
+        let grid_layouts = vec![GridLayout {
+            ballot_style_id: BallotStyleId::from("bs-1".to_owned()),
+            grid_positions: vec![
+                grid_position(1, BallotSide::Front, 2.0, 3.0),
+                grid_position(1, BallotSide::Front, 2.0, 3.0),
+                grid_position(1, BallotSide::Back, 2.0, 5.5),
+                grid_position(2, BallotSide::Front, 4.0, 3.0),
+            ],
+        }];
+        let candidates = candidates(&grid_layouts);
+        assert_eq!(
+            candidates
+                .iter()
+                .map(|candidate| (
+                    candidate.sheet_number,
+                    candidate.front.clone(),
+                    candidate.back.clone()
+                ))
+                .collect_vec(),
+            vec![
+                (
+                    1,
+                    vec![location_key(2.0, 3.0)],
+                    vec![location_key(2.0, 5.5)]
+                ),
+                (2, vec![location_key(4.0, 3.0)], vec![]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! Interprets summary ballots, i.e. BMD-printed pages listing the voter's
+//! selections with a QR code encoding them.
+//!
+//! Unlike bubble ballots, everything about a summary ballot is in its QR code,
+//! so interpretation is finding that QR code on one side of the card, decoding
+//! it as a [`CastVoteRecord`], and checking that it belongs in this election
+//! and on this scanner.
+
+use image::GrayImage;
+use serde::Serialize;
+use types_rs::{
+    ballot_card::BallotSide,
+    bmd::{cvr::CastVoteRecord, BMD_PRELUDE},
+    bubble_ballot::{self, PartialBallotHash},
+    coding,
+    election::{Election, PrecinctId},
+};
+
+use crate::{
+    ballot_card::Orientation,
+    debug::ImageDebugWriter,
+    qr_code::{self, QrCodeKind, SearchStrategy},
+};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// What a summary ballot must match to be accepted.
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub election: Election,
+    pub expected_ballot_hash: PartialBallotHash,
+
+    /// Whether the scanner is in test mode. Test ballots are only accepted in
+    /// test mode, and official ballots only outside it.
+    pub is_test_mode: bool,
+
+    /// The precincts the scanner is configured for.
+    pub valid_precinct_ids: Vec<PrecinctId>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterpretedSummaryBallot {
+    pub cast_vote_record: CastVoteRecord,
+
+    /// Which image has the summary ballot: `front` for side A, `back` for
+    /// side B. The other side of the card is expected to be blank.
+    pub side: BallotSide,
+
+    /// The orientation of the summary ballot as determined by the QR code
+    /// position.
+    pub orientation: Orientation,
+}
+
+#[derive(Debug, Serialize, Clone, thiserror::Error)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Error {
+    #[error("no summary ballot QR code found on either side")]
+    QrCodeNotFound,
+
+    #[error("summary ballot QR codes found on both sides")]
+    MultipleQrCodes,
+
+    #[error("invalid summary ballot QR code data: {message}")]
+    InvalidQrCodeData { message: String },
+
+    #[error("invalid ballot hash: expected {expected:02x?}, actual {actual:02x?}")]
+    #[serde(rename_all = "camelCase")]
+    InvalidBallotHash {
+        #[serde(with = "bubble_ballot::ballot_hash_serde")]
+        expected: PartialBallotHash,
+        #[serde(with = "bubble_ballot::ballot_hash_serde")]
+        actual: PartialBallotHash,
+    },
+
+    #[error("invalid page number: page {page_number} of {total_pages}")]
+    #[serde(rename_all = "camelCase")]
+    InvalidPageNumber { page_number: u8, total_pages: u8 },
+
+    #[error("invalid test mode: expected {expected}, actual {actual}")]
+    InvalidTestMode { expected: bool, actual: bool },
+
+    #[error("invalid precinct: {precinct_id}")]
+    #[serde(rename_all = "camelCase")]
+    InvalidPrecinct { precinct_id: PrecinctId },
+}
+
+/// Finds the summary ballot QR code on one side of a card, decodes it, and
+/// validates it with [`decode_summary_ballot`].
+///
+/// # Errors
+///
+/// Returns an error if neither side or both sides have a summary ballot QR
+/// code, or if the one found does not pass validation.
+#[allow(clippy::similar_names)]
+pub fn interpret_summary_ballot(
+    side_a_image: &GrayImage,
+    side_b_image: &GrayImage,
+    options: &Options,
+) -> Result<InterpretedSummaryBallot> {
+    let detect = |image: &GrayImage| {
+        qr_code::detect_with_strategy(
+            image,
+            SearchStrategy::Broad,
+            &ImageDebugWriter::disabled(),
+        )
+        .ok()
+        .filter(|detected| detected.kind() == QrCodeKind::SummaryBallot)
+    };
+    let (side, detected) = match rayon::join(|| detect(side_a_image), || detect(side_b_image)) {
+        (Some(detected), None) => (BallotSide::Front, detected),
+        (None, Some(detected)) => (BallotSide::Back, detected),
+        (None, None) => return Err(Error::QrCodeNotFound),
+        (Some(_), Some(_)) => return Err(Error::MultipleQrCodes),
+    };
+
+    Ok(InterpretedSummaryBallot {
+        cast_vote_record: decode_summary_ballot(detected.bytes(), options)?,
+        side,
+        orientation: detected.orientation(),
+    })
+}
+
+/// Decodes the data from a summary ballot QR code and checks that it matches
+/// the expected ballot hash, the scanner's test mode and precincts, and that
+/// its page number is within its page count.
+///
+/// The ballot hash is checked before decoding the rest, since the contests
+/// and options of another election can't be decoded against this one.
+///
+/// # Errors
+///
+/// Returns an error if the data can't be decoded or fails any of the checks.
+pub fn decode_summary_ballot(bytes: &[u8], options: &Options) -> Result<CastVoteRecord> {
+    let actual_ballot_hash: PartialBallotHash = bytes
+        .get(BMD_PRELUDE.len()..)
+        .and_then(|bytes| bytes.get(..options.expected_ballot_hash.len()))
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| Error::InvalidQrCodeData {
+            message: format!("too short: {} bytes", bytes.len()),
+        })?;
+    if actual_ballot_hash != options.expected_ballot_hash {
+        return Err(Error::InvalidBallotHash {
+            expected: options.expected_ballot_hash,
+            actual: actual_ballot_hash,
+        });
+    }
+
+    let cast_vote_record = coding::decode_with::<CastVoteRecord>(bytes, &options.election)
+        .map_err(|err| Error::InvalidQrCodeData {
+            message: err.to_string(),
+        })?;
+
+    let page_number = cast_vote_record.page_number.get();
+    let total_pages = cast_vote_record.total_pages.get();
+    if page_number > total_pages {
+        return Err(Error::InvalidPageNumber {
+            page_number,
+            total_pages,
+        });
+    }
+
+    if cast_vote_record.is_test_mode != options.is_test_mode {
+        return Err(Error::InvalidTestMode {
+            expected: options.is_test_mode,
+            actual: cast_vote_record.is_test_mode,
+        });
+    }
+
+    if !options
+        .valid_precinct_ids
+        .contains(&cast_vote_record.precinct_id)
+    {
+        return Err(Error::InvalidPrecinct {
+            precinct_id: cast_vote_record.precinct_id,
+        });
+    }
+
+    Ok(cast_vote_record)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use image::Luma;
+    use qrcode::{Color, QrCode};
+    use sha2::{Digest, Sha256};
+    use types_rs::{
+        ballot_card::BallotType,
+        bmd::{cvr::PageNumber, encoding::BallotAuditId},
+        election::BallotStyleId,
+    };
+
+    use super::*;
+
+    fn load_options() -> Options {
+        let bytes = std::fs::read(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../hmpb/fixtures/vx-general-election/letter-en/election.json"),
+        )
+        .unwrap();
+        let election: Election = serde_json::from_slice(&bytes).unwrap();
+        let mut expected_ballot_hash = PartialBallotHash::default();
+        let len = expected_ballot_hash.len();
+        expected_ballot_hash.copy_from_slice(&Sha256::digest(&bytes)[..len]);
+        Options {
+            valid_precinct_ids: election
+                .precincts
+                .iter()
+                .map(|precinct| precinct.id.clone())
+                .collect(),
+            election,
+            expected_ballot_hash,
+            is_test_mode: true,
+        }
+    }
+
+    fn cast_vote_record(options: &Options) -> CastVoteRecord {
+        CastVoteRecord {
+            ballot_hash: options.expected_ballot_hash,
+            ballot_style_id: BallotStyleId::from("12".to_owned()),
+            precinct_id: PrecinctId::from("23".to_owned()),
+            page_number: PageNumber::new(1).unwrap(),
+            total_pages: PageNumber::new(2).unwrap(),
+            is_test_mode: true,
+            ballot_type: BallotType::Precinct,
+            ballot_audit_id: BallotAuditId::new("audit-id").unwrap(),
+            contest_ids: vec![],
+            votes: HashMap::new(),
+        }
+    }
+
+    fn encode(cast_vote_record: &CastVoteRecord, options: &Options) -> Vec<u8> {
+        coding::encode_with(cast_vote_record, &options.election).unwrap()
+    }
+
+    /// Draws `bytes` as a base64 QR code, as a BMD prints it, on an otherwise
+    /// blank letter-size page at 200 DPI.
+    fn summary_ballot_page(bytes: &[u8]) -> GrayImage {
+        const MODULE_SIZE: u32 = 6;
+        let mut image = blank_page();
+        let qr_code = QrCode::new(STANDARD.encode(bytes)).unwrap();
+        let modules = qr_code.width() as u32;
+        for (index, color) in qr_code.to_colors().into_iter().enumerate() {
+            if color == Color::Dark {
+                let left = 700 + (index as u32 % modules) * MODULE_SIZE;
+                let top = 1500 + (index as u32 / modules) * MODULE_SIZE;
+                for y in top..top + MODULE_SIZE {
+                    for x in left..left + MODULE_SIZE {
+                        image.put_pixel(x, y, Luma([0]));
+                    }
+                }
+            }
+        }
+        image
+    }
+
+    fn blank_page() -> GrayImage {
+        GrayImage::from_pixel(1700, 2200, Luma([255]))
+    }
+
+    #[test]
+    fn test_decode_summary_ballot() {
+        let options = load_options();
+        let expected = cast_vote_record(&options);
+        let decoded = decode_summary_ballot(&encode(&expected, &options), &options).unwrap();
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn test_decode_summary_ballot_invalid_ballot_hash() {
+        let mut options = load_options();
+        let bytes = encode(&cast_vote_record(&options), &options);
+        let actual = options.expected_ballot_hash;
+        options.expected_ballot_hash = actual.map(|b| !b);
+
+        match decode_summary_ballot(&bytes, &options) {
+            Err(Error::InvalidBallotHash {
+                expected,
+                actual: decoded_actual,
+            }) => {
+                assert_eq!(expected, options.expected_ballot_hash);
+                assert_eq!(decoded_actual, actual);
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_summary_ballot_too_short() {
+        let options = load_options();
+        assert!(matches!(
+            decode_summary_ballot(BMD_PRELUDE, &options),
+            Err(Error::InvalidQrCodeData { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_summary_ballot_invalid_page_number() {
+        let options = load_options();
+        let cast_vote_record = CastVoteRecord {
+            page_number: PageNumber::new(3).unwrap(),
+            ..cast_vote_record(&options)
+        };
+        assert!(matches!(
+            decode_summary_ballot(&encode(&cast_vote_record, &options), &options),
+            Err(Error::InvalidPageNumber {
+                page_number: 3,
+                total_pages: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_decode_summary_ballot_invalid_test_mode() {
+        let options = load_options();
+        let cast_vote_record = CastVoteRecord {
+            is_test_mode: false,
+            ..cast_vote_record(&options)
+        };
+        assert!(matches!(
+            decode_summary_ballot(&encode(&cast_vote_record, &options), &options),
+            Err(Error::InvalidTestMode {
+                expected: true,
+                actual: false
+            })
+        ));
+    }
+
+    #[test]
+    fn test_decode_summary_ballot_invalid_precinct() {
+        let mut options = load_options();
+        let bytes = encode(&cast_vote_record(&options), &options);
+        options
+            .valid_precinct_ids
+            .retain(|precinct_id| precinct_id.to_string() != "23");
+
+        match decode_summary_ballot(&bytes, &options) {
+            Err(Error::InvalidPrecinct { precinct_id }) => {
+                assert_eq!(precinct_id.to_string(), "23");
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_interpret_summary_ballot() {
+        let options = load_options();
+        let expected = cast_vote_record(&options);
+        let page = summary_ballot_page(&encode(&expected, &options));
+
+        let interpreted = interpret_summary_ballot(&blank_page(), &page, &options).unwrap();
+        assert_eq!(interpreted.cast_vote_record, expected);
+        assert_eq!(interpreted.side, BallotSide::Back);
+        assert_eq!(interpreted.orientation, Orientation::Portrait);
+    }
+
+    #[test]
+    fn test_interpret_summary_ballot_qr_code_not_found() {
+        let options = load_options();
+        assert!(matches!(
+            interpret_summary_ballot(&blank_page(), &blank_page(), &options),
+            Err(Error::QrCodeNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_interpret_summary_ballot_multiple_qr_codes() {
+        let options = load_options();
+        let page = summary_ballot_page(&encode(&cast_vote_record(&options), &options));
+        assert!(matches!(
+            interpret_summary_ballot(&page, &page, &options),
+            Err(Error::MultipleQrCodes)
+        ));
+    }
+}
@@ -0,0 +1,588 @@
+//! Renders synthetic ballot card images from an election definition.
+//!
+//! Scanned fixtures go stale whenever the ballot format changes, and they only
+//! cover whatever marks happened to be on the paper. The images produced here
+//! are drawn from the same geometry the interpreter expects — timing marks from
+//! [`PaperInfo::compute_geometry`], bubbles at the election's grid positions,
+//! and a QR code encoding real [`bubble_ballot::Metadata`] — so tests can mark
+//! any combination of bubbles, degrade the image the way a scanner might, and
+//! compare the interpretation against known ground truth.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{
+    imageops::{resize, FilterType},
+    GrayImage, Luma,
+};
+use qrcode::{Color, QrCode};
+use types_rs::{
+    ballot_card::{BallotSide, BallotType, PageNumber, PaperSize},
+    bubble_ballot::{self, PartialBallotHash},
+    coding,
+    election::{BallotStyleId, Election, GridPosition, PrecinctId},
+    geometry::{Inch, PixelPosition, PixelUnit, Point, SubGridUnit, SubPixelUnit},
+    pair::Pair,
+};
+
+use crate::{
+    ballot_card::{BubbleTemplate, Geometry, PaperInfo, SCAN_PIXELS_PER_INCH},
+    image_utils::{BLACK, WHITE},
+};
+
+/// Width of the QR code, including its quiet zone, on a rendered page.
+const QR_CODE_SIZE: Inch = Inch::new(0.6);
+
+/// Number of modules of white space around the QR code.
+const QR_CODE_QUIET_ZONE_MODULES: usize = 2;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("no ballot style with ID {0}")]
+    UnknownBallotStyle(BallotStyleId),
+
+    #[error("ballot style {ballot_style_id} has no sheet {sheet_number}")]
+    InvalidSheetNumber {
+        ballot_style_id: BallotStyleId,
+        sheet_number: u32,
+    },
+
+    #[error("unable to encode ballot metadata: {0}")]
+    EncodeMetadata(#[from] bubble_ballot::Error),
+
+    #[error("unable to encode QR code: {0}")]
+    EncodeQrCode(#[from] qrcode::types::QrError),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Identifies the ballot card to render.
+#[derive(Debug, Clone)]
+pub struct BallotCard<'a> {
+    pub election: &'a Election,
+    pub ballot_style_id: BallotStyleId,
+    pub precinct_id: PrecinctId,
+    /// Which sheet of the ballot style to render, starting at 1.
+    pub sheet_number: u32,
+    pub ballot_hash: PartialBallotHash,
+    pub is_test_mode: bool,
+    pub ballot_type: BallotType,
+}
+
+/// A dark vertical line across the whole image, like the ones left by dust
+/// on a scanner's sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Streak {
+    pub x: PixelPosition,
+    pub width: PixelUnit,
+}
+
+/// How to render and degrade the images.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Density of the rendered images.
+    pub pixels_per_inch: PixelUnit,
+
+    /// The outline drawn at each bubble position.
+    pub bubble_template: BubbleTemplate,
+
+    /// Clockwise rotation of the page in the image, in degrees.
+    pub skew_degrees: f32,
+
+    /// Factor by which to stretch the page in the scan direction, i.e.
+    /// vertically. Values above 1.0 mimic paper slipping in the feeder.
+    pub vertical_stretch: f32,
+
+    /// Maximum amount by which to randomly lighten or darken each pixel.
+    pub noise: u8,
+
+    /// Vertical streaks to draw over the finished image.
+    pub streaks: Vec<Streak>,
+
+    /// Seed for the noise, so that a given set of options always renders the
+    /// same images.
+    pub seed: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            pixels_per_inch: SCAN_PIXELS_PER_INCH,
+            bubble_template: BubbleTemplate::default(),
+            skew_degrees: 0.0,
+            vertical_stretch: 1.0,
+            noise: 0,
+            streaks: vec![],
+            seed: 0,
+        }
+    }
+}
+
+/// Renders both sides of `card`, filling in each bubble for which `is_marked`
+/// returns `true`. Returns the front and back images, in that order.
+///
+/// # Errors
+///
+/// Fails if the ballot style or sheet doesn't exist, or if the metadata can't
+/// be encoded for the election.
+pub fn render_ballot_card(
+    card: &BallotCard<'_>,
+    is_marked: impl Fn(&GridPosition) -> bool,
+    options: &Options,
+) -> Result<Pair<GrayImage>> {
+    let election = card.election;
+    let grid_layout = election
+        .grid_layouts()
+        .into_iter()
+        .find(|grid_layout| grid_layout.ballot_style_id == card.ballot_style_id)
+        .ok_or_else(|| Error::UnknownBallotStyle(card.ballot_style_id.clone()))?;
+    let page_numbers = u8::try_from(card.sheet_number * 2)
+        .ok()
+        .and_then(|back| Some((PageNumber::new(back - 1)?, PageNumber::new(back)?)))
+        .filter(|_| {
+            grid_layout
+                .grid_positions
+                .iter()
+                .any(|grid_position| grid_position.sheet_number() == card.sheet_number)
+        })
+        .ok_or_else(|| Error::InvalidSheetNumber {
+            ballot_style_id: card.ballot_style_id.clone(),
+            sheet_number: card.sheet_number,
+        })?;
+
+    let paper_size = election
+        .ballot_layout
+        .as_ref()
+        .map_or(PaperSize::Letter, |ballot_layout| ballot_layout.paper_size);
+    let geometry = PaperInfo::scanned_with_size(paper_size)
+        .with_pixels_per_inch(options.pixels_per_inch)
+        .compute_geometry();
+    let bubble_template = options
+        .bubble_template
+        .at_pixels_per_inch(options.pixels_per_inch);
+
+    Pair::new(
+        (BallotSide::Front, page_numbers.0),
+        (BallotSide::Back, page_numbers.1),
+    )
+    .map(|(side, page_number)| {
+        let metadata = bubble_ballot::Metadata {
+            ballot_hash: card.ballot_hash,
+            precinct_id: card.precinct_id.clone(),
+            ballot_style_id: card.ballot_style_id.clone(),
+            page_number,
+            is_test_mode: card.is_test_mode,
+            ballot_type: card.ballot_type,
+            ballot_audit_id: None,
+        };
+        let mut image = blank_page(&geometry);
+        draw_timing_marks(&mut image, &geometry);
+        draw_qr_code(
+            &mut image,
+            &geometry,
+            &coding::encode_with(&metadata, election)?,
+        )?;
+        for grid_position in grid_layout.grid_positions.iter().filter(|grid_position| {
+            grid_position.sheet_number() == card.sheet_number
+                && grid_position.location().side == side
+        }) {
+            let location = grid_position.location();
+            draw_bubble(
+                &mut image,
+                &bubble_template,
+                grid_point_center(&geometry, location.column, location.row),
+                is_marked(grid_position),
+            );
+        }
+        Ok(degrade(image, options))
+    })
+    .into_result()
+}
+
+fn blank_page(geometry: &Geometry) -> GrayImage {
+    GrayImage::from_pixel(
+        geometry.canvas_width_pixels().round() as PixelUnit,
+        geometry.canvas_height_pixels().round() as PixelUnit,
+        WHITE,
+    )
+}
+
+/// Gets the center of the given grid position on an undistorted page.
+fn grid_point_center(
+    geometry: &Geometry,
+    column: SubGridUnit,
+    row: SubGridUnit,
+) -> Point<SubPixelUnit> {
+    Point::new(
+        geometry.content_area.left() as SubPixelUnit
+            + geometry.timing_mark_width_pixels() / 2.0
+            + column * geometry.horizontal_timing_mark_center_to_center_pixel_distance(),
+        geometry.content_area.top() as SubPixelUnit
+            + geometry.timing_mark_height_pixels() / 2.0
+            + row * geometry.vertical_timing_mark_center_to_center_pixel_distance(),
+    )
+}
+
+fn fill_rect(
+    image: &mut GrayImage,
+    left: SubPixelUnit,
+    top: SubPixelUnit,
+    right: SubPixelUnit,
+    bottom: SubPixelUnit,
+    luma: Luma<u8>,
+) {
+    let (width, height) = image.dimensions();
+    for y in (top.round().max(0.0) as u32)..(bottom.round() as u32).min(height) {
+        for x in (left.round().max(0.0) as u32)..(right.round() as u32).min(width) {
+            image.put_pixel(x, y, luma);
+        }
+    }
+}
+
+/// Draws the complete border of timing marks.
+fn draw_timing_marks(image: &mut GrayImage, geometry: &Geometry) {
+    let half_width = geometry.timing_mark_width_pixels() / 2.0;
+    let half_height = geometry.timing_mark_height_pixels() / 2.0;
+    let last_column = geometry.grid_size.width - 1;
+    let last_row = geometry.grid_size.height - 1;
+    let border = (0..=last_column)
+        .flat_map(|column| [(column, 0), (column, last_row)])
+        .chain((1..last_row).flat_map(|row| [(0, row), (last_column, row)]));
+    for (column, row) in border {
+        let center = grid_point_center(geometry, column as SubGridUnit, row as SubGridUnit);
+        fill_rect(
+            image,
+            center.x - half_width,
+            center.y - half_height,
+            center.x + half_width,
+            center.y + half_height,
+            BLACK,
+        );
+    }
+}
+
+/// Draws a QR code of `payload` in the bottom left corner of the grid, where
+/// our ballots print it. The payload is base64-encoded as it is on printed
+/// ballots.
+fn draw_qr_code(image: &mut GrayImage, geometry: &Geometry, payload: &[u8]) -> Result<()> {
+    let qr_code = QrCode::new(STANDARD.encode(payload))?;
+    let modules = qr_code.width();
+    let colors = qr_code.to_colors();
+    let size = QR_CODE_SIZE.pixels(geometry.pixels_per_inch);
+    let module_size = size / (modules + 2 * QR_CODE_QUIET_ZONE_MODULES) as SubPixelUnit;
+    let last_row = (geometry.grid_size.height - 1) as SubGridUnit;
+    let bottom_left = grid_point_center(geometry, 1.0, last_row - 1.0);
+    let left = bottom_left.x - geometry.timing_mark_width_pixels() / 2.0;
+    let top = bottom_left.y + geometry.timing_mark_height_pixels() / 2.0 - size;
+    fill_rect(image, left, top, left + size, top + size, WHITE);
+    for (index, color) in colors.into_iter().enumerate() {
+        if color == Color::Dark {
+            let module_left = left
+                + ((index % modules) + QR_CODE_QUIET_ZONE_MODULES) as SubPixelUnit * module_size;
+            let module_top = top
+                + ((index / modules) + QR_CODE_QUIET_ZONE_MODULES) as SubPixelUnit * module_size;
+            fill_rect(
+                image,
+                module_left,
+                module_top,
+                module_left + module_size,
+                module_top + module_size,
+                BLACK,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Draws the bubble template centered on `center`, filling the inside of the
+/// outline if `is_marked`.
+fn draw_bubble(
+    image: &mut GrayImage,
+    bubble_template: &GrayImage,
+    center: Point<SubPixelUnit>,
+    is_marked: bool,
+) {
+    let left = (center.x - bubble_template.width() as SubPixelUnit / 2.0).round() as i64;
+    let top = (center.y - bubble_template.height() as SubPixelUnit / 2.0).round() as i64;
+    for (y, row) in bubble_template.rows().enumerate() {
+        let row = row
+            .map(|pixel| pixel.0[0] < u8::MAX / 2)
+            .collect::<Vec<_>>();
+        let filled = if is_marked {
+            row.iter()
+                .position(|&dark| dark)
+                .zip(row.iter().rposition(|&dark| dark))
+        } else {
+            None
+        };
+        for (x, &dark) in row.iter().enumerate() {
+            let inside = filled.is_some_and(|(first, last)| (first..=last).contains(&x));
+            if !(dark || inside) {
+                continue;
+            }
+            let (Ok(image_x), Ok(image_y)) = (
+                u32::try_from(left + x as i64),
+                u32::try_from(top + y as i64),
+            ) else {
+                continue;
+            };
+            if image_x < image.width() && image_y < image.height() {
+                image.put_pixel(image_x, image_y, BLACK);
+            }
+        }
+    }
+}
+
+/// Applies the stretch, skew, streaks and noise from `options`, in the order
+/// they'd happen to a sheet of paper going through a scanner.
+fn degrade(image: GrayImage, options: &Options) -> GrayImage {
+    let image = if (options.vertical_stretch - 1.0).abs() < f32::EPSILON {
+        image
+    } else {
+        let height = (image.height() as f32 * options.vertical_stretch).round() as u32;
+        resize(&image, image.width(), height, FilterType::Triangle)
+    };
+    let mut image = rotate(&image, options.skew_degrees);
+
+    let height = image.height() as SubPixelUnit;
+    for streak in &options.streaks {
+        let left = streak.x as SubPixelUnit;
+        fill_rect(
+            &mut image,
+            left,
+            0.0,
+            left + streak.width as SubPixelUnit,
+            height,
+            BLACK,
+        );
+    }
+
+    if options.noise > 0 {
+        let mut rng = SplitMix64(options.seed);
+        let range = u64::from(options.noise) * 2 + 1;
+        for pixel in image.pixels_mut() {
+            let offset = (rng.next() % range) as i16 - i16::from(options.noise);
+            pixel.0[0] = (i16::from(pixel.0[0]) + offset).clamp(0, 255) as u8;
+        }
+    }
+
+    image
+}
+
+/// Rotates `image` clockwise about its center by `degrees`, keeping its
+/// dimensions and filling the uncovered corners with white.
+fn rotate(image: &GrayImage, degrees: f32) -> GrayImage {
+    if degrees == 0.0 {
+        return image.clone();
+    }
+    let (width, height) = image.dimensions();
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let center_x = width as f32 / 2.0;
+    let center_y = height as f32 / 2.0;
+    let sample = |x: f32, y: f32| -> f32 {
+        if x < 0.0 || y < 0.0 || x >= (width - 1) as f32 || y >= (height - 1) as f32 {
+            return f32::from(WHITE.0[0]);
+        }
+        let (x0, y0) = (x.floor(), y.floor());
+        let (dx, dy) = (x - x0, y - y0);
+        let at = |x: f32, y: f32| f32::from(image.get_pixel(x as u32, y as u32).0[0]);
+        let top = at(x0, y0) * (1.0 - dx) + at(x0 + 1.0, y0) * dx;
+        let bottom = at(x0, y0 + 1.0) * (1.0 - dx) + at(x0 + 1.0, y0 + 1.0) * dx;
+        top * (1.0 - dy) + bottom * dy
+    };
+    GrayImage::from_fn(width, height, |x, y| {
+        // Map each output pixel back to where it came from in the source.
+        let dx = x as f32 - center_x;
+        let dy = y as f32 - center_y;
+        let source_x = center_x + dx * cos + dy * sin;
+        let source_y = center_y - dx * sin + dy * cos;
+        Luma([sample(source_x, source_y).round() as u8])
+    })
+}
+
+/// A tiny deterministic PRNG, good enough for pixel noise.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use proptest::prelude::*;
+    use sha2::{Digest, Sha256};
+
+    use crate::{
+        interpret::{self, InterpretedBallotCard},
+        votes::DEFAULT_MARK_THRESHOLDS,
+    };
+
+    use super::*;
+
+    fn load_election() -> (Election, PartialBallotHash) {
+        let bytes = std::fs::read(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../hmpb/fixtures/vx-general-election/letter-en/election.json"),
+        )
+        .unwrap();
+        let election: Election = serde_json::from_slice(&bytes).unwrap();
+        let mut hash = PartialBallotHash::default();
+        let len = hash.len();
+        hash.copy_from_slice(&Sha256::digest(&bytes)[..len]);
+        (election, hash)
+    }
+
+    fn ballot_card(election: &Election, ballot_hash: PartialBallotHash) -> BallotCard<'_> {
+        BallotCard {
+            election,
+            ballot_style_id: BallotStyleId::from("12".to_owned()),
+            precinct_id: PrecinctId::from("23".to_owned()),
+            sheet_number: 1,
+            ballot_hash,
+            is_test_mode: false,
+            ballot_type: BallotType::Precinct,
+        }
+    }
+
+    fn interpret(
+        election: Election,
+        ballot_hash: PartialBallotHash,
+        (front, back): (GrayImage, GrayImage),
+    ) -> InterpretedBallotCard {
+        interpret::ballot_card(front, back, &interpret::Options::new(election, ballot_hash))
+            .unwrap()
+    }
+
+    /// Returns the grid positions whose bubbles the interpreter scored as
+    /// marked, in the order they appear on the card.
+    fn marked_positions(card: &InterpretedBallotCard) -> Vec<GridPosition> {
+        [&card.front, &card.back]
+            .into_iter()
+            .flat_map(|page| &page.marks)
+            .filter(|(_, mark)| {
+                mark.as_ref()
+                    .is_some_and(|mark| mark.fill_score.0 >= DEFAULT_MARK_THRESHOLDS.definite)
+            })
+            .map(|(grid_position, _)| grid_position.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_render_blank_ballot_card() {
+        let (election, ballot_hash) = load_election();
+        let images = render_ballot_card(
+            &ballot_card(&election, ballot_hash),
+            |_| false,
+            &Options::default(),
+        )
+        .unwrap();
+        let interpreted = interpret(election.clone(), ballot_hash, images.into());
+
+        let metadata = &interpreted.front.metadata;
+        assert_eq!(
+            *metadata.ballot_style_id(),
+            BallotStyleId::from("12".to_owned())
+        );
+        assert_eq!(
+            metadata.precinct_id(),
+            Some(&PrecinctId::from("23".to_owned()))
+        );
+        assert_eq!(metadata.page_number().get(), 1);
+        assert!(!interpreted.front.marks.is_empty());
+        assert!(marked_positions(&interpreted).is_empty());
+    }
+
+    #[test]
+    fn test_render_unknown_ballot_style() {
+        let (election, ballot_hash) = load_election();
+        let card = BallotCard {
+            ballot_style_id: BallotStyleId::from("99".to_owned()),
+            ..ballot_card(&election, ballot_hash)
+        };
+        assert!(matches!(
+            render_ballot_card(&card, |_| false, &Options::default()),
+            Err(Error::UnknownBallotStyle(_))
+        ));
+    }
+
+    #[test]
+    fn test_render_invalid_sheet_number() {
+        let (election, ballot_hash) = load_election();
+        let card = BallotCard {
+            sheet_number: 99,
+            ..ballot_card(&election, ballot_hash)
+        };
+        assert!(matches!(
+            render_ballot_card(&card, |_| false, &Options::default()),
+            Err(Error::InvalidSheetNumber { .. })
+        ));
+    }
+
+    proptest! {
+        // Interpreting a card takes a while in debug builds, so keep the
+        // number of cases small.
+        #![proptest_config(ProptestConfig::with_cases(4))]
+
+        /// Whatever bubbles are filled in, and however the image is degraded
+        /// within what a scanner might plausibly do, the interpreter finds
+        /// exactly the bubbles that were filled in.
+        #[test]
+        #[ignore = "slow; run with `cargo test --features synthetic -- --ignored`"]
+        fn test_interpret_rendered_marks(
+            marks in proptest::collection::vec(any::<bool>(), 64),
+            skew_degrees in -0.75f32..0.75,
+            vertical_stretch in 1.0f32..1.03,
+            noise in 0u8..40,
+            pixels_per_inch in prop_oneof![Just(150), Just(200), Just(300)],
+            seed in any::<u64>(),
+        ) {
+            let (election, ballot_hash) = load_election();
+            let grid_positions = election
+                .grid_layouts()
+                .into_iter()
+                .find(|grid_layout| grid_layout.ballot_style_id.to_string() == "12")
+                .unwrap()
+                .grid_positions
+                .into_iter()
+                .filter(|grid_position| grid_position.sheet_number() == 1)
+                .collect::<Vec<_>>();
+            let is_marked = |grid_position: &GridPosition| {
+                grid_positions
+                    .iter()
+                    .position(|other| other.location() == grid_position.location())
+                    .is_some_and(|index| marks[index % marks.len()])
+            };
+            let images = render_ballot_card(
+                &ballot_card(&election, ballot_hash),
+                is_marked,
+                &Options {
+                    pixels_per_inch,
+                    skew_degrees,
+                    vertical_stretch,
+                    noise,
+                    seed,
+                    ..Options::default()
+                },
+            )
+            .unwrap();
+            let interpreted = interpret(election.clone(), ballot_hash, images.into());
+
+            let expected = grid_positions
+                .iter()
+                .filter(|grid_position| is_marked(grid_position))
+                .map(GridPosition::location)
+                .collect::<Vec<_>>();
+            let actual = marked_positions(&interpreted)
+                .iter()
+                .map(GridPosition::location)
+                .collect::<Vec<_>>();
+            prop_assert_eq!(actual, expected);
+        }
+    }
+}
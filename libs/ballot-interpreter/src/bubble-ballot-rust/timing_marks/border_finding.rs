@@ -5,21 +5,27 @@ use crate::{
     interpret::Error,
     scoring::UnitIntervalScore,
     timing_marks::{
-        corner_finding::BallotGridCorners, mark_finding::BallotGridCandidateMarks,
-        util::mark_distances_to_point, Border, CandidateTimingMark, DefaultForGeometry,
+        corner_finding::{BallotGridCorner, BallotGridCorners},
+        mark_finding::BallotGridCandidateMarks,
+        util::{inferred_mark, mark_distances_to_point},
+        Border, CandidateTimingMark, DefaultForGeometry,
     },
 };
 use image::RgbImage;
 use itertools::Itertools;
-use types_rs::geometry::{Segment, SubPixelUnit};
+use types_rs::geometry::{Point, Segment, SubPixelUnit};
 
-/// Represents the borders of a ballot grid. Only the borders parallel to the
-/// scan direction (always left and right at the moment) are recorded; the
-/// top and bottom borders are not used to build the grid.
+/// Represents the borders of a ballot grid. The borders parallel to the scan
+/// direction (always left and right at the moment) are required to build the
+/// grid, so a few marks missing from them are inferred from the surviving marks
+/// and the opposite border. The top and bottom borders only refine it, so they
+/// are recorded only if every one of their marks is found.
 #[derive(Debug, Clone)]
 pub struct BallotGridBorders {
     pub left: GridBorder,
     pub right: GridBorder,
+    pub top: Option<GridBorder>,
+    pub bottom: Option<GridBorder>,
 }
 
 impl BallotGridBorders {
@@ -42,47 +48,102 @@ impl BallotGridBorders {
         let left = GridBorder::find_between_corners(
             vertical_timing_mark_center_to_center_distance,
             maximum_timing_mark_center_distance_error,
+            options.max_consecutive_inferred_timing_marks,
             Border::Left,
-            &candidates
-                .left
-                .iter()
-                .filter(|m| m.scores().mark_score() >= options.min_border_timing_mark_score)
-                .copied()
-                .collect_vec(),
+            &vertical_border_candidates(
+                &candidates.left,
+                (corners.top_left(), corners.bottom_left()),
+                options.min_border_timing_mark_score,
+            ),
             (top_left, bottom_left),
         );
 
         let right = GridBorder::find_between_corners(
             vertical_timing_mark_center_to_center_distance,
             maximum_timing_mark_center_distance_error,
+            options.max_consecutive_inferred_timing_marks,
             Border::Right,
-            &candidates
-                .right
-                .iter()
-                .filter(|m| m.scores().mark_score() >= options.min_border_timing_mark_score)
-                .copied()
-                .collect_vec(),
+            &vertical_border_candidates(
+                &candidates.right,
+                (corners.top_right(), corners.bottom_right()),
+                options.min_border_timing_mark_score,
+            ),
             (top_right, bottom_right),
         );
 
-        let height = geometry.grid_size.height as usize;
-        let validate_mark_count = |gb: GridBorder| {
+        let horizontal_timing_mark_center_to_center_distance =
+            geometry.horizontal_timing_mark_center_to_center_pixel_distance();
+        let maximum_horizontal_timing_mark_center_distance_error =
+            horizontal_timing_mark_center_to_center_distance
+                * options.maximum_vertical_timing_mark_center_distance_error_ratio;
+
+        // The corner marks were found among the left and right candidates, so
+        // make sure they're part of the top and bottom candidates too.
+        let find_horizontal_border =
+            |border: Border,
+             candidates: &[CandidateTimingMark],
+             (start, end): (&CandidateTimingMark, &CandidateTimingMark)| {
+                let candidates = candidates
+                    .iter()
+                    .filter(|m| m.scores().mark_score() >= options.min_border_timing_mark_score)
+                    .filter(|m| m.rect() != start.rect() && m.rect() != end.rect())
+                    .chain([start, end])
+                    .copied()
+                    .collect_vec();
+                GridBorder::find_between_corners(
+                    horizontal_timing_mark_center_to_center_distance,
+                    maximum_horizontal_timing_mark_center_distance_error,
+                    0,
+                    border,
+                    &candidates,
+                    (start, end),
+                )
+            };
+        let top = find_horizontal_border(Border::Top, &candidates.top, (top_left, top_right));
+        let bottom = find_horizontal_border(
+            Border::Bottom,
+            &candidates.bottom,
+            (bottom_left, bottom_right),
+        );
+
+        let validate_mark_count = |gb: GridBorder, expected_count: usize| {
             let actual_count = gb.marks.len();
-            if actual_count == height {
+            if actual_count == expected_count {
                 Ok(gb)
             } else {
                 Err(Error::MissingTimingMarks {
                     reason: format!(
                         "{:?} timing mark border has an unexpected number of marks. Expected {} marks, found {}",
-                        gb.border, height, actual_count
+                        gb.border, expected_count, actual_count
                     ),
                 })
             }
         };
+        let height = geometry.grid_size.height as usize;
+        let width = geometry.grid_size.width as usize;
+
+        let mut left = left.and_then(|gb| validate_mark_count(gb, height))?;
+        let mut right = right.and_then(|gb| validate_mark_count(gb, height))?;
+        left.mark_inferred_corners(corners.top_left(), corners.bottom_left());
+        right.mark_inferred_corners(corners.top_right(), corners.bottom_right());
+
+        let max_inferred_marks =
+            (height as f32 * options.max_inferred_timing_mark_ratio).floor() as usize;
+        for border in [&left, &right] {
+            border.validate_inferred_marks(
+                max_inferred_marks,
+                maximum_timing_mark_center_distance_error,
+            )?;
+        }
+        let left_for_reference = left.clone();
+        left.refine_inferred_marks(&right);
+        right.refine_inferred_marks(&left_for_reference);
 
         Ok(Self {
-            left: left.and_then(validate_mark_count)?,
-            right: right.and_then(validate_mark_count)?,
+            left,
+            right,
+            top: top.and_then(|gb| validate_mark_count(gb, width)).ok(),
+            bottom: bottom.and_then(|gb| validate_mark_count(gb, width)).ok(),
         })
     }
 
@@ -92,6 +153,8 @@ impl BallotGridBorders {
             .marks
             .iter()
             .chain(self.right.marks.iter())
+            .chain(self.top.iter().flat_map(|gb| gb.marks.iter()))
+            .chain(self.bottom.iter().flat_map(|gb| gb.marks.iter()))
             .zip(rainbow())
         {
             draw_filled_rect_mut(canvas, *mark.rect(), color);
@@ -103,17 +166,24 @@ impl BallotGridBorders {
 pub struct GridBorder {
     border: Border,
     marks: Vec<CandidateTimingMark>,
+
+    /// Indexes into `marks` of the marks that were not found and were inferred
+    /// instead, in ascending order.
+    inferred: Vec<usize>,
 }
 
 impl GridBorder {
     /// Find the set of marks along a border by moving along the space between
     /// two corners, finding timing marks that are close enough to the expected
-    /// location to be counted.
+    /// location to be counted. Up to `max_consecutive_inferred_marks` marks in
+    /// a row may be missing, in which case a mark is inferred at the expected
+    /// location and the search continues from there.
     #[allow(clippy::result_large_err)]
     #[allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
     pub fn find_between_corners(
         timing_mark_center_to_center_distance: SubPixelUnit,
         maximum_timing_mark_center_distance_error: SubPixelUnit,
+        max_consecutive_inferred_marks: usize,
         border: Border,
         candidate_timing_marks: &[CandidateTimingMark],
         corners: (&CandidateTimingMark, &CandidateTimingMark),
@@ -136,6 +206,8 @@ impl GridBorder {
 
         let mut last_expected_mark_center = starting_mark.rect().center();
         let mut marks = vec![*starting_mark];
+        let mut inferred = vec![];
+        let mut consecutive_inferred_marks = 0;
 
         loop {
             let next_expected_mark_center = last_expected_mark_center + unit_vector;
@@ -144,15 +216,24 @@ impl GridBorder {
                     .filter(|(distance, _)| *distance <= maximum_timing_mark_center_distance_error)
                     .min_by(|(a, _), (b, _)| a.total_cmp(b))
             else {
-                return Err(Error::MissingTimingMarks {
+                if consecutive_inferred_marks == max_consecutive_inferred_marks {
+                    return Err(Error::MissingTimingMarks {
                         reason: format!(
                             "Unable to find mark along {border:?} border at index {index}; no marks close enough?",
                             index = marks.len()
                         ),
                     });
+                }
+
+                consecutive_inferred_marks += 1;
+                inferred.push(marks.len());
+                marks.push(inferred_mark(starting_mark, next_expected_mark_center));
+                last_expected_mark_center = next_expected_mark_center;
+                continue;
             };
 
             marks.push(*closest_mark_to_expected_center);
+            consecutive_inferred_marks = 0;
 
             if closest_mark_to_expected_center == ending_mark {
                 break;
@@ -161,7 +242,117 @@ impl GridBorder {
             last_expected_mark_center = closest_mark_to_expected_center.rect().center();
         }
 
-        Ok(Self { border, marks })
+        Ok(Self {
+            border,
+            marks,
+            inferred,
+        })
+    }
+
+    /// Records the corner marks at either end of this border that were
+    /// inferred from the other corners.
+    fn mark_inferred_corners(&mut self, start: &BallotGridCorner, end: &BallotGridCorner) {
+        if start.is_inferred() {
+            self.inferred.insert(0, 0);
+        }
+        if end.is_inferred() {
+            self.inferred.push(self.marks.len() - 1);
+        }
+    }
+
+    /// Checks that a border with inferred marks can be trusted: no more than
+    /// `max_inferred_marks` may be inferred, and the marks that were found must
+    /// all be within `max_distance_from_line` of the line that best fits them.
+    #[allow(clippy::result_large_err)]
+    fn validate_inferred_marks(
+        &self,
+        max_inferred_marks: usize,
+        max_distance_from_line: SubPixelUnit,
+    ) -> Result<(), Error> {
+        if self.inferred.is_empty() {
+            return Ok(());
+        }
+
+        if self.inferred.len() > max_inferred_marks {
+            return Err(Error::MissingTimingMarks {
+                reason: format!(
+                    "{:?} timing mark border is missing too many marks. Expected at most {} missing marks, found {}",
+                    self.border,
+                    max_inferred_marks,
+                    self.inferred.len()
+                ),
+            });
+        }
+
+        let found_centers = self
+            .found_marks()
+            .map(|(_, mark)| mark.rect().center())
+            .collect_vec();
+        let max_distance = max_distance_from_best_fit_line(&found_centers);
+        if max_distance > max_distance_from_line {
+            return Err(Error::MissingTimingMarks {
+                reason: format!(
+                    "{:?} timing mark border marks do not fit a line well enough to infer missing marks. Expected all marks within {max_distance_from_line:.1}px of the line, found one {max_distance:.1}px away",
+                    self.border
+                ),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Moves each inferred mark to where the nearest found marks on either
+    /// side of it put it. If the opposite border has found marks in the same
+    /// rows, their spacing is used to place the mark between its neighbors, so
+    /// that uneven feed speed is followed; otherwise the marks are spaced
+    /// evenly.
+    fn refine_inferred_marks(&mut self, opposite: &Self) {
+        for &index in &self.inferred {
+            let (Some(before), Some(after)) = (
+                (0..index).rev().find(|i| !self.inferred.contains(i)),
+                (index + 1..self.marks.len()).find(|i| !self.inferred.contains(i)),
+            ) else {
+                continue;
+            };
+
+            let opposite_center = |i: usize| {
+                (!opposite.inferred.contains(&i))
+                    .then(|| opposite.marks.get(i).map(|mark| mark.rect().center()))
+                    .flatten()
+            };
+            let fraction = match (
+                opposite_center(before),
+                opposite_center(index),
+                opposite_center(after),
+            ) {
+                (Some(start), Some(point), Some(end)) => fraction_along(start, end, point),
+                _ => (index - before) as SubPixelUnit / (after - before) as SubPixelUnit,
+            };
+
+            let start = self.marks[before].rect().center();
+            let end = self.marks[after].rect().center();
+            let center = Point::new(
+                (end.x - start.x).mul_add(fraction, start.x),
+                (end.y - start.y).mul_add(fraction, start.y),
+            );
+            self.marks[index] = inferred_mark(&self.marks[before], center);
+        }
+    }
+
+    /// The marks along this border that were found rather than inferred, along
+    /// with their index.
+    fn found_marks(&self) -> impl Iterator<Item = (usize, &CandidateTimingMark)> {
+        self.marks
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.inferred.contains(index))
+    }
+
+    /// Indexes of the marks along this border that were inferred rather than
+    /// found.
+    #[must_use]
+    pub fn inferred(&self) -> &[usize] {
+        &self.inferred
     }
 
     #[allow(dead_code)]
@@ -176,9 +367,95 @@ impl GridBorder {
     }
 }
 
+/// The candidates for a left or right border that are good enough to be part
+/// of it. An inferred corner mark usually has no ink, so it would never pass
+/// the score filter; it's added back so the border can be walked to or from
+/// it. A corner can also be inferred when its mark is printed but the mark
+/// next to it in the row isn't, as on the back of an `AccuVote` card, so any
+/// candidate overlapping an inferred corner mark is replaced by it.
+fn vertical_border_candidates(
+    candidates: &[CandidateTimingMark],
+    (start, end): (&BallotGridCorner, &BallotGridCorner),
+    min_border_timing_mark_score: UnitIntervalScore,
+) -> Vec<CandidateTimingMark> {
+    let inferred_corner_marks = [start, end]
+        .into_iter()
+        .filter(|corner| corner.is_inferred())
+        .map(|corner| *corner.best_corner_grouping().corner_mark())
+        .collect_vec();
+    candidates
+        .iter()
+        .filter(|m| m.scores().mark_score() >= min_border_timing_mark_score)
+        .filter(|m| {
+            !inferred_corner_marks
+                .iter()
+                .any(|corner_mark| corner_mark.rect().overlaps(m.rect()))
+        })
+        .copied()
+        .chain(inferred_corner_marks.iter().copied())
+        .collect_vec()
+}
+
+/// How far along the segment from `start` to `end` the projection of `point`
+/// falls, clamped to the segment.
+fn fraction_along(
+    start: Point<SubPixelUnit>,
+    end: Point<SubPixelUnit>,
+    point: Point<SubPixelUnit>,
+) -> SubPixelUnit {
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let length_squared = dx.mul_add(dx, dy * dy);
+    if length_squared == 0.0 {
+        return 0.0;
+    }
+    ((point.x - start.x).mul_add(dx, (point.y - start.y) * dy) / length_squared).clamp(0.0, 1.0)
+}
+
+/// Fits a line to `points` by total least squares and returns the largest
+/// distance from it to any of the points.
+fn max_distance_from_best_fit_line(points: &[Point<SubPixelUnit>]) -> SubPixelUnit {
+    if points.len() < 2 {
+        return 0.0;
+    }
+
+    let count = points.len() as SubPixelUnit;
+    let mean = points
+        .iter()
+        .fold(Point::new(0.0, 0.0), |sum, point| sum + *point);
+    let mean = Point::new(mean.x / count, mean.y / count);
+    let (sxx, syy, sxy) = points
+        .iter()
+        .fold((0.0, 0.0, 0.0), |(sxx, syy, sxy), point| {
+            let (dx, dy) = (point.x - mean.x, point.y - mean.y);
+            (
+                dx.mul_add(dx, sxx),
+                dy.mul_add(dy, syy),
+                dx.mul_add(dy, sxy),
+            )
+        });
+    let angle = 0.5 * (2.0 * sxy).atan2(sxx - syy);
+    let (sin, cos) = angle.sin_cos();
+
+    points
+        .iter()
+        .map(|point| ((point.x - mean.x) * sin - (point.y - mean.y) * cos).abs())
+        .fold(0.0, SubPixelUnit::max)
+}
+
 pub struct Options {
+    /// How far a mark may be from where the previous mark along a border
+    /// predicts it, as a ratio of the timing mark center-to-center distance.
+    /// Applies to both the vertical and horizontal borders.
     pub maximum_vertical_timing_mark_center_distance_error_ratio: f32,
     pub min_border_timing_mark_score: UnitIntervalScore,
+
+    /// How many marks in a row may be missing from the left or right border,
+    /// e.g. because of a tear or a scribble, and still be inferred.
+    pub max_consecutive_inferred_timing_marks: usize,
+
+    /// The largest fraction of the left or right border's marks that may be
+    /// inferred.
+    pub max_inferred_timing_mark_ratio: f32,
 }
 
 impl DefaultForGeometry for Options {
@@ -186,6 +463,39 @@ impl DefaultForGeometry for Options {
         Self {
             maximum_vertical_timing_mark_center_distance_error_ratio: 0.5,
             min_border_timing_mark_score: UnitIntervalScore(0.8),
+            max_consecutive_inferred_timing_marks: 3,
+            max_inferred_timing_mark_ratio: 0.2,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fraction_along() {
+        let start = Point::new(10.0, 0.0);
+        let end = Point::new(10.0, 100.0);
+        assert!((fraction_along(start, end, Point::new(12.0, 25.0)) - 0.25).abs() < 1e-6);
+        assert!((fraction_along(start, end, Point::new(10.0, -5.0))).abs() < 1e-6);
+        assert!((fraction_along(start, end, start)).abs() < 1e-6);
+        assert!((fraction_along(start, start, end)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_max_distance_from_best_fit_line() {
+        // a slightly skewed column of marks fits its line exactly
+        let column = (0..10)
+            .map(|i| Point::new(0.05f32.mul_add(i as f32, 20.0), 50.0 * i as f32))
+            .collect_vec();
+        assert!(max_distance_from_best_fit_line(&column) < 1e-3);
+
+        let mut bent = column;
+        bent[5].x += 12.0;
+        let distance = max_distance_from_best_fit_line(&bent);
+        assert!(distance > 9.0 && distance < 12.0, "{distance}");
+
+        assert!(max_distance_from_best_fit_line(&[Point::new(1.0, 1.0)]).abs() < f32::EPSILON);
+    }
+}
@@ -1,17 +1,17 @@
-use image::RgbImage;
+use image::{Rgb, RgbImage};
 use itertools::Itertools;
-use types_rs::geometry::{Point, Size};
+use types_rs::geometry::{Point, Segment, Size};
 
 use crate::{
     ballot_card::Geometry,
-    draw_utils::draw_filled_rect_mut,
+    draw_utils::{draw_filled_rect_mut, draw_hollow_rect_mut},
     image_utils::rainbow,
     impl_cornerwise,
     interpret::Error,
     scoring::UnitIntervalScore,
     timing_marks::{
         mark_finding::BallotGridCandidateMarks,
-        util::{mark_distances_to_point, CornerWise, EdgeWise},
+        util::{inferred_mark, mark_distances_to_point, CornerWise},
         CandidateTimingMark, Corner, DefaultForGeometry,
     },
 };
@@ -125,58 +125,50 @@ impl BallotGridCorners {
                 Point::new(0.0, -vertical_timing_mark_center_to_center_distance),
             );
 
-        let [top_left_result, top_right_result, bottom_left_result, bottom_right_result] = [
-            (top_left_corner_candidates.clone(), Corner::TopLeft),
-            (top_right_corner_candidates.clone(), Corner::TopRight),
-            (bottom_left_corner_candidates.clone(), Corner::BottomLeft),
-            (bottom_right_corner_candidates.clone(), Corner::BottomRight),
-        ]
-        .map_edgewise(|(corner_candidates, corner)| {
-            corner_candidates
-                .into_iter()
-                .find(|grouping| {
-                    grouping.iter().all(|mark| {
-                        mark.scores().mark_score() >= options.min_corner_timing_mark_score
-                    })
-                })
-                .ok_or_else(|| Error::MissingTimingMarks {
-                    reason: format!("Could not find corner: {corner:?}"),
-                })
-        });
-
-        let (
-            best_top_left_grouping,
-            best_top_right_grouping,
-            best_bottom_left_grouping,
-            best_bottom_right_grouping,
-        ) = (
-            top_left_result?,
-            top_right_result?,
-            bottom_left_result?,
-            bottom_right_result?,
-        );
+        let corners = choose_best_corners(
+            [
+                top_left_corner_candidates,
+                top_right_corner_candidates,
+                bottom_left_corner_candidates,
+                bottom_right_corner_candidates,
+            ],
+            image_size,
+            geometry,
+            options,
+        )?;
 
-        Ok(Self {
-            top_left: BallotGridCorner {
-                best_corner_grouping: best_top_left_grouping,
-                all_possible_corner_groupings: top_left_corner_candidates,
-            },
-            top_right: BallotGridCorner {
-                best_corner_grouping: best_top_right_grouping,
-                all_possible_corner_groupings: top_right_corner_candidates,
-            },
-            bottom_left: BallotGridCorner {
-                best_corner_grouping: best_bottom_left_grouping,
-                all_possible_corner_groupings: bottom_left_corner_candidates,
-            },
-            bottom_right: BallotGridCorner {
-                best_corner_grouping: best_bottom_right_grouping,
-                all_possible_corner_groupings: bottom_right_corner_candidates,
-            },
-        })
+        Ok(Self::from_array(corners))
+    }
+
+    /// The corner that was inferred from the other three rather than found,
+    /// e.g. because it was folded over or torn off.
+    #[must_use]
+    pub fn inferred_corner(&self) -> Option<Corner> {
+        CORNERS
+            .into_iter()
+            .zip([
+                &self.top_left,
+                &self.top_right,
+                &self.bottom_left,
+                &self.bottom_right,
+            ])
+            .find_map(|(corner, grid_corner)| grid_corner.inferred.then_some(corner))
     }
 
     pub fn debug_draw(&self, canvas: &mut RgbImage) {
+        for grid_corner in [
+            &self.top_left,
+            &self.top_right,
+            &self.bottom_left,
+            &self.bottom_right,
+        ] {
+            if grid_corner.inferred {
+                for mark in grid_corner.best_corner_grouping.iter() {
+                    draw_hollow_rect_mut(canvas, *mark.rect(), INFERRED_CORNER_COLOR);
+                }
+            }
+        }
+
         for (grouping, color) in self
             .top_left
             .all_possible_corner_groupings()
@@ -193,10 +185,82 @@ impl BallotGridCorners {
     }
 }
 
+/// Picks the best grouping for each corner from its candidates, both ordered
+/// cornerwise. If exactly one corner has no grouping good enough to use, it is
+/// inferred from the other three.
+#[allow(clippy::result_large_err)]
+fn choose_best_corners(
+    all_corner_candidates: [Vec<CandidateCornerMarkGrouping>; 4],
+    image_size: Size<u32>,
+    geometry: &Geometry,
+    options: &Options,
+) -> Result<[BallotGridCorner; 4], Error> {
+    let best_groupings = all_corner_candidates.each_ref().map(|corner_candidates| {
+        corner_candidates
+            .iter()
+            .find(|grouping| {
+                grouping
+                    .iter()
+                    .all(|mark| mark.scores().mark_score() >= options.min_corner_timing_mark_score)
+            })
+            .cloned()
+    });
+
+    let missing_corners = CORNERS
+        .into_iter()
+        .zip(&best_groupings)
+        .filter_map(|(corner, grouping)| grouping.is_none().then_some(corner))
+        .collect_vec();
+    let inferred_grouping = match missing_corners.as_slice() {
+        [] => None,
+        [corner] => Some(CandidateCornerMarkGrouping::infer_from_other_corners(
+            *corner,
+            &best_groupings,
+            image_size,
+            geometry,
+        )?),
+        [corner, ..] => {
+            return Err(Error::MissingTimingMarks {
+                reason: format!("Could not find corner: {corner:?}"),
+            })
+        }
+    };
+
+    let mut best_groupings = best_groupings.into_iter();
+    Ok(all_corner_candidates.map(|all_possible_corner_groupings| {
+        let (best_corner_grouping, inferred) =
+            match (best_groupings.next().flatten(), &inferred_grouping) {
+                (Some(grouping), _) => (grouping, false),
+                (None, Some(inferred_grouping)) => (inferred_grouping.clone(), true),
+                (None, None) => unreachable!("every missing corner has an inferred grouping"),
+            };
+        BallotGridCorner {
+            best_corner_grouping,
+            all_possible_corner_groupings,
+            inferred,
+        }
+    }))
+}
+
+/// The corners in the order used by [`CornerWise`]: TL, TR, BL, BR.
+const CORNERS: [Corner; 4] = [
+    Corner::TopLeft,
+    Corner::TopRight,
+    Corner::BottomLeft,
+    Corner::BottomRight,
+];
+
+const INFERRED_CORNER_COLOR: Rgb<u8> = Rgb([255, 0, 255]);
+
 #[derive(Debug, Clone)]
 pub struct BallotGridCorner {
     best_corner_grouping: CandidateCornerMarkGrouping,
     all_possible_corner_groupings: Vec<CandidateCornerMarkGrouping>,
+
+    /// Whether `best_corner_grouping` was inferred from the other three
+    /// corners because none of the candidates for this corner were good
+    /// enough.
+    inferred: bool,
 }
 
 impl BallotGridCorner {
@@ -209,6 +273,11 @@ impl BallotGridCorner {
     pub fn all_possible_corner_groupings(&self) -> &[CandidateCornerMarkGrouping] {
         &self.all_possible_corner_groupings
     }
+
+    #[must_use]
+    pub const fn is_inferred(&self) -> bool {
+        self.inferred
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -269,6 +338,70 @@ impl CandidateCornerMarkGrouping {
     }
 }
 
+impl CandidateCornerMarkGrouping {
+    /// Builds the grouping for the missing `corner` by completing the
+    /// parallelogram formed by the other three corners' marks, i.e. by moving
+    /// from the opposite corner along both borders. The row and column marks
+    /// are placed one timing mark apart along the borders towards the
+    /// neighboring corners. `groupings` is ordered cornerwise and must have a
+    /// grouping for every corner but `corner`.
+    #[allow(clippy::result_large_err)]
+    fn infer_from_other_corners(
+        corner: Corner,
+        groupings: &[Option<CandidateCornerMarkGrouping>; 4],
+        image_size: Size<u32>,
+        geometry: &Geometry,
+    ) -> Result<Self, Error> {
+        let index = CORNERS
+            .iter()
+            .position(|c| *c == corner)
+            .unwrap_or_default();
+        // Flipping the low bit of a cornerwise index moves across the
+        // top/bottom border, flipping the high bit moves along the left/right
+        // border.
+        let (Some(horizontal_neighbor), Some(vertical_neighbor), Some(opposite)) = (
+            &groupings[index ^ 1],
+            &groupings[index ^ 2],
+            &groupings[index ^ 3],
+        ) else {
+            return Err(Error::MissingTimingMarks {
+                reason: format!("Could not find corner: {corner:?}"),
+            });
+        };
+
+        let horizontal_neighbor_center = horizontal_neighbor.corner.rect().center();
+        let vertical_neighbor_center = vertical_neighbor.corner.rect().center();
+        let opposite_center = opposite.corner.rect().center();
+        let center = Point::new(
+            horizontal_neighbor_center.x + vertical_neighbor_center.x - opposite_center.x,
+            horizontal_neighbor_center.y + vertical_neighbor_center.y - opposite_center.y,
+        );
+
+        if !(0.0..image_size.width as f32).contains(&center.x)
+            || !(0.0..image_size.height as f32).contains(&center.y)
+        {
+            return Err(Error::MissingTimingMarks {
+                reason: format!(
+                    "Could not find corner: {corner:?}; the other corners place it outside the image"
+                ),
+            });
+        }
+
+        let row_center = Segment::new(center, horizontal_neighbor_center)
+            .with_length(geometry.horizontal_timing_mark_center_to_center_pixel_distance())
+            .end;
+        let column_center = Segment::new(center, vertical_neighbor_center)
+            .with_length(geometry.vertical_timing_mark_center_to_center_pixel_distance())
+            .end;
+
+        Ok(Self {
+            corner: inferred_mark(&opposite.corner, center),
+            row: inferred_mark(&opposite.row, row_center),
+            column: inferred_mark(&opposite.column, column_center),
+        })
+    }
+}
+
 impl IntoIterator for CandidateCornerMarkGrouping {
     type Item = CandidateTimingMark;
     type IntoIter = core::array::IntoIter<Self::Item, 3>;
@@ -1,22 +1,26 @@
 use ab_glyph::PxScale;
 use image::RgbImage;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use types_rs::pair::Pair;
 
 use crate::{
     ballot_card::{BallotImage, Geometry},
     debug::monospace_font,
     draw_utils::{draw_filled_rect_mut, draw_text_mut, text_size},
     image_utils::rainbow,
-    timing_marks::{shape_finding::BallotGridBorderShapes, CandidateTimingMark},
+    impl_edgewise,
+    timing_marks::{shape_finding::BallotGridBorderShapes, util::EdgeWise, CandidateTimingMark},
 };
 
 /// Represents the candidate timing marks found on a ballot grid.
 pub struct BallotGridCandidateMarks {
     pub left: Vec<CandidateTimingMark>,
     pub right: Vec<CandidateTimingMark>,
+    pub top: Vec<CandidateTimingMark>,
+    pub bottom: Vec<CandidateTimingMark>,
 }
 
+impl_edgewise!(BallotGridCandidateMarks, Vec<CandidateTimingMark>);
+
 impl BallotGridCandidateMarks {
     /// Converts a set of ballot grid border shapes into a set of candidate
     /// timing marks. This operation cannot fail because it doesn't do any
@@ -25,20 +29,16 @@ impl BallotGridCandidateMarks {
     pub fn from_shapes(
         ballot_image: &BallotImage,
         geometry: &Geometry,
-        BallotGridBorderShapes { left, right }: BallotGridBorderShapes,
+        shapes: BallotGridBorderShapes,
     ) -> Self {
         // Since we're scoring the shapes by examining every pixel in every shape,
         // we use parallel processing to speed up the operation.
-        let (left, right) = Pair::new(left, right)
-            .par_map(|shapes| {
-                shapes
-                    .par_iter()
-                    .map(|shape| shape.to_candidate_timing_mark(ballot_image, geometry))
-                    .collect()
-            })
-            .into();
-
-        Self { left, right }
+        shapes.par_map_edgewise(|shapes| {
+            shapes
+                .par_iter()
+                .map(|shape| shape.to_candidate_timing_mark(ballot_image, geometry))
+                .collect()
+        })
     }
 
     pub fn debug_draw(&self, canvas: &mut RgbImage) {
@@ -72,6 +72,11 @@ impl BallotGridCandidateMarks {
             );
         }
 
+        // Top and bottom edges - no text, as there's no room between marks
+        for (mark, color) in self.top.iter().chain(self.bottom.iter()).zip(rainbow()) {
+            draw_filled_rect_mut(canvas, *mark.rect(), color);
+        }
+
         // Right edge - text on the left (inside the grid)
         for (mark, color) in self.right.iter().zip(rainbow()) {
             let rect = mark.rect();
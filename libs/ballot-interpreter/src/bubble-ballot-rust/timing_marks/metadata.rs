@@ -0,0 +1,431 @@
+//! Decodes the metadata `AccuVote` ballots encode in their bottom row of timing
+//! marks, for ballots printed without a QR code.
+//!
+//! The bottom row of an `AccuVote` ballot has a timing mark in each corner and
+//! 32 positions between them, each of which is either printed or left blank.
+//! Reading right to left, the printed positions are the bits of the page
+//! metadata, least significant first. The front of a card says which precinct
+//! and ballot style (the `AccuVote` "card number") it is for, and the back
+//! carries the election date and type followed by a fixed ender code, so the
+//! two sides can always be told apart.
+
+use serde::Serialize;
+use types_rs::{
+    ballot_card::PageNumber,
+    election::{BallotStyleId, Election, PrecinctId},
+    geometry::{PixelUnit, Rect, SubGridUnit},
+    pair::Pair,
+};
+
+use crate::{
+    ballot_card::{BallotImage, Orientation},
+    timing_marks::{scoring::CandidateTimingMark, TimingMarks},
+};
+
+/// The number of positions between the bottom corner timing marks.
+pub const BITS: usize = 32;
+
+/// The code ending the back of every card, from bit 21 to bit 31.
+const ENDER_CODE: [bool; 11] = [
+    false, true, true, true, true, false, true, true, true, true, false,
+];
+
+/// How much of a position must be dark for it to count as printed.
+const MIN_PRINTED_MARK_SCORE: f32 = 0.6;
+
+/// How much of a position may be dark for it to still count as blank. Anything
+/// between this and [`MIN_PRINTED_MARK_SCORE`] is too smudged to trust.
+const MAX_BLANK_MARK_SCORE: f32 = 0.3;
+
+/// Metadata decoded from the bottom row of timing marks on one side of an
+/// `AccuVote` card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "side", rename_all = "camelCase")]
+pub enum TimingMarkMetadata {
+    Front(FrontMetadata),
+    Back(BackMetadata),
+}
+
+/// Metadata encoded on the front of an `AccuVote` card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrontMetadata {
+    /// The number of set bits from bit 2 on, modulo 4. Bits 0-1.
+    pub mod_4_checksum: u8,
+
+    /// Bits 2-14.
+    pub batch_or_precinct_number: u16,
+
+    /// Identifies the ballot style. Bits 15-27.
+    pub card_number: u16,
+
+    /// Which sheet of a multi-sheet ballot this is, counting from 0. Bits
+    /// 28-30, followed by a start bit that is always set.
+    pub sequence_number: u8,
+}
+
+/// Metadata encoded on the back of an `AccuVote` card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackMetadata {
+    /// Bits 0-4.
+    pub election_day: u8,
+
+    /// Bits 5-8.
+    pub election_month: u8,
+
+    /// The last two digits of the year. Bits 9-15.
+    pub election_year: u8,
+
+    /// A letter, e.g. `G` for a general election. Bits 16-20.
+    pub election_type: char,
+}
+
+/// Where a page of an `AccuVote` card belongs in the election, as mapped from
+/// its [`FrontMetadata`]. Unlike a QR code, the card carries no ballot hash,
+/// test mode flag or ballot type, so there are none here for callers to
+/// mistake for decoded values; they must be checked some other way.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappedMetadata {
+    pub precinct_id: PrecinctId,
+    pub ballot_style_id: BallotStyleId,
+    pub page_number: PageNumber,
+}
+
+/// The letters an election type can be, i.e. those that fit in its 5 bits.
+const ELECTION_TYPES: std::ops::RangeInclusive<u8> = b'A'..=b'A' + 31;
+
+/// Reads the value of `bits`, least significant first.
+fn read_value(bits: &[bool]) -> u16 {
+    bits.iter()
+        .rev()
+        .fold(0, |value, &bit| (value << 1) | u16::from(bit))
+}
+
+/// Writes `value` to `bits`, least significant first, dropping any bits that
+/// don't fit.
+fn write_value(bits: &mut [bool], value: u16) {
+    for (index, bit) in bits.iter_mut().enumerate() {
+        *bit = (value >> index) & 1 == 1;
+    }
+}
+
+/// Counts the set bits covered by the checksum, modulo 4.
+fn compute_mod_4_checksum(bits: &[bool; BITS]) -> u8 {
+    (bits[2..].iter().filter(|&&bit| bit).count() % 4) as u8
+}
+
+impl TimingMarkMetadata {
+    /// Decodes the 32 bits read from a bottom row of timing marks, in order
+    /// from right to left.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the bits are neither a valid front nor a valid back, e.g.
+    /// because the checksum doesn't match.
+    pub fn decode(bits: &[bool; BITS]) -> Result<Self, String> {
+        if bits[21..] == ENDER_CODE {
+            return Ok(Self::Back(BackMetadata {
+                election_day: read_value(&bits[0..5]) as u8,
+                election_month: read_value(&bits[5..9]) as u8,
+                election_year: read_value(&bits[9..16]) as u8,
+                election_type: char::from(b'A' + read_value(&bits[16..21]) as u8),
+            }));
+        }
+
+        if !bits[31] {
+            return Err("neither the start bit nor the ender code is present".to_owned());
+        }
+
+        let mod_4_checksum = read_value(&bits[0..2]) as u8;
+        let computed_mod_4_checksum = compute_mod_4_checksum(bits);
+        if mod_4_checksum != computed_mod_4_checksum {
+            return Err(format!(
+                "checksum mismatch: encoded {mod_4_checksum}, computed {computed_mod_4_checksum}"
+            ));
+        }
+
+        Ok(Self::Front(FrontMetadata {
+            mod_4_checksum,
+            batch_or_precinct_number: read_value(&bits[2..15]),
+            card_number: read_value(&bits[15..28]),
+            sequence_number: read_value(&bits[28..31]) as u8,
+        }))
+    }
+
+    /// Encodes the metadata as the 32 bits of a bottom row of timing marks, in
+    /// order from right to left. The checksum of a front is computed rather
+    /// than copied.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the election type of a back isn't one of the 32 characters
+    /// from `A` that fit in its bits.
+    pub fn encode(&self) -> Result<[bool; BITS], String> {
+        let mut bits = [false; BITS];
+        match self {
+            Self::Front(front) => {
+                write_value(&mut bits[2..15], front.batch_or_precinct_number);
+                write_value(&mut bits[15..28], front.card_number);
+                write_value(&mut bits[28..31], u16::from(front.sequence_number));
+                bits[31] = true;
+                let mod_4_checksum = compute_mod_4_checksum(&bits);
+                write_value(&mut bits[0..2], u16::from(mod_4_checksum));
+            }
+            Self::Back(back) => {
+                let election_type = u8::try_from(back.election_type)
+                    .ok()
+                    .filter(|election_type| ELECTION_TYPES.contains(election_type))
+                    .ok_or_else(|| {
+                        format!(
+                            "election type {:?} is not a character from 'A' to {:?}",
+                            back.election_type,
+                            char::from(*ELECTION_TYPES.end())
+                        )
+                    })?;
+                write_value(&mut bits[0..5], u16::from(back.election_day));
+                write_value(&mut bits[5..9], u16::from(back.election_month));
+                write_value(&mut bits[9..16], u16::from(back.election_year));
+                write_value(&mut bits[16..21], u16::from(election_type - b'A'));
+                bits[21..].copy_from_slice(&ENDER_CODE);
+            }
+        }
+        Ok(bits)
+    }
+
+    /// Reads and decodes the bottom row of timing marks on a page, returning
+    /// the metadata along with the page orientation. The grid's top row is
+    /// checked too, since that's where the bottom row is on a page scanned
+    /// upside down.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the row can't be read or decoded in either orientation.
+    pub fn read(
+        ballot_image: &BallotImage,
+        timing_marks: &TimingMarks,
+    ) -> Result<(Self, Orientation), String> {
+        let portrait = read_bottom_row_bits(ballot_image, timing_marks, Orientation::Portrait)
+            .and_then(|bits| Self::decode(&bits));
+        let portrait_reversed =
+            read_bottom_row_bits(ballot_image, timing_marks, Orientation::PortraitReversed)
+                .and_then(|bits| Self::decode(&bits));
+        match (portrait, portrait_reversed) {
+            (Ok(metadata), Err(_)) => Ok((metadata, Orientation::Portrait)),
+            (Err(_), Ok(metadata)) => Ok((metadata, Orientation::PortraitReversed)),
+            (Ok(_), Ok(_)) => {
+                Err("both the top and bottom rows of timing marks decode as metadata".to_owned())
+            }
+            (Err(bottom_error), Err(top_error)) => Err(format!(
+                "bottom row: {bottom_error}; top row (if upside down): {top_error}"
+            )),
+        }
+    }
+}
+
+/// Reads which positions between the corners of the bottom row of timing
+/// marks are printed, in order from right to left as seen with the page in
+/// `orientation`.
+fn read_bottom_row_bits(
+    ballot_image: &BallotImage,
+    timing_marks: &TimingMarks,
+    orientation: Orientation,
+) -> Result<[bool; BITS], String> {
+    let geometry = &timing_marks.geometry;
+    let grid_width = geometry.grid_size.width as usize;
+    if grid_width != BITS + 2 {
+        return Err(format!(
+            "expected {} timing mark columns, found {grid_width}",
+            BITS + 2
+        ));
+    }
+
+    let last_row = (geometry.grid_size.height - 1) as SubGridUnit;
+    let mut bits = [false; BITS];
+    for (index, bit) in bits.iter_mut().enumerate() {
+        // Upside down, the bottom row is the image's top row, and reading it
+        // right to left means reading the image left to right.
+        let (column, row) = match orientation {
+            Orientation::Portrait => ((grid_width - 2 - index) as SubGridUnit, last_row),
+            Orientation::PortraitReversed => ((index + 1) as SubGridUnit, 0.0),
+        };
+        let center = timing_marks
+            .point_for_location(column, row)
+            .ok_or_else(|| format!("bit {index} is outside the timing mark grid"))?;
+        let width = geometry.timing_mark_width_pixels();
+        let height = geometry.timing_mark_height_pixels();
+        let rect = Rect::new(
+            (center.x - width / 2.0).round() as i32,
+            (center.y - height / 2.0).round() as i32,
+            width.round() as PixelUnit,
+            height.round() as PixelUnit,
+        );
+        let mark_score = CandidateTimingMark::scored(ballot_image, geometry, rect)
+            .scores()
+            .mark_score()
+            .0;
+        *bit = if mark_score >= MIN_PRINTED_MARK_SCORE {
+            true
+        } else if mark_score <= MAX_BLANK_MARK_SCORE {
+            false
+        } else {
+            return Err(format!(
+                "bit {index} is neither clearly printed nor clearly blank \
+                 (mark score {mark_score:.2})"
+            ));
+        };
+    }
+    Ok(bits)
+}
+
+/// Maps the metadata read from both sides of an `AccuVote` card to the
+/// election. The precinct and ballot style are the ones whose IDs are the
+/// front's precinct and card numbers, and the ballot style must be used in
+/// that precinct.
+///
+/// # Errors
+///
+/// Fails unless exactly one side is a front, or if the precinct or ballot
+/// style isn't in the election.
+pub fn map_to_election(
+    election: &Election,
+    metadata: Pair<&TimingMarkMetadata>,
+) -> Result<Pair<MappedMetadata>, String> {
+    let (front, front_is_first) = match metadata.into() {
+        (TimingMarkMetadata::Front(front), TimingMarkMetadata::Back(_)) => (front, true),
+        (TimingMarkMetadata::Back(_), TimingMarkMetadata::Front(front)) => (front, false),
+        (TimingMarkMetadata::Front(_), TimingMarkMetadata::Front(_)) => {
+            return Err("both sides decode as the front of a card".to_owned())
+        }
+        (TimingMarkMetadata::Back(_), TimingMarkMetadata::Back(_)) => {
+            return Err("both sides decode as the back of a card".to_owned())
+        }
+    };
+
+    let front_metadata = front.map_to_election(election)?;
+    let back_metadata = MappedMetadata {
+        page_number: front_metadata.page_number.opposite(),
+        ..front_metadata.clone()
+    };
+    Ok(if front_is_first {
+        Pair::new(front_metadata, back_metadata)
+    } else {
+        Pair::new(back_metadata, front_metadata)
+    })
+}
+
+impl FrontMetadata {
+    /// Maps the front of a card to the election. See [`map_to_election`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the precinct or ballot style isn't in the election.
+    pub fn map_to_election(&self, election: &Election) -> Result<MappedMetadata, String> {
+        let precinct_number = self.batch_or_precinct_number.to_string();
+        let precinct = election
+            .precincts
+            .iter()
+            .find(|precinct| precinct.id.to_string() == precinct_number)
+            .ok_or_else(|| format!("no precinct with ID {precinct_number}"))?;
+
+        let card_number = self.card_number.to_string();
+        let ballot_style = election
+            .ballot_styles
+            .iter()
+            .find(|ballot_style| ballot_style.id.to_string() == card_number)
+            .ok_or_else(|| format!("no ballot style with ID {card_number}"))?;
+        if !ballot_style.precincts.contains(&precinct.id) {
+            return Err(format!(
+                "ballot style {card_number} is not used in precinct {precinct_number}"
+            ));
+        }
+
+        let page_number = PageNumber::new(self.sequence_number * 2 + 1)
+            .ok_or_else(|| format!("invalid sequence number {}", self.sequence_number))?;
+        Ok(MappedMetadata {
+            precinct_id: precinct.id.clone(),
+            ballot_style_id: ballot_style.id.clone(),
+            page_number,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_front() {
+        let front = TimingMarkMetadata::Front(FrontMetadata {
+            mod_4_checksum: 0,
+            batch_or_precinct_number: 23,
+            card_number: 12,
+            sequence_number: 1,
+        });
+        let mut bits = front.encode().unwrap();
+        let set_bits = bits[2..].iter().filter(|&&bit| bit).count() as u8;
+        assert_eq!(
+            TimingMarkMetadata::decode(&bits),
+            Ok(TimingMarkMetadata::Front(FrontMetadata {
+                mod_4_checksum: set_bits % 4,
+                batch_or_precinct_number: 23,
+                card_number: 12,
+                sequence_number: 1,
+            }))
+        );
+
+        // Flipping any bit of the precinct number breaks the checksum.
+        bits[5] = !bits[5];
+        assert!(TimingMarkMetadata::decode(&bits)
+            .unwrap_err()
+            .contains("checksum mismatch"));
+    }
+
+    #[test]
+    fn test_decode_back() {
+        let back = TimingMarkMetadata::Back(BackMetadata {
+            election_day: 3,
+            election_month: 11,
+            election_year: 26,
+            election_type: 'G',
+        });
+        let bits = back.encode().unwrap();
+        assert_eq!(bits[21..], ENDER_CODE);
+        assert_eq!(TimingMarkMetadata::decode(&bits), Ok(back));
+    }
+
+    #[test]
+    fn test_encode_election_type_out_of_range() {
+        for (election_type, is_valid) in [('A', true), ('`', true), ('a', false), ('@', false)] {
+            let back = TimingMarkMetadata::Back(BackMetadata {
+                election_day: 3,
+                election_month: 11,
+                election_year: 26,
+                election_type,
+            });
+            assert_eq!(back.encode().is_ok(), is_valid, "{election_type:?}");
+            if is_valid {
+                assert_eq!(
+                    TimingMarkMetadata::decode(&back.encode().unwrap()),
+                    Ok(back)
+                );
+            }
+        }
+        assert!(TimingMarkMetadata::Back(BackMetadata {
+            election_day: 3,
+            election_month: 11,
+            election_year: 26,
+            election_type: '\u{1f5f3}',
+        })
+        .encode()
+        .is_err());
+    }
+
+    #[test]
+    fn test_decode_complete_row() {
+        // A row with every timing mark printed, like the top row, is neither.
+        assert!(TimingMarkMetadata::decode(&[true; BITS]).is_err());
+        assert!(TimingMarkMetadata::decode(&[false; BITS]).is_err());
+    }
+}
@@ -3,23 +3,32 @@ use std::str::FromStr;
 use itertools::Itertools;
 use serde::Serialize;
 use types_rs::{
+    ballot_card::{BallotType, PageNumber},
     bubble_ballot,
-    geometry::{GridUnit, PixelPosition, Point, Rect, Segment, Size, SubGridUnit, SubPixelUnit},
+    election::{BallotStyleId, PrecinctId},
+    geometry::{
+        GridUnit, PixelPosition, Point, Quadrilateral, Rect, Segment, Size, SubGridRect,
+        SubGridUnit, SubPixelUnit,
+    },
 };
 
 use crate::ballot_card::{BallotImage, Geometry};
 use crate::interpret::Error;
 use crate::scoring::UnitIntervalScore;
+use crate::timing_marks::border_finding::GridBorder;
 use crate::timing_marks::scoring::CandidateTimingMark;
 
 pub mod border_finding;
 pub mod corner_finding;
 pub mod mark_finding;
+pub mod metadata;
 pub mod scoring;
 pub mod shape_finding;
 pub mod util;
+pub mod warp;
 
 use crate::timing_marks::util::CornerWise;
+use crate::timing_marks::warp::GridWarp;
 
 pub struct Options {
     pub shape: shape_finding::Options,
@@ -103,12 +112,22 @@ pub fn find_timing_mark_grid(
     ]
     .map_cornerwise(|mark| mark.rect().center());
 
+    let inferred_corner = corners.inferred_corner();
+    let inferred_marks = InferredMarks {
+        left: borders.left.inferred().to_vec(),
+        right: borders.right.inferred().to_vec(),
+    };
     let border_marks = BorderMarks {
         left: borders.left.into_marks(),
         right: borders.right.into_marks(),
+        top: borders.top.map(GridBorder::into_marks).unwrap_or_default(),
+        bottom: borders
+            .bottom
+            .map(GridBorder::into_marks)
+            .unwrap_or_default(),
     };
 
-    let timing_marks = TimingMarks {
+    let mut timing_marks = TimingMarks {
         geometry: geometry.clone(),
         top_left_corner,
         top_right_corner,
@@ -119,7 +138,11 @@ pub fn find_timing_mark_grid(
         bottom_left_mark: *bottom_left_mark,
         bottom_right_mark: *bottom_right_mark,
         border_marks,
+        inferred_marks,
+        inferred_corner,
+        warp: GridWarp::default(),
     };
+    timing_marks.warp = timing_marks.fit_warp();
 
     Ok(timing_marks)
 }
@@ -137,16 +160,43 @@ pub struct TimingMarks {
     pub bottom_left_mark: CandidateTimingMark,
     pub bottom_right_mark: CandidateTimingMark,
     pub border_marks: BorderMarks,
+    pub inferred_marks: InferredMarks,
+    /// The corner whose mark was inferred from the other three corners, e.g.
+    /// because it was folded over or torn off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inferred_corner: Option<Corner>,
+    pub warp: GridWarp,
 }
 
-/// The per-border timing-mark sequences. Only the left and right borders
-/// (i.e. those parallel to the scan direction) are recorded; the grid
-/// is reconstructed from those plus the four corner marks.
+/// The per-border timing-mark sequences. The left and right borders (i.e.
+/// those parallel to the scan direction) are always complete, possibly with
+/// some [`InferredMarks`], and the grid is reconstructed from them. The top
+/// and bottom borders are empty unless every one of their marks was found,
+/// and are only used to fit [`GridWarp`].
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BorderMarks {
     pub left: Vec<CandidateTimingMark>,
     pub right: Vec<CandidateTimingMark>,
+    pub top: Vec<CandidateTimingMark>,
+    pub bottom: Vec<CandidateTimingMark>,
+}
+
+/// Indexes into the left and right [`BorderMarks`] of the marks that could
+/// not be found (e.g. because they were torn off or scribbled over) and were
+/// inferred from the marks around them instead.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InferredMarks {
+    pub left: Vec<usize>,
+    pub right: Vec<usize>,
+}
+
+impl InferredMarks {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty() && self.right.is_empty()
+    }
 }
 
 impl TimingMarks {
@@ -174,21 +224,34 @@ impl TimingMarks {
                 .collect()
         };
 
-        let BorderMarks { left, right } = std::mem::replace(
+        let BorderMarks {
+            left,
+            right,
+            top,
+            bottom,
+        } = std::mem::replace(
             &mut self.border_marks,
             BorderMarks {
                 left: vec![],
                 right: vec![],
+                top: vec![],
+                bottom: vec![],
             },
         );
         let mut rotated_left = rotate_marks(left);
         let mut rotated_right = rotate_marks(right);
+        let mut rotated_top = rotate_marks(top);
+        let mut rotated_bottom = rotate_marks(bottom);
         rotated_left.sort_by_key(|m| m.rect().top());
         rotated_right.sort_by_key(|m| m.rect().top());
-        // Rotation by 180° swaps left/right.
+        rotated_top.sort_by_key(|m| m.rect().left());
+        rotated_bottom.sort_by_key(|m| m.rect().left());
+        // Rotation by 180° swaps left/right and top/bottom.
         let border_marks = BorderMarks {
             left: rotated_right,
             right: rotated_left,
+            top: rotated_bottom,
+            bottom: rotated_top,
         };
 
         self.top_left_corner = top_left_corner;
@@ -200,6 +263,77 @@ impl TimingMarks {
         self.bottom_left_mark = bottom_left_mark;
         self.bottom_right_mark = bottom_right_mark;
         self.border_marks = border_marks;
+
+        // Rotation reverses the order of the marks along each border.
+        let height = self.geometry.grid_size.height as usize;
+        let rotate_indexes = |indexes: &[usize]| -> Vec<usize> {
+            indexes
+                .iter()
+                .rev()
+                .map(|index| height - 1 - index)
+                .collect()
+        };
+        self.inferred_marks = InferredMarks {
+            left: rotate_indexes(&self.inferred_marks.right),
+            right: rotate_indexes(&self.inferred_marks.left),
+        };
+        self.inferred_corner = self.inferred_corner.map(Corner::rotate180);
+        self.warp = self.fit_warp();
+    }
+
+    /// Fits a [`GridWarp`] to every timing mark on the border, comparing each
+    /// mark's center to where interpolating between the left and right marks
+    /// puts it. The left and right marks themselves are included so the
+    /// correction stays anchored to them.
+    #[must_use]
+    pub fn fit_warp(&self) -> GridWarp {
+        let Size { width, height } = self.geometry.grid_size;
+        let (last_column, last_row) = ((width - 1) as SubGridUnit, (height - 1) as SubGridUnit);
+        let BorderMarks {
+            left,
+            right,
+            top,
+            bottom,
+        } = &self.border_marks;
+
+        let locations = (left.iter().enumerate())
+            .map(|(row, mark)| (0.0, row as SubGridUnit, mark))
+            .chain(
+                right
+                    .iter()
+                    .enumerate()
+                    .map(|(row, mark)| (last_column, row as SubGridUnit, mark)),
+            )
+            // The first and last marks on the top and bottom are the corners,
+            // which are already included with the left and right marks.
+            .chain(
+                top.iter()
+                    .enumerate()
+                    .skip(1)
+                    .take(top.len().saturating_sub(2))
+                    .map(|(column, mark)| (column as SubGridUnit, 0.0, mark)),
+            )
+            .chain(
+                bottom
+                    .iter()
+                    .enumerate()
+                    .skip(1)
+                    .take(bottom.len().saturating_sub(2))
+                    .map(|(column, mark)| (column as SubGridUnit, last_row, mark)),
+            );
+
+        let displacements = locations
+            .filter_map(|(column, row, mark)| {
+                let expected = self.interpolate_between_border_marks(column, row)?;
+                let actual = mark.rect().center();
+                Some((
+                    Point::new(column, row),
+                    Point::new(actual.x - expected.x, actual.y - expected.y),
+                ))
+            })
+            .collect_vec();
+
+        GridWarp::fit(&displacements)
     }
 
     /// Returns the center of the grid position at the given coordinates. Timing
@@ -221,11 +355,37 @@ impl TimingMarks {
     ///    two rows).
     /// 2. Interpolating horizontally between the left/right timing mark
     ///    positions based on the given column index.
+    /// 3. Adding the [`GridWarp`] correction fitted to the whole border, which
+    ///    accounts for rows and columns that aren't straight lines.
     #[must_use]
     pub fn point_for_location(
         &self,
         column: SubGridUnit,
         row: SubGridUnit,
+    ) -> Option<Point<SubPixelUnit>> {
+        let point = self.interpolate_between_border_marks(column, row)?;
+        let offset = self.warp.offset_at(column, row);
+        Some(Point::new(point.x + offset.x, point.y + offset.y))
+    }
+
+    /// Returns the shape in the image of an area given in grid coordinates, or
+    /// `None` if any of its corners are outside the grid.
+    #[must_use]
+    pub fn quadrilateral_for_area(&self, area: SubGridRect) -> Option<Quadrilateral> {
+        Some(Quadrilateral {
+            top_left: self.point_for_location(area.x, area.y)?,
+            top_right: self.point_for_location(area.x + area.width, area.y)?,
+            bottom_left: self.point_for_location(area.x, area.y + area.height)?,
+            bottom_right: self.point_for_location(area.x + area.width, area.y + area.height)?,
+        })
+    }
+
+    /// Returns the center of the grid position at the given coordinates based
+    /// only on the left and right timing marks. See [`Self::point_for_location`].
+    fn interpolate_between_border_marks(
+        &self,
+        column: SubGridUnit,
+        row: SubGridUnit,
     ) -> Option<Point<SubPixelUnit>> {
         if column >= self.geometry.grid_size.width as SubGridUnit
             || row >= self.geometry.grid_size.height as SubGridUnit
@@ -235,7 +395,7 @@ impl TimingMarks {
 
         // Find the left and right timing marks for the given row, interpolating
         // vertically if given a fractional row index
-        let BorderMarks { left, right } = &self.border_marks;
+        let BorderMarks { left, right, .. } = &self.border_marks;
         let row_before = row.floor() as GridUnit;
         let row_after = row.ceil() as GridUnit;
         let distance_percentage_between_rows = row - row_before as f32;
@@ -324,6 +484,101 @@ fn median(values: impl IntoIterator<Item = SubPixelUnit>) -> Option<SubPixelUnit
 #[serde(tag = "source", rename_all = "kebab-case")]
 pub enum BallotPageMetadata {
     QrCode(bubble_ballot::Metadata),
+
+    /// Metadata for a page whose ballot style and sheet were identified from
+    /// where its bubbles are printed because no QR code could be read. Nothing
+    /// else about the ballot can be known this way. See
+    /// [`crate::style_inference`].
+    #[serde(rename_all = "camelCase")]
+    Inferred {
+        ballot_style_id: BallotStyleId,
+        page_number: PageNumber,
+    },
+
+    /// Metadata for a page of an `AccuVote` ballot, which has no QR code,
+    /// decoded from its bottom row of timing marks. See [`metadata`].
+    #[serde(rename_all = "camelCase")]
+    TimingMarks {
+        #[serde(flatten)]
+        metadata: metadata::MappedMetadata,
+        timing_mark_metadata: metadata::TimingMarkMetadata,
+    },
+}
+
+impl BallotPageMetadata {
+    /// Gets the complete bubble ballot metadata. Only a QR code has all of
+    /// it, including the ballot hash, test mode and ballot type.
+    #[must_use]
+    pub const fn bubble_ballot_metadata(&self) -> Option<&bubble_ballot::Metadata> {
+        match self {
+            Self::QrCode(metadata) => Some(metadata),
+            Self::Inferred { .. } | Self::TimingMarks { .. } => None,
+        }
+    }
+
+    pub const fn ballot_style_id(&self) -> &BallotStyleId {
+        match self {
+            Self::QrCode(metadata) => &metadata.ballot_style_id,
+            Self::Inferred {
+                ballot_style_id, ..
+            } => ballot_style_id,
+            Self::TimingMarks { metadata, .. } => &metadata.ballot_style_id,
+        }
+    }
+
+    pub const fn page_number(&self) -> PageNumber {
+        match self {
+            Self::QrCode(metadata) => metadata.page_number,
+            Self::Inferred { page_number, .. } => *page_number,
+            Self::TimingMarks { metadata, .. } => metadata.page_number,
+        }
+    }
+
+    /// Gets the precinct, unless the ballot style was inferred, since only
+    /// the QR code says which of the style's precincts the ballot is for.
+    #[must_use]
+    pub const fn precinct_id(&self) -> Option<&PrecinctId> {
+        match self {
+            Self::QrCode(metadata) => Some(&metadata.precinct_id),
+            Self::Inferred { .. } => None,
+            Self::TimingMarks { metadata, .. } => Some(&metadata.precinct_id),
+        }
+    }
+
+    /// Gets the ballot type, if the metadata came from somewhere that has it.
+    #[must_use]
+    pub fn ballot_type(&self) -> Option<BallotType> {
+        self.bubble_ballot_metadata()
+            .map(|metadata| metadata.ballot_type)
+    }
+
+    /// Gets the metadata for the other side of the same sheet, e.g. for a
+    /// page scanned on its own.
+    #[must_use]
+    pub fn for_opposite_page(&self) -> Self {
+        match self {
+            Self::QrCode(metadata) => {
+                Self::QrCode(bubble_ballot::infer_missing_page_metadata(metadata))
+            }
+            Self::Inferred {
+                ballot_style_id,
+                page_number,
+            } => Self::Inferred {
+                ballot_style_id: ballot_style_id.clone(),
+                page_number: page_number.opposite(),
+            },
+            Self::TimingMarks {
+                metadata,
+                timing_mark_metadata,
+            } => Self::TimingMarks {
+                metadata: metadata::MappedMetadata {
+                    page_number: metadata.page_number.opposite(),
+                    ..metadata.clone()
+                },
+                timing_mark_metadata: *timing_mark_metadata,
+            },
+        }
+    }
 }
 
 struct Rotator180 {
@@ -369,7 +624,8 @@ impl Rotator180 {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum Corner {
     TopLeft,
     TopRight,
@@ -377,6 +633,19 @@ pub enum Corner {
     BottomRight,
 }
 
+impl Corner {
+    /// The corner this one ends up at when the page is rotated 180°.
+    #[must_use]
+    pub const fn rotate180(self) -> Self {
+        match self {
+            Self::TopLeft => Self::BottomRight,
+            Self::TopRight => Self::BottomLeft,
+            Self::BottomLeft => Self::TopRight,
+            Self::BottomRight => Self::TopLeft,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Border {
     Left,
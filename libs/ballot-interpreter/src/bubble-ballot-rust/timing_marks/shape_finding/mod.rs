@@ -2,17 +2,17 @@ use std::ops::RangeInclusive;
 
 use image::RgbImage;
 use itertools::Itertools;
-use types_rs::{
-    geometry::{PixelUnit, Point, Rect},
-    pair::Pair,
-};
+use types_rs::geometry::{PixelUnit, Point, Rect};
 
 use crate::{
     ballot_card::{BallotImage, Geometry},
     image_utils::{rainbow, Inset},
+    impl_edgewise,
     timing_marks::{
-        rect_could_be_timing_mark, shape_finding::shape_list_builder::ShapeListBuilder,
-        util::median_filter, CandidateTimingMark, DefaultForGeometry,
+        rect_could_be_timing_mark,
+        shape_finding::shape_list_builder::ShapeListBuilder,
+        util::{median_filter, EdgeWise},
+        CandidateTimingMark, DefaultForGeometry,
     },
 };
 
@@ -23,8 +23,12 @@ mod shape_list_builder;
 pub struct BallotGridBorderShapes {
     pub left: Vec<TimingMarkShape>,
     pub right: Vec<TimingMarkShape>,
+    pub top: Vec<TimingMarkShape>,
+    pub bottom: Vec<TimingMarkShape>,
 }
 
+impl_edgewise!(BallotGridBorderShapes, Vec<TimingMarkShape>);
+
 impl BallotGridBorderShapes {
     /// Searches the given ballot image within the given inset area for timing
     /// mark shapes. Searches each of the four sides independently, so the
@@ -40,7 +44,7 @@ impl BallotGridBorderShapes {
         let image = ballot_image.image();
         let (width, height) = image.dimensions();
 
-        let search_areas = Pair::new(
+        let search_areas = [
             Rect::new(0, 0, search_inset.left, height),
             Rect::new(
                 width as i32 - search_inset.right as i32,
@@ -48,19 +52,29 @@ impl BallotGridBorderShapes {
                 search_inset.right,
                 height,
             ),
-        );
-
-        let (left, right) = search_areas
-            .par_map(|search_area| {
-                find_timing_mark_shapes(ballot_image, geometry, search_area, options)
-            })
-            .into();
+            Rect::new(0, 0, width, search_inset.top),
+            Rect::new(
+                0,
+                height as i32 - search_inset.bottom as i32,
+                width,
+                search_inset.bottom,
+            ),
+        ];
 
-        Self { left, right }
+        search_areas.par_map_edgewise(|search_area| {
+            find_timing_mark_shapes(ballot_image, geometry, search_area, options)
+        })
     }
 
     pub fn debug_draw(&self, canvas: &mut RgbImage) {
-        for (shape, color) in self.left.iter().chain(self.right.iter()).zip(rainbow()) {
+        for (shape, color) in self
+            .left
+            .iter()
+            .chain(self.right.iter())
+            .chain(self.top.iter())
+            .chain(self.bottom.iter())
+            .zip(rainbow())
+        {
             for point in shape.points() {
                 canvas.get_pixel_mut(point.x, point.y).0 = color.0;
             }
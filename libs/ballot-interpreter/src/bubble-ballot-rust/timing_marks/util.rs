@@ -1,8 +1,11 @@
 use std::iter::repeat;
 
-use types_rs::geometry::Point;
+use types_rs::geometry::{PixelPosition, Point, Rect, SubPixelUnit};
 
-use crate::timing_marks::CandidateTimingMark;
+use crate::{
+    scoring::UnitIntervalScore,
+    timing_marks::{scoring::TimingMarkScore, CandidateTimingMark},
+};
 
 pub fn mark_distances_to_point(
     marks: &[CandidateTimingMark],
@@ -14,6 +17,25 @@ pub fn mark_distances_to_point(
         .map(|(mark, point)| (mark.rect().center().distance_to(&point), mark))
 }
 
+/// Builds a stand-in for a mark that wasn't found, the same size as
+/// `template` and centered at `center`. Inferred marks have no ink, so their
+/// scores are zero.
+pub fn inferred_mark(
+    template: &CandidateTimingMark,
+    center: Point<SubPixelUnit>,
+) -> CandidateTimingMark {
+    let rect = template.rect();
+    CandidateTimingMark::new(
+        Rect::new(
+            (center.x - rect.width() as SubPixelUnit / 2.0).round() as PixelPosition,
+            (center.y - rect.height() as SubPixelUnit / 2.0).round() as PixelPosition,
+            rect.width(),
+            rect.height(),
+        ),
+        TimingMarkScore::new(UnitIntervalScore(0.0), UnitIntervalScore(0.0)),
+    )
+}
+
 /// Implements the `EdgeWise` trait for a given type.
 #[macro_export]
 macro_rules! impl_edgewise {
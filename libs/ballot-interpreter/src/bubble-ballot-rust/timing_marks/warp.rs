@@ -0,0 +1,340 @@
+use serde::Serialize;
+use types_rs::geometry::{Point, SubGridUnit, SubPixelUnit};
+
+/// How strongly the warp resists bending to pass through every mark, in the
+/// same units as the thin-plate spline kernel over grid coordinates. Larger
+/// values let the fit ignore single misplaced marks at the cost of following
+/// real distortion less closely. Chosen so that mark-finding noise (about a
+/// pixel) shows up as residual rather than as local kinks in the grid.
+const SMOOTHING: f64 = 1.0;
+
+/// A correction to the grid built by interpolating between left and right
+/// timing marks, fitted to every timing mark found on the grid border.
+///
+/// Row interpolation already follows the left and right borders exactly, so it
+/// handles uneven feed speed, but it assumes each row is a straight line
+/// between its two marks. Paper that buckles or shifts sideways while being
+/// fed bends rows and columns, which shows up as top and bottom marks that are
+/// not where row interpolation puts them. The correction is a smoothing
+/// thin-plate spline over grid coordinates fitted to those displacements
+/// (and to the zero displacement of the left and right marks), so it is
+/// strongest near the borders that disagree and fades into the ballot.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridWarp {
+    #[serde(skip)]
+    control_points: Vec<Point<SubGridUnit>>,
+
+    /// Spline weights for each control point, as `(x, y)` pixel offsets.
+    #[serde(skip)]
+    weights: Vec<[f64; 2]>,
+
+    /// Affine part of the correction: `[constant, column, row]` coefficients
+    /// for the x and y pixel offsets.
+    #[serde(skip)]
+    affine: [[f64; 3]; 2],
+
+    residuals: WarpResiduals,
+}
+
+/// How far the timing marks are from where the fitted grid places them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WarpResiduals {
+    /// The number of timing marks the warp was fitted to.
+    pub mark_count: usize,
+
+    /// The root mean square distance in pixels between each timing mark's
+    /// center and its fitted grid location.
+    pub rms: SubPixelUnit,
+
+    /// The largest distance in pixels between a timing mark's center and its
+    /// fitted grid location.
+    pub max: SubPixelUnit,
+}
+
+impl GridWarp {
+    /// Fits a warp to the given grid locations and the pixel offsets between
+    /// where each timing mark was found and where the unwarped grid puts it.
+    /// Falls back to no correction if there is nothing to correct or the
+    /// locations don't determine a fit, e.g. because they are all in a line.
+    #[must_use]
+    pub fn fit(displacements: &[(Point<SubGridUnit>, Point<SubPixelUnit>)]) -> Self {
+        let warp = if displacements
+            .iter()
+            .all(|(_, offset)| offset.x == 0.0 && offset.y == 0.0)
+        {
+            None
+        } else {
+            Self::fit_spline(displacements)
+        }
+        .unwrap_or_default();
+
+        let squared_residuals = displacements
+            .iter()
+            .map(|(location, offset)| {
+                let fitted = warp.offset_at(location.x, location.y);
+                (offset.x - fitted.x).powi(2) + (offset.y - fitted.y).powi(2)
+            })
+            .collect::<Vec<_>>();
+        let residuals = WarpResiduals {
+            mark_count: displacements.len(),
+            rms: if squared_residuals.is_empty() {
+                0.0
+            } else {
+                (squared_residuals.iter().sum::<SubPixelUnit>() / squared_residuals.len() as f32)
+                    .sqrt()
+            },
+            max: squared_residuals
+                .iter()
+                .copied()
+                .fold(0.0, SubPixelUnit::max)
+                .sqrt(),
+        };
+
+        Self { residuals, ..warp }
+    }
+
+    /// Solves the smoothing thin-plate spline system
+    ///
+    /// ```text
+    /// ┌ K + λI  P ┐ ┌ w ┐   ┌ d ┐
+    /// └ Pᵀ      0 ┘ └ a ┘ = └ 0 ┘
+    /// ```
+    ///
+    /// where `K` holds the kernel between each pair of control points, `P`
+    /// holds `[1, column, row]` for each control point, and `d` holds the
+    /// observed offsets.
+    fn fit_spline(displacements: &[(Point<SubGridUnit>, Point<SubPixelUnit>)]) -> Option<Self> {
+        let n = displacements.len();
+        let size = n + 3;
+        let mut matrix = vec![0.0; size * size];
+        let mut rhs = vec![[0.0; 2]; size];
+
+        for (i, (a, offset)) in displacements.iter().enumerate() {
+            for (j, (b, _)) in displacements.iter().enumerate() {
+                matrix[i * size + j] = kernel(*a, *b);
+            }
+            matrix[i * size + i] += SMOOTHING;
+            for (k, value) in [1.0, f64::from(a.x), f64::from(a.y)]
+                .into_iter()
+                .enumerate()
+            {
+                matrix[i * size + n + k] = value;
+                matrix[(n + k) * size + i] = value;
+            }
+            rhs[i] = [f64::from(offset.x), f64::from(offset.y)];
+        }
+
+        let solution = solve(matrix, rhs)?;
+        let (weights, affine) = solution.split_at(n);
+
+        Some(Self {
+            control_points: displacements
+                .iter()
+                .map(|(location, _)| *location)
+                .collect(),
+            weights: weights.to_vec(),
+            affine: [
+                [affine[0][0], affine[1][0], affine[2][0]],
+                [affine[0][1], affine[1][1], affine[2][1]],
+            ],
+            residuals: WarpResiduals::default(),
+        })
+    }
+
+    /// Gets the pixel offset to add to the unwarped grid at the given grid
+    /// location.
+    pub fn offset_at(&self, column: SubGridUnit, row: SubGridUnit) -> Point<SubPixelUnit> {
+        if self.control_points.is_empty() {
+            return Point::new(0.0, 0.0);
+        }
+
+        let location = Point::new(column, row);
+        let [x, y] = self.control_points.iter().zip(&self.weights).fold(
+            [
+                self.affine[0][0]
+                    + self.affine[0][1] * f64::from(column)
+                    + self.affine[0][2] * f64::from(row),
+                self.affine[1][0]
+                    + self.affine[1][1] * f64::from(column)
+                    + self.affine[1][2] * f64::from(row),
+            ],
+            |[x, y], (control_point, [wx, wy])| {
+                let u = kernel(location, *control_point);
+                [x + wx * u, y + wy * u]
+            },
+        );
+
+        Point::new(x as SubPixelUnit, y as SubPixelUnit)
+    }
+
+    /// How well the warp fits the timing marks it was built from.
+    #[must_use]
+    pub const fn residuals(&self) -> WarpResiduals {
+        self.residuals
+    }
+}
+
+/// The thin-plate spline radial basis function, `r² ln r`.
+fn kernel(a: Point<SubGridUnit>, b: Point<SubGridUnit>) -> f64 {
+    let r2 = f64::from(a.x - b.x).powi(2) + f64::from(a.y - b.y).powi(2);
+    if r2 == 0.0 {
+        0.0
+    } else {
+        r2 * r2.ln() / 2.0
+    }
+}
+
+/// Solves `matrix * x = rhs` for a square row-major `matrix` and two
+/// right-hand sides at once using Gaussian elimination with partial pivoting.
+/// Returns `None` if the matrix is singular.
+fn solve(mut matrix: Vec<f64>, mut rhs: Vec<[f64; 2]>) -> Option<Vec<[f64; 2]>> {
+    let size = rhs.len();
+
+    for column in 0..size {
+        let pivot = (column..size).max_by(|&a, &b| {
+            matrix[a * size + column]
+                .abs()
+                .total_cmp(&matrix[b * size + column].abs())
+        })?;
+        if matrix[pivot * size + column].abs() < 1e-12 {
+            return None;
+        }
+        if pivot != column {
+            for k in 0..size {
+                matrix.swap(pivot * size + k, column * size + k);
+            }
+            rhs.swap(pivot, column);
+        }
+
+        for row in column + 1..size {
+            let factor = matrix[row * size + column] / matrix[column * size + column];
+            if factor == 0.0 {
+                continue;
+            }
+            for k in column..size {
+                matrix[row * size + k] -= factor * matrix[column * size + k];
+            }
+            let [x, y] = rhs[column];
+            rhs[row][0] -= factor * x;
+            rhs[row][1] -= factor * y;
+        }
+    }
+
+    let mut solution = vec![[0.0; 2]; size];
+    for row in (0..size).rev() {
+        let mut value = rhs[row];
+        for k in row + 1..size {
+            value[0] -= matrix[row * size + k] * solution[k][0];
+            value[1] -= matrix[row * size + k] * solution[k][1];
+        }
+        let diagonal = matrix[row * size + row];
+        solution[row] = [value[0] / diagonal, value[1] / diagonal];
+    }
+
+    Some(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn border_locations(width: usize, height: usize) -> Vec<Point<SubGridUnit>> {
+        let (right, bottom) = ((width - 1) as SubGridUnit, (height - 1) as SubGridUnit);
+        (0..height)
+            .flat_map(|row| {
+                [
+                    Point::new(0.0, row as SubGridUnit),
+                    Point::new(right, row as SubGridUnit),
+                ]
+            })
+            .chain((1..width - 1).flat_map(|column| {
+                [
+                    Point::new(column as SubGridUnit, 0.0),
+                    Point::new(column as SubGridUnit, bottom),
+                ]
+            }))
+            .collect()
+    }
+
+    #[test]
+    fn test_no_displacement_is_no_correction() {
+        let displacements = border_locations(34, 41)
+            .into_iter()
+            .map(|location| (location, Point::new(0.0, 0.0)))
+            .collect::<Vec<_>>();
+        let warp = GridWarp::fit(&displacements);
+        assert_eq!(warp.offset_at(16.5, 20.0), Point::new(0.0, 0.0));
+        assert_eq!(
+            warp.residuals(),
+            WarpResiduals {
+                mark_count: displacements.len(),
+                rms: 0.0,
+                max: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_no_marks_is_no_correction() {
+        let warp = GridWarp::fit(&[]);
+        assert_eq!(warp.offset_at(3.0, 4.0), Point::new(0.0, 0.0));
+        assert_eq!(warp.residuals(), WarpResiduals::default());
+    }
+
+    #[test]
+    fn test_bowed_columns() {
+        // Columns bowed sideways at the top and bottom of the ballot, as if the
+        // paper buckled while feeding, but not along the left and right edges.
+        let (width, height) = (34, 41);
+        let bow = |column: SubGridUnit, row: SubGridUnit| {
+            let u = column / (width - 1) as SubGridUnit;
+            let v = row / (height - 1) as SubGridUnit;
+            // Zero on the left and right, 6px in the middle of the top and
+            // bottom, and 3px along the middle row.
+            6.0 * 4.0 * u * (1.0 - u) * (1.0 - v * (1.0 - v) * 2.0)
+        };
+        let displacements = border_locations(width, height)
+            .into_iter()
+            .map(|location| (location, Point::new(bow(location.x, location.y), 0.0)))
+            .collect::<Vec<_>>();
+        let warp = GridWarp::fit(&displacements);
+
+        let residuals = warp.residuals();
+        assert_eq!(residuals.mark_count, displacements.len());
+        assert!(residuals.max < 0.5, "{residuals:?}");
+
+        for (column, row) in [(16.5, 0.0), (16.5, 40.0), (0.0, 20.0), (33.0, 20.0)] {
+            let offset = warp.offset_at(column, row);
+            assert!((offset.x - bow(column, row)).abs() < 0.5, "{offset:?}");
+            assert!(offset.y.abs() < 0.5, "{offset:?}");
+        }
+
+        // The interior follows the bend rather than staying on the unwarped
+        // grid.
+        let middle = warp.offset_at(16.5, 20.0);
+        assert!(middle.x > 1.0 && middle.x < 6.0, "{middle:?}");
+    }
+
+    #[test]
+    fn test_misplaced_mark_shows_in_residuals() {
+        let mut displacements = border_locations(34, 41)
+            .into_iter()
+            .map(|location| (location, Point::new(0.0, 0.0)))
+            .collect::<Vec<_>>();
+        let misplaced = displacements
+            .iter()
+            .position(|(location, _)| *location == Point::new(10.0, 0.0))
+            .unwrap();
+        displacements[misplaced].1 = Point::new(0.0, 8.0);
+
+        let warp = GridWarp::fit(&displacements);
+        let residuals = warp.residuals();
+        assert!(residuals.max > 0.0, "{residuals:?}");
+        assert!(residuals.rms < residuals.max, "{residuals:?}");
+
+        // The misplaced mark's pull doesn't reach far into the ballot.
+        assert!(warp.offset_at(10.0, 20.0).y.abs() < 1.0);
+    }
+}
@@ -0,0 +1,1155 @@
+use serde::Serialize;
+use types_rs::election::{
+    Candidate, CandidateContest, Contest, ContestId, Election, MarkThresholds, NamedCandidate,
+    OptionId, PartyId, RankedChoiceContest,
+};
+
+use crate::interpret::{Error, Result};
+use crate::scoring::{MarkClassification, ScoredBubbleMark, ScoredBubbleMarks};
+
+/// Default mark thresholds, used when neither the caller nor the election
+/// definition provide any. These values must match `DEFAULT_MARK_THRESHOLDS`
+/// in `libs/types/src/system_settings.ts`.
+pub const DEFAULT_MARK_THRESHOLDS: MarkThresholds = MarkThresholds {
+    definite: 0.07,
+    marginal: 0.05,
+    write_in_text_area: Some(0.025),
+};
+
+/// Gets the mark thresholds from `election`, or [`DEFAULT_MARK_THRESHOLDS`] if
+/// it does not define any.
+pub fn election_mark_thresholds(election: &Election) -> MarkThresholds {
+    election
+        .mark_thresholds
+        .clone()
+        .unwrap_or(DEFAULT_MARK_THRESHOLDS)
+}
+
+/// How a scored bubble is treated once mark thresholds are applied. Mirrors
+/// `MarkStatus` in `libs/types/src/hmpb.ts`.
+///
+/// Variants are ordered from least to most marked so that the strongest status
+/// for an option with several bubbles can be found with [`Ord::max`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MarkStatus {
+    Unmarked,
+    Marginal,
+    Marked,
+}
+
+impl MarkStatus {
+    /// Classifies a scored bubble using `thresholds`. Bubbles that could not be
+    /// scored (e.g. because they fall off the image) are unmarked.
+    #[must_use]
+    pub fn for_scored_bubble_mark(
+        scored_bubble_mark: Option<&ScoredBubbleMark>,
+        thresholds: &MarkThresholds,
+    ) -> Self {
+        match scored_bubble_mark {
+            Some(mark) if mark.fill_score.0 >= thresholds.definite => Self::Marked,
+            Some(mark) if mark.fill_score.0 >= thresholds.marginal => Self::Marginal,
+            _ => Self::Unmarked,
+        }
+    }
+}
+
+/// A reason a ballot card may need adjudication. Mirrors
+/// `AdjudicationReasonInfo` in `libs/types/src/election.ts`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum AdjudicationReasonInfo {
+    /// An option's strongest bubble scores between the marginal and definite
+    /// thresholds, or scores below them but looks erased. `classification`
+    /// says what kind of mark the bubble looks like, so that adjudicators can
+    /// tell an erasure from a light fill that scores the same.
+    #[serde(rename_all = "camelCase")]
+    MarginalMark {
+        contest_id: ContestId,
+        option_id: OptionId,
+        classification: MarkClassification,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    Overvote {
+        contest_id: ContestId,
+        option_ids: Vec<OptionId>,
+        expected: u32,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    Undervote {
+        contest_id: ContestId,
+        option_ids: Vec<OptionId>,
+        expected: u32,
+    },
+
+    /// A ranked-choice contest has no option marked at `rank`, but does have
+    /// one marked at a later rank.
+    #[serde(rename_all = "camelCase")]
+    SkippedRank {
+        contest_id: ContestId,
+        rank: u32,
+    },
+
+    /// An option in a ranked-choice contest is marked at more than one rank.
+    #[serde(rename_all = "camelCase")]
+    DuplicateRanking {
+        contest_id: ContestId,
+        option_id: OptionId,
+        ranks: Vec<u32>,
+    },
+
+    /// More than one option in a ranked-choice contest is marked at `rank`.
+    #[serde(rename_all = "camelCase")]
+    OvervotedRank {
+        contest_id: ContestId,
+        rank: u32,
+        option_ids: Vec<OptionId>,
+    },
+
+    /// A contest has ink outside its bubbles and write-in areas, e.g. a
+    /// circled name or a checkmark, that may indicate the voter's intent.
+    #[serde(rename_all = "camelCase")]
+    PossibleVoterIntent {
+        contest_id: ContestId,
+    },
+
+    BlankBallot,
+
+    /// The card's QR codes couldn't be read, so its ballot style and sheet
+    /// were identified from where its bubbles are printed.
+    InferredBallotStyle,
+}
+
+/// The votes derived for a single contest along with any contest-level
+/// adjudication reasons.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContestVotes {
+    pub contest_id: ContestId,
+
+    /// Options whose strongest bubble meets the definite threshold, in ballot
+    /// order. In a ranked-choice contest this is instead the voter's ranking,
+    /// first choice first: ranks that are empty or overvoted are skipped, as
+    /// are options already ranked earlier.
+    pub option_ids: Vec<OptionId>,
+
+    /// Marginal-mark, undervote and overvote reasons for this contest, in that
+    /// order. These reflect the bubbles the voter marked, so a contest filled
+    /// in by straight-party expansion is still reported as undervoted.
+    /// Ranked-choice contests report skipped ranks, overvoted ranks and
+    /// duplicate rankings in place of overvotes. Possible voter intent from
+    /// stray marks is reported last.
+    pub adjudication_reasons: Vec<AdjudicationReasonInfo>,
+
+    /// For ranked-choice contests, the options marked at each rank, starting
+    /// with the first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ranks: Option<Vec<Vec<OptionId>>>,
+
+    /// Options added to `option_ids` by straight-party expansion. Empty unless
+    /// the voter marked no options in this contest.
+    pub straight_party_option_ids: Vec<OptionId>,
+}
+
+impl ContestVotes {
+    #[must_use]
+    pub fn is_overvote(&self) -> bool {
+        self.adjudication_reasons
+            .iter()
+            .any(|reason| matches!(reason, AdjudicationReasonInfo::Overvote { .. }))
+    }
+}
+
+/// Tabulation-ready votes for a ballot card.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BallotCardVotes {
+    /// One entry per contest on the card, in the order the contests appear in
+    /// the grid layout.
+    pub contests: Vec<ContestVotes>,
+
+    /// Whether the card has contests but no marked bubbles.
+    pub is_blank_ballot: bool,
+
+    /// The party selected in the straight-party contest, if the card has one
+    /// and exactly one party is marked.
+    pub straight_party_id: Option<PartyId>,
+
+    /// Whether the card's ballot style was inferred rather than read from its
+    /// QR codes, in which case it is always held for adjudication.
+    pub is_ballot_style_inferred: bool,
+}
+
+impl BallotCardVotes {
+    /// Gets the votes for a contest, if it appears on this card.
+    #[must_use]
+    pub fn contest(&self, contest_id: &ContestId) -> Option<&ContestVotes> {
+        self.contests
+            .iter()
+            .find(|contest| &contest.contest_id == contest_id)
+    }
+
+    /// Lists every adjudication reason for the card: the per-contest reasons in
+    /// contest order, followed by [`AdjudicationReasonInfo::BlankBallot`] and
+    /// [`AdjudicationReasonInfo::InferredBallotStyle`] if applicable.
+    #[must_use]
+    pub fn adjudication_reasons(&self) -> Vec<AdjudicationReasonInfo> {
+        self.contests
+            .iter()
+            .flat_map(|contest| contest.adjudication_reasons.iter().cloned())
+            .chain(
+                self.is_blank_ballot
+                    .then_some(AdjudicationReasonInfo::BlankBallot),
+            )
+            .chain(
+                self.is_ballot_style_inferred
+                    .then_some(AdjudicationReasonInfo::InferredBallotStyle),
+            )
+            .collect()
+    }
+
+    /// Lists the contests that received votes through straight-party
+    /// expansion.
+    pub fn straight_party_expanded_contest_ids(&self) -> impl Iterator<Item = &ContestId> {
+        self.contests
+            .iter()
+            .filter(|contest| !contest.straight_party_option_ids.is_empty())
+            .map(|contest| &contest.contest_id)
+    }
+}
+
+/// Applies `thresholds` to scored bubble marks and groups the result by
+/// contest. This follows `getAllPossibleAdjudicationReasons` in
+/// `libs/ballot-interpreter/src/adjudication_reasons.ts`: an option printed in
+/// more than one place takes its strongest mark, and a contest is under- or
+/// overvoted relative to [`Contest::votes_allowed`]. Straight-party votes are
+/// then expanded with [`expand_straight_party_votes`].
+///
+/// In ranked-choice contests each bubble is an (option, rank) pair, and the
+/// marked pairs are turned into a ranking by [`derive_ranked_contest_votes`].
+///
+/// # Errors
+///
+/// Fails if a scored position refers to a contest that is not in `election`.
+#[allow(clippy::result_large_err)]
+pub fn derive_votes<'a>(
+    election: &Election,
+    pages: impl IntoIterator<Item = &'a ScoredBubbleMarks>,
+    thresholds: &MarkThresholds,
+) -> Result<BallotCardVotes> {
+    // (contest ID, [(option ID, rank, strongest mark status, classification)])
+    // in ballot order.
+    let mut statuses: Vec<(ContestId, Vec<OptionStatus>)> = Vec::new();
+
+    for (grid_position, scored_bubble_mark) in pages.into_iter().flatten() {
+        let contest_id = grid_position.contest_id();
+        let option_id = grid_position.option_id();
+        let rank = grid_position.rank();
+        let classification = scored_bubble_mark
+            .as_ref()
+            .map_or(MarkClassification::Blank, |mark| mark.classification);
+        // An erasure can leave too little ink to reach the marginal threshold,
+        // but the voter may not have meant to take the vote back.
+        let mark_status =
+            match MarkStatus::for_scored_bubble_mark(scored_bubble_mark.as_ref(), thresholds) {
+                MarkStatus::Unmarked if classification == MarkClassification::Erased => {
+                    MarkStatus::Marginal
+                }
+                mark_status => mark_status,
+            };
+
+        let contest_index = statuses
+            .iter()
+            .position(|(id, _)| *id == contest_id)
+            .unwrap_or_else(|| {
+                statuses.push((contest_id, Vec::new()));
+                statuses.len() - 1
+            });
+        let options = &mut statuses[contest_index].1;
+
+        match options
+            .iter_mut()
+            .find(|(id, r, _, _)| *id == option_id && *r == rank)
+        {
+            Some((_, _, status, _)) if *status >= mark_status => {}
+            Some(option) => {
+                option.2 = mark_status;
+                option.3 = classification;
+            }
+            None => options.push((option_id, rank, mark_status, classification)),
+        }
+    }
+
+    let contests = statuses
+        .into_iter()
+        .map(|(contest_id, options)| {
+            let Some(contest) = election.contests.iter().find(|c| *c.id() == contest_id) else {
+                return Err(Error::InvalidElection {
+                    message: format!("grid layout refers to unknown contest {contest_id}"),
+                });
+            };
+            Ok(match contest {
+                Contest::RankedChoice(ranked_choice_contest) => {
+                    derive_ranked_contest_votes(ranked_choice_contest, &options)
+                }
+                _ => derive_contest_votes(contest, &options),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let is_blank_ballot =
+        !contests.is_empty() && contests.iter().all(|contest| contest.option_ids.is_empty());
+
+    let mut votes = BallotCardVotes {
+        contests,
+        is_blank_ballot,
+        straight_party_id: None,
+        is_ballot_style_inferred: false,
+    };
+    expand_straight_party_votes(election, &mut votes);
+    Ok(votes)
+}
+
+/// Adds [`AdjudicationReasonInfo::PossibleVoterIntent`] to each contest in
+/// `contest_ids` that appears on the card, at most once per contest.
+pub fn flag_possible_voter_intent<'a>(
+    votes: &mut BallotCardVotes,
+    contest_ids: impl IntoIterator<Item = &'a ContestId>,
+) {
+    for contest_id in contest_ids {
+        let Some(contest) = votes
+            .contests
+            .iter_mut()
+            .find(|contest| contest.contest_id == *contest_id)
+        else {
+            continue;
+        };
+
+        let reason = AdjudicationReasonInfo::PossibleVoterIntent {
+            contest_id: contest_id.clone(),
+        };
+        if !contest.adjudication_reasons.contains(&reason) {
+            contest.adjudication_reasons.push(reason);
+        }
+    }
+}
+
+/// Applies a straight-party selection to the partisan candidate contests on
+/// the card. This follows `deriveStraightPartyVotesForContest` in
+/// `libs/utils/src/tabulation/straight_party.ts`, except that only contests
+/// with no explicit votes are expanded.
+///
+/// A contest is expanded only if its candidates from the selected party fit in
+/// its seats; otherwise it is left empty, just as if the voter had marked too
+/// many bubbles.
+pub fn expand_straight_party_votes(election: &Election, votes: &mut BallotCardVotes) {
+    let straight_party_id = votes.contests.iter().find_map(|contest_votes| {
+        let is_straight_party = election
+            .contests
+            .iter()
+            .any(|c| matches!(c, Contest::StraightParty(_)) && *c.id() == contest_votes.contest_id);
+        match contest_votes.option_ids.as_slice() {
+            [option_id] if is_straight_party => Some(PartyId::from(option_id.to_string())),
+            _ => None,
+        }
+    });
+
+    let Some(straight_party_id) = straight_party_id else {
+        return;
+    };
+
+    for contest_votes in &mut votes.contests {
+        if !contest_votes.option_ids.is_empty() {
+            continue;
+        }
+
+        let Some(Contest::Candidate(CandidateContest {
+            seats, candidates, ..
+        })) = election
+            .contests
+            .iter()
+            .find(|c| *c.id() == contest_votes.contest_id)
+        else {
+            continue;
+        };
+
+        let party_candidate_ids: Vec<OptionId> = candidates
+            .iter()
+            .filter_map(|candidate| match candidate {
+                Candidate::Named(NamedCandidate {
+                    id,
+                    party_ids: Some(party_ids),
+                    ..
+                }) if party_ids.contains(&straight_party_id) => Some(id.clone()),
+                _ => None,
+            })
+            .collect();
+
+        if party_candidate_ids.len() as u32 > *seats {
+            continue;
+        }
+
+        contest_votes.option_ids.clone_from(&party_candidate_ids);
+        contest_votes.straight_party_option_ids = party_candidate_ids;
+    }
+
+    votes.straight_party_id = Some(straight_party_id);
+}
+
+/// An option's bubble, its rank if it is part of a ranked-choice contest, the
+/// strongest status of any bubble printed for that pair, and that bubble's
+/// classification.
+type OptionStatus = (OptionId, Option<u32>, MarkStatus, MarkClassification);
+
+fn derive_contest_votes(contest: &Contest, options: &[OptionStatus]) -> ContestVotes {
+    let contest_id = contest.id().clone();
+    let expected = contest.votes_allowed();
+    let mut adjudication_reasons = Vec::new();
+
+    for (option_id, _, status, classification) in options {
+        if *status == MarkStatus::Marginal {
+            adjudication_reasons.push(AdjudicationReasonInfo::MarginalMark {
+                contest_id: contest_id.clone(),
+                option_id: option_id.clone(),
+                classification: *classification,
+            });
+        }
+    }
+
+    let option_ids: Vec<OptionId> = options
+        .iter()
+        .filter(|(_, _, status, _)| *status == MarkStatus::Marked)
+        .map(|(option_id, _, _, _)| option_id.clone())
+        .collect();
+
+    if (option_ids.len() as u32) < expected {
+        adjudication_reasons.push(AdjudicationReasonInfo::Undervote {
+            contest_id: contest_id.clone(),
+            option_ids: option_ids.clone(),
+            expected,
+        });
+    }
+
+    if (option_ids.len() as u32) > expected {
+        adjudication_reasons.push(AdjudicationReasonInfo::Overvote {
+            contest_id: contest_id.clone(),
+            option_ids: option_ids.clone(),
+            expected,
+        });
+    }
+
+    ContestVotes {
+        contest_id,
+        option_ids,
+        adjudication_reasons,
+        ranks: None,
+        straight_party_option_ids: Vec::new(),
+    }
+}
+
+/// Turns the marked (option, rank) bubbles of a ranked-choice contest into
+/// the voter's ranking. Ranks with no marked option or more than one marked
+/// option contribute nothing, and an option ranked more than once counts only
+/// at its first rank. Each of these is reported for adjudication, as is a
+/// contest with nothing ranked at all.
+fn derive_ranked_contest_votes(
+    contest: &RankedChoiceContest,
+    options: &[OptionStatus],
+) -> ContestVotes {
+    let contest_id = contest.id.clone();
+    let mut adjudication_reasons = Vec::new();
+
+    for (option_id, _, status, classification) in options {
+        let is_reported = adjudication_reasons.iter().any(|reason| {
+            matches!(reason, AdjudicationReasonInfo::MarginalMark { option_id: id, .. } if id == option_id)
+        });
+        if *status == MarkStatus::Marginal && !is_reported {
+            adjudication_reasons.push(AdjudicationReasonInfo::MarginalMark {
+                contest_id: contest_id.clone(),
+                option_id: option_id.clone(),
+                classification: *classification,
+            });
+        }
+    }
+
+    let ranks: Vec<Vec<OptionId>> = (1..=contest.max_rankings)
+        .map(|rank| {
+            options
+                .iter()
+                .filter(|(_, r, status, _)| *r == Some(rank) && *status == MarkStatus::Marked)
+                .map(|(option_id, _, _, _)| option_id.clone())
+                .collect()
+        })
+        .collect();
+    let last_marked_rank = ranks.iter().rposition(|options| !options.is_empty());
+
+    let mut option_ids: Vec<OptionId> = Vec::new();
+    for (index, rank_option_ids) in ranks.iter().enumerate() {
+        let rank = index as u32 + 1;
+        match rank_option_ids.as_slice() {
+            [] if last_marked_rank.is_some_and(|last| index < last) => {
+                adjudication_reasons.push(AdjudicationReasonInfo::SkippedRank {
+                    contest_id: contest_id.clone(),
+                    rank,
+                });
+            }
+            [] => {}
+            [option_id] => {
+                if !option_ids.contains(option_id) {
+                    option_ids.push(option_id.clone());
+                }
+            }
+            _ => adjudication_reasons.push(AdjudicationReasonInfo::OvervotedRank {
+                contest_id: contest_id.clone(),
+                rank,
+                option_ids: rank_option_ids.clone(),
+            }),
+        }
+    }
+
+    for option_id in ranks.iter().flatten() {
+        let option_ranks: Vec<u32> = ranks
+            .iter()
+            .zip(1..)
+            .filter(|(rank_option_ids, _)| rank_option_ids.contains(option_id))
+            .map(|(_, rank)| rank)
+            .collect();
+        let is_reported = adjudication_reasons.iter().any(|reason| {
+            matches!(reason, AdjudicationReasonInfo::DuplicateRanking { option_id: id, .. } if id == option_id)
+        });
+        if option_ranks.len() > 1 && !is_reported {
+            adjudication_reasons.push(AdjudicationReasonInfo::DuplicateRanking {
+                contest_id: contest_id.clone(),
+                option_id: option_id.clone(),
+                ranks: option_ranks,
+            });
+        }
+    }
+
+    if option_ids.is_empty() {
+        adjudication_reasons.push(AdjudicationReasonInfo::Undervote {
+            contest_id: contest_id.clone(),
+            option_ids: vec![],
+            expected: 1,
+        });
+    }
+
+    ContestVotes {
+        contest_id,
+        option_ids,
+        adjudication_reasons,
+        ranks: Some(ranks),
+        straight_party_option_ids: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use types_rs::ballot_card::BallotSide;
+    use types_rs::election::{
+        DistrictId, GridLocation, GridPosition, GridRect, StraightPartyContest, YesNoContest,
+        YesNoOption,
+    };
+    use types_rs::geometry::Rect;
+
+    use crate::scoring::{BubbleGrayscaleStats, MarkClassification, UnitIntervalScore};
+
+    use super::*;
+
+    const THRESHOLDS: MarkThresholds = MarkThresholds {
+        definite: 0.1,
+        marginal: 0.05,
+        write_in_text_area: None,
+    };
+
+    fn candidate_contest(id: &str, seats: u32, candidate_ids: &[&str]) -> Contest {
+        Contest::Candidate(CandidateContest {
+            id: ContestId::from(id.to_owned()),
+            district_id: DistrictId::from("district".to_owned()),
+            title: id.to_owned(),
+            seats,
+            candidates: candidate_ids
+                .iter()
+                .map(|candidate_id| {
+                    Candidate::Named(NamedCandidate {
+                        id: OptionId::from((*candidate_id).to_owned()),
+                        name: (*candidate_id).to_owned(),
+                        party_ids: None,
+                    })
+                })
+                .collect(),
+            allow_write_ins: false,
+            party_id: None,
+            term_description: None,
+        })
+    }
+
+    /// Builds a general-election candidate contest from `(candidate, party)`
+    /// pairs.
+    fn partisan_contest(id: &str, seats: u32, candidates: &[(&str, &str)]) -> Contest {
+        Contest::Candidate(CandidateContest {
+            id: ContestId::from(id.to_owned()),
+            district_id: DistrictId::from("district".to_owned()),
+            title: id.to_owned(),
+            seats,
+            candidates: candidates
+                .iter()
+                .map(|(candidate_id, party_id)| {
+                    Candidate::Named(NamedCandidate {
+                        id: OptionId::from((*candidate_id).to_owned()),
+                        name: (*candidate_id).to_owned(),
+                        party_ids: Some(vec![PartyId::from((*party_id).to_owned())]),
+                    })
+                })
+                .collect(),
+            allow_write_ins: false,
+            party_id: None,
+            term_description: None,
+        })
+    }
+
+    fn straight_party_contest(id: &str, party_ids: &[&str]) -> Contest {
+        Contest::StraightParty(StraightPartyContest {
+            id: ContestId::from(id.to_owned()),
+            district_id: DistrictId::from("district".to_owned()),
+            title: id.to_owned(),
+            option_ids: party_ids
+                .iter()
+                .map(|party_id| PartyId::from((*party_id).to_owned()))
+                .collect(),
+        })
+    }
+
+    fn yes_no_contest(id: &str) -> Contest {
+        Contest::YesNo(YesNoContest {
+            id: ContestId::from(id.to_owned()),
+            district_id: DistrictId::from("district".to_owned()),
+            title: id.to_owned(),
+            description: String::new(),
+            options: ["yes", "no"]
+                .into_iter()
+                .map(|option_id| YesNoOption {
+                    id: OptionId::from(option_id.to_owned()),
+                    label: option_id.to_owned(),
+                })
+                .collect(),
+        })
+    }
+
+    fn ranked_choice_contest(id: &str, max_rankings: u32, candidate_ids: &[&str]) -> Contest {
+        Contest::RankedChoice(RankedChoiceContest {
+            id: ContestId::from(id.to_owned()),
+            district_id: DistrictId::from("district".to_owned()),
+            title: id.to_owned(),
+            max_rankings,
+            candidates: candidate_ids
+                .iter()
+                .map(|candidate_id| {
+                    Candidate::Named(NamedCandidate {
+                        id: OptionId::from((*candidate_id).to_owned()),
+                        name: (*candidate_id).to_owned(),
+                        party_ids: None,
+                    })
+                })
+                .collect(),
+            allow_write_ins: false,
+            party_id: None,
+        })
+    }
+
+    fn election(contests: Vec<Contest>) -> Election {
+        Election {
+            title: "Test".to_owned(),
+            ballot_styles: vec![],
+            precincts: vec![],
+            mark_thresholds: None,
+            contests,
+            ballot_layout: None,
+        }
+    }
+
+    fn scored(
+        contest_id: &str,
+        option_id: &str,
+        fill_score: f32,
+    ) -> (GridPosition, Option<ScoredBubbleMark>) {
+        scored_rank(contest_id, option_id, None, fill_score)
+    }
+
+    fn scored_rank(
+        contest_id: &str,
+        option_id: &str,
+        rank: Option<u32>,
+        fill_score: f32,
+    ) -> (GridPosition, Option<ScoredBubbleMark>) {
+        let location = GridLocation::new(BallotSide::Front, 1.0, 1.0);
+        (
+            GridPosition::Option {
+                sheet_number: 1,
+                side: BallotSide::Front,
+                column: location.column,
+                row: location.row,
+                contest_id: ContestId::from(contest_id.to_owned()),
+                option_id: OptionId::from(option_id.to_owned()),
+                rank,
+                bounds: GridRect::default(),
+            },
+            Some(ScoredBubbleMark {
+                location,
+                match_score: UnitIntervalScore(0.9),
+                fill_score: UnitIntervalScore(fill_score),
+                expected_bounds: Rect::new(0, 0, 1, 1),
+                matched_bounds: Rect::new(0, 0, 1, 1),
+                grayscale_stats: BubbleGrayscaleStats::default(),
+                classification: MarkClassification::Blank,
+            }),
+        )
+    }
+
+    fn option_ids(ids: &[&str]) -> Vec<OptionId> {
+        ids.iter()
+            .map(|id| OptionId::from((*id).to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_mark_status_thresholds() {
+        let (_, mark) = scored("c", "o", 0.1);
+        assert_eq!(
+            MarkStatus::for_scored_bubble_mark(mark.as_ref(), &THRESHOLDS),
+            MarkStatus::Marked
+        );
+        let (_, mark) = scored("c", "o", 0.07);
+        assert_eq!(
+            MarkStatus::for_scored_bubble_mark(mark.as_ref(), &THRESHOLDS),
+            MarkStatus::Marginal
+        );
+        let (_, mark) = scored("c", "o", 0.01);
+        assert_eq!(
+            MarkStatus::for_scored_bubble_mark(mark.as_ref(), &THRESHOLDS),
+            MarkStatus::Unmarked
+        );
+        assert_eq!(
+            MarkStatus::for_scored_bubble_mark(None, &THRESHOLDS),
+            MarkStatus::Unmarked
+        );
+    }
+
+    #[test]
+    fn test_votes_undervote_and_overvote() {
+        let election = election(vec![
+            candidate_contest("mayor", 1, &["alice", "bob"]),
+            candidate_contest("council", 2, &["carol", "dave", "erin"]),
+            yes_no_contest("measure"),
+        ]);
+        let front = vec![
+            scored("mayor", "alice", 0.5),
+            scored("mayor", "bob", 0.4),
+            scored("council", "carol", 0.3),
+            scored("council", "dave", 0.06),
+            scored("council", "erin", 0.0),
+        ];
+        let back = vec![scored("measure", "yes", 0.2), scored("measure", "no", 0.0)];
+
+        let votes = derive_votes(&election, [&front, &back], &THRESHOLDS).unwrap();
+
+        assert!(!votes.is_blank_ballot);
+        assert_eq!(votes.contests.len(), 3);
+
+        let mayor = votes.contest(&ContestId::from("mayor".to_owned())).unwrap();
+        assert_eq!(mayor.option_ids, option_ids(&["alice", "bob"]));
+        assert!(mayor.is_overvote());
+
+        let council = votes
+            .contest(&ContestId::from("council".to_owned()))
+            .unwrap();
+        assert_eq!(council.option_ids, option_ids(&["carol"]));
+        assert_eq!(
+            council.adjudication_reasons,
+            vec![
+                AdjudicationReasonInfo::MarginalMark {
+                    contest_id: ContestId::from("council".to_owned()),
+                    option_id: OptionId::from("dave".to_owned()),
+                    classification: MarkClassification::Blank,
+                },
+                AdjudicationReasonInfo::Undervote {
+                    contest_id: ContestId::from("council".to_owned()),
+                    option_ids: option_ids(&["carol"]),
+                    expected: 2,
+                },
+            ]
+        );
+
+        let measure = votes
+            .contest(&ContestId::from("measure".to_owned()))
+            .unwrap();
+        assert_eq!(measure.option_ids, option_ids(&["yes"]));
+        assert!(measure.adjudication_reasons.is_empty());
+    }
+
+    #[test]
+    fn test_votes_option_in_multiple_positions_uses_strongest_mark() {
+        let election = election(vec![candidate_contest("mayor", 1, &["alice", "bob"])]);
+        let marks = vec![
+            scored("mayor", "alice", 0.0),
+            scored("mayor", "bob", 0.0),
+            scored("mayor", "alice", 0.5),
+        ];
+
+        let votes = derive_votes(&election, [&marks], &THRESHOLDS).unwrap();
+
+        assert_eq!(votes.contests[0].option_ids, option_ids(&["alice"]));
+        assert!(votes.contests[0].adjudication_reasons.is_empty());
+    }
+
+    #[test]
+    fn test_votes_marginal_marks_by_classification() {
+        let election = election(vec![candidate_contest(
+            "mayor",
+            2,
+            &["alice", "bob", "carol"],
+        )]);
+        let classified = |option_id: &str, fill_score: f32, classification| {
+            let (grid_position, mark) = scored("mayor", option_id, fill_score);
+            (
+                grid_position,
+                mark.map(|mark| ScoredBubbleMark {
+                    classification,
+                    ..mark
+                }),
+            )
+        };
+        let marks = vec![
+            // too little graphite left to reach the marginal threshold
+            classified("alice", 0.01, MarkClassification::Erased),
+            classified("bob", 0.06, MarkClassification::Partial),
+            classified("carol", 0.01, MarkClassification::StrayDot),
+        ];
+
+        let votes = derive_votes(&election, [&marks], &THRESHOLDS).unwrap();
+
+        let contest_id = ContestId::from("mayor".to_owned());
+        assert!(votes.contests[0].option_ids.is_empty());
+        assert_eq!(
+            votes.contests[0].adjudication_reasons[..2],
+            [
+                AdjudicationReasonInfo::MarginalMark {
+                    contest_id: contest_id.clone(),
+                    option_id: OptionId::from("alice".to_owned()),
+                    classification: MarkClassification::Erased,
+                },
+                AdjudicationReasonInfo::MarginalMark {
+                    contest_id,
+                    option_id: OptionId::from("bob".to_owned()),
+                    classification: MarkClassification::Partial,
+                },
+            ]
+        );
+        assert!(matches!(
+            votes.contests[0].adjudication_reasons[2..],
+            [AdjudicationReasonInfo::Undervote { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_votes_blank_ballot() {
+        let election = election(vec![
+            candidate_contest("mayor", 1, &["alice"]),
+            yes_no_contest("measure"),
+        ]);
+        let marks = vec![
+            scored("mayor", "alice", 0.06),
+            scored("measure", "yes", 0.0),
+            scored("measure", "no", 0.0),
+        ];
+
+        let votes = derive_votes(&election, [&marks], &THRESHOLDS).unwrap();
+
+        assert!(votes.is_blank_ballot);
+        assert_eq!(
+            votes.adjudication_reasons().last(),
+            Some(&AdjudicationReasonInfo::BlankBallot)
+        );
+        assert_eq!(
+            votes
+                .adjudication_reasons()
+                .iter()
+                .filter(|reason| matches!(reason, AdjudicationReasonInfo::Undervote { .. }))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_votes_no_contests_is_not_blank() {
+        let votes = derive_votes(&election(vec![]), std::iter::empty(), &THRESHOLDS).unwrap();
+        assert!(!votes.is_blank_ballot);
+        assert!(votes.adjudication_reasons().is_empty());
+    }
+
+    #[test]
+    fn test_votes_unknown_contest() {
+        let marks = vec![scored("mayor", "alice", 0.5)];
+        assert!(matches!(
+            derive_votes(&election(vec![]), [&marks], &THRESHOLDS),
+            Err(Error::InvalidElection { .. })
+        ));
+    }
+
+    fn straight_party_election() -> Election {
+        election(vec![
+            straight_party_contest("straight-party", &["dem", "rep"]),
+            partisan_contest("governor", 1, &[("gov-dem", "dem"), ("gov-rep", "rep")]),
+            partisan_contest(
+                "council",
+                2,
+                &[
+                    ("council-dem", "dem"),
+                    ("council-rep-1", "rep"),
+                    ("council-rep-2", "rep"),
+                ],
+            ),
+            partisan_contest(
+                "board",
+                1,
+                &[
+                    ("board-rep-1", "rep"),
+                    ("board-rep-2", "rep"),
+                    ("board-dem", "dem"),
+                ],
+            ),
+            yes_no_contest("measure"),
+        ])
+    }
+
+    #[test]
+    fn test_straight_party_expands_contests_without_votes() {
+        let election = straight_party_election();
+        let marks = vec![
+            scored("straight-party", "dem", 0.0),
+            scored("straight-party", "rep", 0.5),
+            scored("governor", "gov-dem", 0.5),
+            scored("governor", "gov-rep", 0.0),
+            scored("council", "council-dem", 0.0),
+            scored("council", "council-rep-1", 0.0),
+            scored("council", "council-rep-2", 0.0),
+            scored("board", "board-rep-1", 0.0),
+            scored("board", "board-rep-2", 0.0),
+            scored("board", "board-dem", 0.0),
+            scored("measure", "yes", 0.0),
+            scored("measure", "no", 0.0),
+        ];
+
+        let votes = derive_votes(&election, [&marks], &THRESHOLDS).unwrap();
+
+        assert_eq!(
+            votes.straight_party_id,
+            Some(PartyId::from("rep".to_owned()))
+        );
+
+        // explicit votes are left alone
+        let governor = votes
+            .contest(&ContestId::from("governor".to_owned()))
+            .unwrap();
+        assert_eq!(governor.option_ids, option_ids(&["gov-dem"]));
+        assert!(governor.straight_party_option_ids.is_empty());
+
+        // the party's candidates fit in the contest's seats
+        let council = votes
+            .contest(&ContestId::from("council".to_owned()))
+            .unwrap();
+        assert_eq!(
+            council.option_ids,
+            option_ids(&["council-rep-1", "council-rep-2"])
+        );
+        assert_eq!(council.straight_party_option_ids, council.option_ids);
+        assert!(matches!(
+            council.adjudication_reasons.as_slice(),
+            [AdjudicationReasonInfo::Undervote { .. }]
+        ));
+
+        // the party's candidates do not fit in the contest's seats
+        let board = votes.contest(&ContestId::from("board".to_owned())).unwrap();
+        assert!(board.option_ids.is_empty());
+
+        // non-candidate contests are never expanded
+        let measure = votes
+            .contest(&ContestId::from("measure".to_owned()))
+            .unwrap();
+        assert!(measure.option_ids.is_empty());
+
+        assert_eq!(
+            votes
+                .straight_party_expanded_contest_ids()
+                .collect::<Vec<_>>(),
+            vec![&ContestId::from("council".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_straight_party_overvote_does_not_expand() {
+        let election = straight_party_election();
+        let marks = vec![
+            scored("straight-party", "dem", 0.5),
+            scored("straight-party", "rep", 0.5),
+            scored("governor", "gov-dem", 0.0),
+            scored("governor", "gov-rep", 0.0),
+        ];
+
+        let votes = derive_votes(&election, [&marks], &THRESHOLDS).unwrap();
+
+        assert_eq!(votes.straight_party_id, None);
+        assert!(votes
+            .contest(&ContestId::from("governor".to_owned()))
+            .unwrap()
+            .option_ids
+            .is_empty());
+        assert_eq!(votes.straight_party_expanded_contest_ids().count(), 0);
+    }
+
+    /// Scores every (candidate, rank) bubble of a ranked-choice contest,
+    /// marking the ranks listed in `marked`.
+    fn scored_rankings(
+        contest_id: &str,
+        candidate_ids: &[&str],
+        max_rankings: u32,
+        marked: &[(&str, u32)],
+    ) -> Vec<(GridPosition, Option<ScoredBubbleMark>)> {
+        candidate_ids
+            .iter()
+            .flat_map(|candidate_id| {
+                (1..=max_rankings).map(move |rank| {
+                    let is_marked = marked.contains(&(*candidate_id, rank));
+                    scored_rank(
+                        contest_id,
+                        candidate_id,
+                        Some(rank),
+                        if is_marked { 0.5 } else { 0.0 },
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ranked_choice_votes() {
+        let candidates = ["alice", "bob", "carol"];
+        let election = election(vec![ranked_choice_contest("mayor", 3, &candidates)]);
+        let marks = scored_rankings("mayor", &candidates, 3, &[("carol", 1), ("alice", 2)]);
+
+        let votes = derive_votes(&election, [&marks], &THRESHOLDS).unwrap();
+
+        let mayor = &votes.contests[0];
+        assert_eq!(mayor.option_ids, option_ids(&["carol", "alice"]));
+        assert_eq!(
+            mayor.ranks,
+            Some(vec![option_ids(&["carol"]), option_ids(&["alice"]), vec![]])
+        );
+        assert!(mayor.adjudication_reasons.is_empty());
+        assert!(!votes.is_blank_ballot);
+    }
+
+    #[test]
+    fn test_ranked_choice_skipped_duplicate_and_overvoted_ranks() {
+        let candidates = ["alice", "bob", "carol", "dave"];
+        let election = election(vec![ranked_choice_contest("mayor", 4, &candidates)]);
+        let marks = scored_rankings(
+            "mayor",
+            &candidates,
+            4,
+            &[("bob", 1), ("alice", 3), ("carol", 3), ("bob", 4)],
+        );
+
+        let votes = derive_votes(&election, [&marks], &THRESHOLDS).unwrap();
+
+        let contest_id = ContestId::from("mayor".to_owned());
+        let mayor = &votes.contests[0];
+        assert_eq!(mayor.option_ids, option_ids(&["bob"]));
+        assert_eq!(
+            mayor.adjudication_reasons,
+            vec![
+                AdjudicationReasonInfo::SkippedRank {
+                    contest_id: contest_id.clone(),
+                    rank: 2,
+                },
+                AdjudicationReasonInfo::OvervotedRank {
+                    contest_id: contest_id.clone(),
+                    rank: 3,
+                    option_ids: option_ids(&["alice", "carol"]),
+                },
+                AdjudicationReasonInfo::DuplicateRanking {
+                    contest_id,
+                    option_id: OptionId::from("bob".to_owned()),
+                    ranks: vec![1, 4],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ranked_choice_nothing_ranked() {
+        let candidates = ["alice", "bob"];
+        let election = election(vec![ranked_choice_contest("mayor", 2, &candidates)]);
+        let mut marks = scored_rankings("mayor", &candidates, 2, &[]);
+        marks[1] = scored_rank("mayor", "alice", Some(2), 0.06);
+
+        let votes = derive_votes(&election, [&marks], &THRESHOLDS).unwrap();
+
+        let mayor = &votes.contests[0];
+        assert!(mayor.option_ids.is_empty());
+        assert_eq!(
+            mayor.adjudication_reasons,
+            vec![
+                AdjudicationReasonInfo::MarginalMark {
+                    contest_id: ContestId::from("mayor".to_owned()),
+                    option_id: OptionId::from("alice".to_owned()),
+                    classification: MarkClassification::Blank,
+                },
+                AdjudicationReasonInfo::Undervote {
+                    contest_id: ContestId::from("mayor".to_owned()),
+                    option_ids: vec![],
+                    expected: 1,
+                },
+            ]
+        );
+        assert!(votes.is_blank_ballot);
+    }
+
+    #[test]
+    fn test_flag_possible_voter_intent() {
+        let election = election(vec![
+            candidate_contest("mayor", 1, &["alice", "bob"]),
+            yes_no_contest("measure"),
+        ]);
+        let marks = vec![
+            scored("mayor", "alice", 0.5),
+            scored("mayor", "bob", 0.0),
+            scored("measure", "yes", 0.0),
+            scored("measure", "no", 0.5),
+        ];
+        let mayor = ContestId::from("mayor".to_owned());
+        let unknown = ContestId::from("unknown".to_owned());
+
+        let mut votes = derive_votes(&election, [&marks], &THRESHOLDS).unwrap();
+        flag_possible_voter_intent(&mut votes, [&mayor, &unknown, &mayor]);
+
+        assert_eq!(
+            votes.contest(&mayor).unwrap().adjudication_reasons,
+            vec![AdjudicationReasonInfo::PossibleVoterIntent {
+                contest_id: mayor.clone()
+            }]
+        );
+        assert!(votes
+            .contest(&ContestId::from("measure".to_owned()))
+            .unwrap()
+            .adjudication_reasons
+            .is_empty());
+        assert_eq!(
+            votes.contest(&mayor).unwrap().option_ids,
+            option_ids(&["alice"])
+        );
+    }
+}
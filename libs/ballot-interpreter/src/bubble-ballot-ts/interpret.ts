@@ -3,13 +3,18 @@ import { sliceBallotHashForEncoding } from '@votingworks/ballot-encoder';
 import { ImageData } from 'canvas';
 import {
   ElectionDefinition,
+  MarkThresholds,
   SheetOf,
   DEFAULT_MAX_CUMULATIVE_STREAK_WIDTH,
   DEFAULT_RETRY_STREAK_WIDTH_THRESHOLD,
 } from '@votingworks/types';
 import type { BridgeInterpretOptions } from '../../index';
 import { napi } from './napi';
-import { BridgeInterpretResult, HmpbInterpretResult } from './types';
+import {
+  BridgeInterpretResult,
+  ChannelMix,
+  HmpbInterpretResult,
+} from './types';
 
 /**
  * Options for interpreting a ballot at the bridge layer.
@@ -19,10 +24,19 @@ export interface InterpretOptions {
   electionDefinition: ElectionDefinition;
   ballotImages: SheetOf<string> | SheetOf<ImageData>;
   scoreWriteIns?: boolean;
+  cropAreas?: boolean;
+  detectStrayMarks?: boolean;
+  detectLineArtifacts?: boolean;
+  inferBallotStyle?: boolean;
   disableVerticalStreakDetection?: boolean;
   minimumDetectedScale?: number;
   maxCumulativeStreakWidth?: number;
   retryStreakWidthThreshold?: number;
+  markThresholds?: MarkThresholds;
+  scanPixelsPerInch?: number;
+  channelMix?: ChannelMix;
+  bubbleTemplate?: string;
+  metadataSource?: 'qr-code' | 'timing-marks';
   debug?: boolean;
   frontNormalizedImageOutputPath?: string;
   backNormalizedImageOutputPath?: string;
@@ -79,12 +93,21 @@ function buildBridgeOptions(options: InterpretOptions): BridgeInterpretOptions {
     debugBasePathSideA,
     debugBasePathSideB,
     scoreWriteIns: options.scoreWriteIns,
+    cropAreas: options.cropAreas,
+    detectStrayMarks: options.detectStrayMarks,
+    detectLineArtifacts: options.detectLineArtifacts,
+    inferBallotStyle: options.inferBallotStyle,
     disableVerticalStreakDetection: options.disableVerticalStreakDetection,
     minimumDetectedScale: options.minimumDetectedScale,
     maxCumulativeStreakWidth:
       options.maxCumulativeStreakWidth ?? DEFAULT_MAX_CUMULATIVE_STREAK_WIDTH,
     retryStreakWidthThreshold:
       options.retryStreakWidthThreshold ?? DEFAULT_RETRY_STREAK_WIDTH_THRESHOLD,
+    markThresholds: options.markThresholds,
+    scanPixelsPerInch: options.scanPixelsPerInch,
+    channelMix: options.channelMix,
+    bubbleTemplate: options.bubbleTemplate,
+    metadataSource: options.metadataSource,
     frontNormalizedImageOutputPath: options.frontNormalizedImageOutputPath,
     backNormalizedImageOutputPath: options.backNormalizedImageOutputPath,
   };
@@ -1,5 +1,5 @@
 import {
-  HmpbBallotPaperSize,
+  InterpretableHmpbBallotPaperSize,
   GridPosition,
   HmpbBallotPageMetadata,
   PrecinctId,
@@ -7,6 +7,9 @@ import {
   Side,
   BallotType,
   ContestId,
+  ContestOptionId,
+  AdjudicationReasonInfo,
+  PartyId,
 } from '@votingworks/types';
 import { Optional, Result } from '@votingworks/basics';
 
@@ -104,6 +107,38 @@ export type BridgeInterpretResult =
 export interface InterpretedBallotCard {
   front: InterpretedBallotPage;
   back: InterpretedBallotPage;
+  votes: BallotCardVotes;
+  inferredBallotStyle?: InferredBallotStyle;
+}
+
+/**
+ * The ballot style and sheet of a card whose QR codes couldn't be read, as
+ * identified from where its bubbles are printed.
+ */
+export interface InferredBallotStyle {
+  ballotStyleId: BallotStyleId;
+  sheetNumber: number;
+  precinctIds: PrecinctId[];
+  score: number;
+  runnerUpScore: Optional<number>;
+}
+
+/** Votes derived from the scored bubbles of a ballot card. */
+export interface BallotCardVotes {
+  contests: ContestVotes[];
+  isBlankBallot: boolean;
+  straightPartyId: Optional<PartyId>;
+  isBallotStyleInferred: boolean;
+}
+
+/** Votes and adjudication reasons for a single contest. */
+export interface ContestVotes {
+  contestId: ContestId;
+  optionIds: ContestOptionId[];
+  adjudicationReasons: AdjudicationReasonInfo[];
+  /** For ranked-choice contests, the options marked at each rank. */
+  ranks?: ContestOptionId[][];
+  straightPartyOptionIds: ContestOptionId[];
 }
 
 /** A successfully imported ballot page. */
@@ -113,6 +148,74 @@ export interface InterpretedBallotPage {
   marks: ScoredBubbleMarks;
   writeIns: ScoredPositionArea[];
   contestLayouts: InterpretedContestLayout[];
+  quality: QualityReport;
+  crops?: AreaCrops;
+  strayMarks?: ContestStrayMarks[];
+}
+
+/**
+ * Image-health measurements for a ballot page, taken from its timing marks.
+ */
+export interface QualityReport {
+  /** Rotation of the timing mark grid in degrees, positive is clockwise. */
+  skewDegrees: number;
+  horizontalPixelsPerInch: number;
+  verticalPixelsPerInch: number;
+  /** Measured horizontal density relative to the expected density. */
+  horizontalStretch: number;
+  /** Measured vertical density relative to the expected density. */
+  verticalStretch: number;
+  paperLuminosity: number;
+  inkLuminosity: number;
+  /** Paper/ink luminosity difference as a fraction of the full range. */
+  contrast: number;
+  /** Sharpness of the timing mark edges, from 0 (blurry) to 1 (sharp). */
+  focus: number;
+  /** The luminosity threshold used to separate ink from paper. */
+  threshold: number;
+}
+
+/**
+ * A perspective-corrected, binarized image of an area of a ballot page, sized
+ * as the area would be in a straight 200 DPI scan.
+ */
+export interface AreaCrop {
+  shape: Quadrilateral;
+  width: PixelUnit;
+  height: PixelUnit;
+  /** Base64-encoded PNG. */
+  png: string;
+}
+
+/** A crop of the area where a voter would write in a candidate's name. */
+export interface WriteInAreaCrop extends AreaCrop {
+  gridPosition: GridPosition;
+}
+
+/** A crop of a contest. */
+export interface ContestAreaCrop extends AreaCrop {
+  contestId: ContestId;
+}
+
+/** Crops of the write-in areas and contests on a ballot page. */
+export interface AreaCrops {
+  writeIns: WriteInAreaCrop[];
+  contests: ContestAreaCrop[];
+}
+
+/**
+ * A blob of ink in a contest that is not on a bubble or in a write-in area and
+ * is too large to be printed text, e.g. a circled name or a checkmark.
+ */
+export interface StrayMark {
+  bounds: Rect;
+  pixelCount: number;
+}
+
+/** The stray marks found in a contest, a possible indication of voter intent. */
+export interface ContestStrayMarks {
+  contestId: ContestId;
+  marks: StrayMark[];
 }
 
 /** The pixel bounds outlining a contest option in the normalized ballot image. */
@@ -141,13 +244,57 @@ export interface ScoredPositionArea {
 }
 
 /** Metadata from the ballot card. */
-export type BallotPageMetadata = BallotPageQrCodeMetadata;
+export type BallotPageMetadata =
+  | BallotPageQrCodeMetadata
+  | BallotPageInferredMetadata
+  | BallotPageTimingMarkMetadata;
 
 /** Metadata from a ballot card QR code. */
 export interface BallotPageQrCodeMetadata extends HmpbBallotPageMetadata {
   source: 'qr-code';
 }
 
+/**
+ * Metadata inferred from where bubbles are printed on a ballot card whose QR
+ * codes couldn't be read. Only the ballot style and page are known, so the
+ * precinct, test mode flag and ballot type must be checked some other way.
+ */
+export interface BallotPageInferredMetadata
+  extends Pick<HmpbBallotPageMetadata, 'ballotStyleId' | 'pageNumber'> {
+  source: 'inferred';
+}
+
+/**
+ * Metadata decoded from the bottom row of timing marks on an AccuVote ballot
+ * page, which has no QR code. The card carries no ballot hash, test mode flag
+ * or ballot type, so those must be checked some other way.
+ */
+export interface BallotPageTimingMarkMetadata
+  extends Pick<
+    HmpbBallotPageMetadata,
+    'precinctId' | 'ballotStyleId' | 'pageNumber'
+  > {
+  source: 'timing-marks';
+  timingMarkMetadata: TimingMarkMetadata;
+}
+
+/** Metadata as encoded in the bottom row of timing marks on an AccuVote card. */
+export type TimingMarkMetadata =
+  | {
+      side: 'front';
+      mod4Checksum: number;
+      batchOrPrecinctNumber: number;
+      cardNumber: number;
+      sequenceNumber: number;
+    }
+  | {
+      side: 'back';
+      electionDay: number;
+      electionMonth: number;
+      electionYear: number;
+      electionType: string;
+    };
+
 /** Represents partial timing marks found in a ballot card. */
 export interface PartialTimingMarks {
   geometry: Geometry;
@@ -177,16 +324,56 @@ export interface TimingMarks {
   bottomLeftMark: CandidateTimingMark;
   bottomRightMark: CandidateTimingMark;
   borderMarks: BorderMarks;
+  inferredMarks: InferredMarks;
+  /**
+   * The corner whose mark was inferred from the other three corners, e.g.
+   * because it was folded over or torn off.
+   */
+  inferredCorner?: Corner;
+  warp: GridWarp;
 }
 
 /**
- * The per-border timing-mark sequences. Only the borders parallel to the scan
- * direction (currently left and right) are recorded; the grid is reconstructed
- * from those plus the four corner marks.
+ * A corner of the timing mark grid.
+ */
+export type Corner = 'topLeft' | 'topRight' | 'bottomLeft' | 'bottomRight';
+
+/**
+ * The per-border timing-mark sequences. The borders parallel to the scan
+ * direction (currently left and right) are always complete, possibly with some
+ * inferred marks, and the grid is reconstructed from them. The top and bottom borders are empty unless all of
+ * their marks were found, and are only used to fit the grid warp.
  */
 export interface BorderMarks {
   left: CandidateTimingMark[];
   right: CandidateTimingMark[];
+  top: CandidateTimingMark[];
+  bottom: CandidateTimingMark[];
+}
+
+/**
+ * Indexes into the left and right border marks of the marks that could not be
+ * found (e.g. because they were torn off or scribbled over) and were inferred
+ * from the marks around them instead.
+ */
+export interface InferredMarks {
+  left: number[];
+  right: number[];
+}
+
+/**
+ * A correction to the timing mark grid fitted to every timing mark on the
+ * border, accounting for rows and columns that aren't straight lines.
+ */
+export interface GridWarp {
+  residuals: WarpResiduals;
+}
+
+/** How far the timing marks are from where the fitted grid places them. */
+export interface WarpResiduals {
+  markCount: number;
+  rms: SubPixelUnit;
+  max: SubPixelUnit;
 }
 
 /** A possible timing mark. */
@@ -241,8 +428,62 @@ export interface ScoredBubbleMark {
    * determined to be the best match.
    */
   matchedBounds: Rect;
+
+  /**
+   * Grayscale statistics for the template's blank-paper pixels at
+   * `matchedBounds`. Unlike `fillScore`, these see ink that is lighter than the
+   * image threshold, e.g. what is left after an erasure.
+   */
+  grayscaleStats: BubbleGrayscaleStats;
+
+  /**
+   * What kind of mark, if any, `grayscaleStats` suggests the voter made.
+   */
+  classification: MarkClassification;
 }
 
+/**
+ * Grayscale statistics for the blank-paper pixels of a bubble, leaving out
+ * those right next to the outline. Darkness is relative to the paper: 0 is
+ * paper and 1 is black. Positions are relative to the center of the bubble
+ * template, with its edges at a distance of 1.
+ */
+export interface BubbleGrayscaleStats {
+  meanDarkness: f32;
+  darknessVariance: f32;
+  /** Fraction of pixels darker than the image threshold. */
+  inkCoverage: f32;
+  /** Fraction of pixels with any visible mark, however faint. */
+  visibleCoverage: f32;
+  visibleMeanDarkness: f32;
+  inkCentroidOffset: f32;
+  inkSpread: f32;
+}
+
+/**
+ * What kind of mark a voter made in a bubble. Independent of the mark
+ * thresholds; reported with marginal marks, and an `'erased'` bubble is
+ * flagged as marginal even if it scores below the marginal threshold.
+ */
+export type MarkClassification =
+  | 'blank'
+  | 'filled'
+  | 'erased'
+  | 'partial'
+  | 'strayDot';
+
+/**
+ * How to combine the channels of a color scan into a grayscale image: a
+ * named mix, or comma-separated red, green and blue weights, e.g.
+ * `'0.2,0.7,0.1'`. `'red'` drops out red and pink ink.
+ */
+export type ChannelMix =
+  | 'luma'
+  | 'red'
+  | 'green'
+  | 'blue'
+  | `${number},${number},${number}`;
+
 /**
  * A value between 0 and 1, inclusive.
  *
@@ -275,7 +516,7 @@ export enum BallotSide {
  * ballot card.
  */
 export interface Geometry {
-  ballotPaperSize: HmpbBallotPaperSize;
+  ballotPaperSize: InterpretableHmpbBallotPaperSize;
   pixelsPerInch: PixelUnit;
   canvasSize: Size<PixelUnit>;
   contentArea: Rect;
@@ -360,6 +601,7 @@ export type MetadataMismatch =
 export type InterpretError = { isBubbleBallot: boolean } & (
   | { type: 'borderInsetNotFound'; path: string }
   | { type: 'invalidQrCodeMetadata'; label: string; message: string }
+  | { type: 'invalidTimingMarkMetadata'; label: string; message: string }
   | {
       type: 'mismatchedBallotMetadata';
       sideA: HmpbBallotPageMetadata;
@@ -393,6 +635,11 @@ export type InterpretError = { isBubbleBallot: boolean } & (
       label: string;
       xCoordinates: PixelPosition[];
     }
+  | {
+      type: 'lineArtifactsDetected';
+      label: string;
+      contestIds: ContestId[];
+    }
   | {
       type: 'invalidElection';
       message: string;
@@ -418,7 +665,9 @@ export type RustCandidateVote =
 export type RustContestVote =
   | { type: 'candidate'; value: RustCandidateVote[] }
   | { type: 'yesNo'; value: string[] }
-  | { type: 'straightParty'; value: string[] };
+  | { type: 'straightParty'; value: string[] }
+  /** Candidates in the order the voter ranked them, first choice first. */
+  | { type: 'rankedChoice'; value: RustCandidateVote[] };
 
 /**
  * Rust-decoded CastVoteRecord (VB\x01 prelude).
@@ -440,3 +689,29 @@ export interface RustCastVoteRecord {
  * Result of decoding raw BMD ballot bytes via the Rust decoder.
  */
 export type BridgeDecodeBmdResult = RustCastVoteRecord;
+
+/** A summary ballot interpreted and validated by the Rust interpreter. */
+export interface InterpretedSummaryBallot {
+  castVoteRecord: RustCastVoteRecord;
+
+  /**
+   * Which image has the summary ballot: `front` for side A, `back` for side
+   * B. The other side of the card is expected to be blank.
+   */
+  side: BallotSide;
+  orientation: Orientation;
+}
+
+/** Reasons the Rust interpreter rejects a summary ballot. */
+export type InterpretSummaryBallotError =
+  | { type: 'qrCodeNotFound' }
+  | { type: 'multipleQrCodes' }
+  | { type: 'invalidQrCodeData'; message: string }
+  | { type: 'invalidBallotHash'; expected: string; actual: string }
+  | { type: 'invalidPageNumber'; pageNumber: number; totalPages: number }
+  | { type: 'invalidTestMode'; expected: boolean; actual: boolean }
+  | { type: 'invalidPrecinct'; precinctId: string };
+
+export type BridgeInterpretSummaryBallotResult =
+  | { type: 'ok'; value: InterpretedSummaryBallot }
+  | { type: 'err'; value: InterpretSummaryBallotError };
@@ -9,6 +9,7 @@ import {
   getContests,
   getBallotStyle,
   Candidate,
+  RankedChoiceContest,
   YesNoVote,
 } from '@votingworks/types';
 import {
@@ -21,6 +22,7 @@ import {
   arbitraryBallotId,
   arbitraryElectionDefinition,
 } from '@votingworks/test-utils';
+import { readElectionGeneralDefinition } from '@votingworks/fixtures';
 import { Buffer } from 'node:buffer';
 import { throwIllegalValue } from '@votingworks/basics';
 import { napi } from './bubble-ballot-ts/napi';
@@ -33,7 +35,9 @@ import type {
 /**
  * Generates votes for a set of contests. For candidate contests, selects up
  * to `seats` named candidates (plus a write-in if there's room and
- * `includeWriteIns` is set). For yes/no contests, picks yes.
+ * `includeWriteIns` is set). For ranked-choice contests, ranks up to
+ * `maxRankings` named candidates (plus a write-in first choice if there's
+ * room and `includeWriteIns` is set). For yes/no contests, picks yes.
  */
 function generateVotesForContests(
   contests: readonly Contest[],
@@ -71,6 +75,21 @@ function generateVotesForContests(
         votes[contest.id] = contest.optionIds.slice(0, 1);
         break;
       }
+      case 'ranked-choice': {
+        // Rank candidates last to first so that the ranked order differs from
+        // the order the candidates are listed in.
+        const ranked = contest.candidates
+          .filter((c) => !c.isWriteIn)
+          .reverse()
+          .slice(0, contest.maxRankings);
+        votes[contest.id] =
+          includeWriteIns &&
+          contest.allowWriteIns &&
+          ranked.length < contest.maxRankings
+            ? [{ id: 'write-in-TEST', name: 'TEST', isWriteIn: true }, ...ranked]
+            : ranked;
+        break;
+      }
       default:
         throwIllegalValue(contest);
     }
@@ -112,7 +131,7 @@ function normalizeRustVotes(
     const contest = contests.find((c) => c.id === contestId);
     if (!contest) continue;
 
-    if (rustVote.type === 'candidate') {
+    if (rustVote.type === 'candidate' || rustVote.type === 'rankedChoice') {
       normalized[contestId] = rustVote.value.map((cv) => cv.candidateId);
     } else {
       normalized[contestId] = rustVote.value;
@@ -261,7 +280,8 @@ function tsVotesToRustVotes(
     if (voteArr.length === 0) continue;
 
     switch (contest.type) {
-      case 'candidate': {
+      case 'candidate':
+      case 'ranked-choice': {
         const candidates: RustCandidateVote[] = voteArr.map((c) => {
           const candidate = c as Candidate;
           if (candidate.isWriteIn) {
@@ -276,7 +296,10 @@ function tsVotesToRustVotes(
             candidateId: candidate.id,
           };
         });
-        rustVotes[contest.id] = { type: 'candidate', value: candidates };
+        rustVotes[contest.id] =
+          contest.type === 'candidate'
+            ? { type: 'candidate', value: candidates }
+            : { type: 'rankedChoice', value: candidates };
         break;
       }
       case 'yesno': {
@@ -378,3 +401,84 @@ test('multi-page BMD ballot: Rust encode matches TS decode', async () => {
     MULTI_PAGE_FC_PARAMS
   );
 });
+
+/**
+ * The general election fixture with a ranked-choice contest added to the
+ * first ballot style, since generated elections have none.
+ */
+function rankedChoiceElectionDefinition(): {
+  electionDefinition: ReturnType<typeof readElectionGeneralDefinition>;
+  contest: RankedChoiceContest;
+} {
+  const baseElectionDefinition = readElectionGeneralDefinition();
+  const { election } = baseElectionDefinition;
+  const contest: RankedChoiceContest = {
+    id: 'mayor-rcv',
+    type: 'ranked-choice',
+    districtId: election.ballotStyles[0]!.districts[0]!,
+    title: 'Mayor',
+    maxRankings: 3,
+    candidates: [
+      { id: 'alice', name: 'Alice' },
+      { id: 'bob', name: 'Bob' },
+      { id: 'carol', name: 'Carol' },
+      { id: 'dave', name: 'Dave' },
+    ],
+    allowWriteIns: true,
+  };
+  return {
+    electionDefinition: {
+      ...baseElectionDefinition,
+      election: { ...election, contests: [...election.contests, contest] },
+    },
+    contest,
+  };
+}
+
+test('ranked-choice BMD ballot round-trips between TS and Rust', async () => {
+  const { electionDefinition, contest } = rankedChoiceElectionDefinition();
+  const { election, ballotHash } = electionDefinition;
+  const ballotStyle = election.ballotStyles[0]!;
+  const precinct = election.precincts.find((p) =>
+    ballotStyle.precincts.includes(p.id)
+  )!;
+  const rankings: Candidate[] = [
+    { id: 'carol', name: 'Carol' },
+    { id: 'write-in-ZED', name: 'ZED', isWriteIn: true },
+    { id: 'alice', name: 'Alice' },
+  ];
+  const page: SummaryBallotPage = {
+    ballotHash,
+    ballotStyleId: ballotStyle.id,
+    precinctId: precinct.id,
+    isTestMode: true,
+    ballotType: BallotType.Precinct,
+    pageNumber: 1,
+    totalPages: 1,
+    ballotAuditId: 'ranked-choice-audit-id',
+    contests: [contest],
+    votes: { [contest.id]: rankings },
+  };
+
+  const encoded = encodeSummaryBallotPage(election, page);
+  const decoded = await napi.decodeBmdBallotData(
+    election,
+    Buffer.from(encoded)
+  );
+  expect(decoded.contestIds).toEqual([contest.id]);
+  expect(decoded.votes[contest.id]).toEqual<RustContestVote>({
+    type: 'rankedChoice',
+    value: [
+      { type: 'namedCandidate', candidateId: 'carol' },
+      { type: 'writeInCandidate', candidateId: 'write-in-ZED', name: 'ZED' },
+      { type: 'namedCandidate', candidateId: 'alice' },
+    ],
+  });
+
+  const reencoded = await napi.encodeBmdBallotData(election, decoded);
+  expect(new Uint8Array(reencoded)).toEqual(encoded);
+  expect(
+    decodeSummaryBallotPage(electionDefinition, new Uint8Array(reencoded))
+      .votes[contest.id]
+  ).toEqual(rankings);
+});
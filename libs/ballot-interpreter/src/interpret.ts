@@ -8,6 +8,7 @@ import {
   throwIllegalValue,
   typedAs,
 } from '@votingworks/basics';
+import { sliceBallotHashForEncoding } from '@votingworks/ballot-encoder';
 import { fromGrayScale, isRgba } from '@votingworks/image-utils';
 import {
   AdjudicationInfo,
@@ -18,6 +19,7 @@ import {
   BallotStyle,
   BallotStyleId,
   BallotTargetMark,
+  BallotType,
   ContestId,
   ContestOption,
   Corners,
@@ -25,6 +27,7 @@ import {
   getBallotStyle,
   getContests,
   GridPosition,
+  HmpbBallotPageMetadata,
   Id,
   InterpretedHmpbPage,
   InvalidPrecinctPage,
@@ -55,6 +58,7 @@ import {
 } from './adjudication_reasons';
 import { interpret as interpretVxBmdBallotSheet } from './summary-ballot';
 import {
+  BallotPageMetadata,
   Geometry,
   InterpretedBallotCard,
   InterpretedContestLayout,
@@ -406,13 +410,81 @@ function convertContestLayouts(
   });
 }
 
+/**
+ * Completes the metadata the Rust interpreter found for a page. AccuVote
+ * timing marks and inferred ballot styles carry no ballot hash, test mode flag
+ * or ballot type, so those are taken from the scanner configuration: the card
+ * is assumed to be for the configured election and mode, and is always a
+ * precinct ballot. An inferred page's precinct is the one configured precinct
+ * its ballot style is used in; if there is more than one, the precinct can't
+ * be known and `undefined` is returned.
+ */
+function convertBallotPageMetadata(
+  electionDefinition: ElectionDefinition,
+  options: InterpreterOptions,
+  metadata: BallotPageMetadata
+): HmpbBallotPageMetadata | undefined {
+  switch (metadata.source) {
+    case 'qr-code':
+      return metadata;
+
+    case 'timing-marks':
+      return {
+        ballotHash: sliceBallotHashForEncoding(electionDefinition.ballotHash),
+        precinctId: metadata.precinctId,
+        ballotStyleId: metadata.ballotStyleId,
+        pageNumber: metadata.pageNumber,
+        isTestMode: options.testMode,
+        ballotType: BallotType.Precinct,
+      };
+
+    case 'inferred': {
+      const ballotStyle = assertDefined(
+        getBallotStyle({
+          ballotStyleId: metadata.ballotStyleId,
+          election: electionDefinition.election,
+        })
+      );
+      const configuredPrecinctIds = ballotStyle.precincts.filter((id) =>
+        options.validPrecinctIds.has(id)
+      );
+      if (configuredPrecinctIds.length > 1) {
+        return undefined;
+      }
+      return {
+        ballotHash: sliceBallotHashForEncoding(electionDefinition.ballotHash),
+        // With no configured precinct, any of the style's precincts will be
+        // rejected as an invalid precinct.
+        precinctId: assertDefined(
+          configuredPrecinctIds[0] ?? ballotStyle.precincts[0]
+        ),
+        ballotStyleId: metadata.ballotStyleId,
+        pageNumber: metadata.pageNumber,
+        isTestMode: options.testMode,
+        ballotType: BallotType.Precinct,
+      };
+    }
+
+    default:
+      /* istanbul ignore next - compile-time check */
+      throwIllegalValue(metadata, 'source');
+  }
+}
+
 function convertInterpretedBallotPage(
   electionDefinition: ElectionDefinition,
   options: InterpreterOptions,
   interpretedBallotCard: InterpretedBallotCard,
   side: 'front' | 'back'
 ): PageInterpretation {
-  const { metadata } = interpretedBallotCard[side];
+  const metadata = convertBallotPageMetadata(
+    electionDefinition,
+    options,
+    interpretedBallotCard[side].metadata
+  );
+  if (!metadata) {
+    return { type: 'UnreadablePage', reason: 'ambiguousInferredPrecinct' };
+  }
 
   const ballotStyle = assertDefined(
     getBallotStyle({
@@ -581,6 +653,9 @@ async function interpretHmpb(
     minimumDetectedScale: options.minimumDetectedScale,
     maxCumulativeStreakWidth: options.maxCumulativeStreakWidth,
     retryStreakWidthThreshold: options.retryStreakWidthThreshold,
+    markThresholds: options.markThresholds,
+    inferBallotStyle: options.inferBallotStyle,
+    metadataSource: options.metadataSource,
     frontNormalizedImageOutputPath: options.frontNormalizedImageOutputPath,
     backNormalizedImageOutputPath: options.backNormalizedImageOutputPath,
   });
@@ -0,0 +1,243 @@
+import { beforeEach, expect, test, vi } from 'vitest';
+import { sliceBallotHashForEncoding } from '@votingworks/ballot-encoder';
+import { assert, assertDefined, ok } from '@votingworks/basics';
+import { vxFamousNamesFixtures } from '@votingworks/hmpb';
+import {
+  asSheet,
+  BallotType,
+  DEFAULT_MARK_THRESHOLDS,
+  SheetOf,
+} from '@votingworks/types';
+import { pdfToPageImages } from '../test/helpers/interpretation';
+import {
+  BallotPageMetadata,
+  interpret as interpretHmpbBallotSheetRust,
+  InterpretOptions,
+} from './bubble-ballot-ts';
+import { interpretSheet } from './interpret';
+
+vi.mock(
+  import('./bubble-ballot-ts'),
+  async (importActual): Promise<typeof import('./bubble-ballot-ts')> => {
+    const actual = await importActual();
+    return { ...actual, interpret: vi.fn(actual.interpret) };
+  }
+);
+
+const { electionDefinition, blankBallotPath } = vxFamousNamesFixtures;
+const { election } = electionDefinition;
+const precinctId = assertDefined(vxFamousNamesFixtures.precinctId);
+const ballotStyleId = election.ballotStyles[0]!.id;
+
+/**
+ * Interprets the blank famous names ballot as if the Rust interpreter had
+ * found `metadata` on its pages instead of the metadata in its QR codes.
+ */
+function mockBallotPageMetadata(metadata: SheetOf<BallotPageMetadata>): void {
+  vi.mocked(interpretHmpbBallotSheetRust).mockImplementationOnce(
+    async (options: InterpretOptions) => {
+      const { interpret } = await vi.importActual<
+        typeof import('./bubble-ballot-ts')
+      >('./bubble-ballot-ts');
+      const card = (await interpret(options)).unsafeUnwrap();
+      return ok({
+        ...card,
+        front: { ...card.front, metadata: metadata[0] },
+        back: { ...card.back, metadata: metadata[1] },
+      });
+    }
+  );
+}
+
+/** Metadata as read from the bottom row of timing marks on an AccuVote card. */
+const accuVoteMetadata: SheetOf<BallotPageMetadata> = [
+  {
+    source: 'timing-marks',
+    precinctId,
+    ballotStyleId,
+    pageNumber: 1,
+    timingMarkMetadata: {
+      side: 'front',
+      mod4Checksum: 2,
+      batchOrPrecinctNumber: 23,
+      cardNumber: 12,
+      sequenceNumber: 0,
+    },
+  },
+  {
+    source: 'timing-marks',
+    precinctId,
+    ballotStyleId,
+    pageNumber: 2,
+    timingMarkMetadata: {
+      side: 'back',
+      electionDay: 3,
+      electionMonth: 11,
+      electionYear: 26,
+      electionType: 'G',
+    },
+  },
+];
+
+beforeEach(() => {
+  vi.mocked(interpretHmpbBallotSheetRust).mockClear();
+});
+
+test('AccuVote timing mark metadata takes the rest from the configuration', async () => {
+  const images = asSheet(await pdfToPageImages(blankBallotPath).toArray());
+  mockBallotPageMetadata(accuVoteMetadata);
+
+  const [frontResult, backResult] = await interpretSheet(
+    {
+      electionDefinition,
+      validPrecinctIds: new Set([precinctId]),
+      testMode: true,
+      markThresholds: DEFAULT_MARK_THRESHOLDS,
+      adjudicationReasons: [],
+      metadataSource: 'timing-marks',
+    },
+    images
+  );
+
+  expect(interpretHmpbBallotSheetRust).toHaveBeenCalledWith(
+    expect.objectContaining({ metadataSource: 'timing-marks' })
+  );
+  assert(frontResult.type === 'InterpretedHmpbPage');
+  assert(backResult.type === 'InterpretedHmpbPage');
+  for (const [result, pageNumber] of [
+    [frontResult, 1],
+    [backResult, 2],
+  ] as const) {
+    expect(result.metadata).toEqual({
+      ballotHash: sliceBallotHashForEncoding(electionDefinition.ballotHash),
+      precinctId,
+      ballotStyleId,
+      pageNumber,
+      isTestMode: true,
+      ballotType: BallotType.Precinct,
+    });
+    expect(result.layout.metadata).toEqual(result.metadata);
+  }
+});
+
+test('AccuVote timing mark metadata is checked against the configured precincts', async () => {
+  const images = asSheet(await pdfToPageImages(blankBallotPath).toArray());
+  const otherPrecinctId = election.precincts[1]!.id;
+  assert(otherPrecinctId !== precinctId);
+  mockBallotPageMetadata(accuVoteMetadata);
+
+  const [frontResult, backResult] = await interpretSheet(
+    {
+      electionDefinition,
+      validPrecinctIds: new Set([otherPrecinctId]),
+      testMode: false,
+      markThresholds: DEFAULT_MARK_THRESHOLDS,
+      adjudicationReasons: [],
+      metadataSource: 'timing-marks',
+    },
+    images
+  );
+
+  expect(frontResult.type).toEqual('InvalidPrecinctPage');
+  expect(backResult.type).toEqual('InvalidPrecinctPage');
+});
+
+/** Metadata for a card identified from where its bubbles are printed. */
+const inferredMetadata: SheetOf<BallotPageMetadata> = [
+  { source: 'inferred', ballotStyleId, pageNumber: 1 },
+  { source: 'inferred', ballotStyleId, pageNumber: 2 },
+];
+
+test('inferred metadata takes the precinct from the configured precincts', async () => {
+  const images = asSheet(await pdfToPageImages(blankBallotPath).toArray());
+  mockBallotPageMetadata(inferredMetadata);
+
+  const [frontResult, backResult] = await interpretSheet(
+    {
+      electionDefinition,
+      validPrecinctIds: new Set(election.precincts.map(({ id }) => id)),
+      testMode: true,
+      markThresholds: DEFAULT_MARK_THRESHOLDS,
+      adjudicationReasons: [],
+      inferBallotStyle: true,
+    },
+    images
+  );
+
+  expect(interpretHmpbBallotSheetRust).toHaveBeenCalledWith(
+    expect.objectContaining({ inferBallotStyle: true })
+  );
+  assert(frontResult.type === 'InterpretedHmpbPage');
+  assert(backResult.type === 'InterpretedHmpbPage');
+  for (const [result, pageNumber] of [
+    [frontResult, 1],
+    [backResult, 2],
+  ] as const) {
+    expect(result.metadata).toEqual({
+      ballotHash: sliceBallotHashForEncoding(electionDefinition.ballotHash),
+      precinctId,
+      ballotStyleId,
+      pageNumber,
+      isTestMode: true,
+      ballotType: BallotType.Precinct,
+    });
+    expect(result.layout.metadata).toEqual(result.metadata);
+  }
+});
+
+test('inferred metadata for an unconfigured precinct is an invalid precinct', async () => {
+  const images = asSheet(await pdfToPageImages(blankBallotPath).toArray());
+  const otherPrecinctId = election.precincts[1]!.id;
+  assert(otherPrecinctId !== precinctId);
+  mockBallotPageMetadata(inferredMetadata);
+
+  const [frontResult, backResult] = await interpretSheet(
+    {
+      electionDefinition,
+      validPrecinctIds: new Set([otherPrecinctId]),
+      testMode: true,
+      markThresholds: DEFAULT_MARK_THRESHOLDS,
+      adjudicationReasons: [],
+      inferBallotStyle: true,
+    },
+    images
+  );
+
+  expect(frontResult.type).toEqual('InvalidPrecinctPage');
+  expect(backResult.type).toEqual('InvalidPrecinctPage');
+});
+
+test('inferred metadata for a style used in several configured precincts is unreadable', async () => {
+  const images = asSheet(await pdfToPageImages(blankBallotPath).toArray());
+  const otherPrecinctId = election.precincts[1]!.id;
+  assert(otherPrecinctId !== precinctId);
+  mockBallotPageMetadata(inferredMetadata);
+
+  const sheet = await interpretSheet(
+    {
+      electionDefinition: {
+        ...electionDefinition,
+        election: {
+          ...election,
+          ballotStyles: election.ballotStyles.map((ballotStyle) =>
+            ballotStyle.id === ballotStyleId
+              ? { ...ballotStyle, precincts: [precinctId, otherPrecinctId] }
+              : ballotStyle
+          ),
+        },
+      },
+      validPrecinctIds: new Set([precinctId, otherPrecinctId]),
+      testMode: true,
+      markThresholds: DEFAULT_MARK_THRESHOLDS,
+      adjudicationReasons: [],
+      inferBallotStyle: true,
+    },
+    images
+  );
+
+  // Unreadable bubble ballot pages fall through to summary ballot
+  // interpretation, which finds no summary ballot QR code either.
+  expect(sheet).not.toContainEqual(
+    expect.objectContaining({ type: 'InterpretedHmpbPage' })
+  );
+});
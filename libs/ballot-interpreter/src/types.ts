@@ -18,6 +18,18 @@ export interface InterpreterOptions {
   minimumDetectedScale?: number;
   maxCumulativeStreakWidth?: number;
   retryStreakWidthThreshold?: number;
+  /**
+   * Whether to identify the ballot style from where bubbles are printed when
+   * neither QR code can be read. The precinct is then taken to be the one
+   * configured precinct that uses the style, and the page is unreadable if
+   * there are several.
+   */
+  inferBallotStyle?: boolean;
+  /**
+   * Where to read ballot metadata from. AccuVote ballots (`'timing-marks'`)
+   * are assumed to match the configured election and test mode.
+   */
+  metadataSource?: 'qr-code' | 'timing-marks';
   frontNormalizedImageOutputPath?: string;
   backNormalizedImageOutputPath?: string;
 }
@@ -1,5 +1,5 @@
 import {
-  HmpbBallotPaperSize,
+  InterpretableHmpbBallotPaperSize,
   BallotType,
   ballotPaperDimensions,
   DEV_MACHINE_ID,
@@ -201,7 +201,7 @@ export async function main(
   const { election, ballotHash } = electionDefinition;
 
   const imagesByPaperSize = new Map<
-    HmpbBallotPaperSize,
+    InterpretableHmpbBallotPaperSize,
     { contents: Buffer; sha256: string }
   >();
   const castVoteRecordsToExport: CastVoteRecordToExport[] = [];
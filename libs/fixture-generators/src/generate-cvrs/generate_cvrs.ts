@@ -30,6 +30,7 @@ import {
   gridPositionsFromBallotPositions,
   mapSheet,
   Size,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
   Vote,
   VotesDict,
@@ -258,6 +259,10 @@ export function* generateCvrs({
             if (contest.type === 'straight-party') {
               return straightPartyNotYetImplemented();
             }
+            /* istanbul ignore next */
+            if (contest.type === 'ranked-choice') {
+              return rankedChoiceNotYetImplemented();
+            }
             switch (contest.type) {
               case 'candidate':
                 optionsForEachContest.set(
@@ -464,6 +469,10 @@ export function* generateCvrs({
                           if (contest.type === 'straight-party') {
                             return straightPartyNotYetImplemented();
                           }
+                          /* istanbul ignore next */
+                          if (contest.type === 'ranked-choice') {
+                            return rankedChoiceNotYetImplemented();
+                          }
                           const contestVotes = votes[contest.id] || [];
                           return {
                             '@type': 'CVR.CVRContest',
@@ -8,6 +8,7 @@ import {
 import {
   ContestId,
   OrderedCandidateOption,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import { CandidateOrdering, RotationParams } from './types';
@@ -46,6 +47,10 @@ export function getCandidateOrderingByPrecinctAlphabetical({
       if (contest.type === 'straight-party') {
         return straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        return rankedChoiceNotYetImplemented();
+      }
       switch (contest.type) {
         case 'yesno':
           // do nothing
@@ -110,6 +115,10 @@ function getDefaultCandidateOrdering({
     if (contest.type === 'straight-party') {
       return straightPartyNotYetImplemented();
     }
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      return rankedChoiceNotYetImplemented();
+    }
     switch (contest.type) {
       case 'candidate':
         orderedCandidatesByContest[contest.id] = contest.candidates.map(
@@ -28,6 +28,7 @@ import {
   isCombinedBallotPrimary,
   StraightPartyContest as StraightPartyContestStruct,
   Party,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 import {
   BackendLanguageContextProvider,
@@ -539,6 +540,10 @@ function Contest({
   ballotStyle: BallotStyle;
   numContestColumns: number;
 }) {
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return (
@@ -25,6 +25,7 @@ import {
   getContests,
   getOrderedCandidatesForContestInBallotStyle,
   getPartyForBallotStyle,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import {
@@ -492,6 +493,10 @@ function Contest({
   if (contest.type === 'straight-party') {
     return straightPartyNotYetImplemented();
   }
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return (
@@ -34,6 +34,7 @@ import {
   hasSplits,
   getContests,
   getOrderedCandidatesForContestInBallotStyle,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import {
@@ -222,6 +223,10 @@ export function getCandidateOrderingSetsForNhBallot({
         if (contest.type === 'straight-party') {
           straightPartyNotYetImplemented();
         }
+        /* istanbul ignore next */
+        if (contest.type === 'ranked-choice') {
+          rankedChoiceNotYetImplemented();
+        }
         switch (contest.type) {
           case 'candidate':
             orderedCandidatesByContest[contest.id] = rotateCandidates(
@@ -747,6 +752,10 @@ function Contest({
   if (contest.type === 'straight-party') {
     return straightPartyNotYetImplemented();
   }
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return (
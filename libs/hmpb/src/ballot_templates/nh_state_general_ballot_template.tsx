@@ -21,6 +21,7 @@ import {
   Election,
   getBallotStyle,
   Party,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
   YesNoContest,
 } from '@votingworks/types';
@@ -702,6 +703,10 @@ export async function BallotPageContent(
       if (contest.type === 'straight-party') {
         return straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        return rankedChoiceNotYetImplemented();
+      }
       return contest.type === 'candidate' ? (
         <CandidateContest
           key={contest.id}
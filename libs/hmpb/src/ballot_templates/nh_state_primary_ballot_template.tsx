@@ -23,6 +23,7 @@ import {
   getOrderedCandidatesForContestInBallotStyle,
   getPartyForBallotStyle,
   getPrecinctById,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import {
@@ -592,6 +593,10 @@ function Contest({
   if (contest.type === 'straight-party') {
     return straightPartyNotYetImplemented();
   }
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return (
@@ -24,6 +24,7 @@ import {
   getContests,
   getOrderedCandidatesForContestInBallotStyle,
   getPartyForBallotStyle,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import {
@@ -444,6 +445,10 @@ function Contest({
   if (contest.type === 'straight-party') {
     return straightPartyNotYetImplemented();
   }
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return (
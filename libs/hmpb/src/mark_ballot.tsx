@@ -6,6 +6,7 @@ import {
   Vote,
   VotesDict,
   WriteInCandidate,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import { assertDefined, iter, throwIllegalValue } from '@votingworks/basics';
@@ -221,6 +222,10 @@ export function createTestVotes(contests: readonly Contest[]): {
       if (contest.type === 'straight-party') {
         return straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        return rankedChoiceNotYetImplemented();
+      }
       if (contest.type === 'candidate') {
         const candidates = iter(contest.candidates)
           .cycle()
@@ -21,6 +21,7 @@ import {
   Rect,
   Vote,
   VotesDict,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 
 import {
@@ -314,6 +315,10 @@ function markInfo(
   layout: { gridPositions: readonly GridPosition[] }
 ): MarkInfo | null {
   for (const vote of votes) {
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      rankedChoiceNotYetImplemented();
+    }
     switch (contest.type) {
       case 'yesno':
       case 'straight-party': {
@@ -11,6 +11,7 @@ import {
   LanguageCode,
   YesNoContest,
   LATEST_SOFTWARE_VERSION,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
   convertLatestElectionToV4p0,
 } from '@votingworks/types';
@@ -410,6 +411,10 @@ test.each(templateSpecificTestCases)(
       if (contest.type === 'straight-party') {
         straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        rankedChoiceNotYetImplemented();
+      }
       switch (contest.type) {
         case 'candidate': {
           const renderedOptions =
@@ -16,6 +16,7 @@ import {
   getBallotStyle,
   getContests,
   LATEST_SOFTWARE_VERSION,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import {
@@ -64,6 +65,10 @@ export function createFullyVotedBallot(
       if (contest.type === 'straight-party') {
         straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        rankedChoiceNotYetImplemented();
+      }
       if (contest.type === 'candidate') {
         return [contest.id, contest.candidates.slice(0, contest.seats)];
       }
@@ -9,6 +9,7 @@ import {
   OptionalVote,
   PrecinctId,
   VotesDict,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 import {
   LinkButton,
@@ -109,6 +110,10 @@ export function ContestPage(props: ContestPageProps): JSX.Element {
       };
 
   const isVoteComplete = (() => {
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      return rankedChoiceNotYetImplemented();
+    }
     switch (contest.type) {
       case 'yesno':
         return !!vote;
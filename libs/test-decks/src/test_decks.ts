@@ -19,6 +19,7 @@ import {
   gridPositionsFromBallotPositions,
   Tabulation,
   getGroupIdFromBallotStyleId,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
   Admin,
 } from '@votingworks/types';
@@ -52,6 +53,10 @@ export function numBallotPositions(contest: Contest): number {
   if (contest.type === 'straight-party') {
     return straightPartyNotYetImplemented();
   }
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   if (contest.type === 'candidate') {
     return (
       contest.candidates.length + (contest.allowWriteIns ? contest.seats : 0)
@@ -156,6 +161,10 @@ export function generateTestDeckBallots({
             if (contest.type === 'straight-party') {
               straightPartyNotYetImplemented();
             }
+            /* istanbul ignore next */
+            if (contest.type === 'ranked-choice') {
+              rankedChoiceNotYetImplemented();
+            }
             return (
               contest.type === 'yesno' ||
               contest.candidates.length > contest.seats
@@ -166,6 +175,10 @@ export function generateTestDeckBallots({
             if (overvoteContest.type === 'straight-party') {
               straightPartyNotYetImplemented();
             }
+            /* istanbul ignore next */
+            if (overvoteContest.type === 'ranked-choice') {
+              rankedChoiceNotYetImplemented();
+            }
             ballots.push({
               ballotStyleId: ballotStyle.id,
               precinctId: currentPrecinctId,
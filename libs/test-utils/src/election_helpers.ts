@@ -10,6 +10,7 @@ import {
   Party,
   Precinct,
   safeParseElectionDefinition,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
   VotesDict,
   YesNoContest,
@@ -195,6 +196,10 @@ export function createMockVotes(
     if (contest.type === 'straight-party') {
       straightPartyNotYetImplemented();
     }
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      rankedChoiceNotYetImplemented();
+    }
     if (contest.type === 'candidate') {
       votes[contest.id] = contest.candidates.slice(0, contest.seats);
     } else {
@@ -1,5 +1,9 @@
 use std::num::NonZeroU8;
-use std::{fmt::Debug, io};
+use std::str::FromStr;
+use std::{
+    fmt::{Debug, Display},
+    io,
+};
 
 use bitstream_io::{FromBitStream, FromBitStreamWith, ToBitStream};
 use serde::{Deserialize, Serialize};
@@ -268,26 +272,93 @@ pub enum BallotSide {
     Back,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+/// The size of the paper a ballot card is printed on. Serialized with the
+/// `HmpbBallotPaperSize` names, plus `a4` and `custom-{width}x{height}` (in
+/// inches, e.g. `custom-8.5x16`) for arbitrary sizes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum PaperSize {
-    #[serde(rename = "letter")]
     Letter,
-    #[serde(rename = "legal")]
     Legal,
-    #[serde(rename = "custom-8.5x17")]
     Custom17,
-    #[serde(rename = "custom-8.5x18")]
     Custom18,
-    #[serde(rename = "custom-8.5x19")]
     Custom19,
-    #[serde(rename = "custom-8.5x20")]
     Custom20,
-    #[serde(rename = "custom-8.5x22")]
     Custom22,
+    A4,
+    /// Any other paper size.
+    Custom(CustomPaperSize),
+}
+
+/// The dimensions of a paper size without a name, stored in hundredths of an
+/// inch so that sizes compare and hash exactly.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CustomPaperSize {
+    width_hundredths: u32,
+    height_hundredths: u32,
+}
+
+impl CustomPaperSize {
+    /// Rounds `size` to the nearest hundredth of an inch, returning `None` if
+    /// either dimension is not a positive, finite size.
+    #[must_use]
+    pub fn new(size: Size<Inch>) -> Option<Self> {
+        let to_hundredths = |inches: Inch| {
+            let hundredths = (inches.get() * 100.0).round();
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            (hundredths.is_finite() && hundredths >= 1.0 && hundredths <= u32::MAX as f32)
+                .then_some(hundredths as u32)
+        };
+
+        Some(Self {
+            width_hundredths: to_hundredths(size.width)?,
+            height_hundredths: to_hundredths(size.height)?,
+        })
+    }
+
+    pub fn dimensions(self) -> Size<Inch> {
+        #[allow(clippy::cast_precision_loss)]
+        let to_inches = |hundredths: u32| Inch::new(hundredths as f32 / 100.0);
+        Size {
+            width: to_inches(self.width_hundredths),
+            height: to_inches(self.height_hundredths),
+        }
+    }
+}
+
+impl Display for CustomPaperSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let write_inches = |f: &mut std::fmt::Formatter<'_>, hundredths: u32| {
+            let (whole, fraction) = (hundredths / 100, hundredths % 100);
+            match fraction {
+                0 => write!(f, "{whole}"),
+                _ if fraction % 10 == 0 => write!(f, "{whole}.{}", fraction / 10),
+                _ => write!(f, "{whole}.{fraction:02}"),
+            }
+        };
+
+        write!(f, "custom-")?;
+        write_inches(f, self.width_hundredths)?;
+        write!(f, "x")?;
+        write_inches(f, self.height_hundredths)
+    }
 }
 
 impl PaperSize {
-    pub const fn dimensions(self) -> Size<Inch> {
+    /// Paper sizes ballots may be printed on without an election definition
+    /// naming a different size. A4 is not included because a slightly
+    /// stretched letter scan is within tolerance of it, so it is only
+    /// considered when an election asks for it.
+    pub const STANDARD: [Self; 7] = [
+        Self::Letter,
+        Self::Legal,
+        Self::Custom17,
+        Self::Custom18,
+        Self::Custom19,
+        Self::Custom20,
+        Self::Custom22,
+    ];
+
+    pub fn dimensions(self) -> Size<Inch> {
         match self {
             PaperSize::Letter => Size {
                 width: Inch::new(8.5),
@@ -317,10 +388,79 @@ impl PaperSize {
                 width: Inch::new(8.5),
                 height: Inch::new(22.0),
             },
+            // 210mm x 297mm
+            PaperSize::A4 => Size {
+                width: Inch::new(210.0 / 25.4),
+                height: Inch::new(297.0 / 25.4),
+            },
+            PaperSize::Custom(size) => size.dimensions(),
+        }
+    }
+}
+
+impl Display for PaperSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Letter => write!(f, "letter"),
+            Self::Legal => write!(f, "legal"),
+            Self::Custom17 => write!(f, "custom-8.5x17"),
+            Self::Custom18 => write!(f, "custom-8.5x18"),
+            Self::Custom19 => write!(f, "custom-8.5x19"),
+            Self::Custom20 => write!(f, "custom-8.5x20"),
+            Self::Custom22 => write!(f, "custom-8.5x22"),
+            Self::A4 => write!(f, "a4"),
+            Self::Custom(size) => write!(f, "{size}"),
         }
     }
 }
 
+impl FromStr for PaperSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(paper_size) = Self::STANDARD
+            .into_iter()
+            .chain([Self::A4])
+            .find(|paper_size| paper_size.to_string() == s)
+        {
+            return Ok(paper_size);
+        }
+
+        let invalid = || format!("Unexpected paper size: {s}");
+        let (width, height) = s
+            .strip_prefix("custom-")
+            .and_then(|dimensions| dimensions.split_once('x'))
+            .ok_or_else(invalid)?;
+        let parse_inches = |value: &str| value.parse().map(Inch::new).map_err(|_| invalid());
+        let size = CustomPaperSize::new(Size {
+            width: parse_inches(width)?,
+            height: parse_inches(height)?,
+        })
+        .ok_or_else(invalid)?;
+
+        // Spellings such as `custom-8.50x17` name a size we already have.
+        Ok(Self::STANDARD
+            .into_iter()
+            .chain([Self::A4])
+            .find(|paper_size| CustomPaperSize::new(paper_size.dimensions()) == Some(size))
+            .unwrap_or(Self::Custom(size)))
+    }
+}
+
+impl Serialize for PaperSize {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PaperSize {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 pub mod tests {
@@ -399,4 +539,87 @@ pub mod tests {
             r#""back""#
         );
     }
+
+    #[test]
+    fn test_paper_size_round_trip() {
+        for paper_size in PaperSize::STANDARD.into_iter().chain([PaperSize::A4]) {
+            let json = serde_json::to_string(&paper_size).unwrap();
+            assert_eq!(
+                serde_json::from_str::<PaperSize>(&json).unwrap(),
+                paper_size
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&PaperSize::Custom17).unwrap(),
+            r#""custom-8.5x17""#
+        );
+        assert_eq!(serde_json::to_string(&PaperSize::A4).unwrap(), r#""a4""#);
+    }
+
+    #[test]
+    fn test_paper_size_custom() {
+        let paper_size: PaperSize = "custom-8.5x16".parse().unwrap();
+        assert_eq!(
+            paper_size,
+            PaperSize::Custom(
+                CustomPaperSize::new(Size {
+                    width: Inch::new(8.5),
+                    height: Inch::new(16.0),
+                })
+                .unwrap()
+            )
+        );
+        assert_eq!(paper_size.to_string(), "custom-8.5x16");
+        assert_eq!(
+            "custom-8.25x16.00"
+                .parse::<PaperSize>()
+                .unwrap()
+                .to_string(),
+            "custom-8.25x16"
+        );
+
+        for named in ["custom-8.5x17", "custom-8.50x17", "custom-8.5x17.0"] {
+            assert_eq!(
+                named.parse::<PaperSize>().unwrap(),
+                PaperSize::Custom17,
+                "{named}"
+            );
+        }
+        assert_eq!(
+            "custom-8.5x11".parse::<PaperSize>().unwrap(),
+            PaperSize::Letter
+        );
+        assert_eq!(
+            "custom-8.27x11.69".parse::<PaperSize>().unwrap(),
+            PaperSize::A4
+        );
+
+        for invalid in [
+            "",
+            "tabloid",
+            "custom-8.5",
+            "custom-0x11",
+            "custom-0.001x11",
+            "custom--8.5x11",
+            "custom-8.5xNaN",
+            "custom-8.5xinf",
+        ] {
+            assert!(invalid.parse::<PaperSize>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_paper_size_hash() {
+        let paper_sizes: std::collections::HashSet<PaperSize> = [
+            "custom-8.5x16",
+            "custom-8.50x16.0",
+            "custom-8.5x17",
+            "legal",
+        ]
+        .into_iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+        assert_eq!(paper_sizes.len(), 3);
+        assert!(paper_sizes.contains(&PaperSize::Custom17));
+    }
 }
@@ -4,7 +4,7 @@ use super::error::Error;
 use super::write_in_name::WriteInName;
 use crate::{
     coding::BitSize,
-    election::{Contest, OptionId, PartyId},
+    election::{Contest, OptionId, PartyId, RankedChoiceContest},
 };
 
 #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -36,6 +36,8 @@ pub enum ContestVote {
     Candidate(Vec<CandidateVote>),
     YesNo(YesNoVote),
     StraightParty(Vec<PartyId>),
+    /// Candidates in the order the voter ranked them, first choice first.
+    RankedChoice(Vec<CandidateVote>),
 }
 
 impl ToBitStreamWith<'_> for ContestVote {
@@ -97,6 +99,10 @@ impl ToBitStreamWith<'_> for ContestVote {
                 }
             }
 
+            (Contest::RankedChoice(ranked_choice_contest), Self::RankedChoice(votes)) => {
+                write_rankings(w, ranked_choice_contest, votes)?;
+            }
+
             _ => {
                 return Err(Error::InvalidVotes {
                     message: format!(
@@ -177,17 +183,281 @@ impl FromBitStreamWith<'_> for ContestVote {
                 }
                 Ok(Self::StraightParty(votes))
             }
+            Contest::RankedChoice(ranked_choice_contest) => {
+                Ok(Self::RankedChoice(read_rankings(r, ranked_choice_contest)?))
+            }
         }
     }
 }
 
+/// Writes the number of rankings followed by one candidate index per ranking.
+/// The index one past the last candidate marks a write-in, which is followed
+/// by the write-in name. A candidate may be ranked only once.
+fn write_rankings<W: bitstream_io::BitWrite + ?Sized>(
+    w: &mut W,
+    contest: &RankedChoiceContest,
+    votes: &[CandidateVote],
+) -> Result<(), Error> {
+    if votes.len() > contest.max_rankings as usize {
+        return Err(Error::InvalidVotes {
+            message: format!(
+                "Contest '{}' allows at most {} rankings, got {}",
+                contest.id,
+                contest.max_rankings,
+                votes.len()
+            ),
+        });
+    }
+
+    if let Some(candidate_id) = duplicate_ranking(votes) {
+        return Err(Error::InvalidVotes {
+            message: format!(
+                "Contest '{}' ranks candidate '{candidate_id}' more than once",
+                contest.id
+            ),
+        });
+    }
+
+    let write_in_index = contest.candidates.len() as u32;
+    w.write_unsigned_var(contest.max_rankings.bit_size(), votes.len() as u32)?;
+
+    for vote in votes {
+        match vote {
+            CandidateVote::NamedCandidate { candidate_id } => {
+                let Some(index) = contest
+                    .candidates
+                    .iter()
+                    .position(|candidate| candidate.id() == candidate_id)
+                else {
+                    return Err(Error::InvalidVotes {
+                        message: format!(
+                            "Contest '{}' has no candidate '{candidate_id}'",
+                            contest.id
+                        ),
+                    });
+                };
+                w.write_unsigned_var(write_in_index.bit_size(), index as u32)?;
+            }
+            CandidateVote::WriteInCandidate { name, .. } => {
+                if !contest.allow_write_ins {
+                    return Err(Error::InvalidVotes {
+                        message: format!("Contest '{}' does not allow write-ins", contest.id),
+                    });
+                }
+                w.write_unsigned_var(write_in_index.bit_size(), write_in_index)?;
+                w.build(name)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_rankings<R: bitstream_io::BitRead + ?Sized>(
+    r: &mut R,
+    contest: &RankedChoiceContest,
+) -> Result<Vec<CandidateVote>, Error> {
+    let write_in_index = contest.candidates.len() as u32;
+    let ranking_count: u32 = r.read_unsigned_var(contest.max_rankings.bit_size())?;
+    if ranking_count > contest.max_rankings {
+        return Err(Error::InvalidVotes {
+            message: format!(
+                "Contest '{}' allows at most {} rankings, got {ranking_count}",
+                contest.id, contest.max_rankings
+            ),
+        });
+    }
+
+    let mut votes = Vec::with_capacity(ranking_count as usize);
+    for _ in 0..ranking_count {
+        let index: u32 = r.read_unsigned_var(write_in_index.bit_size())?;
+        if index == write_in_index && contest.allow_write_ins {
+            let name: WriteInName = r.parse()?;
+            votes.push(CandidateVote::WriteInCandidate {
+                candidate_id: OptionId::from(format!("write-in-{name}")),
+                name,
+            });
+        } else if let Some(candidate) = contest.candidates.get(index as usize) {
+            votes.push(CandidateVote::NamedCandidate {
+                candidate_id: candidate.id().clone(),
+            });
+        } else {
+            return Err(Error::InvalidVotes {
+                message: format!("Contest '{}' has no candidate at index {index}", contest.id),
+            });
+        }
+    }
+
+    if let Some(candidate_id) = duplicate_ranking(&votes) {
+        return Err(Error::InvalidVotes {
+            message: format!(
+                "Contest '{}' ranks candidate '{candidate_id}' more than once",
+                contest.id
+            ),
+        });
+    }
+
+    Ok(votes)
+}
+
+/// Finds a candidate ranked more than once, if any.
+fn duplicate_ranking(votes: &[CandidateVote]) -> Option<&OptionId> {
+    votes.iter().enumerate().find_map(|(index, vote)| {
+        votes[..index]
+            .iter()
+            .any(|earlier| earlier.candidate_id() == vote.candidate_id())
+            .then(|| vote.candidate_id())
+    })
+}
+
 impl ContestVote {
     #[must_use]
     pub fn has_votes(&self) -> bool {
         match self {
-            Self::Candidate(votes) => !votes.is_empty(),
+            Self::Candidate(votes) | Self::RankedChoice(votes) => !votes.is_empty(),
             Self::YesNo(votes) => !votes.is_empty(),
             Self::StraightParty(votes) => !votes.is_empty(),
         }
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use bitstream_io::BitWrite;
+
+    use super::*;
+    use crate::{
+        coding,
+        election::{Candidate, ContestId, DistrictId, NamedCandidate},
+    };
+
+    fn ranked_choice_contest(allow_write_ins: bool) -> Contest {
+        Contest::RankedChoice(RankedChoiceContest {
+            id: ContestId::from("mayor".to_owned()),
+            district_id: DistrictId::from("district-1".to_owned()),
+            title: "Mayor".to_owned(),
+            max_rankings: 3,
+            candidates: ["alice", "bob", "carol", "dave"]
+                .into_iter()
+                .map(|id| {
+                    Candidate::Named(NamedCandidate {
+                        id: OptionId::from(id.to_owned()),
+                        name: id.to_owned(),
+                        party_ids: None,
+                    })
+                })
+                .collect(),
+            allow_write_ins,
+            party_id: None,
+        })
+    }
+
+    fn named(id: &str) -> CandidateVote {
+        CandidateVote::NamedCandidate {
+            candidate_id: OptionId::from(id.to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_ranked_choice_round_trip() {
+        let contest = ranked_choice_contest(false);
+        let vote = ContestVote::RankedChoice(vec![named("carol"), named("alice")]);
+        let bytes = coding::encode_with(&vote, &contest).unwrap();
+        let decoded: ContestVote = coding::decode_with(&bytes, &contest).unwrap();
+        assert_eq!(decoded, vote);
+    }
+
+    #[test]
+    fn test_ranked_choice_round_trip_empty() {
+        let contest = ranked_choice_contest(false);
+        let vote = ContestVote::RankedChoice(vec![]);
+        let bytes = coding::encode_with(&vote, &contest).unwrap();
+        let decoded: ContestVote = coding::decode_with(&bytes, &contest).unwrap();
+        assert_eq!(decoded, vote);
+        assert!(!decoded.has_votes());
+    }
+
+    #[test]
+    fn test_ranked_choice_round_trip_write_in() {
+        let contest = ranked_choice_contest(true);
+        let vote = ContestVote::RankedChoice(vec![
+            named("bob"),
+            CandidateVote::WriteInCandidate {
+                candidate_id: OptionId::from("write-in-ZED".to_owned()),
+                name: WriteInName::new("ZED").unwrap(),
+            },
+            named("dave"),
+        ]);
+        let bytes = coding::encode_with(&vote, &contest).unwrap();
+        let decoded: ContestVote = coding::decode_with(&bytes, &contest).unwrap();
+        assert_eq!(decoded, vote);
+    }
+
+    #[test]
+    fn test_ranked_choice_rejects_invalid_votes() {
+        let contest = ranked_choice_contest(false);
+
+        let too_many = ContestVote::RankedChoice(vec![
+            named("alice"),
+            named("bob"),
+            named("carol"),
+            named("dave"),
+        ]);
+        assert!(matches!(
+            coding::encode_with(&too_many, &contest),
+            Err(Error::InvalidVotes { .. })
+        ));
+
+        let unknown = ContestVote::RankedChoice(vec![named("eve")]);
+        assert!(matches!(
+            coding::encode_with(&unknown, &contest),
+            Err(Error::InvalidVotes { .. })
+        ));
+
+        let write_in = ContestVote::RankedChoice(vec![CandidateVote::WriteInCandidate {
+            candidate_id: OptionId::from("write-in-ZED".to_owned()),
+            name: WriteInName::new("ZED").unwrap(),
+        }]);
+        assert!(matches!(
+            coding::encode_with(&write_in, &contest),
+            Err(Error::InvalidVotes { .. })
+        ));
+
+        let duplicate =
+            ContestVote::RankedChoice(vec![named("alice"), named("bob"), named("alice")]);
+        assert!(matches!(
+            coding::encode_with(&duplicate, &contest),
+            Err(Error::InvalidVotes { .. })
+        ));
+
+        let mismatched = ContestVote::Candidate(vec![named("alice")]);
+        assert!(matches!(
+            coding::encode_with(&mismatched, &contest),
+            Err(Error::InvalidVotes { .. })
+        ));
+    }
+
+    #[test]
+    fn test_ranked_choice_rejects_decoded_duplicate_rankings() {
+        let contest = ranked_choice_contest(false);
+        let Contest::RankedChoice(ranked_choice_contest) = &contest else {
+            unreachable!()
+        };
+
+        // two rankings, both of candidate index 0 ("alice")
+        let candidate_index_bits = (ranked_choice_contest.candidates.len() as u32).bit_size();
+        let bytes = coding::collect_writes::<Error>(|writer| {
+            writer.write_unsigned_var(ranked_choice_contest.max_rankings.bit_size(), 2u32)?;
+            writer.write_unsigned_var(candidate_index_bits, 0u32)?;
+            writer.write_unsigned_var(candidate_index_bits, 0u32)?;
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(matches!(
+            coding::decode_with::<ContestVote>(&bytes, &contest),
+            Err(Error::InvalidVotes { .. })
+        ));
+    }
+}
@@ -0,0 +1,386 @@
+//! Cast vote records as defined by NIST SP 1500-103, the CDF for cast vote
+//! records. Mirrors the subset of `libs/types/src/cdf/cast-vote-records` that
+//! scanners produce.
+
+use serde::{Deserialize, Serialize};
+
+/// A cast vote record for a single ballot sheet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Cvr {
+    #[serde(rename = "@type")]
+    pub object_type: CvrObjectType,
+
+    /// Links the CVR to the paper ballot, e.g. an ID printed on the ballot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ballot_audit_id: Option<String>,
+
+    /// Images of the ballot sheet created by the scanner.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ballot_image: Option<Vec<ImageData>>,
+
+    /// The sheet number of the ballot sheet this CVR represents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ballot_sheet_id: Option<String>,
+
+    pub ballot_style_id: String,
+
+    /// The precinct associated with the ballot.
+    pub ballot_style_unit_id: String,
+
+    pub batch_id: String,
+
+    /// The position of the ballot within its batch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_sequence_id: Option<u32>,
+
+    #[serde(rename = "CVRSnapshot")]
+    pub cvr_snapshot: Vec<CvrSnapshot>,
+
+    pub creating_device_id: String,
+
+    /// The `@id` of the snapshot that should be tabulated.
+    pub current_snapshot_id: String,
+
+    pub election_id: String,
+
+    /// The party of the ballot style, for partisan primaries.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub party_ids: Option<Vec<String>>,
+
+    pub unique_id: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CvrObjectType {
+    #[serde(rename = "CVR.CVR")]
+    Cvr,
+}
+
+/// One version of the contest selections for a CVR, e.g. the marks as scanned
+/// or the votes after contest rules are applied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CvrSnapshot {
+    #[serde(rename = "@id")]
+    pub id: String,
+
+    #[serde(rename = "@type")]
+    pub object_type: CvrSnapshotObjectType,
+
+    #[serde(rename = "CVRContest")]
+    pub cvr_contest: Vec<CvrContest>,
+
+    /// Used when `status` includes [`CvrStatus::Other`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other_status: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Vec<CvrStatus>>,
+
+    pub r#type: CvrType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CvrSnapshotObjectType {
+    #[serde(rename = "CVR.CVRSnapshot")]
+    CvrSnapshot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CvrStatus {
+    NeedsAdjudication,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CvrType {
+    /// Has been adjudicated.
+    Interpreted,
+
+    /// After contest rules applied.
+    Modified,
+
+    /// As scanned, no contest rules applied.
+    Original,
+}
+
+/// A contest within a [`CvrSnapshot`]. Overvotes plus undervotes plus votes
+/// must equal the number of votes allowed in the contest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CvrContest {
+    #[serde(rename = "@type")]
+    pub object_type: CvrContestObjectType,
+
+    #[serde(rename = "CVRContestSelection")]
+    pub cvr_contest_selection: Vec<CvrContestSelection>,
+
+    pub contest_id: String,
+
+    /// The number of votes lost due to overvoting.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overvotes: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Vec<ContestStatus>>,
+
+    /// The number of votes lost due to undervoting.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub undervotes: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_ins: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CvrContestObjectType {
+    #[serde(rename = "CVR.CVRContest")]
+    CvrContest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContestStatus {
+    InvalidatedRules,
+    NotIndicated,
+    Other,
+    Overvoted,
+    Undervoted,
+}
+
+/// A contest option along with the indications made for it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CvrContestSelection {
+    #[serde(rename = "@type")]
+    pub object_type: CvrContestSelectionObjectType,
+
+    pub contest_selection_id: String,
+
+    /// The position of the option within its contest as it appears on the
+    /// ballot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub option_position: Option<u32>,
+
+    pub selection_position: Vec<SelectionPosition>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Vec<ContestSelectionStatus>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CvrContestSelectionObjectType {
+    #[serde(rename = "CVR.CVRContestSelection")]
+    CvrContestSelection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContestSelectionStatus {
+    GeneratedRules,
+    InvalidatedRules,
+    NeedsAdjudication,
+    Other,
+}
+
+/// A single indication (e.g. a filled bubble) for a contest option.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SelectionPosition {
+    #[serde(rename = "@type")]
+    pub object_type: SelectionPositionObjectType,
+
+    #[serde(rename = "CVRWriteIn", skip_serializing_if = "Option::is_none")]
+    pub cvr_write_in: Option<CvrWriteIn>,
+
+    pub has_indication: IndicationStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_allocable: Option<AllocationStatus>,
+
+    /// Whether the indication was generated by contest rules rather than
+    /// made by the voter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_generated: Option<bool>,
+
+    /// The mark metric (e.g. fill score), as a string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mark_metric_value: Option<Vec<String>>,
+
+    pub number_votes: u32,
+
+    /// The rank of this indication in a ranked-choice contest, starting at 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Vec<PositionStatus>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SelectionPositionObjectType {
+    #[serde(rename = "CVR.SelectionPosition")]
+    SelectionPosition,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IndicationStatus {
+    No,
+    Unknown,
+    Yes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AllocationStatus {
+    No,
+    Unknown,
+    Yes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PositionStatus {
+    Adjudicated,
+    GeneratedRules,
+    InvalidatedRules,
+    Other,
+}
+
+/// The text or image of a write-in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CvrWriteIn {
+    #[serde(rename = "@type")]
+    pub object_type: CvrWriteInObjectType,
+
+    /// The write-in name, for machine-marked ballots.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    /// An image containing the write-in, for hand-marked ballots.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_in_image: Option<ImageData>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CvrWriteInObjectType {
+    #[serde(rename = "CVR.CVRWriteIn")]
+    CvrWriteIn,
+}
+
+/// A reference to an image file, such as a scanned ballot side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ImageData {
+    #[serde(rename = "@type")]
+    pub object_type: ImageDataObjectType,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<Hash>,
+
+    /// A URI pointing to the image file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageDataObjectType {
+    #[serde(rename = "CVR.ImageData")]
+    ImageData,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Hash {
+    #[serde(rename = "@type")]
+    pub object_type: HashObjectType,
+
+    pub r#type: HashType,
+
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HashObjectType {
+    #[serde(rename = "CVR.Hash")]
+    Hash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HashType {
+    Md6,
+    Other,
+    #[serde(rename = "sha-256")]
+    Sha256,
+    #[serde(rename = "sha-512")]
+    Sha512,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_selection_position() {
+        let position = SelectionPosition {
+            object_type: SelectionPositionObjectType::SelectionPosition,
+            cvr_write_in: None,
+            has_indication: IndicationStatus::Yes,
+            is_allocable: Some(AllocationStatus::Yes),
+            is_generated: None,
+            mark_metric_value: Some(vec!["0.42".to_owned()]),
+            number_votes: 1,
+            rank: None,
+            status: Some(vec![PositionStatus::GeneratedRules]),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&position).unwrap(),
+            serde_json::json!({
+                "@type": "CVR.SelectionPosition",
+                "HasIndication": "yes",
+                "IsAllocable": "yes",
+                "MarkMetricValue": ["0.42"],
+                "NumberVotes": 1,
+                "Status": ["generated-rules"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_snapshot() {
+        let snapshot = CvrSnapshot {
+            id: "abc-original".to_owned(),
+            object_type: CvrSnapshotObjectType::CvrSnapshot,
+            cvr_contest: vec![],
+            other_status: None,
+            status: None,
+            r#type: CvrType::Original,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&snapshot).unwrap(),
+            serde_json::json!({
+                "@id": "abc-original",
+                "@type": "CVR.CVRSnapshot",
+                "CVRContest": [],
+                "Type": "original",
+            })
+        );
+    }
+
+    #[test]
+    fn test_hash_type() {
+        assert_eq!(
+            serde_json::to_value(HashType::Sha256).unwrap(),
+            serde_json::json!("sha-256")
+        );
+    }
+}
@@ -0,0 +1,3 @@
+//! Types for the NIST Common Data Format (CDF) specifications.
+
+pub mod cvr;
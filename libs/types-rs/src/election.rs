@@ -1,9 +1,10 @@
+use std::collections::HashMap;
 use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    ballot_card::{BallotSide, BallotStyleIndex, PrecinctIndex},
+    ballot_card::{BallotSide, BallotStyleIndex, PaperSize, PrecinctIndex},
     geometry::{SubGridRect, SubGridUnit},
     idtype::idtype,
 };
@@ -23,6 +24,12 @@ idtype!(DistrictId);
 //
 // IF YOU CHANGE ANYTHING HERE, YOU MUST ALSO CHANGE IT THERE.
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BallotLayout {
+    pub paper_size: PaperSize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Election {
@@ -31,9 +38,23 @@ pub struct Election {
     pub precincts: Vec<Precinct>,
     pub mark_thresholds: Option<MarkThresholds>,
     pub contests: Vec<Contest>,
+    pub ballot_layout: Option<BallotLayout>,
 }
 
 impl Election {
+    /// Gets the paper sizes ballots for this election may be printed on: the
+    /// standard sizes plus the size named in the ballot layout, if any.
+    #[must_use]
+    pub fn ballot_paper_sizes(&self) -> Vec<PaperSize> {
+        let mut paper_sizes = PaperSize::STANDARD.to_vec();
+        if let Some(BallotLayout { paper_size }) = self.ballot_layout {
+            if !paper_sizes.contains(&paper_size) {
+                paper_sizes.push(paper_size);
+            }
+        }
+        paper_sizes
+    }
+
     /// Finds the index of the precinct with the given ID, returning `None` if
     /// no such precinct is found or if the index cannot be represented in the
     /// number of bits allowed for a [`PrecinctIndex`].
@@ -117,6 +138,18 @@ pub struct BallotStyle {
     pub languages: Vec<String>,
     #[serde(default)]
     pub ballot_positions: Option<Vec<SheetPositions>>,
+    #[serde(default)]
+    pub ordered_candidates_by_contest: Option<HashMap<ContestId, Vec<OrderedCandidateOption>>>,
+}
+
+/// A candidate as listed on a ballot style. A candidate endorsed by several
+/// parties may be listed once per party, each with its own `party_ids`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderedCandidateOption {
+    pub id: OptionId,
+    #[serde(default)]
+    pub party_ids: Option<Vec<PartyId>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -154,6 +187,10 @@ pub enum ContestOptionPosition {
         bubble_center: GridPoint,
         bounds: GridRect,
         option_id: OptionId,
+        /// The rank this bubble selects in a ranked-choice contest, starting
+        /// at 1. `None` in every other kind of contest.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rank: Option<u32>,
     },
 
     #[serde(rename_all = "camelCase", rename = "write-in")]
@@ -162,6 +199,10 @@ pub enum ContestOptionPosition {
         bounds: GridRect,
         write_in_index: u32,
         write_in_area: GridRect,
+        /// The rank this bubble selects in a ranked-choice contest, starting
+        /// at 1. `None` in every other kind of contest.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rank: Option<u32>,
     },
 }
 
@@ -180,6 +221,7 @@ impl ContestOptionPosition {
                 bubble_center,
                 bounds,
                 option_id,
+                rank,
             } => GridPosition::Option {
                 sheet_number,
                 side,
@@ -187,6 +229,7 @@ impl ContestOptionPosition {
                 row: bubble_center.row,
                 contest_id,
                 option_id: option_id.clone(),
+                rank: *rank,
                 bounds: *bounds,
             },
             Self::WriteIn {
@@ -194,6 +237,7 @@ impl ContestOptionPosition {
                 bounds,
                 write_in_index,
                 write_in_area,
+                rank,
             } => GridPosition::WriteIn {
                 sheet_number,
                 side,
@@ -207,6 +251,7 @@ impl ContestOptionPosition {
                     width: write_in_area.width,
                     height: write_in_area.height,
                 },
+                rank: *rank,
                 bounds: *bounds,
             },
         }
@@ -258,6 +303,10 @@ pub enum GridPosition {
         row: SubGridUnit,
         contest_id: ContestId,
         option_id: OptionId,
+        /// The rank this bubble selects in a ranked-choice contest, starting
+        /// at 1. Each option in such a contest has one bubble per rank.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rank: Option<u32>,
         /// The option's bounding box (grid coordinates). Used to build the
         /// interpreted layout; not serialized as part of the mark output.
         #[serde(skip)]
@@ -274,6 +323,10 @@ pub enum GridPosition {
         contest_id: ContestId,
         write_in_index: u32,
         write_in_area: SubGridRect,
+        /// The rank this bubble selects in a ranked-choice contest, starting
+        /// at 1. Each option in such a contest has one bubble per rank.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rank: Option<u32>,
         /// The option's bounding box (grid coordinates). Used to build the
         /// interpreted layout; not serialized as part of the mark output.
         #[serde(skip)]
@@ -311,6 +364,15 @@ impl GridPosition {
         }
     }
 
+    /// The rank this bubble selects, if it is part of a ranked-choice
+    /// contest.
+    #[must_use]
+    pub const fn rank(&self) -> Option<u32> {
+        match self {
+            Self::Option { rank, .. } | Self::WriteIn { rank, .. } => *rank,
+        }
+    }
+
     #[must_use]
     pub const fn sheet_number(&self) -> u32 {
         match self {
@@ -378,6 +440,8 @@ pub enum Contest {
     YesNo(YesNoContest),
     #[serde(rename = "straight-party")]
     StraightParty(StraightPartyContest),
+    #[serde(rename = "ranked-choice")]
+    RankedChoice(RankedChoiceContest),
 }
 
 impl Contest {
@@ -385,7 +449,8 @@ impl Contest {
         match self {
             Self::Candidate(CandidateContest { id, .. })
             | Self::YesNo(YesNoContest { id, .. })
-            | Self::StraightParty(StraightPartyContest { id, .. }) => id,
+            | Self::StraightParty(StraightPartyContest { id, .. })
+            | Self::RankedChoice(RankedChoiceContest { id, .. }) => id,
         }
     }
 
@@ -393,7 +458,20 @@ impl Contest {
         match self {
             Self::Candidate(CandidateContest { district_id, .. })
             | Self::YesNo(YesNoContest { district_id, .. })
-            | Self::StraightParty(StraightPartyContest { district_id, .. }) => district_id,
+            | Self::StraightParty(StraightPartyContest { district_id, .. })
+            | Self::RankedChoice(RankedChoiceContest { district_id, .. }) => district_id,
+        }
+    }
+
+    /// The number of selections a voter may make in this contest without
+    /// overvoting. In a ranked-choice contest this is the number of ranks,
+    /// each of which allows one selection.
+    #[must_use]
+    pub const fn votes_allowed(&self) -> u32 {
+        match self {
+            Self::Candidate(CandidateContest { seats, .. }) => *seats,
+            Self::RankedChoice(RankedChoiceContest { max_rankings, .. }) => *max_rankings,
+            Self::YesNo(_) | Self::StraightParty(_) => 1,
         }
     }
 
@@ -408,8 +486,10 @@ impl Contest {
             && match self {
                 Contest::YesNo(_)
                 | Contest::StraightParty(_)
-                | Contest::Candidate(CandidateContest { party_id: None, .. }) => true,
-                Contest::Candidate(CandidateContest { party_id, .. }) => {
+                | Contest::Candidate(CandidateContest { party_id: None, .. })
+                | Contest::RankedChoice(RankedChoiceContest { party_id: None, .. }) => true,
+                Contest::Candidate(CandidateContest { party_id, .. })
+                | Contest::RankedChoice(RankedChoiceContest { party_id, .. }) => {
                     party_id == &ballot_style.party_id
                 }
             }
@@ -447,6 +527,37 @@ pub struct CandidateContest {
     pub term_description: Option<String>,
 }
 
+/// A contest in which voters rank candidates in order of preference. Each
+/// candidate is printed with one bubble per rank, and a voter ranks a candidate
+/// by filling the bubble for that rank.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[must_use]
+pub struct RankedChoiceContest {
+    /// The unique ID of this contest.
+    pub id: ContestId,
+
+    /// The district this contest appears in.
+    pub district_id: DistrictId,
+
+    /// The title of the contest to show to voters.
+    pub title: String,
+
+    /// How many candidates a voter may rank, i.e. the number of rank bubbles
+    /// printed for each candidate.
+    pub max_rankings: u32,
+
+    /// The list of candidates, named or write-in, voters may rank.
+    pub candidates: Vec<Candidate>,
+
+    /// Determines whether write-ins are allowed in this contest.
+    pub allow_write_ins: bool,
+
+    /// If this is a primary contest, the party of the candidates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub party_id: Option<PartyId>,
+}
+
 #[derive(Debug, Clone)]
 #[must_use]
 pub enum Candidate {
@@ -621,6 +732,7 @@ mod tests {
             row: 2.0,
             contest_id: ContestId::from("contest-1".to_string()),
             option_id: OptionId::from("option-1".to_string()),
+            rank: None,
             bounds: GridRect::default(),
         };
         assert_eq!(position.location().side, BallotSide::Front);
@@ -661,6 +773,7 @@ mod tests {
                     },
                     bounds: option_bounds,
                     option_id: OptionId::from("option-1".to_string()),
+                    rank: None,
                 },
                 ContestOptionPosition::WriteIn {
                     bubble_center: GridPoint {
@@ -670,6 +783,7 @@ mod tests {
                     bounds: write_in_bounds,
                     write_in_index: 0,
                     write_in_area,
+                    rank: None,
                 },
             ],
         };
@@ -683,10 +797,12 @@ mod tests {
                 party_id: None,
                 languages: vec![],
                 ballot_positions: Some(vec![(vec![contest], vec![])]),
+                ordered_candidates_by_contest: None,
             }],
             precincts: vec![],
             mark_thresholds: None,
             contests: vec![],
+            ballot_layout: None,
         };
 
         let grid_layouts = election.grid_layouts();
@@ -706,6 +822,7 @@ mod tests {
                 row,
                 contest_id,
                 option_id,
+                rank,
                 bounds,
             } => {
                 assert_eq!(*sheet_number, 1);
@@ -714,6 +831,7 @@ mod tests {
                 assert!((*row - 2.0).abs() < f32::EPSILON);
                 assert_eq!(*contest_id, ContestId::from("contest-1".to_string()));
                 assert_eq!(*option_id, OptionId::from("option-1".to_string()));
+                assert_eq!(*rank, None);
                 assert_eq!(*bounds, option_bounds);
             }
             GridPosition::WriteIn { .. } => panic!("expected Option"),
@@ -728,6 +846,7 @@ mod tests {
                 contest_id,
                 write_in_index,
                 write_in_area: area,
+                rank,
                 bounds,
             } => {
                 assert_eq!(*sheet_number, 1);
@@ -745,6 +864,7 @@ mod tests {
                         height: 1.0
                     }
                 );
+                assert_eq!(*rank, None);
                 assert_eq!(*bounds, write_in_bounds);
             }
             GridPosition::Option { .. } => panic!("expected WriteIn"),
@@ -763,14 +883,41 @@ mod tests {
                 party_id: None,
                 languages: vec![],
                 ballot_positions: None,
+                ordered_candidates_by_contest: None,
             }],
             precincts: vec![],
             mark_thresholds: None,
             contests: vec![],
+            ballot_layout: None,
         };
         assert!(election.grid_layouts().is_empty());
     }
 
+    #[test]
+    fn test_ballot_paper_sizes() {
+        let mut election = Election {
+            title: "Test".to_string(),
+            ballot_styles: vec![],
+            precincts: vec![],
+            mark_thresholds: None,
+            contests: vec![],
+            ballot_layout: None,
+        };
+        assert_eq!(election.ballot_paper_sizes(), PaperSize::STANDARD);
+
+        election.ballot_layout = Some(BallotLayout {
+            paper_size: PaperSize::Legal,
+        });
+        assert_eq!(election.ballot_paper_sizes(), PaperSize::STANDARD);
+
+        election.ballot_layout = Some(BallotLayout {
+            paper_size: PaperSize::A4,
+        });
+        let paper_sizes = election.ballot_paper_sizes();
+        assert_eq!(paper_sizes.len(), PaperSize::STANDARD.len() + 1);
+        assert_eq!(paper_sizes.last(), Some(&PaperSize::A4));
+    }
+
     #[test]
     fn test_ballot_positions_deserialization_is_camel_case() {
         // The TypeScript renderer emits ballotPositions with camelCase fields
@@ -816,6 +963,7 @@ mod tests {
             precincts: vec![],
             mark_thresholds: None,
             contests: vec![],
+            ballot_layout: None,
         };
         let grid_layouts = election.grid_layouts();
         assert_eq!(grid_layouts.len(), 1);
@@ -918,4 +1066,65 @@ mod tests {
             GridPosition::Option { .. } => panic!("expected WriteIn"),
         }
     }
+
+    #[test]
+    fn test_ranked_choice_contest_deserialization() {
+        let json = r#"{
+            "type": "ranked-choice",
+            "id": "mayor",
+            "districtId": "district-1",
+            "title": "Mayor",
+            "maxRankings": 3,
+            "candidates": [
+                { "id": "alice", "name": "Alice" },
+                { "id": "bob", "name": "Bob" }
+            ],
+            "allowWriteIns": false
+        }"#;
+        let contest: Contest = serde_json::from_str(json).unwrap();
+        assert_eq!(contest.id(), &ContestId::from("mayor".to_string()));
+        assert_eq!(contest.votes_allowed(), 3);
+        match contest {
+            Contest::RankedChoice(contest) => {
+                assert_eq!(contest.candidates.len(), 2);
+                assert_eq!(contest.party_id, None);
+            }
+            _ => panic!("expected RankedChoice"),
+        }
+    }
+
+    #[test]
+    fn test_grid_position_rank_serialization() {
+        let json = r#"{
+            "type": "option",
+            "sheetNumber": 1,
+            "side": "front",
+            "column": 4,
+            "row": 2,
+            "contestId": "mayor",
+            "optionId": "alice",
+            "rank": 2
+        }"#;
+        let position: GridPosition = serde_json::from_str(json).unwrap();
+        assert_eq!(position.rank(), Some(2));
+        assert_eq!(
+            serde_json::to_value(&position).unwrap()["rank"],
+            serde_json::json!(2)
+        );
+
+        let unranked = GridPosition::Option {
+            sheet_number: 1,
+            side: BallotSide::Front,
+            column: 1.0,
+            row: 2.0,
+            contest_id: ContestId::from("contest-1".to_string()),
+            option_id: OptionId::from("option-1".to_string()),
+            rank: None,
+            bounds: GridRect::default(),
+        };
+        assert!(serde_json::to_value(&unranked)
+            .unwrap()
+            .get("rank")
+            .is_none());
+    }
 }
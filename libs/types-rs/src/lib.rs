@@ -6,6 +6,7 @@
 pub mod ballot_card;
 pub mod bmd;
 pub mod bubble_ballot;
+pub mod cdf;
 pub mod coding;
 pub mod election;
 mod f32_newtype;
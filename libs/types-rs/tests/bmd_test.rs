@@ -70,7 +70,7 @@ fn test_round_trip_with_votes() {
         .iter()
         .find_map(|contest| match contest {
             Contest::Candidate(cc) => Some(cc),
-            Contest::YesNo(_) | Contest::StraightParty(_) => None,
+            Contest::YesNo(_) | Contest::StraightParty(_) | Contest::RankedChoice(_) => None,
         })
         .unwrap();
 
@@ -105,7 +105,7 @@ fn test_round_trip_with_write_in() {
         .iter()
         .find_map(|contest| match contest {
             Contest::Candidate(cc) => Some(cc),
-            Contest::YesNo(_) | Contest::StraightParty(_) => None,
+            Contest::YesNo(_) | Contest::StraightParty(_) | Contest::RankedChoice(_) => None,
         })
         .unwrap();
 
@@ -141,7 +141,7 @@ fn test_round_trip_yesno_contest() {
         .iter()
         .find_map(|contest| match contest {
             Contest::YesNo(yn) => Some(yn),
-            Contest::Candidate(_) | Contest::StraightParty(_) => None,
+            Contest::Candidate(_) | Contest::StraightParty(_) | Contest::RankedChoice(_) => None,
         })
         .unwrap();
 
@@ -174,7 +174,7 @@ fn test_partial_contests_on_page() {
         .iter()
         .find_map(|contest| match contest {
             Contest::Candidate(cc) => Some(cc.id.clone()),
-            Contest::YesNo(_) | Contest::StraightParty(_) => None,
+            Contest::YesNo(_) | Contest::StraightParty(_) | Contest::RankedChoice(_) => None,
         })
         .unwrap();
 
@@ -231,6 +231,22 @@ fn max_votes_for(contest: &Contest) -> (ContestId, ContestVote) {
             yn.id.clone(),
             ContestVote::YesNo(vec![yn.options[0].id.clone()]),
         ),
+        Contest::RankedChoice(rc) => (
+            rc.id.clone(),
+            ContestVote::RankedChoice(
+                rc.candidates
+                    .iter()
+                    .filter_map(|c| match c {
+                        Candidate::Named(n) => Some(n),
+                        Candidate::WriteIn(_) => None,
+                    })
+                    .map(|c| CandidateVote::NamedCandidate {
+                        candidate_id: c.id.clone(),
+                    })
+                    .take(rc.max_rankings as usize)
+                    .collect(),
+            ),
+        ),
         Contest::StraightParty(_) => {
             unimplemented!("STRAIGHT_PARTY_TODO: straight-party contests are not yet implemented")
         }
@@ -21,6 +21,7 @@ pub fn simple_election() -> Election {
             party_id: None,
             languages: vec![],
             ballot_positions: None,
+            ordered_candidates_by_contest: None,
         }],
         precincts: vec![Precinct {
             id: PrecinctId::from("p-1".to_owned()),
@@ -58,6 +59,7 @@ pub fn simple_election() -> Election {
                 ],
             }),
         ],
+        ballot_layout: None,
     }
 }
 
@@ -577,6 +577,10 @@ export function convertVxfElectionToCdfBallotDefinition(
       if (contest.type === 'straight-party') {
         return Vxf.straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        return Vxf.rankedChoiceNotYetImplemented();
+      }
       switch (gridPosition.type) {
         case 'option': {
           switch (contest.type) {
@@ -607,6 +611,10 @@ export function convertVxfElectionToCdfBallotDefinition(
       if (contest.type === 'straight-party') {
         return Vxf.straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (contest.type === 'ranked-choice') {
+        return Vxf.rankedChoiceNotYetImplemented();
+      }
       // For candidate contests, use the ordered candidates from the ballot style
       if (contest.type === 'candidate') {
         const orderedCandidates = getOrderedCandidatesForContestInBallotStyle({
@@ -837,6 +845,10 @@ export function convertVxfElectionToCdfBallotDefinition(
           if (contest.type === 'straight-party') {
             return Vxf.straightPartyNotYetImplemented();
           }
+          /* istanbul ignore next */
+          if (contest.type === 'ranked-choice') {
+            return Vxf.rankedChoiceNotYetImplemented();
+          }
           switch (contest.type) {
             case 'candidate':
               return {
@@ -39,6 +39,7 @@ import {
 import {
   BallotIdSchema,
   HmpbBallotPaperSize,
+  InterpretableHmpbBallotPaperSizeSchema,
   BallotStyleId,
   BallotStyleSchema,
   CandidateContest,
@@ -754,6 +755,33 @@ test('ballotPaperDimensions', () => {
     width: 8,
     height: 13.25,
   });
+  expect(ballotPaperDimensions('a4')).toEqual({
+    width: 210 / 25.4,
+    height: 297 / 25.4,
+  });
+  expect(ballotPaperDimensions('custom-8.5x16')).toEqual({
+    width: 8.5,
+    height: 16,
+  });
+});
+
+test('InterpretableHmpbBallotPaperSizeSchema', () => {
+  for (const paperSize of [
+    HmpbBallotPaperSize.Letter,
+    HmpbBallotPaperSize.Custom17,
+    'a4',
+    'custom-8.5x16',
+    'custom-8.27x11.69',
+  ]) {
+    expect(
+      safeParse(InterpretableHmpbBallotPaperSizeSchema, paperSize).isOk()
+    ).toEqual(true);
+  }
+  for (const paperSize of ['tabloid', 'custom-8.5', 'custom-8.5xtall']) {
+    expect(
+      safeParse(InterpretableHmpbBallotPaperSizeSchema, paperSize).isErr()
+    ).toEqual(true);
+  }
 });
 
 test('hasSplits', () => {
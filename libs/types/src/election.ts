@@ -293,11 +293,70 @@ export function straightPartyNotYetImplemented(): never {
   throw new Error('Straight party contests are not yet implemented');
 }
 
-export type Contest = CandidateContest | YesNoContest | StraightPartyContest;
+/**
+ * A contest in which voters rank candidates in order of preference. Each
+ * candidate is printed with one bubble per rank, and a voter ranks a candidate
+ * by filling the bubble for that rank. Votes are the candidates in ranked
+ * order, first choice first.
+ */
+export interface RankedChoiceContest extends ContestBase {
+  readonly type: 'ranked-choice';
+  /**
+   * How many candidates a voter may rank, i.e. the number of rank bubbles
+   * printed for each candidate.
+   */
+  readonly maxRankings: number;
+  readonly candidates: readonly Candidate[];
+  readonly allowWriteIns: boolean;
+  readonly partyId?: PartyId;
+}
+export const RankedChoiceContestSchema: z.ZodSchema<RankedChoiceContest> =
+  ContestBaseSchema.extend({
+    type: z.literal('ranked-choice'),
+    maxRankings: z.number().int().positive(),
+    candidates: z.array(CandidateSchema),
+    allowWriteIns: z.boolean(),
+    partyId: PartyIdSchema.optional(),
+  }).check((ctx) => {
+    const contest = ctx.value;
+    for (const [index, id] of findDuplicateIds(contest.candidates)) {
+      ctx.issues.push({
+        code: 'custom',
+        path: ['candidates', index, 'id'],
+        message: `Duplicate candidate '${id}' found.`,
+        input: contest,
+      });
+    }
+
+    if (!contest.allowWriteIns && contest.candidates.length === 0) {
+      ctx.issues.push({
+        code: 'custom',
+        path: ['candidates'],
+        message: 'Contest must have at least one candidate or allow write-ins.',
+        input: contest,
+      });
+    }
+  });
+
+/**
+ * This can be placed wherever type narrowing is required, and also marks all
+ * outstanding ranked-choice contest cases. Only the ballot interpreter and
+ * ballot encoder support ranked-choice contests so far.
+ */
+export function rankedChoiceNotYetImplemented(): never {
+  throw new Error('Ranked-choice contests are not yet implemented');
+}
+
+export type Contest =
+  | CandidateContest
+  | YesNoContest
+  | StraightPartyContest
+  | RankedChoiceContest;
 export const ContestSchema: z.ZodSchema<Contest> = z.union([
   CandidateContestSchema,
   YesNoContestSchema,
   StraightPartyContestSchema,
+  RankedChoiceContestSchema,
 ]);
 
 export const ContestsSchema = z.array(ContestSchema).check((ctx) => {
@@ -515,6 +574,11 @@ export interface OptionPosition {
    */
   readonly optionId: Id;
   readonly partyIds?: readonly PartyId[];
+  /**
+   * The rank this bubble selects in a ranked-choice contest, starting at 1.
+   * Each option in such a contest has one position per rank.
+   */
+  readonly rank?: number;
 }
 export const OptionPositionSchema: z.ZodSchema<OptionPosition> = z.object({
   type: z.literal('option'),
@@ -522,6 +586,7 @@ export const OptionPositionSchema: z.ZodSchema<OptionPosition> = z.object({
   bounds: GridRectSchema,
   optionId: IdSchema,
   partyIds: z.array(PartyIdSchema).optional(),
+  rank: z.number().int().positive().optional(),
 });
 
 export interface WriteInPosition {
@@ -538,6 +603,8 @@ export interface WriteInPosition {
    * bubble).
    */
   readonly writeInArea: GridRect;
+  /** The rank this bubble selects in a ranked-choice contest, starting at 1. */
+  readonly rank?: number;
 }
 export const WriteInPositionSchema: z.ZodSchema<WriteInPosition> = z.object({
   type: z.literal('write-in'),
@@ -545,6 +612,7 @@ export const WriteInPositionSchema: z.ZodSchema<WriteInPosition> = z.object({
   bounds: GridRectSchema,
   writeInIndex: z.number().int().nonnegative(),
   writeInArea: GridRectSchema,
+  rank: z.number().int().positive().optional(),
 });
 
 export type ContestOptionPosition = OptionPosition | WriteInPosition;
@@ -595,6 +663,8 @@ export interface GridPositionOption {
   readonly contestId: ContestId;
   readonly optionId: Id;
   readonly partyIds?: readonly PartyId[];
+  /** The rank this bubble selects in a ranked-choice contest, starting at 1. */
+  readonly rank?: number;
 }
 export const GridPositionOptionSchema: z.ZodSchema<GridPositionOption> =
   z.object({
@@ -606,6 +676,7 @@ export const GridPositionOptionSchema: z.ZodSchema<GridPositionOption> =
     contestId: ContestIdSchema,
     optionId: IdSchema,
     partyIds: z.array(PartyIdSchema).optional(),
+    rank: z.number().int().positive().optional(),
   });
 
 export interface GridPositionWriteIn {
@@ -620,6 +691,8 @@ export interface GridPositionWriteIn {
   readonly writeInIndex: number;
   /** Grid coordinates of the write-in area, used to detect unmarked write-ins. */
   readonly writeInArea: Rect;
+  /** The rank this bubble selects in a ranked-choice contest, starting at 1. */
+  readonly rank?: number;
 }
 export const GridPositionWriteInSchema: z.ZodSchema<GridPositionWriteIn> =
   z.object({
@@ -631,6 +704,7 @@ export const GridPositionWriteInSchema: z.ZodSchema<GridPositionWriteIn> =
     contestId: ContestIdSchema,
     writeInIndex: z.number().int().nonnegative(),
     writeInArea: RectSchema,
+    rank: z.number().int().positive().optional(),
   });
 
 export type GridPosition = GridPositionOption | GridPositionWriteIn;
@@ -692,6 +766,39 @@ export enum HmpbBallotPaperSize {
 export const HmpbBallotPaperSizeSchema: z.ZodSchema<HmpbBallotPaperSize> =
   z.enum(HmpbBallotPaperSize);
 
+/**
+ * A paper size without a name, given as `custom-{width}x{height}` in inches,
+ * e.g. `custom-8.5x16`.
+ */
+export type CustomHmpbBallotPaperSize = `custom-${number}x${number}`;
+export const CustomHmpbBallotPaperSizeSchema: z.ZodSchema<CustomHmpbBallotPaperSize> =
+  z.templateLiteral(['custom-', z.number(), 'x', z.number()]);
+
+/**
+ * Paper sizes the ballot interpreter accepts: the sizes we print ballots on,
+ * plus A4 and custom sizes.
+ */
+export type InterpretableHmpbBallotPaperSize =
+  | HmpbBallotPaperSize
+  | 'a4'
+  | CustomHmpbBallotPaperSize;
+export const InterpretableHmpbBallotPaperSizeSchema: z.ZodSchema<InterpretableHmpbBallotPaperSize> =
+  z.union([
+    HmpbBallotPaperSizeSchema,
+    z.literal('a4'),
+    CustomHmpbBallotPaperSizeSchema,
+  ]);
+
+/**
+ * Whether `paperSize` is one we print ballots on, as opposed to one only the
+ * ballot interpreter accepts.
+ */
+export function isHmpbBallotPaperSize(
+  paperSize: InterpretableHmpbBallotPaperSize
+): paperSize is HmpbBallotPaperSize {
+  return (Object.values(HmpbBallotPaperSize) as string[]).includes(paperSize);
+}
+
 export enum BmdBallotPaperSize {
   Vsap150Thermal = 'vsap-150-thermal',
 }
@@ -701,11 +808,11 @@ export const BmdBallotPaperSizeSchema: z.ZodSchema<BmdBallotPaperSize> =
 export type BallotPaperSize = HmpbBallotPaperSize | BmdBallotPaperSize;
 
 export interface BallotLayout {
-  paperSize: HmpbBallotPaperSize;
+  paperSize: InterpretableHmpbBallotPaperSize;
   metadataEncoding: 'qr-code';
 }
 export const BallotLayoutSchema: z.ZodSchema<BallotLayout> = z.object({
-  paperSize: HmpbBallotPaperSizeSchema,
+  paperSize: InterpretableHmpbBallotPaperSizeSchema,
   metadataEncoding: z.enum(['qr-code']),
 });
 
@@ -717,6 +824,11 @@ export enum AdjudicationReason {
   BlankBallot = 'BlankBallot',
   UnmarkedWriteIn = 'UnmarkedWriteIn',
   CrossoverVoting = 'CrossoverVoting',
+  SkippedRank = 'SkippedRank',
+  DuplicateRanking = 'DuplicateRanking',
+  OvervotedRank = 'OvervotedRank',
+  PossibleVoterIntent = 'PossibleVoterIntent',
+  InferredBallotStyle = 'InferredBallotStyle',
 }
 export const AdjudicationReasonSchema: z.ZodSchema<AdjudicationReason> =
   z.enum(AdjudicationReason);
@@ -984,7 +1096,7 @@ export const ElectionSchema = z
     }
 
     for (const [contestIndex, contest] of election.contests.entries()) {
-      if (contest.type === 'candidate') {
+      if (contest.type === 'candidate' || contest.type === 'ranked-choice') {
         if (
           contest.partyId &&
           !election.parties.some(({ id }) => id === contest.partyId)
@@ -1200,12 +1312,19 @@ export interface MarginalMarkAdjudicationReasonInfo {
   type: AdjudicationReason.MarginalMark;
   contestId: ContestId;
   optionId: ContestOptionId;
+  /**
+   * What kind of mark the bubble looks like, e.g. 'erased' or 'partial', if
+   * the interpreter judged it. See `MarkClassification` in
+   * `@votingworks/ballot-interpreter`.
+   */
+  classification?: string;
 }
 export const MarginalMarkAdjudicationReasonInfoSchema: z.ZodSchema<MarginalMarkAdjudicationReasonInfo> =
   z.object({
     type: z.literal(AdjudicationReason.MarginalMark),
     contestId: ContestIdSchema,
     optionId: ContestOptionIdSchema,
+    classification: z.string().optional(),
   });
 
 export interface OvervoteAdjudicationReasonInfo {
@@ -1252,12 +1371,93 @@ export const CrossoverVotingAdjudicationReasonInfoSchema: z.ZodSchema<CrossoverV
     type: z.literal(AdjudicationReason.CrossoverVoting),
   });
 
+/**
+ * A ranked-choice contest has no option marked at `rank`, but does have one
+ * marked at a later rank.
+ */
+export interface SkippedRankAdjudicationReasonInfo {
+  type: AdjudicationReason.SkippedRank;
+  contestId: ContestId;
+  rank: number;
+}
+export const SkippedRankAdjudicationReasonInfoSchema: z.ZodSchema<SkippedRankAdjudicationReasonInfo> =
+  z.object({
+    type: z.literal(AdjudicationReason.SkippedRank),
+    contestId: ContestIdSchema,
+    rank: z.number().int().positive(),
+  });
+
+/**
+ * An option in a ranked-choice contest is marked at more than one rank.
+ */
+export interface DuplicateRankingAdjudicationReasonInfo {
+  type: AdjudicationReason.DuplicateRanking;
+  contestId: ContestId;
+  optionId: ContestOptionId;
+  ranks: readonly number[];
+}
+export const DuplicateRankingAdjudicationReasonInfoSchema: z.ZodSchema<DuplicateRankingAdjudicationReasonInfo> =
+  z.object({
+    type: z.literal(AdjudicationReason.DuplicateRanking),
+    contestId: ContestIdSchema,
+    optionId: ContestOptionIdSchema,
+    ranks: z.array(z.number().int().positive()),
+  });
+
+/**
+ * More than one option in a ranked-choice contest is marked at `rank`.
+ */
+export interface OvervotedRankAdjudicationReasonInfo {
+  type: AdjudicationReason.OvervotedRank;
+  contestId: ContestId;
+  rank: number;
+  optionIds: ReadonlyArray<ContestOption['id']>;
+}
+export const OvervotedRankAdjudicationReasonInfoSchema: z.ZodSchema<OvervotedRankAdjudicationReasonInfo> =
+  z.object({
+    type: z.literal(AdjudicationReason.OvervotedRank),
+    contestId: ContestIdSchema,
+    rank: z.number().int().positive(),
+    optionIds: z.array(ContestOptionIdSchema),
+  });
+
+/**
+ * A contest has ink outside its bubbles and write-in areas, e.g. a circled
+ * name or a checkmark, that may indicate the voter's intent.
+ */
+export interface PossibleVoterIntentAdjudicationReasonInfo {
+  type: AdjudicationReason.PossibleVoterIntent;
+  contestId: ContestId;
+}
+export const PossibleVoterIntentAdjudicationReasonInfoSchema: z.ZodSchema<PossibleVoterIntentAdjudicationReasonInfo> =
+  z.object({
+    type: z.literal(AdjudicationReason.PossibleVoterIntent),
+    contestId: ContestIdSchema,
+  });
+
+/**
+ * The card's QR codes couldn't be read, so its ballot style and sheet were
+ * identified from where its bubbles are printed.
+ */
+export interface InferredBallotStyleAdjudicationReasonInfo {
+  type: AdjudicationReason.InferredBallotStyle;
+}
+export const InferredBallotStyleAdjudicationReasonInfoSchema: z.ZodSchema<InferredBallotStyleAdjudicationReasonInfo> =
+  z.object({
+    type: z.literal(AdjudicationReason.InferredBallotStyle),
+  });
+
 export type AdjudicationReasonInfo =
   | MarginalMarkAdjudicationReasonInfo
   | OvervoteAdjudicationReasonInfo
   | UndervoteAdjudicationReasonInfo
   | BlankBallotAdjudicationReasonInfo
-  | CrossoverVotingAdjudicationReasonInfo;
+  | CrossoverVotingAdjudicationReasonInfo
+  | SkippedRankAdjudicationReasonInfo
+  | DuplicateRankingAdjudicationReasonInfo
+  | OvervotedRankAdjudicationReasonInfo
+  | PossibleVoterIntentAdjudicationReasonInfo
+  | InferredBallotStyleAdjudicationReasonInfo;
 export const AdjudicationReasonInfoSchema: z.ZodSchema<AdjudicationReasonInfo> =
   z.union([
     MarginalMarkAdjudicationReasonInfoSchema,
@@ -1265,6 +1465,11 @@ export const AdjudicationReasonInfoSchema: z.ZodSchema<AdjudicationReasonInfo> =
     UndervoteAdjudicationReasonInfoSchema,
     BlankBallotAdjudicationReasonInfoSchema,
     CrossoverVotingAdjudicationReasonInfoSchema,
+    SkippedRankAdjudicationReasonInfoSchema,
+    DuplicateRankingAdjudicationReasonInfoSchema,
+    OvervotedRankAdjudicationReasonInfoSchema,
+    PossibleVoterIntentAdjudicationReasonInfoSchema,
+    InferredBallotStyleAdjudicationReasonInfoSchema,
   ]);
 
 export type BallotId = string;
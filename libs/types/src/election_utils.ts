@@ -1,10 +1,4 @@
-import {
-  assert,
-  assertDefined,
-  deepEqual,
-  find,
-  throwIllegalValue,
-} from '@votingworks/basics';
+import { assert, assertDefined, deepEqual, find } from '@votingworks/basics';
 import {
   Contest,
   HmpbBallotPaperSize,
@@ -32,6 +26,7 @@ import {
   hasSplits,
   PrecinctOrSplit,
   CandidateVote,
+  InterpretableHmpbBallotPaperSize,
 } from './election';
 
 /**
@@ -49,7 +44,8 @@ export function getContests({
       ballotStyle.districts.includes(contest.districtId) &&
       // In closed primary elections, where each ballot style has a specific
       // party, filter candidate contests by party (if they have one).
-      (ballotStyle.partyId && contest.type === 'candidate'
+      (ballotStyle.partyId &&
+      (contest.type === 'candidate' || contest.type === 'ranked-choice')
         ? !contest.partyId || contest.partyId === ballotStyle.partyId
         : true)
   );
@@ -382,7 +378,9 @@ export function getPartyIdsWithContests(
   return [
     ...new Set(
       election.contests.map((c) =>
-        c.type === 'candidate' ? c.partyId : undefined
+        c.type === 'candidate' || c.type === 'ranked-choice'
+          ? c.partyId
+          : undefined
       )
     ),
   ];
@@ -534,7 +532,9 @@ export function formatElectionHashes(
 }
 
 // In inches
-export function ballotPaperDimensions(paperSize: BallotPaperSize): {
+export function ballotPaperDimensions(
+  paperSize: BallotPaperSize | InterpretableHmpbBallotPaperSize
+): {
   width: number;
   height: number;
 } {
@@ -574,14 +574,26 @@ export function ballotPaperDimensions(paperSize: BallotPaperSize): {
         width: 8.5,
         height: 22,
       };
+    case 'a4':
+      // 210mm x 297mm
+      return {
+        width: 210 / 25.4,
+        height: 297 / 25.4,
+      };
     case BmdBallotPaperSize.Vsap150Thermal:
       return {
         width: 8,
         height: 13.25,
       };
     default: {
-      /* istanbul ignore next */
-      return throwIllegalValue(paperSize);
+      // Only custom sizes are left: `custom-{width}x{height}`.
+      const [, width, height] = assertDefined(
+        /^custom-(.+)x(.+)$/.exec(paperSize)
+      );
+      return {
+        width: Number(width),
+        height: Number(height),
+      };
     }
   }
 }
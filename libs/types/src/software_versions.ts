@@ -27,6 +27,7 @@ import {
   YesNoContest,
   YesNoOption,
   YesNoOptionSchema,
+  rankedChoiceNotYetImplemented,
 } from './election';
 import {
   ballotPositionsFromGridPositions,
@@ -304,6 +305,10 @@ export function convertLatestElectionToV4p0(election: Election): ElectionV4p0 {
       contest.type === 'yesno' && contest.options.length > 2
   );
   const contestsForV4p0 = election.contests.map((contest): ContestV4p0 => {
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      return rankedChoiceNotYetImplemented();
+    }
     if (contest.type !== 'yesno') {
       return contest;
     }
@@ -7,6 +7,7 @@ import {
   VotesDict,
   YesNoContest,
   getContests,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import { readElectionGeneralDefinition } from '@votingworks/fixtures';
@@ -331,6 +332,10 @@ describe('English ballot style', () => {
         if (c.type === 'straight-party') {
           straightPartyNotYetImplemented();
         }
+        /* istanbul ignore next */
+        if (c.type === 'ranked-choice') {
+          rankedChoiceNotYetImplemented();
+        }
         return [
           c.id,
           c.type === 'yesno' ? generateYesNoVote(c) : generateCandidateVotes(c),
@@ -8,6 +8,7 @@ import {
   getContests,
   safeParseElection,
   safeParseElectionDefinition,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
 } from '@votingworks/types';
 import { generateBallotStyleId } from '@votingworks/utils';
@@ -46,6 +47,10 @@ function getDuplicatedContests(idSuffix: string) {
     if (c.type === 'straight-party') {
       straightPartyNotYetImplemented();
     }
+    /* istanbul ignore next */
+    if (c.type === 'ranked-choice') {
+      rankedChoiceNotYetImplemented();
+    }
 
     assert(c.type === 'yesno');
     const contest: YesNoContest = {
@@ -177,6 +182,10 @@ const initialArgs: BmdPaperBallotProps = {
       if (c.type === 'straight-party') {
         straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (c.type === 'ranked-choice') {
+        rankedChoiceNotYetImplemented();
+      }
       return [
         c.id,
         c.type === 'yesno' ? generateYesNoVote(c) : generateCandidateVotes(c),
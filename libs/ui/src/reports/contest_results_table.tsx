@@ -6,6 +6,7 @@ import {
   getContestDistrictName,
   Tabulation,
   Contest,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 import { format, getTallyReportCandidateRows } from '@votingworks/utils';
 import { throwIllegalValue, assert, Optional, find } from '@votingworks/basics';
@@ -219,6 +220,10 @@ export function ContestResultsTable({
 
   const hasManualResults = Boolean(manualContestResults);
 
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate': {
       assert(scannedContestResults.contestType === 'candidate');
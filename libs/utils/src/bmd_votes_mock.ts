@@ -2,6 +2,7 @@ import { assertDefined, iter } from '@votingworks/basics';
 import {
   CandidateContest,
   Election,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
   Vote,
   VotesDict,
@@ -27,6 +28,10 @@ export function generateMockVotes(election: Election): VotesDict {
       if (c.type === 'straight-party') {
         straightPartyNotYetImplemented();
       }
+      /* istanbul ignore next */
+      if (c.type === 'ranked-choice') {
+        rankedChoiceNotYetImplemented();
+      }
       return [
         c.id,
         c.type === 'yesno'
@@ -18,6 +18,7 @@ import {
   CVR,
   CVRSnapshotOtherStatus,
   CVRSnapshotOtherStatusSchema,
+  rankedChoiceNotYetImplemented,
   safeParse,
   safeParseJson,
   safeParseNumber,
@@ -252,6 +253,10 @@ export function isCastVoteRecordWriteInValid(
 }
 
 function getValidContestOptions(contest: Contest): ContestOptionId[] {
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return [
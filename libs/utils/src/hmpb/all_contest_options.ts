@@ -18,6 +18,7 @@ import {
   StraightPartyContest,
   StraightPartyContestOption,
   Election,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 
 /**
@@ -61,6 +62,10 @@ export function* allContestOptionsWithMultiEndorsements(
   contest: Contest,
   ballotStyle?: BallotStyle | BallotStyleGroup
 ): Generator<ContestOption> {
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate': {
       // ballotStyle is guaranteed to be defined for CandidateContest by the function overload
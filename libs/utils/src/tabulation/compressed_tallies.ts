@@ -8,6 +8,7 @@ import {
   Election,
   PrecinctId,
   PrecinctSelection,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
   Tabulation,
   unsafeParse,
@@ -88,6 +89,10 @@ export function compressTally(
     if (contest.type === 'straight-party') {
       return straightPartyNotYetImplemented();
     }
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      return rankedChoiceNotYetImplemented();
+    }
     switch (contest.type) {
       case 'yesno': {
         const contestResults = results.contestResults[contest.id];
@@ -240,6 +245,10 @@ function getContestTalliesForCompressedContest(
   if (contest.type === 'straight-party') {
     return straightPartyNotYetImplemented();
   }
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'yesno': {
       const [undervotes, overvotes, ballots, ...optionTallies] = unsafeParse(
@@ -312,6 +321,10 @@ function getNumberOfEntriesInContest(contest: Contest): number {
   if (contest.type === 'straight-party') {
     return straightPartyNotYetImplemented();
   }
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'yesno':
       // 3 metadata (undervotes, overvotes, ballots) + one slot per option
@@ -369,6 +382,10 @@ export function decodeV0CompressedTally(
     if (contest.type === 'straight-party') {
       straightPartyNotYetImplemented();
     }
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      rankedChoiceNotYetImplemented();
+    }
     if (contest.type === 'yesno') {
       compressedTally.push(
         Array.from(
@@ -412,6 +429,10 @@ function decodeContestEntriesFromUint16Array(
     if (contest.type === 'straight-party') {
       straightPartyNotYetImplemented();
     }
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      rankedChoiceNotYetImplemented();
+    }
     if (contest.type === 'yesno') {
       compressedEntry = entryArray as YesNoContestCompressedTally;
     } else {
@@ -117,6 +117,7 @@ export function groupContestsByParty(
     contests: contests.filter((c) => {
       switch (c.type) {
         case 'candidate':
+        case 'ranked-choice':
           return c.partyId === partyId;
         case 'yesno':
         case 'straight-party':
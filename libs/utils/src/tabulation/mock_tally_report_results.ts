@@ -1,6 +1,7 @@
 import {
   Admin,
   Election,
+  rankedChoiceNotYetImplemented,
   straightPartyNotYetImplemented,
   Tabulation,
 } from '@votingworks/types';
@@ -20,6 +21,10 @@ function buildSimpleMockElectionResults(
     if (contest.type === 'straight-party') {
       straightPartyNotYetImplemented();
     }
+    /* istanbul ignore next */
+    if (contest.type === 'ranked-choice') {
+      rankedChoiceNotYetImplemented();
+    }
     contestResultsSummaries[contest.id] = {
       type: contest.type === 'candidate' ? 'candidate' : 'yesno',
       ballots: ballotCount,
@@ -19,6 +19,7 @@ import {
   Tabulation,
   YesNoContest,
   StraightPartyContest,
+  rankedChoiceNotYetImplemented,
 } from '@votingworks/types';
 import { isGroupByEmpty } from './arguments';
 import { getGroupedBallotStyles } from '../ballot_styles';
@@ -102,6 +103,10 @@ function getEmptyContestResults(
   contest: Contest,
   includeGenericWriteInIfAllowed?: boolean
 ): Tabulation.ContestResults {
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return getEmptyCandidateContestResults(
@@ -622,6 +627,10 @@ export function combineContestResults({
   contest: Contest;
   allContestResults: Tabulation.ContestResults[];
 }): Tabulation.ContestResults {
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return combineCandidateContestResults({
@@ -855,6 +864,10 @@ export function buildContestResultsFixture({
   contestResults.undervotes = contestResultsSummary.undervotes ?? 0;
   contestResults.ballots = contestResultsSummary.ballots;
 
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'yesno': {
       assert(contestResultsSummary.type === 'yesno');
@@ -8,6 +8,7 @@ import {
   Contest,
   MarkStatus,
   MarkThresholds,
+  rankedChoiceNotYetImplemented,
   safeParse,
   safeParseInt,
   StraightPartyVote,
@@ -169,6 +170,10 @@ function markToVotes(
   markThresholds: MarkThresholdsOptionalMarginal,
   mark: BallotTargetMark
 ): Vote {
+  /* istanbul ignore next */
+  if (contest.type === 'ranked-choice') {
+    return rankedChoiceNotYetImplemented();
+  }
   switch (contest.type) {
     case 'candidate':
       return markToCandidateVotes(contest, markThresholds, mark);